@@ -10,6 +10,8 @@ use crate::{DisplayProgress,
             error::{Error,
                     Result},
             hab_http::ApiClient,
+            mirror::{LocalMirror,
+                     MIRROR_URL_SCHEME},
             response};
 use broadcast::BroadcastWriter;
 use bytes::BytesMut;
@@ -20,7 +22,8 @@ use habitat_core::{ChannelIdent,
                         DEFAULT_CACHED_ARTIFACT_PERMISSIONS,
                         DEFAULT_PUBLIC_KEY_PERMISSIONS,
                         DEFAULT_SECRET_KEY_PERMISSIONS,
-                        Permissions},
+                        Permissions,
+                        atomic_rename},
                    origin::{Origin,
                             OriginMemberRole},
                    package::{Identifiable,
@@ -34,10 +37,10 @@ use percent_encoding::{AsciiSet,
                        CONTROLS,
                        percent_encode};
 use reqwest::{Body,
-              IntoUrl,
               RequestBuilder,
               StatusCode,
-              header::CONTENT_LENGTH};
+              header::{CONTENT_LENGTH,
+                       RANGE}};
 use serde::{Deserialize,
             Serialize};
 use serde_json::json;
@@ -45,13 +48,16 @@ use std::{fs::{self,
                File},
           future::Future,
           io::{self,
-               Cursor},
+               Cursor,
+               Write},
           path::{Path,
                  PathBuf},
+          process,
           string::ToString,
           time::Duration};
 use tee::TeeReader;
-use tokio::task;
+use tokio::{io::AsyncWriteExt,
+            task};
 use tokio_util::{codec::{BytesCodec,
                          FramedRead},
                  compat::FuturesAsyncReadCompatExt};
@@ -59,6 +65,15 @@ use url::Url;
 
 const X_FILENAME: &str = "x-filename";
 
+/// Appended to the name of a file while it is being downloaded. A download that is interrupted
+/// leaves this file behind, and the next download of the same file resumes from it.
+const PARTIAL_DOWNLOAD_EXTENSION: &str = "part";
+
+/// Appended to the name of a partial file to name the file that is locked while a download to it
+/// is in progress. The download that holds the lock removes it once it is done with the partial
+/// file.
+const PARTIAL_DOWNLOAD_LOCK_EXTENSION: &str = "lock";
+
 const DEFAULT_API_PATH: &str = "/v1";
 
 /// This constant is used by the retry_builder_api! macro and governs the number of maximum
@@ -138,7 +153,14 @@ pub struct OriginChannelWithPromotion {
     pub promoted_at: String,
 }
 
-pub struct BuilderAPIClient(ApiClient);
+pub struct BuilderAPIClient(Endpoint);
+
+/// Where a `BuilderAPIClient` sends its requests.
+enum Endpoint {
+    Builder(ApiClient),
+    /// A `file://` URL, which only supports what is needed to install packages.
+    Mirror(LocalMirror),
+}
 
 impl BuilderAPIClient {
     pub fn new<U>(endpoint: U,
@@ -146,25 +168,48 @@ impl BuilderAPIClient {
                   version: &str,
                   fs_root_path: Option<&Path>)
                   -> Result<Self>
-        where U: IntoUrl
+        where U: AsRef<str>
     {
-        let mut endpoint = endpoint.into_url().map_err(Error::ReqwestError)?;
+        // reqwest refuses URLs without a host, so a mirror's `file://` URL has to be
+        // recognized before handing the endpoint over to it.
+        let endpoint = Url::parse(endpoint.as_ref())?;
+        if endpoint.scheme() == MIRROR_URL_SCHEME {
+            return Ok(BuilderAPIClient(Endpoint::Mirror(LocalMirror::from_url(&endpoint)?)));
+        }
+        let mut endpoint = endpoint;
+        if !endpoint.has_host() {
+            return Err(Error::UrlParseError(url::ParseError::EmptyHost));
+        }
         if !endpoint.cannot_be_a_base() && endpoint.path() == "/" {
             endpoint.set_path(DEFAULT_API_PATH);
         }
-        let client = BuilderAPIClient(
+        let client = BuilderAPIClient(Endpoint::Builder(
             ApiClient::new(endpoint, product, version, fs_root_path)
                 .map_err(Error::HabitatHttpClient)?,
-        );
+        ));
         Ok(client)
     }
 
+    fn api(&self) -> Result<&ApiClient> {
+        match self.0 {
+            Endpoint::Builder(ref client) => Ok(client),
+            Endpoint::Mirror(_) => Err(Error::NotSupported),
+        }
+    }
+
+    fn mirror(&self) -> Option<&LocalMirror> {
+        match self.0 {
+            Endpoint::Builder(_) => None,
+            Endpoint::Mirror(ref mirror) => Some(mirror),
+        }
+    }
+
     pub fn create<U>(endpoint: U,
                      product: &str,
                      version: &str,
                      fs_root_path: Option<&Path>)
                      -> Result<BuilderAPIClient>
-        where U: IntoUrl
+        where U: AsRef<str>
     {
         Self::new(endpoint, product, version, fs_root_path)
     }
@@ -212,6 +257,129 @@ impl BuilderAPIClient {
         }).await?
     }
 
+    /// Download a file to `dst_file_path`, resuming an earlier download of it that was
+    /// interrupted.
+    ///
+    /// The body is streamed into a partial file next to `dst_file_path`, which is only renamed
+    /// into place once it is complete. If a partial file is already there, the rest of the file
+    /// is requested with a `Range` header. Servers that don't support ranges send the whole
+    /// file, which then replaces the partial one.
+    ///
+    /// The partial file is only used while holding an exclusive lock. If another download of the
+    /// same file holds it, the file is downloaded in full to a partial file of its own instead.
+    async fn download_resumable(&self,
+                                rb: RequestBuilder,
+                                dst_file_path: &Path,
+                                token: Option<&str>,
+                                permissions: Permissions,
+                                progress: Option<Box<dyn DisplayProgress>>)
+                                -> Result<()> {
+        if let Some(parent) = dst_file_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let partial_path = partial_download_path(dst_file_path);
+        let lock_path = partial_download_lock_path(&partial_path);
+        if let Some(_lock) = lock_partial_download(&lock_path)? {
+            let result = self.download_to_partial(rb,
+                                                  dst_file_path,
+                                                  &partial_path,
+                                                  true,
+                                                  token,
+                                                  permissions,
+                                                  progress)
+                             .await;
+            // Removed while the lock is still held, see `lock_partial_download`
+            fs::remove_file(&lock_path).ok();
+            result
+        } else {
+            debug!("{} is already being downloaded, downloading it without resuming",
+                   dst_file_path.display());
+            let partial_path = create_unique_partial_download(dst_file_path)?;
+            let result = self.download_to_partial(rb,
+                                                  dst_file_path,
+                                                  &partial_path,
+                                                  false,
+                                                  token,
+                                                  permissions,
+                                                  progress)
+                             .await;
+            if result.is_err() {
+                // Nothing would resume from it
+                fs::remove_file(&partial_path).ok();
+            }
+            result
+        }
+    }
+
+    /// Download a file to `dst_file_path` through `partial_path`, resuming from what's already in
+    /// `partial_path` if `resume` is set.
+    #[allow(clippy::too_many_arguments)]
+    async fn download_to_partial(&self,
+                                 rb: RequestBuilder,
+                                 dst_file_path: &Path,
+                                 partial_path: &Path,
+                                 resume: bool,
+                                 token: Option<&str>,
+                                 permissions: Permissions,
+                                 mut progress: Option<Box<dyn DisplayProgress>>)
+                                 -> Result<()> {
+        let mut offset = if resume {
+            fs::metadata(partial_path).map(|m| m.len()).unwrap_or(0)
+        } else {
+            0
+        };
+        let mut resp = loop {
+            let mut req = rb.try_clone()
+                            .expect("download requests have no streaming body");
+            if offset > 0 {
+                debug!("Resuming download of {} from byte {}",
+                       dst_file_path.display(),
+                       offset);
+                req = req.header(RANGE, format!("bytes={}-", offset));
+            }
+            let resp = self.maybe_add_authz(req, token).send().await?;
+            if offset > 0 && resp.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+                // The partial file is at least as long as the file being downloaded, so it can't
+                // be a prefix of it
+                debug!("Discarding partial download {}", partial_path.display());
+                fs::remove_file(partial_path)?;
+                offset = 0;
+                continue;
+            }
+            break response::ok_if(resp, &[StatusCode::OK, StatusCode::PARTIAL_CONTENT]).await?;
+        };
+        if resp.status() == StatusCode::OK {
+            offset = 0;
+        }
+
+        let mut file = if offset > 0 {
+            tokio::fs::OpenOptions::new().append(true)
+                                         .open(partial_path)
+                                         .await?
+        } else {
+            tokio::fs::File::create(partial_path).await?
+        };
+        // There will be no CONTENT_LENGTH header if an on prem
+        // builder is using chunked transfer encoding
+        if let (Some(progress), Ok(content_length)) =
+            (progress.as_mut(), response::get_header(&resp, CONTENT_LENGTH))
+        {
+            progress.size(content_length.parse().map_err(Error::ParseIntError)?);
+        }
+        while let Some(chunk) = resp.chunk().await? {
+            file.write_all(&chunk).await?;
+            if let Some(progress) = progress.as_mut() {
+                progress.write_all(&chunk)?;
+            }
+        }
+        file.sync_all().await?;
+        drop(file);
+
+        permissions.apply(partial_path)?;
+        atomic_rename(partial_path, dst_file_path)?;
+        Ok(())
+    }
+
     async fn upload_body(src_path: &Path,
                          progress: Option<Box<dyn DisplayProgress>>)
                          -> Result<Body> {
@@ -242,7 +410,7 @@ impl BuilderAPIClient {
                                        range: usize)
                                        -> Result<(PackageResults<PackageIdent>, bool)> {
         debug!("Searching for package {} with range {}", search_term, range);
-        let req = self.0
+        let req = self.api()?
                       .get_with_custom_url(&package_search(search_term), |url| {
                           url.set_query(Some(&format!("range={:?}&distinct=true", range)));
                       });
@@ -304,7 +472,10 @@ impl BuilderAPIClient {
                .append_pair("limit", &limit.to_string());
         };
 
-        let resp = self.0.get_with_custom_url(&path, custom).send().await?;
+        let resp = self.api()?
+                       .get_with_custom_url(&path, custom)
+                       .send()
+                       .await?;
         let resp = response::ok_if(resp, &[StatusCode::OK]).await?;
 
         Ok(resp.json().await?)
@@ -329,7 +500,10 @@ impl BuilderAPIClient {
                .append_pair("include_projects", &include_projects.to_string());
         };
 
-        let resp = self.0.get_with_custom_url(&path, custom).send().await?;
+        let resp = self.api()?
+                       .get_with_custom_url(&path, custom)
+                       .send()
+                       .await?;
         let resp = response::ok_if(resp, &[StatusCode::OK]).await?;
 
         Ok(resp.json().await?)
@@ -356,7 +530,7 @@ impl BuilderAPIClient {
                .append_pair("target", &target);
         };
 
-        let resp = self.0
+        let resp = self.api()?
                        .post_with_custom_url(&path, custom)
                        .bearer_auth(token)
                        .send()
@@ -384,7 +558,7 @@ impl BuilderAPIClient {
 
         let url = format!("rdeps/{}", ident);
 
-        let resp = self.0
+        let resp = self.api()?
                        .get_with_custom_url(&url, |u| {
                            u.set_query(Some(&format!("target={}", &target.to_string())))
                        })
@@ -425,7 +599,7 @@ impl BuilderAPIClient {
                           if promote { "promote" } else { "demote" },
                           channel);
 
-        response::ok_if_unit(self.0
+        response::ok_if_unit(self.api()?
                                  .post(&url)
                                  .bearer_auth(token)
                                  .json(&body)
@@ -444,7 +618,7 @@ impl BuilderAPIClient {
 
         let url = format!("jobs/group/{}/cancel", group_id);
 
-        response::ok_if_unit(self.0.post(&url).bearer_auth(token).send().await?,
+        response::ok_if_unit(self.api()?.post(&url).bearer_auth(token).send().await?,
                              &[StatusCode::NO_CONTENT]).await
     }
 
@@ -461,7 +635,7 @@ impl BuilderAPIClient {
                                                         dst_path: &'a Path,
                                                         progress: Option<Box<dyn DisplayProgress>>)
                                                         -> Result<PathBuf> {
-        self.download(self.0
+        self.download(self.api()?
                           .get(&format!("depot/origins/{}/encryption_key", origin)),
                       dst_path.as_ref(),
                       Some(token),
@@ -481,7 +655,7 @@ impl BuilderAPIClient {
             "name": origin,
         });
 
-        response::ok_if_unit(self.0
+        response::ok_if_unit(self.api()?
                                  .post("depot/origins")
                                  .bearer_auth(token)
                                  .json(&body)
@@ -501,7 +675,7 @@ impl BuilderAPIClient {
 
         let path = format!("depot/origins/{}", origin);
 
-        response::ok_if_unit(self.0.get(&path).bearer_auth(token).send().await?,
+        response::ok_if_unit(self.api()?.get(&path).bearer_auth(token).send().await?,
                              &[StatusCode::OK]).await
     }
 
@@ -517,7 +691,7 @@ impl BuilderAPIClient {
 
         let path = format!("depot/origins/{}", origin);
 
-        response::ok_if_unit(self.0.delete(&path).bearer_auth(token).send().await?,
+        response::ok_if_unit(self.api()?.delete(&path).bearer_auth(token).send().await?,
                              &[StatusCode::NO_CONTENT]).await
     }
 
@@ -539,7 +713,7 @@ impl BuilderAPIClient {
 
         let path = format!("depot/origins/{}/transfer/{}", origin, account);
 
-        response::ok_if_unit(self.0.post(&path).bearer_auth(token).send().await?,
+        response::ok_if_unit(self.api()?.post(&path).bearer_auth(token).send().await?,
                              &[StatusCode::NO_CONTENT]).await
     }
 
@@ -557,7 +731,7 @@ impl BuilderAPIClient {
 
         let path = format!("depot/origins/{}/depart", origin);
 
-        response::ok_if_unit(self.0.post(&path).bearer_auth(token).send().await?,
+        response::ok_if_unit(self.api()?.post(&path).bearer_auth(token).send().await?,
                              &[StatusCode::NO_CONTENT]).await
     }
 
@@ -586,7 +760,7 @@ impl BuilderAPIClient {
 
         let path = format!("depot/origins/{}/invitations/{}", origin, invitation_id);
 
-        response::ok_if_unit(self.0.put(&path).bearer_auth(token).send().await?,
+        response::ok_if_unit(self.api()?.put(&path).bearer_auth(token).send().await?,
                              &[StatusCode::NO_CONTENT]).await
     }
 
@@ -618,7 +792,7 @@ impl BuilderAPIClient {
         let path = format!("depot/origins/{}/invitations/{}/ignore",
                            origin, invitation_id);
 
-        response::ok_if_unit(self.0.put(&path).bearer_auth(token).send().await?,
+        response::ok_if_unit(self.api()?.put(&path).bearer_auth(token).send().await?,
                              &[StatusCode::NO_CONTENT]).await
     }
 
@@ -643,7 +817,7 @@ impl BuilderAPIClient {
                                        -> Result<UserOriginInvitationsResponse> {
         let path = "user/invitations";
 
-        let resp = self.0.get(path).bearer_auth(token).send().await?;
+        let resp = self.api()?.get(path).bearer_auth(token).send().await?;
         let resp = response::ok_if(resp, &[StatusCode::OK]).await?;
 
         Ok(resp.json().await?)
@@ -662,7 +836,7 @@ impl BuilderAPIClient {
     pub async fn origin_info(&self, token: &str, origin: &str) -> Result<OriginInfoResponse> {
         let path = format!("depot/origins/{}", origin);
 
-        let resp = self.0.get(&path).bearer_auth(token).send().await?;
+        let resp = self.api()?.get(&path).bearer_auth(token).send().await?;
         let resp = response::ok_if(resp, &[StatusCode::OK]).await?;
 
         Ok(resp.json().await?)
@@ -692,7 +866,7 @@ impl BuilderAPIClient {
         debug!("Retrieving pending invitations in origin {}", origin);
         let path = format!("depot/origins/{}/invitations", origin);
 
-        let resp = self.0.get(&path).bearer_auth(token).send().await?;
+        let resp = self.api()?.get(&path).bearer_auth(token).send().await?;
         let resp = response::ok_if(resp, &[StatusCode::OK]).await?;
 
         Ok(resp.json().await?)
@@ -725,7 +899,7 @@ impl BuilderAPIClient {
 
         let path = format!("depot/origins/{}/invitations/{}", origin, invitation_id);
 
-        response::ok_if_unit(self.0.delete(&path).bearer_auth(token).send().await?,
+        response::ok_if_unit(self.api()?.delete(&path).bearer_auth(token).send().await?,
                              &[StatusCode::NO_CONTENT]).await
     }
 
@@ -758,7 +932,7 @@ impl BuilderAPIClient {
         let path = format!("depot/origins/{}/users/{}/invitations",
                            origin, invitee_account);
 
-        response::ok_if_unit(self.0.post(&path).bearer_auth(token).send().await?,
+        response::ok_if_unit(self.api()?.post(&path).bearer_auth(token).send().await?,
                              &[StatusCode::CREATED]).await
     }
 
@@ -776,7 +950,10 @@ impl BuilderAPIClient {
                                       dst_path: &'a Path,
                                       progress: Option<Box<dyn DisplayProgress>>)
                                       -> Result<PathBuf> {
-        self.download(self.0
+        if let Some(mirror) = self.mirror() {
+            return mirror.fetch_origin_key(origin, revision, dst_path);
+        }
+        self.download(self.api()?
                           .get(&format!("depot/origins/{}/keys/{}", origin, revision)),
                       dst_path.as_ref(),
                       None,
@@ -798,7 +975,7 @@ impl BuilderAPIClient {
                                              dst_path: &'a Path,
                                              progress: Option<Box<dyn DisplayProgress>>)
                                              -> Result<PathBuf> {
-        self.download(self.0
+        self.download(self.api()?
                           .get(&format!("depot/origins/{}/secret_keys/latest", origin)),
                       dst_path.as_ref(),
                       Some(token),
@@ -810,7 +987,7 @@ impl BuilderAPIClient {
     pub async fn show_origin_keys(&self, origin: &Origin) -> Result<Vec<OriginKeyIdent>> {
         debug!("Showing origin keys: {}", origin);
 
-        let resp = self.0.get(&origin_keys_path(origin)).send().await?;
+        let resp = self.api()?.get(&origin_keys_path(origin)).send().await?;
         let resp = response::ok_if(resp, &[StatusCode::OK]).await?;
 
         let encoded = resp.text().await.map_err(Error::BadResponseBody)?;
@@ -834,6 +1011,9 @@ impl BuilderAPIClient {
         if !ident.fully_qualified() {
             return Err(Error::IdentNotFullyQualified);
         }
+        if let Some(mirror) = self.mirror() {
+            return mirror.package_channels((ident, target));
+        }
 
         let path = package_channels_path(ident);
 
@@ -841,7 +1021,7 @@ impl BuilderAPIClient {
             url.query_pairs_mut().append_pair("target", &target);
         };
 
-        let resp = self.maybe_add_authz(self.0.get_with_custom_url(&path, custom), token)
+        let resp = self.maybe_add_authz(self.api()?.get_with_custom_url(&path, custom), token)
                        .send()
                        .await?;
         let resp = response::ok_if(resp, &[StatusCode::OK]).await?;
@@ -875,7 +1055,7 @@ impl BuilderAPIClient {
 
         let path = format!("depot/origins/{}/keys/{}", &origin, &revision);
         let body = Self::upload_body(src_path, progress).await?;
-        let resp = self.0
+        let resp = self.api()?
                        .post(&path)
                        .bearer_auth(token)
                        .body(body)
@@ -905,7 +1085,7 @@ impl BuilderAPIClient {
 
         let path = format!("depot/origins/{}/secret_keys/{}", &origin, &revision);
         let body = Self::upload_body(src_path, progress).await?;
-        let resp = self.0
+        let resp = self.api()?
                        .post(&path)
                        .bearer_auth(token)
                        .body(body)
@@ -940,16 +1120,21 @@ impl BuilderAPIClient {
         if !ident.fully_qualified() {
             return Err(Error::IdentNotFullyQualified);
         }
+        if let Some(mirror) = self.mirror() {
+            return mirror.fetch_package((ident, target), dst_path);
+        }
 
-        let req_builder = self.0.get_with_custom_url(&package_download(ident), |u| {
-                                    u.set_query(Some(&format!("target={}", target)))
-                                });
-        let path = self.download(req_builder,
-                                 dst_path.as_ref(),
-                                 token,
-                                 DEFAULT_CACHED_ARTIFACT_PERMISSIONS,
-                                 progress)
-                       .await?;
+        let req_builder = self.api()?
+                              .get_with_custom_url(&package_download(ident), |u| {
+                                  u.set_query(Some(&format!("target={}", target)))
+                              });
+        let path = dst_path.join(ident.archive_name_with_target(target)?);
+        self.download_resumable(req_builder,
+                                &path,
+                                token,
+                                DEFAULT_CACHED_ARTIFACT_PERMISSIONS,
+                                progress)
+            .await?;
         Ok(PackageArchive::new(path)?)
     }
 
@@ -974,7 +1159,7 @@ impl BuilderAPIClient {
 
         let url = channel_package_path(&ChannelIdent::unstable(), package);
 
-        response::ok_if_unit(self.maybe_add_authz(self.0.get_with_custom_url(&url, |u| {
+        response::ok_if_unit(self.maybe_add_authz(self.api()?.get_with_custom_url(&url, |u| {
                                                             u.set_query(Some(&format!("target={}",
                                                                                       target)))
                                                         }),
@@ -1022,6 +1207,9 @@ impl BuilderAPIClient {
                                        -> Result<Package> {
         debug!("Retrieving package metadata for {}, target {}",
                package, target);
+        if let Some(mirror) = self.mirror() {
+            return mirror.show_package_metadata((package, target), channel);
+        }
 
         let mut url = channel_package_path(channel, package);
 
@@ -1029,9 +1217,10 @@ impl BuilderAPIClient {
             url.push_str("/latest");
         }
 
-        let resp = self.maybe_add_authz(self.0.get_with_custom_url(&url, |u| {
-                                                  u.set_query(Some(&format!("target={}", target)))
-                                              }),
+        let resp = self.maybe_add_authz(self.api()?
+                                            .get_with_custom_url(&url, |u| {
+                                                u.set_query(Some(&format!("target={}", target)))
+                                            }),
                                         token)
                        .send()
                        .await?;
@@ -1079,7 +1268,7 @@ impl BuilderAPIClient {
         debug!("Reading from {}", &pa.path.display());
         let body = Self::upload_body(&pa.path, progress).await?;
 
-        let resp = self.0
+        let resp = self.api()?
                        .post_with_custom_url(&path, custom)
                        .bearer_auth(token)
                        .body(body)
@@ -1108,7 +1297,7 @@ impl BuilderAPIClient {
             url.query_pairs_mut().append_pair("target", &target);
         };
 
-        response::ok_if_unit(self.0
+        response::ok_if_unit(self.api()?
                                  .delete_with_custom_url(&path, custom)
                                  .bearer_auth(token)
                                  .send()
@@ -1143,7 +1332,7 @@ impl BuilderAPIClient {
             url.query_pairs_mut().append_pair("target", &target);
         };
 
-        response::ok_if_unit(self.0
+        response::ok_if_unit(self.api()?
                                  .put_with_custom_url(&path, custom)
                                  .bearer_auth(token)
                                  .send()
@@ -1178,7 +1367,7 @@ impl BuilderAPIClient {
             url.query_pairs_mut().append_pair("target", &target);
         };
 
-        response::ok_if_unit(self.0
+        response::ok_if_unit(self.api()?
                                  .put_with_custom_url(&path, custom)
                                  .bearer_auth(token)
                                  .send()
@@ -1199,7 +1388,7 @@ impl BuilderAPIClient {
         debug!("Creating channel {} for origin {}", channel, origin);
 
         let path = format!("depot/channels/{}/{}", origin, channel);
-        response::ok_if_unit(self.0.post(&path).bearer_auth(token).send().await?,
+        response::ok_if_unit(self.api()?.post(&path).bearer_auth(token).send().await?,
                              &[StatusCode::CREATED]).await
     }
 
@@ -1216,7 +1405,7 @@ impl BuilderAPIClient {
         debug!("Deleting channel {} for origin {}", channel, origin);
 
         let path = format!("depot/channels/{}/{}", origin, channel);
-        response::ok_if_unit(self.0.delete(&path).bearer_auth(token).send().await?,
+        response::ok_if_unit(self.api()?.delete(&path).bearer_auth(token).send().await?,
                              &[StatusCode::OK]).await
     }
 
@@ -1236,7 +1425,7 @@ impl BuilderAPIClient {
 
         let path = format!("depot/channels/{}/{}/pkgs/promote", origin, source_channel);
 
-        response::ok_if_unit(self.0
+        response::ok_if_unit(self.api()?
                                  .put_with_custom_url(&path, |url| {
                                      url.query_pairs_mut()
                                         .append_pair("channel", target_channel.as_str());
@@ -1263,7 +1452,7 @@ impl BuilderAPIClient {
 
         let path = format!("depot/channels/{}/{}/pkgs/demote", origin, source_channel);
 
-        response::ok_if_unit(self.0
+        response::ok_if_unit(self.api()?
                                  .put_with_custom_url(&path, |url| {
                                      url.query_pairs_mut()
                                         .append_pair("channel", target_channel.as_str());
@@ -1298,15 +1487,18 @@ impl BuilderAPIClient {
                                include_sandbox_channels: bool)
                                -> Result<Vec<String>> {
         debug!("Listing channels for origin {}", origin);
+        if let Some(mirror) = self.mirror() {
+            return mirror.list_channels(origin);
+        }
 
         let path = format!("depot/channels/{}", origin);
         let resp = if include_sandbox_channels {
-            self.0
+            self.api()?
                 .get_with_custom_url(&path, |url| url.set_query(Some("sandbox=true")))
                 .send()
                 .await?
         } else {
-            self.0.get(&path).send().await?
+            self.api()?.get(&path).send().await?
         };
         debug!("Response Status: {:?}", resp.status());
 
@@ -1335,7 +1527,7 @@ impl BuilderAPIClient {
                member_account, origin);
 
         let path = format!("depot/origins/{}/users/{}/role", origin, member_account);
-        let resp = self.0.get(&path).bearer_auth(token).send().await?;
+        let resp = self.api()?.get(&path).bearer_auth(token).send().await?;
         let resp = response::ok_if(resp, &[StatusCode::OK]).await?;
 
         Ok(resp.json().await?)
@@ -1358,7 +1550,7 @@ impl BuilderAPIClient {
                member_account, role, origin);
 
         let path = format!("depot/origins/{}/users/{}/role", origin, member_account);
-        response::ok_if_unit(self.0
+        response::ok_if_unit(self.api()?
                                  .put_with_custom_url(&path, |url| {
                                      url.query_pairs_mut().append_pair("role", &role.to_string());
                                  })
@@ -1404,6 +1596,104 @@ macro_rules! retry_builder_api {
     };
 }
 
+fn partial_download_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(PARTIAL_DOWNLOAD_EXTENSION);
+    path.with_file_name(file_name)
+}
+
+fn partial_download_lock_path(partial_path: &Path) -> PathBuf {
+    let mut file_name = partial_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(PARTIAL_DOWNLOAD_LOCK_EXTENSION);
+    partial_path.with_file_name(file_name)
+}
+
+/// Lock the file at `lock_path`, creating it if needed. Returns `None` if another download holds
+/// the lock.
+///
+/// The download that holds the lock removes the file before releasing it. A download that opened
+/// the file before then may get the lock of the removed file afterwards, so the lock only counts
+/// if the file is still the one at `lock_path`.
+fn lock_partial_download(lock_path: &Path) -> io::Result<Option<File>> {
+    loop {
+        let lock = match File::options().create(true)
+                                        .write(true)
+                                        .truncate(false)
+                                        .open(lock_path)
+        {
+            Ok(lock) => lock,
+            // On Windows a removed file that is still open can't be opened again
+            Err(err) if cfg!(windows) && err.kind() == io::ErrorKind::PermissionDenied => {
+                return Ok(None);
+            }
+            Err(err) => return Err(err),
+        };
+        match lock.try_lock() {
+            Ok(()) => {
+                if is_file_at(&lock, lock_path)? {
+                    return Ok(Some(lock));
+                }
+            }
+            Err(fs::TryLockError::WouldBlock) => return Ok(None),
+            Err(fs::TryLockError::Error(err)) => return Err(err),
+        }
+    }
+}
+
+/// Whether `file` is the file at `path`, rather than one which was removed from there.
+#[cfg(unix)]
+fn is_file_at(file: &File, path: &Path) -> io::Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+
+    match fs::metadata(path) {
+        Ok(metadata) => {
+            let file_metadata = file.metadata()?;
+            Ok(metadata.dev() == file_metadata.dev() && metadata.ino() == file_metadata.ino())
+        }
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// Whether `file` is the file at `path`, rather than one which was removed from there.
+///
+/// A removed file stays at its path until every handle to it is closed, and can't be opened
+/// again in the meantime, so while `file` is open any file that can be opened there is `file`.
+#[cfg(windows)]
+fn is_file_at(_file: &File, path: &Path) -> io::Result<bool> {
+    match File::options().read(true).open(path) {
+        Ok(_) => Ok(true),
+        Err(err)
+            if matches!(err.kind(),
+                        io::ErrorKind::NotFound | io::ErrorKind::PermissionDenied) =>
+        {
+            Ok(false)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Create a partial file for `path` that no other download uses. It is named unlike the one of
+/// `partial_download_path`, so that no later download resumes from it.
+fn create_unique_partial_download(path: &Path) -> io::Result<PathBuf> {
+    let mut n = 0;
+    loop {
+        let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+        file_name.push(format!(".{}-{}.{}", process::id(), n, PARTIAL_DOWNLOAD_EXTENSION));
+        let unique_path = path.with_file_name(file_name);
+        match File::options().write(true)
+                             .create_new(true)
+                             .open(&unique_path)
+        {
+            Ok(_) => return Ok(unique_path),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => n += 1,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
 fn origin_keys_path(origin: &Origin) -> String { format!("depot/origins/{}/keys", origin) }
 
 fn package_download(package: &PackageIdent) -> String {
@@ -1464,6 +1754,11 @@ mod tests {
     use super::*;
     use futures::future::{self,
                           Ready};
+    use std::{io::Read,
+              net::{Ipv4Addr,
+                    SocketAddr,
+                    TcpListener},
+              thread};
 
     #[test]
     fn json_round_trip_u64_fields() {
@@ -1581,6 +1876,149 @@ mod tests {
         assert_eq!(r.1, 0);
     }
 
+    /// Serve `body` to a single request, answering a range request with the rest of the body
+    /// if `honor_range` is set. Returns the address to request and the request that was made.
+    fn artifact_server(body: &'static [u8],
+                       honor_range: bool)
+                       -> (SocketAddr, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 4096];
+            let len = stream.read(&mut request).unwrap();
+            let request = String::from_utf8_lossy(&request[..len]).to_lowercase();
+            let start = request.lines()
+                               .find_map(|line| line.strip_prefix("range: bytes="))
+                               .filter(|_| honor_range)
+                               .map(|range| range.trim_end_matches('-').parse::<usize>().unwrap());
+            let (status, rest) = match start {
+                Some(start) => ("206 Partial Content", &body[start..]),
+                None => ("200 OK", body),
+            };
+            write!(stream,
+                   "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                   status,
+                   rest.len()).unwrap();
+            stream.write_all(rest).unwrap();
+            request
+        });
+        (addr, handle)
+    }
+
+    #[tokio::test]
+    async fn download_resumes_from_partial_file() {
+        let (addr, server) = artifact_server(b"0123456789", true);
+        let client = BuilderAPIClient::new(format!("http://{}", addr).as_str(), "", "", None)
+            .expect("valid client");
+        let tmpdir = tempfile::TempDir::new().unwrap();
+        let dst = tmpdir.path().join("core-redis.hart");
+        fs::write(partial_download_path(&dst), b"0123").unwrap();
+
+        client.download_resumable(client.api().unwrap().get("download"),
+                                  &dst,
+                                  None,
+                                  Permissions::Standard,
+                                  None)
+              .await
+              .expect("download succeeds");
+
+        assert!(server.join().unwrap().contains("range: bytes=4-"));
+        assert_eq!(fs::read(&dst).unwrap(), b"0123456789");
+        assert!(!partial_download_path(&dst).exists());
+    }
+
+    #[tokio::test]
+    async fn download_starts_over_when_server_ignores_range() {
+        let (addr, server) = artifact_server(b"0123456789", false);
+        let client = BuilderAPIClient::new(format!("http://{}", addr).as_str(), "", "", None)
+            .expect("valid client");
+        let tmpdir = tempfile::TempDir::new().unwrap();
+        let dst = tmpdir.path().join("core-redis.hart");
+        fs::write(partial_download_path(&dst), b"xxxx").unwrap();
+
+        client.download_resumable(client.api().unwrap().get("download"),
+                                  &dst,
+                                  None,
+                                  Permissions::Standard,
+                                  None)
+              .await
+              .expect("download succeeds");
+
+        server.join().unwrap();
+        assert_eq!(fs::read(&dst).unwrap(), b"0123456789");
+        assert!(!partial_download_path(&dst).exists());
+    }
+
+    /// Serve `body` to `count` requests, only answering once all of them were made.
+    fn concurrent_artifact_server(body: &'static [u8],
+                                  count: usize)
+                                  -> (SocketAddr, thread::JoinHandle<()>) {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = thread::spawn(move || {
+            let streams = (0..count).map(|_| {
+                                        let (mut stream, _) = listener.accept().unwrap();
+                                        let mut request = [0; 4096];
+                                        assert!(stream.read(&mut request).unwrap() > 0);
+                                        stream
+                                    })
+                                    .collect::<Vec<_>>();
+            for mut stream in streams {
+                write!(stream,
+                       "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                       body.len()).unwrap();
+                stream.write_all(body).unwrap();
+            }
+        });
+        (addr, handle)
+    }
+
+    #[tokio::test]
+    async fn concurrent_downloads_of_a_file_use_their_own_partial_files() {
+        let (addr, server) = concurrent_artifact_server(b"0123456789", 2);
+        let client = BuilderAPIClient::new(format!("http://{}", addr).as_str(), "", "", None)
+            .expect("valid client");
+        let tmpdir = tempfile::TempDir::new().unwrap();
+        let dst = tmpdir.path().join("core-redis.hart");
+
+        let download = || {
+            client.download_resumable(client.api().unwrap().get("download"),
+                                      &dst,
+                                      None,
+                                      Permissions::Standard,
+                                      None)
+        };
+        let (first, second) = tokio::join!(download(), download());
+        first.expect("first download succeeds");
+        second.expect("second download succeeds");
+
+        server.join().unwrap();
+        assert_eq!(fs::read(&dst).unwrap(), b"0123456789");
+        let mut files = fs::read_dir(tmpdir.path()).unwrap()
+                                                   .map(|entry| entry.unwrap().file_name())
+                                                   .collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(files, ["core-redis.hart"]);
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn file_urls_select_a_local_mirror() {
+        let client = BuilderAPIClient::new("file:///srv/habitat-mirror", "", "", None)
+            .expect("valid client");
+        assert_eq!(client.mirror().map(LocalMirror::root),
+                   Some(Path::new("/srv/habitat-mirror")));
+        assert!(matches!(client.create_channel(&"core".parse().unwrap(),
+                                               &ChannelIdent::stable(),
+                                               "token")
+                               .await,
+                         Err(Error::NotSupported)));
+
+        let client = BuilderAPIClient::new("http://test.com", "", "", None).expect("valid client");
+        assert!(client.mirror().is_none());
+    }
+
     #[tokio::test]
     #[ignore = "takes too long to run regularly; should run on CI"]
     async fn package_search_large() {
@@ -70,6 +70,7 @@ pub enum Error {
     KeyReadError(PathBuf, io::Error),
     MissingHeader(String),
     InvalidHeader(String),
+    InvalidMirrorUrl(String),
     NoFilePart,
     PackageReadError(PathBuf, io::Error),
     ParseIntError(num::ParseIntError),
@@ -103,6 +104,9 @@ impl fmt::Display for Error {
             }
             Error::MissingHeader(ref s) => format!("Response is missing a required header: {}", s),
             Error::InvalidHeader(ref s) => format!("Response header is invalid: {}", s),
+            Error::InvalidMirrorUrl(ref url) => {
+                format!("Local mirror URL does not name a directory: {}", url)
+            }
             Error::NoFilePart => "An invalid path was passed - we needed a filename, and this \
                                   path does not have one"
                                                          .to_string(),
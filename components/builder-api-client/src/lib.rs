@@ -7,6 +7,7 @@ use habitat_http_client as hab_http;
 
 pub mod builder;
 pub mod error;
+pub mod mirror;
 pub mod response;
 
 use std::str::FromStr;
@@ -19,7 +20,6 @@ use std::{fmt,
 
 use chrono::{DateTime,
              Utc};
-use reqwest::IntoUrl;
 use serde::{Deserialize,
             Serialize};
 
@@ -141,7 +141,7 @@ mod json {
     }
 }
 
-#[derive(Clone, Deserialize)]
+#[derive(Clone, Deserialize, Serialize)]
 pub struct Package {
     pub ident:    PackageIdent,
     pub checksum: String,
@@ -323,10 +323,8 @@ impl Client {
                   version: &str,
                   fs_root_path: Option<&Path>)
                   -> Result<BuilderAPIClient>
-        where U: IntoUrl
+        where U: AsRef<str>
     {
-        let endpoint = endpoint.into_url().map_err(Error::ReqwestError)?;
-
         let client = BuilderAPIClient::new(endpoint, product, version, fs_root_path)?;
 
         Ok(client)
@@ -0,0 +1,374 @@
+//! A directory laid out so that it can stand in for Builder.
+//!
+//! `hab pkg mirror sync` fills such a directory with artifacts, the public origin keys that
+//! signed them, their metadata and an index of each channel they were synced from. A
+//! `BuilderAPIClient` created with a `file://` URL pointing at the directory then serves
+//! installs and Supervisor updates from it, which is how air-gapped sites consume packages.
+//!
+//! ```text
+//! <root>/artifacts/<origin>-<name>-<version>-<release>-<target>.hart
+//! <root>/keys/<origin>-<revision>.pub
+//! <root>/pkgs/<target>/<origin>/<name>/<version>/<release>.json
+//! <root>/channels/<origin>/<channel>/<target>.json
+//! ```
+//!
+//! Package metadata is stored in the same JSON form Builder returns it in. A channel index is
+//! a JSON array of the fully-qualified identifiers of the packages in that channel.
+
+use crate::{Package,
+            error::{Error,
+                    Result}};
+use habitat_core::{ChannelIdent,
+                   fs::{AtomicWriter,
+                        DEFAULT_CACHED_ARTIFACT_PERMISSIONS,
+                        DEFAULT_PUBLIC_KEY_PERMISSIONS,
+                        Permissions},
+                   origin::Origin,
+                   package::{Identifiable,
+                             PackageArchive,
+                             PackageIdent,
+                             PackageTarget}};
+use reqwest::StatusCode;
+use std::{collections::BTreeSet,
+          fs::{self,
+               File},
+          io::{self,
+               Write},
+          path::{Path,
+                 PathBuf}};
+use url::Url;
+
+/// The URL scheme which selects a local mirror instead of a remote Builder.
+pub const MIRROR_URL_SCHEME: &str = "file";
+
+#[derive(Clone, Debug)]
+pub struct LocalMirror {
+    root: PathBuf,
+}
+
+impl LocalMirror {
+    pub fn new(root: impl Into<PathBuf>) -> Self { LocalMirror { root: root.into() } }
+
+    pub fn from_url(url: &Url) -> Result<Self> {
+        url.to_file_path()
+           .map(LocalMirror::new)
+           .map_err(|_| Error::InvalidMirrorUrl(url.to_string()))
+    }
+
+    pub fn root(&self) -> &Path { &self.root }
+
+    pub fn artifacts_path(&self) -> PathBuf { self.root.join("artifacts") }
+
+    pub fn keys_path(&self) -> PathBuf { self.root.join("keys") }
+
+    fn metadata_path(&self, ident: &PackageIdent, target: PackageTarget) -> Result<PathBuf> {
+        match (ident.version(), ident.release()) {
+            (Some(version), Some(release)) => {
+                Ok(self.root
+                       .join("pkgs")
+                       .join(target.as_ref())
+                       .join(ident.origin())
+                       .join(ident.name())
+                       .join(version)
+                       .join(format!("{}.json", release)))
+            }
+            _ => Err(Error::IdentNotFullyQualified),
+        }
+    }
+
+    fn channel_path(&self, origin: &str, channel: &ChannelIdent, target: PackageTarget) -> PathBuf {
+        self.root
+            .join("channels")
+            .join(origin)
+            .join(channel.as_str())
+            .join(format!("{}.json", target))
+    }
+
+    /// Returns the fully-qualified identifiers of the packages of `origin` in `channel`.
+    pub fn channel_packages(&self,
+                            origin: &str,
+                            channel: &ChannelIdent,
+                            target: PackageTarget)
+                            -> Result<Vec<PackageIdent>> {
+        match fs::read_to_string(self.channel_path(origin, channel, target)) {
+            Ok(index) => Ok(serde_json::from_str(&index)?),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(Error::IO(e)),
+        }
+    }
+
+    /// The mirror's equivalent of `BuilderAPIClient::show_package_metadata`.
+    pub fn show_package_metadata(&self,
+                                 (package, target): (&PackageIdent, PackageTarget),
+                                 channel: &ChannelIdent)
+                                 -> Result<Package> {
+        let latest = self.channel_packages(package.origin(), channel, target)?
+                         .into_iter()
+                         .filter(|ident| ident.satisfies(package))
+                         .max()
+                         .ok_or_else(|| {
+                             not_found(&format!("{} for {} in channel {}",
+                                                package, target, channel))
+                         })?;
+        let metadata = fs::read_to_string(self.metadata_path(&latest, target)?).map_err(|e| {
+                           if e.kind() == io::ErrorKind::NotFound {
+                               not_found(&format!("metadata of {} for {}", latest, target))
+                           } else {
+                               Error::IO(e)
+                           }
+                       })?;
+        Ok(serde_json::from_str(&metadata)?)
+    }
+
+    /// The mirror's equivalent of `BuilderAPIClient::fetch_package`.
+    pub fn fetch_package(&self,
+                         (ident, target): (&PackageIdent, PackageTarget),
+                         dst_path: &Path)
+                         -> Result<PackageArchive> {
+        if !ident.fully_qualified() {
+            return Err(Error::IdentNotFullyQualified);
+        }
+        let file_name = ident.archive_name_with_target(target)?;
+        let dst_file_path = dst_path.join(&file_name);
+        copy_file(&self.artifacts_path().join(&file_name),
+                  &dst_file_path,
+                  DEFAULT_CACHED_ARTIFACT_PERMISSIONS)?;
+        Ok(PackageArchive::new(dst_file_path)?)
+    }
+
+    /// The mirror's equivalent of `BuilderAPIClient::fetch_origin_key`.
+    pub fn fetch_origin_key(&self,
+                            origin: &str,
+                            revision: &str,
+                            dst_path: &Path)
+                            -> Result<PathBuf> {
+        let file_name = format!("{}-{}.pub", origin, revision);
+        let dst_file_path = dst_path.join(&file_name);
+        copy_file(&self.keys_path().join(&file_name),
+                  &dst_file_path,
+                  DEFAULT_PUBLIC_KEY_PERMISSIONS)?;
+        Ok(dst_file_path)
+    }
+
+    /// The mirror's equivalent of `BuilderAPIClient::list_channels`.
+    pub fn list_channels(&self, origin: &Origin) -> Result<Vec<String>> {
+        let path = self.root.join("channels").join(origin.as_ref());
+        let entries = match fs::read_dir(path) {
+            Ok(entries) => entries,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(Error::IO(e)),
+        };
+        let mut channels = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                channels.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+        channels.sort();
+        Ok(channels)
+    }
+
+    /// The mirror's equivalent of `BuilderAPIClient::package_channels`.
+    pub fn package_channels(&self,
+                            (ident, target): (&PackageIdent, PackageTarget))
+                            -> Result<Vec<String>> {
+        if !ident.fully_qualified() {
+            return Err(Error::IdentNotFullyQualified);
+        }
+        let origin = ident.origin().parse::<Origin>()?;
+        let mut channels = Vec::new();
+        for channel in self.list_channels(&origin)? {
+            if self.channel_packages(ident.origin(),
+                                     &ChannelIdent::from(channel.as_str()),
+                                     target)?
+                   .contains(ident)
+            {
+                channels.push(channel);
+            }
+        }
+        Ok(channels)
+    }
+
+    /// Records the metadata of a package whose artifact was placed in `artifacts_path`.
+    pub fn add_package(&self, package: &Package, target: PackageTarget) -> Result<()> {
+        let path = self.metadata_path(&package.ident, target)?;
+        write_file(&path, &serde_json::to_string_pretty(package)?)
+    }
+
+    /// Whether the metadata of a package has already been recorded.
+    pub fn has_package(&self, ident: &PackageIdent, target: PackageTarget) -> bool {
+        self.metadata_path(ident, target)
+            .map(|path| path.is_file())
+            .unwrap_or(false)
+    }
+
+    /// Records the metadata of `artifact`, reading it from the artifact itself.
+    pub fn add_artifact(&self, artifact: &PackageArchive) -> Result<()> {
+        let package = Package { ident:    artifact.ident()?,
+                                checksum: artifact.checksum()?,
+                                manifest: artifact.manifest()?.to_string(),
+                                deps:     artifact.deps()?,
+                                tdeps:    artifact.tdeps()?,
+                                exposes:  artifact.exposes()?.into_iter().map(u32::from).collect(),
+                                config:   artifact.config().unwrap_or_default().to_string(), };
+        self.add_package(&package, artifact.target()?)
+    }
+
+    /// Adds fully-qualified identifiers to the index of `channel`, keeping the packages that
+    /// are already in it.
+    pub fn add_to_channel(&self,
+                          channel: &ChannelIdent,
+                          target: PackageTarget,
+                          idents: &[PackageIdent])
+                          -> Result<()> {
+        let origins = idents.iter()
+                            .map(Identifiable::origin)
+                            .collect::<BTreeSet<_>>();
+        for origin in origins {
+            let mut index = self.channel_packages(origin, channel, target)?
+                                .into_iter()
+                                .collect::<BTreeSet<_>>();
+            index.extend(idents.iter()
+                               .filter(|ident| ident.origin() == origin)
+                               .cloned());
+
+            let path = self.channel_path(origin, channel, target);
+            write_file(&path, &serde_json::to_string_pretty(&index)?)?;
+        }
+        Ok(())
+    }
+}
+
+fn not_found(what: &str) -> Error {
+    Error::APIError(StatusCode::NOT_FOUND,
+                    format!("{} not found in local mirror", what))
+}
+
+fn copy_file(src: &Path, dst: &Path, permissions: Permissions) -> Result<()> {
+    let mut src_file = File::open(src).map_err(|e| {
+                                          if e.kind() == io::ErrorKind::NotFound {
+                                              not_found(&src.display().to_string())
+                                          } else {
+                                              Error::IO(e)
+                                          }
+                                      })?;
+    if let Some(parent) = dst.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    AtomicWriter::new_with_permissions(dst, permissions)?.with_writer(|f| {
+                                                             io::copy(&mut src_file, f)
+                                                         })?;
+    Ok(())
+}
+
+/// Writes `contents` to `dst` atomically, so that Supervisors installing from the mirror while
+/// it is being synced never read a partially written index or metadata file.
+fn write_file(dst: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = dst.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    AtomicWriter::new_with_permissions(dst, DEFAULT_CACHED_ARTIFACT_PERMISSIONS)?.with_writer(|f| {
+        f.write_all(contents.as_bytes())
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn package(ident: &str) -> Package {
+        Package { ident:    ident.parse().unwrap(),
+                  checksum: String::from("0123456789abcdef"),
+                  manifest: String::new(),
+                  deps:     Vec::new(),
+                  tdeps:    Vec::new(),
+                  exposes:  Vec::new(),
+                  config:   String::new(), }
+    }
+
+    fn mirror_with(channel: &ChannelIdent, idents: &[&str]) -> (TempDir, LocalMirror) {
+        let dir = TempDir::new().unwrap();
+        let mirror = LocalMirror::new(dir.path());
+        let target = PackageTarget::active_target();
+        for ident in idents {
+            mirror.add_package(&package(ident), target).unwrap();
+        }
+        let idents = idents.iter()
+                           .map(|ident| ident.parse().unwrap())
+                           .collect::<Vec<_>>();
+        mirror.add_to_channel(channel, target, &idents).unwrap();
+        (dir, mirror)
+    }
+
+    #[test]
+    fn show_package_metadata_returns_latest_in_channel() {
+        let stable = ChannelIdent::stable();
+        let (_dir, mirror) = mirror_with(&stable,
+                                         &["core/redis/4.0.14/20190319155852",
+                                           "core/redis/5.0.4/20190709130417",
+                                           "core/redis/4.0.14/20200101000000"]);
+        let target = PackageTarget::active_target();
+
+        let latest = mirror.show_package_metadata((&"core/redis".parse().unwrap(), target),
+                                                  &stable)
+                           .unwrap();
+        assert_eq!(latest.ident.to_string(), "core/redis/5.0.4/20190709130417");
+
+        let latest = mirror.show_package_metadata((&"core/redis/4.0.14".parse().unwrap(), target),
+                                                  &stable)
+                           .unwrap();
+        assert_eq!(latest.ident.to_string(), "core/redis/4.0.14/20200101000000");
+    }
+
+    #[test]
+    fn show_package_metadata_is_not_found_outside_channel() {
+        let (_dir, mirror) = mirror_with(&ChannelIdent::stable(),
+                                         &["core/redis/4.0.14/20190319155852"]);
+        let result = mirror.show_package_metadata((&"core/redis".parse().unwrap(),
+                                                   PackageTarget::active_target()),
+                                                  &ChannelIdent::unstable());
+        assert!(matches!(result, Err(Error::APIError(StatusCode::NOT_FOUND, _))));
+    }
+
+    #[test]
+    fn add_to_channel_keeps_existing_packages() {
+        let stable = ChannelIdent::stable();
+        let (_dir, mirror) = mirror_with(&stable, &["core/redis/4.0.14/20190319155852"]);
+        let target = PackageTarget::active_target();
+        mirror.add_to_channel(&stable,
+                              target,
+                              &["core/redis/5.0.4/20190709130417".parse().unwrap()])
+              .unwrap();
+
+        assert_eq!(mirror.channel_packages("core", &stable, target)
+                         .unwrap()
+                         .len(),
+                   2);
+        assert_eq!(mirror.list_channels(&"core".parse().unwrap()).unwrap(),
+                   vec![String::from("stable")]);
+        assert_eq!(mirror.package_channels((&"core/redis/5.0.4/20190709130417".parse().unwrap(),
+                                            target))
+                         .unwrap(),
+                   vec![String::from("stable")]);
+    }
+
+    #[test]
+    fn fetch_origin_key_copies_key_from_mirror() {
+        let dir = TempDir::new().unwrap();
+        let mirror = LocalMirror::new(dir.path().join("mirror"));
+        fs::create_dir_all(mirror.keys_path()).unwrap();
+        fs::write(mirror.keys_path().join("core-20160810182414.pub"), "key").unwrap();
+
+        let dst = dir.path().join("cache");
+        let path = mirror.fetch_origin_key("core", "20160810182414", &dst)
+                         .unwrap();
+        assert_eq!(path, dst.join("core-20160810182414.pub"));
+        assert_eq!(fs::read_to_string(path).unwrap(), "key");
+
+        let missing = mirror.fetch_origin_key("core", "20200101000000", &dst);
+        assert!(matches!(missing, Err(Error::APIError(StatusCode::NOT_FOUND, _))));
+    }
+}
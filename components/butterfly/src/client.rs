@@ -11,17 +11,24 @@ use crate::{ZMQ_CONTEXT,
                     service_config::ServiceConfig,
                     service_file::ServiceFile}};
 use habitat_core::{crypto::keys::RingKey,
-                   service::ServiceGroup};
+                   service::ServiceGroup,
+                   tls::gossip::MemberAuthenticator};
 
-/// Holds a ZMQ Push socket, and an optional ring encryption key.
+/// Holds a ZMQ Push socket, an optional ring encryption key, and an optional member
+/// authenticator.
 pub struct Client {
-    socket:   zmq::Socket,
-    ring_key: Option<RingKey>,
+    socket:        zmq::Socket,
+    ring_key:      Option<RingKey>,
+    authenticator: Option<MemberAuthenticator>,
 }
 
 impl Client {
-    /// Connect this client to the address, and optionally encrypt the traffic.
-    pub fn new(addr: &str, ring_key: Option<RingKey>) -> Result<Client> {
+    /// Connect this client to the address, and optionally encrypt the traffic and sign it as the
+    /// member the authenticator is for.
+    pub fn new(addr: &str,
+               ring_key: Option<RingKey>,
+               authenticator: Option<MemberAuthenticator>)
+               -> Result<Client> {
         let socket = (**ZMQ_CONTEXT).as_mut()
                                     .socket(zmq::PUSH)
                                     .expect("Failure to create the ZMQ push socket");
@@ -37,7 +44,9 @@ impl Client {
               .expect("Failure to set the ZMQ send timeout");
         let to_addr = format!("tcp://{}", addr);
         socket.connect(&to_addr).map_err(Error::ZmqConnectError)?;
-        Ok(Client { socket, ring_key })
+        Ok(Client { socket,
+                    ring_key,
+                    authenticator })
     }
 
     /// Create a departure notification and send it to the server.
@@ -80,7 +89,8 @@ impl Client {
         where T: Rumor
     {
         let bytes = rumor.write_to_bytes()?;
-        let wire_msg = message::generate_wire(bytes, self.ring_key.as_ref())?;
+        let wire_msg =
+            message::generate_wire(bytes, self.ring_key.as_ref(), self.authenticator.as_ref())?;
         self.socket.send(wire_msg, 0).map_err(Error::ZmqSendError)
     }
 }
@@ -1,3 +1,4 @@
+use habitat_core::tls::gossip;
 use std::{error,
           fmt,
           io,
@@ -12,15 +13,21 @@ pub type Result<T> = result::Result<T, Error>;
 pub enum Error {
     BadDataPath(PathBuf, io::Error),
     CannotBind(io::Error),
+    ConfigHistoryIO(PathBuf, io::Error),
+    ConfigHistoryParse(PathBuf, serde_json::Error),
     DatFileIO(PathBuf, io::Error),
     DecodeError(prost::DecodeError),
     EncodeError(prost::EncodeError),
     HabitatCore(habitat_core::error::Error),
     IncarnationIO(PathBuf, io::Error),
     IncarnationParse(PathBuf, num::ParseIntError),
+    LeaderHandoff(String),
+    MemberAuthentication(gossip::Error),
+    MemberCertificateMismatch(String),
     NonExistentRumor(String, String),
     OsError(io::Error),
     ProtocolMismatch(&'static str),
+    RingKeyRotation(String),
     ServiceConfigDecode(String, String),
     ServiceConfigNotUtf8(String, str::Utf8Error),
     SocketSetReadTimeout(io::Error),
@@ -40,6 +47,16 @@ impl fmt::Display for Error {
                         err)
             }
             Error::CannotBind(ref err) => format!("Cannot bind to port: {:?}", err),
+            Error::ConfigHistoryIO(ref path, ref err) => {
+                format!("Error reading or writing config history file {}: {}",
+                        path.display(),
+                        err)
+            }
+            Error::ConfigHistoryParse(ref path, ref err) => {
+                format!("Error parsing config history file {}: {}",
+                        path.display(),
+                        err)
+            }
             Error::DatFileIO(ref path, ref err) => {
                 format!("Error reading or writing to DatFile, {}, {}",
                         path.display(),
@@ -59,6 +76,14 @@ impl fmt::Display for Error {
                         path.display(),
                         err)
             }
+            Error::LeaderHandoff(ref msg) => format!("Cannot hand off leadership: {}", msg),
+            Error::MemberAuthentication(ref err) => {
+                format!("Gossip member authentication failed: {}", err)
+            }
+            Error::MemberCertificateMismatch(ref member_id) => {
+                format!("Gossip member certificate was not issued for member ID {}",
+                        member_id)
+            }
             Error::NonExistentRumor(ref member_id, ref rumor_id) => {
                 format!("Non existent rumor asked to be written to bytes: {} {}",
                         member_id, rumor_id)
@@ -68,6 +93,7 @@ impl fmt::Display for Error {
                 format!("Received an unsupported or bad protocol message. Missing field: {}",
                         field)
             }
+            Error::RingKeyRotation(ref msg) => format!("Cannot rotate ring key: {}", msg),
             Error::ServiceConfigDecode(ref sg, ref err) => {
                 format!("Cannot decode service config: group={}, {}", sg, err)
             }
@@ -100,6 +126,9 @@ impl From<prost::EncodeError> for Error {
 impl From<habitat_core::error::Error> for Error {
     fn from(err: habitat_core::error::Error) -> Error { Error::HabitatCore(err) }
 }
+impl From<gossip::Error> for Error {
+    fn from(err: gossip::Error) -> Error { Error::MemberAuthentication(err) }
+}
 impl From<io::Error> for Error {
     fn from(err: io::Error) -> Error { Error::UnknownIOError(err) }
 }
@@ -31,6 +31,7 @@ pub mod error;
 pub mod member;
 pub mod message;
 pub mod protocol;
+pub mod ring_keys;
 pub mod rumor;
 pub mod server;
 pub mod swim;
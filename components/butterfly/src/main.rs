@@ -38,6 +38,7 @@ fn main() {
                                          None,
                                          None,
                                          None,
+                                         None,
                                          Arc::new(ZeroSuitability)).unwrap();
     println!("Server ID: {}", server.member_id());
 
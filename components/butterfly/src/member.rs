@@ -47,6 +47,9 @@ use uuid::Uuid;
 /// How many nodes do we target when we need to run PingReq.
 const PINGREQ_TARGETS: usize = 5;
 
+/// The segment of members that don't specify one.
+pub const DEFAULT_SEGMENT: &str = "default";
+
 lazy_static! {
     static ref PEER_HEALTH_COUNT: IntGaugeVec =
         register_int_gauge_vec!("hab_butterfly_peer_health_total",
@@ -138,6 +141,10 @@ pub struct Member {
     pub persistent:  bool,
     pub departed:    bool,
     pub probe_ping:  bool,
+    /// The segment of the ring the member belongs to, usually a region or a zone.
+    pub segment:     String,
+    /// Whether the member relays rumors between its segment and the other segments.
+    pub gateway:     bool,
 }
 
 impl Member {
@@ -156,6 +163,13 @@ impl Member {
             }
         }
     }
+
+    /// Whether this member probes and sends rumors to `other` directly. Members only talk to the
+    /// members of their own segment, except for gateways, which also talk to the gateways of the
+    /// other segments so that rumors can cross between segments.
+    pub fn talks_to(&self, other: &Member) -> bool {
+        self.segment == other.segment || (self.gateway && other.gateway)
+    }
 }
 
 impl Default for Member {
@@ -172,7 +186,9 @@ impl Default for Member {
                  gossip_port: 0,
                  persistent:  false,
                  departed:    false,
-                 probe_ping:  false, }
+                 probe_ping:  false,
+                 segment:     DEFAULT_SEGMENT.to_string(),
+                 gateway:     false, }
     }
 }
 
@@ -197,7 +213,9 @@ impl From<Member> for proto::Member {
                         gossip_port: Some(value.gossip_port.into()),
                         persistent:  Some(value.persistent),
                         departed:    Some(value.departed),
-                        probe_ping:  Some(value.probe_ping), }
+                        probe_ping:  Some(value.probe_ping),
+                        segment:     Some(value.segment),
+                        gateway:     Some(value.gateway), }
     }
 }
 
@@ -309,7 +327,9 @@ impl FromProto<proto::Member> for Member {
                                       .ok_or(Error::ProtocolMismatch("gossip-port"))?,
                     persistent:  proto.persistent.unwrap_or(false),
                     departed:    proto.departed.unwrap_or(false),
-                    probe_ping:  proto.probe_ping.unwrap_or(false), })
+                    probe_ping:  proto.probe_ping.unwrap_or(false),
+                    segment:     proto.segment.unwrap_or_else(|| DEFAULT_SEGMENT.to_string()),
+                    gateway:     proto.gateway.unwrap_or(false), })
     }
 }
 
@@ -348,6 +368,9 @@ mod member_list {
 pub struct MemberList {
     entries:         Lock<HashMap<UuidSimple, member_list::Entry>>,
     initial_members: Lock<Vec<Member>>,
+    /// When each recently purged member was purged, so that we don't take a member back in when
+    /// a member that hasn't purged it yet tells us it departed.
+    purged:          Lock<HashMap<UuidSimple, Instant>>,
     update_counter:  AtomicUsize,
 }
 
@@ -385,6 +408,7 @@ impl MemberList {
     pub fn new() -> MemberList {
         MemberList { entries:         Lock::new(HashMap::new()),
                      initial_members: Lock::new(Vec::new()),
+                     purged:          Lock::new(HashMap::new()),
                      update_counter:  AtomicUsize::new(0), }
     }
 
@@ -538,6 +562,13 @@ impl MemberList {
                     false
                 }
             }
+            hash_map::Entry::Vacant(_)
+                if incoming.health == Health::Departed
+                   && self.purged.read().contains_key(&member_id) =>
+            {
+                trace!("Purged: Not Created");
+                false
+            }
             hash_map::Entry::Vacant(entry) => {
                 entry.insert(member_list::Entry { member:            incoming.member,
                                                   health:            incoming.health,
@@ -570,17 +601,24 @@ impl MemberList {
     /// # Locking (see locking.md)
     /// * `MemberList::entries` (write)
     pub fn set_departed_mlw(&self, member_id: &str) {
-        if let Some(member_list::Entry { member, health, .. }) =
+        if let Some(member_list::Entry { member,
+                                         health,
+                                         health_updated_at, }) =
             self.write_entries().get_mut(member_id)
         {
             debug!("Setting health of {:?}, {} -> {}",
                    member,
                    health,
                    Health::Departed);
-            *health = Health::Departed;
+            if *health != Health::Departed {
+                *health = Health::Departed;
+                *health_updated_at = Instant::now();
+            }
         } else {
             trace!("set_departed called on unknown member {}", member_id);
+            return;
         }
+        self.calculate_peer_health_metrics_mlr();
     }
 
     /// # Locking (see locking.md)
@@ -690,39 +728,44 @@ impl MemberList {
     /// * `MemberList::entries` (read)
     pub fn is_empty_mlr(&self) -> bool { self.read_entries().is_empty() }
 
-    /// A randomized list of members to check.
+    /// A randomized list of the members `myself` talks to, to check.
     ///
     /// # Locking (see locking.md)
     /// * `MemberList::entries` (read)
-    pub fn check_list_mlr(&self, exclude_id: &str) -> Vec<Member> {
-        let mut members: Vec<_> = self.read_entries()
-                                      .values()
-                                      .map(|member_list::Entry { member, .. }| member)
-                                      .filter(|member| member.id != exclude_id)
-                                      .cloned()
-                                      .collect();
+    pub fn check_list_mlr(&self, myself: &Member) -> Vec<Member> {
+        let mut members: Vec<_> =
+            self.read_entries()
+                .values()
+                .map(|member_list::Entry { member, .. }| member)
+                .filter(|member| member.id != myself.id && myself.talks_to(member))
+                .cloned()
+                .collect();
         members.shuffle(&mut rng());
 
         members
     }
 
     /// Takes a function whose first argument is a member, and calls it for every pingreq target.
+    /// Pingreq targets are members that talk to both the sending member and the target member,
+    /// so a probe never leaves the target's segment unless the target is a gateway.
     ///
     /// # Locking (see locking.md)
     /// * `MemberList::entries` (read)
     /// * Additionally `with_closure` is called with this lock held, so the closure must not call
     ///   any functions which take this lock.
     pub fn with_pingreq_targets_mlr(&self,
-                                    sending_member_id: &str,
-                                    target_member_id: &str,
+                                    sending_member: &Member,
+                                    target_member: &Member,
                                     mut with_closure: impl FnMut(&Member)) {
         for member_list::Entry { member, .. } in
             self.read_entries()
                 .values()
                 .filter(|member_list::Entry { member, health, .. }| {
-                    member.id != sending_member_id
-                    && member.id != target_member_id
+                    member.id != sending_member.id
+                    && member.id != target_member.id
                     && *health == Health::Alive
+                    && sending_member.talks_to(member)
+                    && member.talks_to(target_member)
                 })
                 .sample(&mut rng(), PINGREQ_TARGETS)
         {
@@ -827,6 +870,45 @@ impl MemberList {
 
         if !expired.is_empty() {
             self.increment_update_counter();
+            self.calculate_peer_health_metrics_mlr();
+        }
+
+        expired
+    }
+
+    /// Remove the members that have been `Departed` for longer than
+    /// `ttl`, returning their IDs.
+    ///
+    /// The IDs are remembered for another `ttl`, during which we
+    /// ignore news of those members departing. This keeps members
+    /// that haven't purged them yet from gossiping them back to us.
+    ///
+    /// # Locking (see locking.md)
+    /// * `MemberList::entries` (write)
+    /// * `MemberList::purged` (write)
+    pub fn purge_departed_mlw(&self, ttl: Duration) -> Vec<String> {
+        let now = Instant::now();
+        let mut entries = self.write_entries();
+        let mut purged = self.purged.write();
+        purged.retain(|_, purged_at| now < *purged_at + ttl);
+
+        let expired = entries.iter()
+                             .filter(|(_, entry)| {
+                                 entry.health == Health::Departed
+                                 && now >= entry.health_updated_at + ttl
+                             })
+                             .map(|(id, _)| id.clone())
+                             .collect::<Vec<_>>();
+        for id in &expired {
+            entries.remove(id);
+            purged.insert(id.clone(), now);
+        }
+        drop(purged);
+        drop(entries);
+
+        if !expired.is_empty() {
+            self.increment_update_counter();
+            self.calculate_peer_health_metrics_mlr();
         }
 
         expired
@@ -876,12 +958,14 @@ impl Serialize for MemberProxy<'_> {
     fn serialize<S>(&self, serializer: S) -> result::Result<S::Ok, S::Error>
         where S: Serializer
     {
-        let mut strukt = serializer.serialize_struct("member", 6)?;
+        let mut strukt = serializer.serialize_struct("member", 8)?;
         strukt.serialize_field("address", &self.0.address)?;
         strukt.serialize_field("gossip_port", &self.0.gossip_port)?;
         strukt.serialize_field("incarnation", &self.0.incarnation)?;
         strukt.serialize_field("persistent", &self.0.persistent)?;
         strukt.serialize_field("swim_port", &self.0.swim_port)?;
+        strukt.serialize_field("segment", &self.0.segment)?;
+        strukt.serialize_field("gateway", &self.0.gateway)?;
         strukt.serialize_field("health", &self.1)?;
         strukt.end()
     }
@@ -944,7 +1028,8 @@ mod tests {
     }
 
     mod member_list {
-        use crate::member::{Health,
+        use crate::member::{DEFAULT_SEGMENT,
+                            Health,
                             Member,
                             MemberList,
                             Membership,
@@ -974,11 +1059,38 @@ mod tests {
         #[test]
         fn check_list() {
             let ml = populated_member_list(1000);
-            let list_a = ml.check_list_mlr("foo");
-            let list_b = ml.check_list_mlr("foo");
+            let myself = Member::default();
+            let list_a = ml.check_list_mlr(&myself);
+            let list_b = ml.check_list_mlr(&myself);
             assert!(list_a != list_b);
         }
 
+        #[test]
+        fn check_list_is_scoped_to_the_segment() {
+            let ml = populated_member_list(4);
+            let remote = Member { segment: "remote".to_string(),
+                                  ..Default::default() };
+            let remote_gateway = Member { segment: "remote".to_string(),
+                                          gateway: true,
+                                          ..Default::default() };
+            ml.insert_mlw(remote.clone(), Health::Alive);
+            ml.insert_mlw(remote_gateway.clone(), Health::Alive);
+
+            let myself = Member::default();
+            let check_list = ml.check_list_mlr(&myself);
+            assert_eq!(check_list.len(), 4);
+            assert!(check_list.iter()
+                              .all(|member| member.segment == DEFAULT_SEGMENT));
+
+            let gateway = Member { gateway: true,
+                                   ..Default::default() };
+            let check_list = ml.check_list_mlr(&gateway);
+            assert_eq!(check_list.len(), 5);
+            assert!(check_list.iter()
+                              .any(|member| member.id == remote_gateway.id));
+            assert!(!check_list.iter().any(|member| member.id == remote.id));
+        }
+
         #[test]
         fn health_of() {
             let ml = populated_member_list(1);
@@ -1006,7 +1118,7 @@ mod tests {
                   let from = i.next().unwrap();
                   let target = i.nth(1).unwrap();
                   let mut counter: usize = 0;
-                  ml.with_pingreq_targets_mlr(&from.id, &target.id, |_m| counter += 1);
+                  ml.with_pingreq_targets_mlr(from, target, |_m| counter += 1);
                   assert_eq!(counter, PINGREQ_TARGETS);
               });
         }
@@ -1018,7 +1130,7 @@ mod tests {
                   let from = i.next().unwrap();
                   let target = i.nth(1).unwrap();
                   let mut excluded_appears: bool = false;
-                  ml.with_pingreq_targets_mlr(&from.id, &target.id, |m| {
+                  ml.with_pingreq_targets_mlr(from, target, |m| {
                         if m.id == from.id {
                             excluded_appears = true
                         }
@@ -1034,7 +1146,7 @@ mod tests {
                   let from = i.next().unwrap();
                   let target = i.nth(1).unwrap();
                   let mut excluded_appears: bool = false;
-                  ml.with_pingreq_targets_mlr(&from.id, &target.id, |m| {
+                  ml.with_pingreq_targets_mlr(from, target, |m| {
                         if m.id == target.id {
                             excluded_appears = true
                         }
@@ -1050,11 +1162,32 @@ mod tests {
                   let from = i.next().unwrap();
                   let target = i.nth(1).unwrap();
                   let mut counter: isize = 0;
-                  ml.with_pingreq_targets_mlr(&from.id, &target.id, |_m| counter += 1);
+                  ml.with_pingreq_targets_mlr(from, target, |_m| counter += 1);
                   assert_eq!(counter, 1);
               });
         }
 
+        #[test]
+        fn pingreq_targets_are_in_the_target_segment() {
+            let ml = populated_member_list(3);
+            let from = Member { gateway: true,
+                                ..Default::default() };
+            let target = Member { segment: "remote".to_string(),
+                                  gateway: true,
+                                  ..Default::default() };
+            let remote = Member { segment: "remote".to_string(),
+                                  ..Default::default() };
+            let remote_gateway = Member { segment: "remote".to_string(),
+                                          gateway: true,
+                                          ..Default::default() };
+            for member in [&from, &target, &remote, &remote_gateway] {
+                ml.insert_mlw(member.clone(), Health::Alive);
+            }
+            let mut targets = Vec::new();
+            ml.with_pingreq_targets_mlr(&from, &target, |m| targets.push(m.id.clone()));
+            assert_eq!(targets, vec![remote_gateway.id]);
+        }
+
         #[test]
         fn insert_no_member() {
             let ml = MemberList::new();
@@ -1592,6 +1725,33 @@ mod tests {
                            "Member 3 should still have a health of Confirmed, because it hasn't \
                             timed out yet");
             }
+
+            #[test]
+            fn purging_departed_members() {
+                let ml = MemberList::new();
+                let departed = Member::default();
+                let confirmed = Member::default();
+                let ttl = Duration::from_secs(1);
+
+                assert!(ml.insert_mlw(departed.clone(), Health::Departed));
+                assert!(ml.insert_mlw(confirmed.clone(), Health::Confirmed));
+                assert!(ml.purge_departed_mlw(ttl).is_empty(),
+                        "Nothing should be purged before the TTL is up");
+
+                // Allow the Departed to age
+                thread::sleep(ttl);
+
+                assert_eq!(ml.purge_departed_mlw(ttl), vec![departed.id.clone()]);
+                assert!(!ml.contains_member_mlr(&departed.id));
+                assert!(ml.contains_member_mlr(&confirmed.id),
+                        "Only Departed members should be purged");
+
+                assert!(!ml.insert_mlw(departed.clone(), Health::Departed),
+                        "A purged member shouldn't be taken back in when it's gossiped as \
+                         Departed");
+                assert!(ml.insert_mlw(departed.clone(), Health::Alive),
+                        "A purged member that is alive again should be taken back in");
+            }
         }
     }
 }
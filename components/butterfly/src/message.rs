@@ -1,12 +1,19 @@
 use bytes::BytesMut;
-use habitat_core::crypto::keys::RingKey;
+use habitat_core::{crypto::keys::RingKey,
+                   tls::gossip::{AuthenticatedMember,
+                                 MemberAuthenticator}};
 use prost::Message;
+use rustls::pki_types::CertificateDer;
 
 use crate::{error::{Error,
                     Result},
-            protocol::Wire};
+            protocol::Wire,
+            ring_keys::RingKeys};
 
-pub fn generate_wire(payload: Vec<u8>, ring_key: Option<&RingKey>) -> Result<Vec<u8>> {
+pub fn generate_wire(payload: Vec<u8>,
+                     ring_key: Option<&RingKey>,
+                     authenticator: Option<&MemberAuthenticator>)
+                     -> Result<Vec<u8>> {
     let mut wire = Wire::default();
     if let Some(ring_key) = ring_key {
         wire.encrypted = Some(true);
@@ -16,19 +23,43 @@ pub fn generate_wire(payload: Vec<u8>, ring_key: Option<&RingKey>) -> Result<Vec
     } else {
         wire.payload = Some(payload);
     }
+    if let Some(authenticator) = authenticator {
+        wire.signature = Some(authenticator.sign(&wire.encode_to_vec())?);
+        wire.certificates = authenticator.certificates()
+                                         .iter()
+                                         .map(|certificate| certificate.to_vec())
+                                         .collect();
+    }
     let mut buf = BytesMut::with_capacity(wire.encoded_len());
     wire.encode(&mut buf)?;
     Ok(buf.to_vec())
 }
 
-pub fn unwrap_wire(payload: &[u8], ring_key: Option<&RingKey>) -> Result<Vec<u8>> {
-    let wire = Wire::decode(payload)?;
+/// Unwrap the payload of a message, decrypting it with `ring_keys` and, if an `authenticator` is
+/// given, authenticating the member that sent it.
+pub fn unwrap_wire(payload: &[u8],
+                   ring_keys: Option<&RingKeys>,
+                   authenticator: Option<&MemberAuthenticator>)
+                   -> Result<(Vec<u8>, Option<AuthenticatedMember>)> {
+    let mut wire = Wire::decode(payload)?;
+    let sender = match authenticator {
+        Some(authenticator) => {
+            let signature = wire.signature
+                                .take()
+                                .ok_or(Error::ProtocolMismatch("missing signature"))?;
+            let certificates = std::mem::take(&mut wire.certificates).into_iter()
+                                                                     .map(CertificateDer::from)
+                                                                     .collect::<Vec<_>>();
+            Some(authenticator.authenticate(&certificates, &wire.encode_to_vec(), &signature)?)
+        }
+        None => None,
+    };
     let payload = wire.payload
                       .ok_or(Error::ProtocolMismatch("missing payload"))?;
-    if let Some(ring_key) = ring_key {
+    if let Some(ring_keys) = ring_keys {
         let nonce = wire.nonce.ok_or(Error::ProtocolMismatch("missing nonce"))?;
-        Ok(ring_key.decrypt(&nonce, &payload)?)
+        Ok((ring_keys.decrypt(&nonce, &payload)?, sender))
     } else {
-        Ok(payload)
+        Ok((payload, sender))
     }
 }
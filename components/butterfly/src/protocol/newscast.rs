@@ -24,6 +24,7 @@ impl fmt::Display for RumorType {
             RumorType::Fake2 => "fake2",
             RumorType::ElectionUpdate => "election-update",
             RumorType::Departure => "departure",
+            RumorType::Digest => "digest",
         };
 
         write!(f, "{}", value)
@@ -42,12 +43,14 @@ impl From<CDeparture> for Rumor {
 
 impl From<CElection> for Rumor {
     fn from(value: CElection) -> Self {
-        let payload = Election { member_id:     Some(value.member_id.clone()),
-                                 service_group: Some(value.service_group.to_string()),
-                                 term:          Some(value.term),
-                                 suitability:   Some(value.suitability),
-                                 status:        Some(value.status as i32),
-                                 votes:         value.votes.clone(), };
+        let payload = Election { member_id:           Some(value.member_id.clone()),
+                                 service_group:       Some(value.service_group.to_string()),
+                                 term:                Some(value.term),
+                                 suitability:         Some(value.suitability),
+                                 status:              Some(value.status as i32),
+                                 votes:               value.votes.clone(),
+                                 excluded_member_id:  value.excluded_member_id.clone(),
+                                 preferred_member_id: value.preferred_member_id.clone(), };
         Rumor { r#type:  RumorType::Election as i32,
                 tag:     Vec::default(),
                 from_id: Some(value.member_id),
@@ -57,12 +60,14 @@ impl From<CElection> for Rumor {
 
 impl From<CElectionUpdate> for Rumor {
     fn from(value: CElectionUpdate) -> Self {
-        let payload = Election { member_id:     Some(value.member_id.clone()),
-                                 service_group: Some(value.service_group.to_string()),
-                                 term:          Some(value.term),
-                                 suitability:   Some(value.suitability),
-                                 status:        Some(value.status as i32),
-                                 votes:         value.votes.clone(), };
+        let payload = Election { member_id:           Some(value.member_id.clone()),
+                                 service_group:       Some(value.service_group.to_string()),
+                                 term:                Some(value.term),
+                                 suitability:         Some(value.suitability),
+                                 status:              Some(value.status as i32),
+                                 votes:               value.votes.clone(),
+                                 excluded_member_id:  value.excluded_member_id.clone(),
+                                 preferred_member_id: value.preferred_member_id.clone(), };
         Rumor { r#type:  RumorType::ElectionUpdate as i32,
                 tag:     Vec::default(),
                 from_id: Some(value.member_id.clone()),
@@ -79,7 +84,9 @@ impl From<CService> for Rumor {
                                 pkg:             Some(value.pkg),
                                 pkg_incarnation: Some(value.pkg_incarnation),
                                 cfg:             Some(value.cfg),
-                                sys:             Some(value.sys.into()), };
+                                sys:             Some(value.sys.into()),
+                                ready:           Some(value.ready),
+                                unloaded:        Some(value.unloaded), };
         Rumor { r#type:  RumorType::Service as i32,
                 tag:     Vec::default(),
                 from_id: Some(value.member_id),
@@ -0,0 +1,165 @@
+//! The set of ring keys a Butterfly server uses to encrypt and decrypt its traffic.
+//!
+//! Rotating a ring key happens in three steps, each of which must be completed on every member
+//! of the ring before the next one is started:
+//!
+//! 1. The new key is *staged*: members decrypt traffic with it, but keep encrypting with the old
+//!    key.
+//! 1. The new key is *activated*: members encrypt with it, but still decrypt traffic encrypted with
+//!    the old key, which is sent by members that haven't activated the new key yet.
+//! 1. The old key is *retired*: members no longer decrypt traffic with it.
+//!
+//! At no point is there a pair of members that can't understand each other, so the ring never
+//! partitions.
+
+use crate::error::{Error,
+                   Result};
+use habitat_core::crypto::keys::{Key,
+                                 NamedRevision,
+                                 RingKey};
+
+/// The ring key used to encrypt traffic, along with the other revisions of it that are accepted
+/// when decrypting.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RingKeys {
+    primary:  RingKey,
+    accepted: Vec<RingKey>,
+}
+
+impl RingKeys {
+    pub fn new(primary: RingKey) -> Self {
+        RingKeys { primary,
+                   accepted: Vec::new() }
+    }
+
+    /// The key traffic is encrypted with.
+    pub fn primary(&self) -> &RingKey { &self.primary }
+
+    /// The keys, besides the primary key, that traffic may be decrypted with.
+    pub fn accepted(&self) -> &[RingKey] { &self.accepted }
+
+    /// Accept traffic encrypted with `key`. Staging a key that is already known does nothing.
+    pub fn stage(&mut self, key: RingKey) -> Result<()> {
+        if key.named_revision().name() != self.primary.named_revision().name() {
+            return Err(Error::RingKeyRotation(format!("{} is not a revision of \
+                                                       the ring key {}",
+                                                      key.named_revision(),
+                                                      self.primary
+                                                          .named_revision()
+                                                          .name())));
+        }
+        if !self.contains(key.named_revision()) {
+            self.accepted.push(key);
+        }
+        Ok(())
+    }
+
+    /// Encrypt traffic with the staged key `named_revision`. The previous primary key is still
+    /// accepted until it is retired.
+    pub fn activate(&mut self, named_revision: &NamedRevision) -> Result<()> {
+        if self.primary.named_revision() == named_revision {
+            return Ok(());
+        }
+        let index = self.accepted
+                        .iter()
+                        .position(|key| key.named_revision() == named_revision)
+                        .ok_or_else(|| {
+                            Error::RingKeyRotation(format!("{} has not been staged",
+                                                           named_revision))
+                        })?;
+        let key = self.accepted.remove(index);
+        let previous = std::mem::replace(&mut self.primary, key);
+        self.accepted.push(previous);
+        Ok(())
+    }
+
+    /// Stop accepting traffic encrypted with `named_revision`. The primary key can't be retired.
+    pub fn retire(&mut self, named_revision: &NamedRevision) -> Result<()> {
+        if self.primary.named_revision() == named_revision {
+            return Err(Error::RingKeyRotation(format!("{} is the primary ring \
+                                                       key and can't be retired",
+                                                      named_revision)));
+        }
+        self.accepted
+            .retain(|key| key.named_revision() != named_revision);
+        Ok(())
+    }
+
+    /// Encrypt `data` with the primary key, returning the nonce and the ciphertext.
+    pub fn encrypt(&self, data: &[u8]) -> (Vec<u8>, Vec<u8>) { self.primary.encrypt(data) }
+
+    /// Decrypt `ciphertext` with the first key, primary or accepted, that is able to.
+    pub fn decrypt(&self, nonce: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        self.accepted
+            .iter()
+            .fold(self.primary.decrypt(nonce, ciphertext), |result, key| {
+                result.or_else(|_| key.decrypt(nonce, ciphertext))
+            })
+            .map_err(Error::from)
+    }
+
+    fn contains(&self, named_revision: &NamedRevision) -> bool {
+        self.primary.named_revision() == named_revision
+        || self.accepted
+               .iter()
+               .any(|key| key.named_revision() == named_revision)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{thread,
+              time::Duration};
+
+    /// Ring key revisions are timestamped to the second, so wait between generating them.
+    fn new_revisions(name: &str, count: usize) -> Vec<RingKey> {
+        (0..count).map(|i| {
+                      if i > 0 {
+                          thread::sleep(Duration::from_secs(1));
+                      }
+                      RingKey::new(name)
+                  })
+                  .collect()
+    }
+
+    #[test]
+    fn decrypts_with_primary_and_accepted_keys() {
+        let keys = new_revisions("ring", 2);
+        let mut ring_keys = RingKeys::new(keys[0].clone());
+        let (nonce, ciphertext) = keys[1].encrypt(b"hello");
+        assert!(ring_keys.decrypt(&nonce, &ciphertext).is_err());
+
+        ring_keys.stage(keys[1].clone()).unwrap();
+        assert_eq!(ring_keys.decrypt(&nonce, &ciphertext).unwrap(), b"hello");
+
+        let (nonce, ciphertext) = ring_keys.encrypt(b"hello");
+        assert_eq!(keys[0].decrypt(&nonce, &ciphertext).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn rotation() {
+        let keys = new_revisions("ring", 2);
+        let mut ring_keys = RingKeys::new(keys[0].clone());
+
+        assert!(ring_keys.activate(keys[1].named_revision()).is_err());
+        ring_keys.stage(keys[1].clone()).unwrap();
+        ring_keys.stage(keys[1].clone()).unwrap();
+        assert_eq!(ring_keys.accepted(), &keys[1..]);
+
+        ring_keys.activate(keys[1].named_revision()).unwrap();
+        assert_eq!(ring_keys.primary(), &keys[1]);
+        assert_eq!(ring_keys.accepted(), &keys[..1]);
+
+        assert!(ring_keys.retire(keys[1].named_revision()).is_err());
+        ring_keys.retire(keys[0].named_revision()).unwrap();
+        assert_eq!(ring_keys.primary(), &keys[1]);
+        assert!(ring_keys.accepted().is_empty());
+    }
+
+    #[test]
+    fn only_revisions_of_the_same_ring_can_be_staged() {
+        let mut ring_keys = RingKeys::new(RingKey::new("ring"));
+        assert!(ring_keys.stage(RingKey::new("other-ring")).is_err());
+    }
+}
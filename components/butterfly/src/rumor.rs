@@ -8,6 +8,7 @@
 
 pub mod dat_file;
 pub mod departure;
+pub mod digest;
 pub mod election;
 pub mod heat;
 pub mod service;
@@ -33,9 +34,12 @@ use std::{collections::{HashMap,
           result,
           sync::{Arc,
                  atomic::{AtomicUsize,
-                          Ordering}}};
+                          Ordering}},
+          time::{Duration,
+                 Instant}};
 
 pub use self::{departure::Departure,
+               digest::Digest,
                election::{Election,
                           ElectionUpdate},
                service::Service,
@@ -58,6 +62,7 @@ lazy_static! {
 #[derive(Debug, Clone, Serialize)]
 pub enum RumorKind {
     Departure(Departure),
+    Digest(Digest),
     Election(Election),
     ElectionUpdate(ElectionUpdate),
     Membership(Membership),
@@ -70,6 +75,7 @@ impl From<RumorKind> for RumorPayload {
     fn from(value: RumorKind) -> Self {
         match value {
             RumorKind::Departure(departure) => RumorPayload::Departure(departure.into()),
+            RumorKind::Digest(digest) => RumorPayload::Digest(digest.into()),
             RumorKind::Election(election) => RumorPayload::Election(election.into()),
             RumorKind::ElectionUpdate(election) => RumorPayload::Election(election.into()),
             RumorKind::Membership(membership) => RumorPayload::Member(membership.into()),
@@ -125,6 +131,10 @@ pub trait Rumor: Message<ProtoRumor> + Sized {
     fn key(&self) -> &str;
     fn id(&self) -> &str;
     fn merge(&mut self, other: Self) -> bool;
+
+    /// The incarnation of rumors that are versioned by one, which is kept in the tombstone of
+    /// the rumor when it is purged. See `RumorStore::purged_incarnation_rsr`.
+    fn incarnation(&self) -> Option<u64> { None }
 }
 
 pub trait ConstKeyRumor: Rumor {
@@ -147,7 +157,8 @@ type RumorMap<T> = HashMap<RumorKeyKey, RumorSubMap<T>>;
 mod storage {
     use super::*;
     use habitat_common::sync::{Lock,
-                               ReadGuard};
+                               ReadGuard,
+                               WriteGuard};
     use serde::{Serialize,
                 Serializer,
                 ser::{SerializeMap,
@@ -275,6 +286,15 @@ mod storage {
         fn deref(&self) -> &Self::Target { &self.0 }
     }
 
+    /// What remains of a rumor after it is purged: enough to recognize the same rumor if a member
+    /// that hasn't purged it yet gossips it back to us.
+    #[derive(Debug, Clone)]
+    struct Tombstone {
+        hash:        Vec<u8>,
+        incarnation: Option<u64>,
+        purged_at:   Instant,
+    }
+
     /// Storage for Rumors. It takes a rumor and stores it according to the member that produced it,
     /// and the service group it is related to.
     ///
@@ -282,9 +302,21 @@ mod storage {
     #[derive(Debug, Clone)]
     pub struct RumorStore<T> {
         list:           Arc<Lock<RumorMap<T>>>,
+        tombstones:     Arc<Lock<HashMap<(RumorKeyKey, RumorKeyId), Tombstone>>>,
         update_counter: Arc<AtomicUsize>,
     }
 
+    /// Remove the rumor for `key` and `id` from `list`, along with its service group if that
+    /// leaves it empty.
+    fn remove_from<T>(list: &mut WriteGuard<'_, RumorMap<T>>, key: &str, id: &str) -> Option<T> {
+        let rumors = list.get_mut(key)?;
+        let removed = rumors.remove(id);
+        if rumors.is_empty() {
+            list.remove(key);
+        }
+        removed
+    }
+
     impl<T> RumorStore<T> {
         pub fn get_update_counter(&self) -> usize { self.update_counter.load(Ordering::Relaxed) }
 
@@ -306,8 +338,32 @@ mod storage {
         /// # Locking (see locking.md)
         /// * `RumorStore::list` (write)
         pub fn remove_rsw(&self, key: &str, id: &str) {
-            let mut list = self.list.write();
-            list.get_mut(key).and_then(|r| r.remove(id));
+            if remove_from(&mut self.list.write(), key, id).is_some() {
+                self.increment_update_counter();
+            }
+        }
+
+        /// The incarnation of the rumor for `key` and `id` if it was purged, so that the member
+        /// which started it can start the next one from a higher incarnation. Members that
+        /// haven't purged it yet would otherwise ignore the new rumor.
+        ///
+        /// # Locking (see locking.md)
+        /// * `RumorStore::tombstones` (read)
+        pub fn purged_incarnation_rsr(&self, key: &str, id: &str) -> Option<u64> {
+            self.tombstones
+                .read()
+                .get(&(String::from(key), String::from(id)))
+                .and_then(|tombstone| tombstone.incarnation)
+        }
+
+        /// Forget the tombstones of rumors that were purged longer than `ttl` ago.
+        ///
+        /// # Locking (see locking.md)
+        /// * `RumorStore::tombstones` (write)
+        pub fn expire_tombstones_rsw(&self, ttl: Duration) {
+            self.tombstones
+                .write()
+                .retain(|_, tombstone| tombstone.purged_at.elapsed() < ttl);
         }
     }
 
@@ -319,9 +375,14 @@ mod storage {
         /// * `RumorStore::list` (write)
         pub fn insert_rsw(&self, rumor: R) -> bool {
             let mut list = self.list.write();
-            let rumors = list.entry(String::from(rumor.key())).or_default();
             let kind_ignored_count =
                 IGNORED_RUMOR_COUNT.with_label_values(&[&rumor.kind().to_string()]);
+            if self.is_tombstoned_rsw(&rumor) {
+                // This is a rumor we purged, gossiped back to us by a member that still has it.
+                kind_ignored_count.inc();
+                return false;
+            }
+            let rumors = list.entry(String::from(rumor.key())).or_default();
             // Result reveals if there was a change so we can increment the counter if needed.
             let result = match rumors.entry(rumor.id().into()) {
                 Entry::Occupied(mut entry) => entry.get_mut().merge(rumor),
@@ -339,11 +400,53 @@ mod storage {
             }
             result
         }
+
+        /// Remove a rumor from the store, leaving a tombstone so that the same rumor is ignored
+        /// if it is gossiped back to us. Returns true if the rumor was present.
+        ///
+        /// # Locking (see locking.md)
+        /// * `RumorStore::list` (write)
+        /// * `RumorStore::tombstones` (write)
+        pub fn purge_rsw(&self, key: &str, id: &str) -> bool {
+            let mut list = self.list.write();
+            match remove_from(&mut list, key, id) {
+                Some(rumor) => {
+                    let tombstone = Tombstone { hash:        digest::rumor_hash(&rumor),
+                                                incarnation: rumor.incarnation(),
+                                                purged_at:   Instant::now(), };
+                    self.tombstones
+                        .write()
+                        .insert((String::from(key), String::from(id)), tombstone);
+                    self.increment_update_counter();
+                    true
+                }
+                None => false,
+            }
+        }
+
+        /// Whether `rumor` is exactly the one we left a tombstone for. A different version of
+        /// the rumor clears the tombstone instead.
+        ///
+        /// # Locking (see locking.md)
+        /// * `RumorStore::tombstones` (write)
+        fn is_tombstoned_rsw(&self, rumor: &R) -> bool {
+            let mut tombstones = self.tombstones.write();
+            let key = (String::from(rumor.key()), String::from(rumor.id()));
+            match tombstones.get(&key) {
+                Some(tombstone) if tombstone.hash == digest::rumor_hash(rumor) => true,
+                Some(_) => {
+                    tombstones.remove(&key);
+                    false
+                }
+                None => false,
+            }
+        }
     }
 
     impl<T> Default for RumorStore<T> {
         fn default() -> RumorStore<T> {
             RumorStore { list:           Arc::default(),
+                         tombstones:     Arc::default(),
                          update_counter: Arc::default(), }
         }
     }
@@ -490,6 +593,7 @@ impl RumorEnvelope {
                            .ok_or(Error::ProtocolMismatch("from-id"))?;
         let kind = match r#type {
             RumorType::Departure => RumorKind::Departure(Departure::from_proto(proto)?),
+            RumorType::Digest => RumorKind::Digest(Digest::from_proto(proto)?),
             RumorType::Election => RumorKind::Election(Election::from_proto(proto)?),
             RumorType::ElectionUpdate => {
                 RumorKind::ElectionUpdate(ElectionUpdate::from_proto(proto)?)
@@ -626,6 +730,7 @@ mod tests {
         use crate::{error::Error,
                     rumor::{Rumor,
                             RumorStore}};
+        use std::time::Duration;
 
         #[test]
         fn insert_adds_rumor_when_empty() {
@@ -705,6 +810,39 @@ mod tests {
             assert!(!rs.insert_rsw(f2));
         }
 
+        #[test]
+        fn remove_drops_empty_service_groups() {
+            let rs = RumorStore::default();
+            let f1 = FakeRumor::default();
+            let key = f1.key.clone();
+            let member_id = f1.id.clone();
+            rs.insert_rsw(f1);
+
+            rs.remove_rsw(&key, &member_id);
+            assert!(rs.lock_rsr().is_empty());
+            assert_eq!(rs.get_update_counter(), 2);
+        }
+
+        #[test]
+        fn purged_rumors_are_ignored_until_their_tombstone_expires() {
+            let rs = RumorStore::default();
+            let f1 = FakeRumor::default();
+            let key = f1.key.clone();
+            let member_id = f1.id.clone();
+            assert!(rs.insert_rsw(f1.clone()));
+
+            assert!(rs.purge_rsw(&key, &member_id));
+            assert!(!rs.purge_rsw(&key, &member_id));
+            assert!(rs.lock_rsr().is_empty());
+            assert!(!rs.insert_rsw(f1.clone()),
+                    "A purged rumor shouldn't be inserted again");
+            assert!(rs.insert_rsw(FakeRumor::default()),
+                    "Other rumors should still be inserted");
+
+            rs.expire_tombstones_rsw(Duration::from_secs(0));
+            assert!(rs.insert_rsw(f1));
+        }
+
         #[test]
         fn map_rumor_calls_closure_with_rumor() {
             let rs = RumorStore::default();
@@ -520,6 +520,7 @@ impl Header {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rumor::ConstKeyRumor;
     use std::fs;
     use tempfile::tempdir;
 
@@ -573,4 +574,36 @@ mod tests {
         assert_eq!(content.header.version, 2);
         assert_eq!(content.header.size, 64);
     }
+
+    #[test]
+    fn rewriting_leaves_out_purged_rumors() {
+        let dir = tempdir().expect("temp dir created");
+        let file_path = dir.path().join("test-datfile");
+        let departure_store = RumorStore::default();
+        departure_store.insert_rsw(Departure::new("member-1"));
+        departure_store.insert_rsw(Departure::new("member-2"));
+
+        let writer = DatFileWriter::new(file_path.clone());
+        let write = || {
+            writer.write_rsr_mlr(&MemberList::new(),
+                                 &RumorStore::default(),
+                                 &RumorStore::default(),
+                                 &RumorStore::default(),
+                                 &RumorStore::default(),
+                                 &RumorStore::default(),
+                                 &departure_store)
+                  .expect("dat file written")
+        };
+        write();
+        let length_before = fs::metadata(&file_path).unwrap().len();
+
+        departure_store.purge_rsw(Departure::const_key(), "member-1");
+        write();
+        assert!(fs::metadata(&file_path).unwrap().len() < length_before);
+
+        let departures = DatFileReader::read(file_path).unwrap()
+                                                       .read_rumors::<Departure>()
+                                                       .unwrap();
+        assert_eq!(departures, vec![Departure::new("member-2")]);
+    }
 }
@@ -0,0 +1,311 @@
+//! Digests of the rumor stores, for anti-entropy.
+//!
+//! Rumors are shared epidemically until they cool off, so a member that was unreachable while a
+//! rumor was hot can miss it entirely. To catch up, members periodically exchange digests with a
+//! random peer:
+//!
+//! 1. A member sends a peer the *summary* of each of its rumor stores: a single hash of the store's
+//!    contents.
+//! 1. For the stores whose summary differs from its own, the peer replies with a *detailed* digest,
+//!    holding the hash of every rumor in those stores.
+//! 1. The member sends the peer the rumors it has that the peer lacks or that differ from the
+//!    peer's, and replies with its own detailed digest of the same stores.
+//! 1. The peer does the same, sending the member the rumors the member lacks or that differ.
+//!
+//! Each side merges the rumors it receives as usual, so once the exchange is done both members
+//! hold the latest version of every rumor either of them knew about.
+
+use crate::{error::{Error,
+                    Result},
+            protocol::{FromProto,
+                       newscast::{self,
+                                  Rumor as ProtoRumor,
+                                  digest::{Entry as ProtoEntry,
+                                           Summary as ProtoSummary}}},
+            rumor::{Rumor,
+                    RumorKey,
+                    RumorPayload,
+                    RumorStore,
+                    RumorType}};
+use habitat_core::crypto::Blake2bHash;
+use prost::Message as _;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// The hash of every rumor in one rumor store.
+#[derive(Debug, Clone)]
+pub struct StoreDigest {
+    kind:   RumorType,
+    hashes: HashMap<RumorKey, Vec<u8>>,
+}
+
+impl StoreDigest {
+    /// Hash the rumors in `store`, whose rumors are of type `kind`.
+    ///
+    /// # Locking (see locking.md)
+    /// * `RumorStore::list` (read)
+    pub fn of_rsr<R: Rumor>(kind: RumorType, store: &RumorStore<R>) -> Self {
+        let hashes = store.lock_rsr()
+                          .rumors()
+                          .map(|rumor| (RumorKey::from(rumor), rumor_hash(rumor)))
+                          .collect();
+        StoreDigest { kind, hashes }
+    }
+
+    pub fn kind(&self) -> RumorType { self.kind }
+
+    /// A single hash of the whole store.
+    pub fn summary(&self) -> Vec<u8> {
+        let mut entries = self.hashes.iter().collect::<Vec<_>>();
+        entries.sort_by(|(a, _), (b, _)| (&a.key, &a.id).cmp(&(&b.key, &b.id)));
+        let mut bytes = Vec::new();
+        for (rumor_key, hash) in entries {
+            bytes.extend_from_slice(rumor_key.key.as_bytes());
+            bytes.push(0);
+            bytes.extend_from_slice(rumor_key.id.as_bytes());
+            bytes.push(0);
+            bytes.extend_from_slice(hash);
+        }
+        Blake2bHash::from_bytes(bytes).as_ref().to_vec()
+    }
+
+    /// The rumors in this store that are missing from `entries`, or whose hash differs from the
+    /// one in `entries`.
+    pub fn differing_from(&self, entries: &[DigestEntry]) -> Vec<RumorKey> {
+        let theirs =
+            entries.iter()
+                   .filter(|entry| entry.kind == self.kind)
+                   .map(|entry| (RumorKey::new(entry.kind, &entry.id, &entry.key), &entry.hash))
+                   .collect::<HashMap<_, _>>();
+        self.hashes
+            .iter()
+            .filter(|(rumor_key, hash)| theirs.get(*rumor_key) != Some(hash))
+            .map(|(rumor_key, _)| rumor_key.clone())
+            .collect()
+    }
+}
+
+/// The hash of `rumor`, leaving out the ID of the member it was received from, which can differ
+/// between members that hold the same rumor.
+pub fn rumor_hash<R: Rumor>(rumor: &R) -> Vec<u8> {
+    let mut proto: ProtoRumor = rumor.clone().into();
+    proto.from_id = None;
+    Blake2bHash::from_bytes(proto.encode_to_vec()).as_ref()
+                                                  .to_vec()
+}
+
+/// The hash of a single rumor.
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestEntry {
+    pub kind: RumorType,
+    pub key:  String,
+    pub id:   String,
+    pub hash: Vec<u8>,
+}
+
+/// A digest of some of a member's rumor stores.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Digest {
+    /// The summary of each store in the digest, by rumor type.
+    pub summaries: Vec<(RumorType, Vec<u8>)>,
+    /// The hash of every rumor in the stores in `summaries`, if the digest is detailed.
+    pub entries:   Option<Vec<DigestEntry>>,
+    /// Whether the receiver should reply with a detailed digest of its own.
+    pub reply:     bool,
+}
+
+impl Digest {
+    /// A digest holding only the summaries of `stores`.
+    pub fn summary(stores: &[StoreDigest]) -> Self {
+        Digest { summaries: summaries(stores),
+                 entries:   None,
+                 reply:     false, }
+    }
+
+    /// A digest holding the hash of every rumor in `stores`.
+    pub fn detailed(stores: &[StoreDigest], reply: bool) -> Self {
+        let entries = stores.iter()
+                            .flat_map(|store| {
+                                store.hashes.iter().map(|(rumor_key, hash)| {
+                                                       DigestEntry { kind: store.kind,
+                                                                     key:  rumor_key.key.clone(),
+                                                                     id:   rumor_key.id.clone(),
+                                                                     hash: hash.clone(), }
+                                                   })
+                            })
+                            .collect();
+        Digest { summaries: summaries(stores),
+                 entries: Some(entries),
+                 reply }
+    }
+
+    /// The summary of the store of `kind` rumors, if the digest has one.
+    pub fn summary_of(&self, kind: RumorType) -> Option<&[u8]> {
+        self.summaries
+            .iter()
+            .find(|(summary_kind, _)| *summary_kind == kind)
+            .map(|(_, summary)| summary.as_slice())
+    }
+}
+
+fn summaries(stores: &[StoreDigest]) -> Vec<(RumorType, Vec<u8>)> {
+    stores.iter()
+          .map(|store| (store.kind, store.summary()))
+          .collect()
+}
+
+impl FromProto<ProtoRumor> for Digest {
+    fn from_proto(rumor: ProtoRumor) -> Result<Self> {
+        let payload = match rumor.payload.ok_or(Error::ProtocolMismatch("payload"))? {
+            RumorPayload::Digest(payload) => payload,
+            _ => panic!("from-bytes digest"),
+        };
+        let summaries = payload.summaries
+                               .into_iter()
+                               .map(|summary| {
+                                   Ok((rumor_type(summary.r#type)?,
+                                       summary.hash.ok_or(Error::ProtocolMismatch("hash"))?))
+                               })
+                               .collect::<Result<_>>()?;
+        let entries = if payload.detailed.unwrap_or(false) {
+            let entries = payload.entries
+                                 .into_iter()
+                                 .map(|entry| {
+                                     Ok(DigestEntry { kind: rumor_type(entry.r#type)?,
+                                            key:  entry.key
+                                                       .ok_or(Error::ProtocolMismatch("key"))?,
+                                            id:   entry.id.ok_or(Error::ProtocolMismatch("id"))?,
+                                            hash: entry.hash
+                                                       .ok_or(Error::ProtocolMismatch("hash"))?, })
+                                 })
+                                 .collect::<Result<_>>()?;
+            Some(entries)
+        } else {
+            None
+        };
+        Ok(Digest { summaries,
+                    entries,
+                    reply: payload.reply.unwrap_or(false) })
+    }
+}
+
+fn rumor_type(value: Option<i32>) -> Result<RumorType> {
+    value.and_then(|value| RumorType::try_from(value).ok())
+         .ok_or(Error::ProtocolMismatch("type"))
+}
+
+impl From<Digest> for newscast::Digest {
+    fn from(value: Digest) -> Self {
+        let summaries = value.summaries
+                             .into_iter()
+                             .map(|(kind, hash)| {
+                                 ProtoSummary { r#type: Some(kind as i32),
+                                                hash:   Some(hash), }
+                             })
+                             .collect();
+        let detailed = value.entries.is_some();
+        let entries = value.entries
+                           .unwrap_or_default()
+                           .into_iter()
+                           .map(|entry| {
+                               ProtoEntry { r#type: Some(entry.kind as i32),
+                                            key:    Some(entry.key),
+                                            id:     Some(entry.id),
+                                            hash:   Some(entry.hash), }
+                           })
+                           .collect();
+        newscast::Digest { summaries,
+                           detailed: Some(detailed),
+                           entries,
+                           reply: Some(value.reply) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rumor::{RumorEnvelope,
+                       RumorKind,
+                       ServiceConfig};
+    use habitat_core::service::ServiceGroup;
+    use std::str::FromStr;
+
+    fn service_config(service_group: &str, incarnation: u64) -> ServiceConfig {
+        let mut service_config = ServiceConfig::new("member",
+                                                    ServiceGroup::from_str(service_group).unwrap(),
+                                                    Vec::from("config"));
+        service_config.incarnation = incarnation;
+        service_config
+    }
+
+    fn store_of(service_configs: Vec<ServiceConfig>) -> RumorStore<ServiceConfig> {
+        let store = RumorStore::default();
+        for service_config in service_configs {
+            store.insert_rsw(service_config);
+        }
+        store
+    }
+
+    #[test]
+    fn stores_with_the_same_rumors_have_the_same_summary() {
+        let ours = store_of(vec![service_config("redis.prod", 1),
+                                 service_config("nginx.prod", 1)]);
+        let theirs = store_of(vec![service_config("nginx.prod", 1),
+                                   service_config("redis.prod", 1)]);
+        let ours = StoreDigest::of_rsr(RumorType::ServiceConfig, &ours);
+        let theirs = StoreDigest::of_rsr(RumorType::ServiceConfig, &theirs);
+        assert_eq!(ours.summary(), theirs.summary());
+        assert!(ours.differing_from(Digest::detailed(&[theirs], false).entries.as_ref().unwrap())
+                    .is_empty());
+    }
+
+    #[test]
+    fn rumors_that_are_missing_or_differ_are_found() {
+        let ours = store_of(vec![service_config("redis.prod", 2),
+                                 service_config("nginx.prod", 1),
+                                 service_config("haproxy.prod", 1)]);
+        let theirs = store_of(vec![service_config("redis.prod", 1),
+                                   service_config("nginx.prod", 1)]);
+        let ours = StoreDigest::of_rsr(RumorType::ServiceConfig, &ours);
+        let theirs = StoreDigest::of_rsr(RumorType::ServiceConfig, &theirs);
+        assert_ne!(ours.summary(), theirs.summary());
+
+        let theirs = Digest::detailed(&[theirs], false);
+        let mut differing = ours.differing_from(theirs.entries.as_ref().unwrap())
+                                .into_iter()
+                                .map(|rumor_key| rumor_key.key)
+                                .collect::<Vec<_>>();
+        differing.sort();
+        assert_eq!(differing, vec!["haproxy.prod", "redis.prod"]);
+    }
+
+    #[test]
+    fn encode_decode_roundtrip() {
+        fn roundtrip(digest: Digest) -> Digest {
+            let envelope = RumorEnvelope { r#type:  RumorType::Digest,
+                                           from_id: String::from("member"),
+                                           kind:    RumorKind::Digest(digest), };
+            match RumorEnvelope::decode(&envelope.encode().unwrap()).unwrap()
+                                                                    .kind
+            {
+                RumorKind::Digest(digest) => digest,
+                kind => panic!("Decoded a digest as {:?}", kind),
+            }
+        }
+
+        let store = StoreDigest::of_rsr(RumorType::ServiceConfig,
+                                        &store_of(vec![service_config("redis.prod", 1)]));
+        let digest = Digest::detailed(&[store], true);
+        let decoded = roundtrip(digest.clone());
+        assert_eq!(decoded.summaries, digest.summaries);
+        assert!(decoded.reply);
+        let entries = decoded.entries.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].key, "redis.prod");
+
+        let decoded = roundtrip(Digest::summary(&[]));
+        assert!(decoded.summaries.is_empty());
+        assert!(decoded.entries.is_none());
+        assert!(!decoded.reply);
+    }
+}
@@ -40,12 +40,16 @@ pub type Term = u64;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct Election {
-    pub member_id:     String,
-    pub service_group: String,
-    pub term:          u64,
-    pub suitability:   u64,
-    pub status:        ElectionStatus,
-    pub votes:         Vec<String>,
+    pub member_id:           String,
+    pub service_group:       String,
+    pub term:                u64,
+    pub suitability:         u64,
+    pub status:              ElectionStatus,
+    pub votes:               Vec<String>,
+    /// The member that stepped down as leader; it can't win this term.
+    pub excluded_member_id:  Option<String>,
+    /// The member that leadership is being transferred to; it wins this term if it is alive.
+    pub preferred_member_id: Option<String>,
 }
 
 impl fmt::Display for Election {
@@ -77,7 +81,23 @@ impl Election {
                    } else {
                        ElectionStatus::NoQuorum
                    },
-                   votes: vec![from_id] }
+                   votes: vec![from_id],
+                   excluded_member_id: None,
+                   preferred_member_id: None }
+    }
+
+    /// Whether this term was started by hand to move leadership away from, or to, a member.
+    pub fn is_handoff(&self) -> bool {
+        self.excluded_member_id.is_some() || self.preferred_member_id.is_some()
+    }
+
+    /// How strongly the member this election votes for should win: a member that stepped down
+    /// never beats one that didn't, the member leadership is being transferred to beats everyone
+    /// else, and suitability decides the rest.
+    fn rank(&self) -> (bool, bool, u64) {
+        (self.excluded_member_id.as_deref() != Some(self.member_id.as_str()),
+         self.preferred_member_id.as_deref() == Some(self.member_id.as_str()),
+         self.suitability)
     }
 
     /// Insert a vote for the election.
@@ -121,6 +141,8 @@ impl PartialEq for Election {
         && self.votes == other.votes
         && self.status == other.status
         && self.term == other.term
+        && self.excluded_member_id == other.excluded_member_id
+        && self.preferred_member_id == other.preferred_member_id
     }
 }
 
@@ -135,26 +157,30 @@ impl FromProto<ProtoRumor> for Election {
             _ => panic!("from-bytes election"),
         };
         let from_id = rumor.from_id.ok_or(Error::ProtocolMismatch("from-id"))?;
-        Ok(Election { member_id:     from_id,
-                      service_group: payload.service_group
-                                            .ok_or(Error::ProtocolMismatch("service-group"))?,
-                      term:          payload.term.unwrap_or(0),
-                      suitability:   payload.suitability.unwrap_or(0),
-                      status:        payload.status
-                                            .and_then(|es| ElectionStatus::try_from(es).ok())
-                                            .unwrap_or(ElectionStatus::Running),
-                      votes:         payload.votes, })
+        Ok(Election { member_id:           from_id,
+                      service_group:       payload.service_group
+                                                  .ok_or(Error::ProtocolMismatch("service-group"))?,
+                      term:                payload.term.unwrap_or(0),
+                      suitability:         payload.suitability.unwrap_or(0),
+                      status:              payload.status
+                                                  .and_then(|es| ElectionStatus::try_from(es).ok())
+                                                  .unwrap_or(ElectionStatus::Running),
+                      votes:               payload.votes,
+                      excluded_member_id:  payload.excluded_member_id,
+                      preferred_member_id: payload.preferred_member_id, })
     }
 }
 
 impl From<Election> for newscast::Election {
     fn from(value: Election) -> Self {
-        newscast::Election { member_id:     Some(value.member_id),
-                             service_group: Some(value.service_group.to_string()),
-                             term:          Some(value.term),
-                             suitability:   Some(value.suitability),
-                             status:        Some(value.status as i32),
-                             votes:         value.votes, }
+        newscast::Election { member_id:           Some(value.member_id),
+                             service_group:       Some(value.service_group.to_string()),
+                             term:                Some(value.term),
+                             suitability:         Some(value.suitability),
+                             status:              Some(value.status as i32),
+                             votes:               value.votes,
+                             excluded_member_id:  value.excluded_member_id,
+                             preferred_member_id: value.preferred_member_id, }
     }
 }
 
@@ -184,11 +210,11 @@ impl Rumor for Election {
         } else if self.term > other.term {
             debug!("stored rumor represents a newer term than received; keep sharing it");
             true
-        } else if self.suitability > other.suitability {
+        } else if self.rank() > other.rank() {
             debug!("stored rumor is more suitable; take received rumor's votes and share");
             self.steal_votes(&mut other);
             true
-        } else if other.suitability > self.suitability {
+        } else if other.rank() > self.rank() {
             debug!("received rumor is more suitable; take stored rumor's votes, replace stored \
                     and share");
             other.steal_votes(self);
@@ -391,4 +417,36 @@ mod tests {
         assert_eq!(e1.member_id, "d");
         assert_eq!(e1.votes.len(), 4);
     }
+
+    #[test]
+    fn merge_four_with_leader_stepping_down() {
+        let mut elections = ["a", "b", "c", "d"].map(|member_id| {
+                                                    let mut e = create_election(member_id, 0);
+                                                    e.excluded_member_id = Some("d".to_string());
+                                                    e
+                                                });
+        elections[3].suitability = u64::MAX;
+        let [mut e1, e2, e3, e4] = elections;
+        assert!(e1.merge(e2));
+        assert!(e1.merge(e3));
+        assert!(e1.merge(e4));
+        assert_eq!(e1.member_id, "c");
+        assert_eq!(e1.votes.len(), 4);
+    }
+
+    #[test]
+    fn merge_four_with_leadership_transferred() {
+        let mut elections = ["a", "b", "c", "d"].map(|member_id| {
+                                                    let mut e = create_election(member_id, 1);
+                                                    e.preferred_member_id = Some("a".to_string());
+                                                    e
+                                                });
+        elections[0].suitability = 0;
+        let [mut e1, e2, e3, e4] = elections;
+        assert!(e1.merge(e2));
+        assert!(e1.merge(e3));
+        assert!(e1.merge(e4));
+        assert_eq!(e1.member_id, "a");
+        assert_eq!(e1.votes.len(), 4);
+    }
 }
@@ -167,6 +167,13 @@ pub(crate) mod sync {
             }
             debug!("Purged {} heat count entries for {:?}", count, id);
         }
+
+        /// Stop sharing a rumor we no longer have, forgetting how
+        /// hot it is for every member.
+        ///
+        /// # Locking (see locking.md)
+        /// * `RumorHeat::inner` (write)
+        pub fn forget(&mut self, rumor: &RumorKey) { self.0.remove(rumor); }
     }
 
     /// Tracks the number of times a given rumor has been sent to each
@@ -32,6 +32,10 @@ pub struct Service {
     pub pkg_incarnation: u64,
     pub cfg:             Vec<u8>,
     pub sys:             SysInfo,
+    /// Whether the service passes its readiness check
+    pub ready:           bool,
+    /// Whether the member unloaded the service
+    pub unloaded:        bool,
 }
 
 impl fmt::Display for Service {
@@ -47,7 +51,7 @@ impl Serialize for Service {
     fn serialize<S>(&self, serializer: S) -> result::Result<S::Ok, S::Error>
         where S: Serializer
     {
-        let mut strukt = serializer.serialize_struct("service", 7)?;
+        let mut strukt = serializer.serialize_struct("service", 10)?;
         let cfg: toml::value::Table =
             toml::from_str(str::from_utf8(&self.cfg).unwrap_or_default()).unwrap_or_default();
         strukt.serialize_field("member_id", &self.member_id)?;
@@ -58,6 +62,8 @@ impl Serialize for Service {
         strukt.serialize_field("cfg", &cfg)?;
         strukt.serialize_field("sys", &self.sys)?;
         strukt.serialize_field("initialized", &self.initialized)?;
+        strukt.serialize_field("ready", &self.ready)?;
+        strukt.serialize_field("unloaded", &self.unloaded)?;
         strukt.end()
     }
 }
@@ -104,6 +110,8 @@ impl Service {
                   pkg: package.to_string(),
                   pkg_incarnation: 0,
                   sys,
+                  ready: true,
+                  unloaded: false,
                   cfg: cfg.map(|v| {
                               // Directly serializing a toml::value::Table can lead to an error
                               // Wrapping it in a toml::value::Value makes this operation safe
@@ -138,7 +146,10 @@ impl FromProto<newscast::Rumor> for Service {
                      cfg:             payload.cfg.unwrap_or_default(),
                      sys:             payload.sys
                                              .ok_or(Error::ProtocolMismatch("sys"))
-                                             .and_then(SysInfo::from_proto)?, })
+                                             .and_then(SysInfo::from_proto)?,
+                     // Supervisors which predate readiness checks are always ready
+                     ready:           payload.ready.unwrap_or(true),
+                     unloaded:        payload.unloaded.unwrap_or(false), })
     }
 }
 
@@ -151,7 +162,9 @@ impl From<Service> for newscast::Service {
                             pkg:             Some(value.pkg),
                             pkg_incarnation: Some(value.pkg_incarnation),
                             cfg:             Some(value.cfg),
-                            sys:             Some(value.sys.into()), }
+                            sys:             Some(value.sys.into()),
+                            ready:           Some(value.ready),
+                            unloaded:        Some(value.unloaded), }
     }
 }
 
@@ -172,6 +185,8 @@ impl Rumor for Service {
     fn id(&self) -> &str { &self.member_id }
 
     fn key(&self) -> &str { self.service_group.as_ref() }
+
+    fn incarnation(&self) -> Option<u64> { Some(self.incarnation) }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -236,8 +251,11 @@ mod tests {
                        service::ServiceGroup};
 
     use super::Service;
-    use crate::rumor::{Rumor,
-                       service::SysInfo};
+    use crate::{protocol::{FromProto,
+                           newscast},
+                rumor::{Rumor,
+                        RumorPayload,
+                        service::SysInfo}};
 
     fn create_service(member_id: &str) -> Service {
         let pkg = PackageIdent::from_str("core/neurosis/1.2.3/20161208121212").unwrap();
@@ -320,6 +338,34 @@ mod tests {
         assert_eq!(s1, s1_check);
     }
 
+    #[test]
+    fn services_are_ready_unless_gossiped_otherwise() {
+        let mut service = create_service("adam");
+        service.ready = false;
+        let mut rumor = newscast::Rumor::from(service);
+        assert!(!Service::from_proto(rumor.clone()).unwrap().ready);
+
+        // As gossiped by a Supervisor which predates readiness checks
+        if let Some(RumorPayload::Service(payload)) = rumor.payload.as_mut() {
+            payload.ready = None;
+        }
+        assert!(Service::from_proto(rumor).unwrap().ready);
+    }
+
+    #[test]
+    fn services_are_loaded_unless_gossiped_otherwise() {
+        let mut service = create_service("adam");
+        service.unloaded = true;
+        let mut rumor = newscast::Rumor::from(service);
+        assert!(Service::from_proto(rumor.clone()).unwrap().unloaded);
+
+        // As gossiped by a Supervisor which predates gossiping unloads
+        if let Some(RumorPayload::Service(payload)) = rumor.payload.as_mut() {
+            payload.unloaded = None;
+        }
+        assert!(!Service::from_proto(rumor).unwrap().unloaded);
+    }
+
     #[test]
     #[should_panic]
     fn service_package_name_mismatch() {
@@ -1,10 +1,12 @@
 //! The Butterfly server.
 //!
 //! Creates `Server` structs, that hold everything we need to run the SWIM and Gossip protocol.
-//! Winds up with 5 separate threads - inbound (incoming connections), outbound (the Probe
+//! Winds up with 6 separate threads - inbound (incoming connections), outbound (the Probe
 //! protocol), expire (turning Suspect members into Confirmed members), push (the fan-out rumors),
-//! and pull (the inbound receipt of rumors.).
+//! pull (the inbound receipt of rumors.), and anti-entropy (catching up on missed rumors).
 
+mod anti_entropy;
+pub mod config_history;
 mod expire;
 mod inbound;
 mod incarnation_store;
@@ -13,7 +15,9 @@ mod pull;
 mod push;
 pub mod timing;
 
-use self::{incarnation_store::IncarnationStore,
+use self::{config_history::{ConfigHistory,
+                            ConfigHistoryEntry},
+           incarnation_store::IncarnationStore,
            sync::Myself};
 use crate::{error::{Error,
                     Result},
@@ -24,7 +28,9 @@ use crate::{error::{Error,
                      MemberListProxy},
             message,
             probe_list::ProbeList,
+            ring_keys::RingKeys,
             rumor::{ConstIdRumor,
+                    ConstKeyRumor,
                     Rumor,
                     RumorKey,
                     RumorStore,
@@ -45,7 +51,10 @@ use crate::{error::{Error,
 use habitat_common::{FeatureFlag,
                      liveliness_checker,
                      sync::Lock};
-use habitat_core::crypto::keys::RingKey;
+use habitat_core::{crypto::keys::{NamedRevision,
+                                  RingKey},
+                   tls::gossip::{AuthenticatedMember,
+                                 MemberAuthenticator}};
 use lazy_static::lazy_static;
 use log::{debug,
           error,
@@ -54,9 +63,11 @@ use log::{debug,
           warn};
 use prometheus::{HistogramTimer,
                  HistogramVec,
+                 IntCounter,
                  IntGauge,
                  opts,
                  register_histogram_vec,
+                 register_int_counter,
                  register_int_gauge};
 use serde::{Serialize,
             Serializer,
@@ -75,6 +86,7 @@ use std::{collections::{HashMap,
           result,
           sync::{Arc,
                  Mutex,
+                 RwLock,
                  atomic::{AtomicBool,
                           AtomicIsize,
                           Ordering},
@@ -96,6 +108,12 @@ lazy_static! {
         register_histogram_vec!("hab_butterfly_election_duration_seconds",
                                 "How long it takes to complete an election",
                                 &["service_group"]).unwrap();
+    static ref SWIM_ROUNDS: IntCounter =
+        register_int_counter!(opts!("hab_butterfly_swim_rounds_total",
+                                    "Total number of completed SWIM protocol rounds")).unwrap();
+    static ref GOSSIP_ROUNDS: IntCounter =
+        register_int_counter!(opts!("hab_butterfly_gossip_rounds_total",
+                                    "Total number of started gossip protocol rounds")).unwrap();
 }
 
 // We need this here to track how long it takes to complete an election. We need to store the timer
@@ -289,7 +307,8 @@ pub struct Server {
     myself:                   Arc<Myself>,
     pub member_list:          Arc<MemberList>,
     pub probe_list:           Arc<ProbeList>,
-    ring_key:                 Arc<Option<RingKey>>,
+    ring_keys:                Arc<RwLock<Option<RingKeys>>>,
+    member_authenticator:     Option<MemberAuthenticator>,
     rumor_heat:               Arc<RumorHeat>,
     pub service_store:        RumorStore<Service>,
     pub service_config_store: RumorStore<ServiceConfig>,
@@ -297,6 +316,7 @@ pub struct Server {
     pub election_store:       RumorStore<Election>,
     pub update_store:         RumorStore<ElectionUpdate>,
     pub departure_store:      RumorStore<Departure>,
+    config_history:           Arc<Mutex<ConfigHistory>>,
     swim_addr:                SocketAddr,
     gossip_addr:              SocketAddr,
     suitability_lookup:       Arc<dyn Suitability>,
@@ -319,7 +339,8 @@ impl Clone for Server {
                  myself:               self.myself.clone(),
                  member_list:          self.member_list.clone(),
                  probe_list:           self.probe_list.clone(),
-                 ring_key:             self.ring_key.clone(),
+                 ring_keys:            self.ring_keys.clone(),
+                 member_authenticator: self.member_authenticator.clone(),
                  rumor_heat:           self.rumor_heat.clone(),
                  service_store:        self.service_store.clone(),
                  service_config_store: self.service_config_store.clone(),
@@ -327,6 +348,7 @@ impl Clone for Server {
                  election_store:       self.election_store.clone(),
                  update_store:         self.update_store.clone(),
                  departure_store:      self.departure_store.clone(),
+                 config_history:       self.config_history.clone(),
                  swim_addr:            self.swim_addr,
                  gossip_addr:          self.gossip_addr,
                  suitability_lookup:   self.suitability_lookup.clone(),
@@ -344,12 +366,14 @@ impl Clone for Server {
 
 impl Server {
     /// Create a new server, bound to the `addr`, hosting a particular `member`, and with a
-    /// ring_key if you want encryption on the wire, and an optional server name.
+    /// set of ring keys if you want encryption on the wire, a member authenticator if you want
+    /// gossip to be authenticated with certificates, and an optional server name.
     #[allow(clippy::too_many_arguments)]
     pub fn new(swim_addr: SocketAddr,
                gossip_addr: SocketAddr,
                mut member: Member,
-               ring_key: Option<RingKey>,
+               ring_keys: Option<RingKeys>,
+               member_authenticator: Option<MemberAuthenticator>,
                name: Option<String>,
                // TODO (CM): having data_path as optional is only something
                // that's used in testing, but it cascades outward and
@@ -367,6 +391,11 @@ impl Server {
                 member.gossip_port = gossip_socket_addr.port();
 
                 let member_id = member.id.clone();
+                if let Some(ref authenticator) = member_authenticator
+                   && !authenticator.is_for(&member_id)
+                {
+                    return Err(Error::MemberCertificateMismatch(member_id));
+                }
 
                 // TODO (CM): This None really wants to go away. Not
                 // currently setting a IncarnationStore, because it
@@ -382,7 +411,8 @@ impl Server {
                             myself: Arc::new(myself),
                             member_list: Arc::new(MemberList::new()),
                             probe_list: Arc::new(ProbeList::new()),
-                            ring_key: Arc::new(ring_key),
+                            ring_keys: Arc::new(RwLock::new(ring_keys)),
+                            member_authenticator,
                             rumor_heat: Arc::default(),
                             service_store: RumorStore::default(),
                             service_config_store: RumorStore::default(),
@@ -390,6 +420,7 @@ impl Server {
                             election_store: RumorStore::default(),
                             update_store: RumorStore::default(),
                             departure_store: RumorStore::default(),
+                            config_history: Arc::default(),
                             swim_addr: swim_socket_addr,
                             gossip_addr: gossip_socket_addr,
                             suitability_lookup,
@@ -420,6 +451,7 @@ impl Server {
 
     /// Adds 1 to the current round, atomically.
     fn update_swim_round(&self) {
+        SWIM_ROUNDS.inc();
         let current_round = self.swim_rounds.load(Ordering::SeqCst);
         match current_round.checked_add(1) {
             Some(_number) => {
@@ -442,6 +474,7 @@ impl Server {
 
     /// Adds 1 to the current round, atomically.
     fn update_gossip_round(&self) {
+        GOSSIP_ROUNDS.inc();
         let current_round = self.gossip_rounds.load(Ordering::SeqCst);
         match current_round.checked_add(1) {
             Some(_number) => {
@@ -477,6 +510,19 @@ impl Server {
                 return Err(Error::BadDataPath(path.to_path_buf(), err));
             }
 
+            {
+                // Load the config history before ingesting the rumors, so the service configs
+                // we read back that it already has aren't recorded twice.
+                let history_path = path.join(format!("{}.cfg_history", &self.member_id));
+                let mut config_history = self.config_history
+                                             .lock()
+                                             .expect("ConfigHistory lock poisoned");
+                *config_history = ConfigHistory::with_configured_limit(history_path);
+                if let Err(err) = config_history.load() {
+                    error!("{}", err);
+                }
+            }
+
             let dat_path = path.join(format!("{}.rst", &self.member_id));
             let mut reader = DatFileReader::read_or_create_rsr_mlr(dat_path.clone(),
                                                                    &self.member_list,
@@ -532,12 +578,19 @@ impl Server {
                              self.clone(),
                              timing.clone())?;
 
-        pull::spawn_thread(format!("pull-{}", self.name()), self.clone())?;
+        let digests = anti_entropy::spawn_worker_thread(format!("anti-entropy-worker-{}",
+                                                                self.name()),
+                                                        self.clone())?;
+        pull::spawn_thread(format!("pull-{}", self.name()), self.clone(), digests)?;
 
         push::spawn_thread(format!("push-{}", self.name()),
                            self.clone(),
                            timing.clone())?;
 
+        anti_entropy::spawn_thread(format!("anti-entropy-{}", self.name()),
+                                   self.clone(),
+                                   timing.clone())?;
+
         if self.dat_file.is_some() {
             spawn_persist_thread(format!("persist-{}", self.name()), self.clone())?;
         }
@@ -574,6 +627,18 @@ impl Server {
         self.block_list.read().contains(member_id)
     }
 
+    /// Exchange rumor digests with the member `member_id` right away, rather than waiting for the
+    /// anti-entropy thread to pick it.
+    ///
+    /// # Locking (see locking.md)
+    /// * `MemberList::entries` (read)
+    /// * `RumorStore::list` (read)
+    pub fn exchange_digests_mlr_rsr(&self, member_id: &str) {
+        if let Some(member) = self.member_list.get_cloned_mlr(member_id) {
+            anti_entropy::start_exchange_rsr(self, &member);
+        }
+    }
+
     /// Stop the outbound and inbound threads from processing work.
     pub fn pause(&mut self) {
         self.pause
@@ -673,7 +738,8 @@ impl Server {
                 .lock_rhw()
                 .start_hot_rumor(RumorKey::new(RumorType::Member, &*self.member_id, ""));
 
-            let check_list = self.member_list.check_list_mlr(&self.member_id);
+            let myself = self.myself.lock_smr().to_member();
+            let check_list = self.member_list.check_list_mlr(&myself);
 
             // TODO (CM): Even though we marked the rumor as hot
             // above, when we gossip, we send out the 5 "coolest but
@@ -819,6 +885,30 @@ impl Server {
         }
     }
 
+    /// Gossip that this member unloaded `service_group`, so that every member eventually purges
+    /// its Service rumor.
+    ///
+    /// # Locking (see locking.md)
+    /// * `RumorStore::list` (write)
+    /// * `RumorHeat::inner` (write)
+    pub fn unload_service_rsw_rhw(&self, service_group: &str) {
+        let unloaded = self.service_store
+                           .lock_rsr()
+                           .service_group(service_group)
+                           .map_rumor(self.member_id(), |rumor| {
+                               let mut rumor = rumor.clone();
+                               rumor.incarnation += 1;
+                               rumor.unloaded = true;
+                               rumor
+                           });
+        if let Some(service) = unloaded {
+            let rk = RumorKey::from(&service);
+            if self.service_store.insert_rsw(service) {
+                self.rumor_heat.lock_rhw().start_hot_rumor(rk);
+            }
+        }
+    }
+
     /// Insert a service config rumor into the service store.
     ///
     /// # Locking (see locking.md)
@@ -826,11 +916,40 @@ impl Server {
     /// * `RumorHeat::inner` (write)
     pub fn insert_service_config_rsw_rhw(&self, service_config: ServiceConfig) {
         let rk = RumorKey::from(&service_config);
+        let history = service_config.clone();
         if self.service_config_store.insert_rsw(service_config) {
+            if let Err(err) = self.config_history
+                                  .lock()
+                                  .expect("ConfigHistory lock poisoned")
+                                  .record(&history)
+            {
+                error!("Unable to record {} in the config history: {}",
+                       history, err);
+            }
             self.rumor_heat.lock_rhw().start_hot_rumor(rk);
         }
     }
 
+    /// The incarnation of the service config rumor of `service_group`, if there is one.
+    ///
+    /// # Locking (see locking.md)
+    /// * `RumorStore::list` (read)
+    pub fn service_config_incarnation_rsr(&self, service_group: &str) -> Option<u64> {
+        self.service_config_store
+            .lock_rsr()
+            .service_group(service_group)
+            .map_rumor(ServiceConfig::const_id(), |config| config.incarnation)
+    }
+
+    /// The configurations recently applied to `service_group`, oldest first.
+    pub fn service_config_history(&self, service_group: &str) -> Vec<ConfigHistoryEntry> {
+        self.config_history
+            .lock()
+            .expect("ConfigHistory lock poisoned")
+            .entries(service_group)
+            .to_vec()
+    }
+
     /// Insert a service file rumor into the service file store.
     ///
     /// # Locking (see locking.md)
@@ -954,15 +1073,34 @@ impl Server {
                                           service_group: &str,
                                           term: u64,
                                           suitability: Option<u64>) {
+        self.start_handoff_election_rsw_mlr_rhw_msr(service_group, term, suitability, None, None);
+    }
+
+    /// Start an election like `start_election_rsw_mlr_rhw_msr`, for a term in which
+    /// `excluded_member_id` can't win, or in which `preferred_member_id` wins.
+    ///
+    /// # Locking (see locking.md)
+    /// * `RumorStore::list` (write)
+    /// * `MemberList::entries` (read)
+    /// * `RumorHeat::inner` (write)
+    /// * `ManagerServices::inner` (read)
+    fn start_handoff_election_rsw_mlr_rhw_msr(&self,
+                                              service_group: &str,
+                                              term: u64,
+                                              suitability: Option<u64>,
+                                              excluded_member_id: Option<String>,
+                                              preferred_member_id: Option<String>) {
         let suitability = suitability.unwrap_or_else(|| {
                                          self.suitability_lookup.suitability_for_msr(service_group)
                                      });
         let has_quorum = self.check_quorum_mlr(service_group);
-        let e = Election::new(self.member_id(),
-                              service_group,
-                              term,
-                              suitability,
-                              has_quorum);
+        let mut e = Election::new(self.member_id(),
+                                  service_group,
+                                  term,
+                                  suitability,
+                                  has_quorum);
+        e.excluded_member_id = excluded_member_id;
+        e.preferred_member_id = preferred_member_id;
         if !has_quorum {
             warn!("start_election check_quorum failed: {:?}", e);
         }
@@ -1125,6 +1263,89 @@ impl Server {
         }
     }
 
+    /// Start a new election term for `service_group` in which its current leader steps down, or,
+    /// if `preferred_member_id` is given, in which leadership is transferred to that member. The
+    /// suitability hook still decides between the members that can win. Any member of the group
+    /// can start the term. Returns the id of the current leader.
+    ///
+    /// # Locking (see locking.md)
+    /// * `RumorStore::list` (write)
+    /// * `MemberList::entries` (read)
+    /// * `RumorHeat::inner` (write)
+    /// * `ManagerServices::inner` (read)
+    pub fn hand_off_leadership_rsw_mlr_rhw_msr(&self,
+                                               service_group: &str,
+                                               preferred_member_id: Option<&str>)
+                                               -> Result<String> {
+        if !self.service_store
+                .lock_rsr()
+                .service_group(service_group)
+                .contains_id(self.member_id())
+        {
+            return Err(Error::LeaderHandoff(format!("this Supervisor is not \
+                                                     running a member of {}",
+                                                    service_group)));
+        }
+        let (term, leader) = match self.election_store
+                                       .lock_rsr()
+                                       .get(service_group)
+                                       .and_then(|rumors| rumors.get(Election::const_id()))
+        {
+            Some(election) if election.is_finished() => (election.term, election.member_id.clone()),
+            Some(_) => {
+                return Err(Error::LeaderHandoff(format!("{} is still electing a \
+                                                         leader",
+                                                        service_group)));
+            }
+            None => {
+                return Err(Error::LeaderHandoff(format!("{} has not elected a \
+                                                         leader; is it running in \
+                                                         the leader topology?",
+                                                        service_group)));
+            }
+        };
+        let electorate = self.get_electorate_rsr_mlr(service_group);
+        match preferred_member_id {
+            Some(member_id) if member_id == leader => {
+                return Err(Error::LeaderHandoff(format!("{} is already the leader \
+                                                         of {}",
+                                                        member_id, service_group)));
+            }
+            Some(member_id) if !electorate.iter().any(|m| m == member_id) => {
+                return Err(Error::LeaderHandoff(format!("{} is not an alive member \
+                                                         of {}",
+                                                        member_id, service_group)));
+            }
+            Some(_) => {}
+            None if !electorate.iter().any(|m| *m != leader) => {
+                return Err(Error::LeaderHandoff(format!("{} has no other alive \
+                                                         member to take over from {}",
+                                                        service_group, leader)));
+            }
+            None => {}
+        }
+        if !self.check_quorum_mlr(service_group) {
+            return Err(Error::LeaderHandoff(format!("{} does not have quorum", service_group)));
+        }
+
+        let (excluded_member_id, preferred_member_id) = match preferred_member_id {
+            Some(member_id) => (None, Some(member_id.to_string())),
+            None => (Some(leader.clone()), None),
+        };
+        warn!("Starting a new election for {} {} to hand off leadership from {}",
+              service_group,
+              term + 1,
+              leader);
+        self.election_store
+            .remove_rsw(service_group, Election::const_id());
+        self.start_handoff_election_rsw_mlr_rhw_msr(service_group,
+                                                    term + 1,
+                                                    None,
+                                                    excluded_member_id,
+                                                    preferred_member_id);
+        Ok(leader)
+    }
+
     /// Insert an election into the election store. Handles creating a new election rumor for this
     /// member on receipt of an election rumor for a service this server cares about. Also handles
     /// stopping the election if we are the winner and we have enough votes.
@@ -1156,8 +1377,21 @@ impl Server {
                                    .map(|stored_term| election.term > stored_term)
                                    .unwrap_or(false);
                 if new_term {
-                    if Some(self.member_id())
-                       == self.election_store.lock_rsr().get_member_id(election.key())
+                    if election.is_handoff() {
+                        debug!("Received New Term election handing off leadership. Starting my \
+                                own to merge. Term: {}",
+                               election.term);
+                        self.election_store
+                            .remove_rsw(election.key(), election.id());
+                        self.start_handoff_election_rsw_mlr_rhw_msr(&election.service_group,
+                                                                    election.term,
+                                                                    None,
+                                                                    election.excluded_member_id
+                                                                            .clone(),
+                                                                    election.preferred_member_id
+                                                                            .clone());
+                    } else if Some(self.member_id())
+                              == self.election_store.lock_rsr().get_member_id(election.key())
                     {
                         debug!("I am the leader of previous term!");
                         debug!("removing old rumor and starting new election with highest \
@@ -1232,7 +1466,11 @@ impl Server {
                                               .lock()
                                               .expect("Election timers lock poisoned");
                 existing_timers.insert(election.service_group.clone(), ElectionTimer(timer));
-                self.start_election_rsw_mlr_rhw_msr(&election.service_group, election.term, None);
+                self.start_handoff_election_rsw_mlr_rhw_msr(&election.service_group,
+                                                            election.term,
+                                                            None,
+                                                            election.excluded_member_id.clone(),
+                                                            election.preferred_member_id.clone());
             }
 
             if !election.is_finished() {
@@ -1327,11 +1565,179 @@ impl Server {
     }
 
     fn generate_wire(&self, payload: Vec<u8>) -> Result<Vec<u8>> {
-        message::generate_wire(payload, (*self.ring_key).as_ref())
+        let ring_keys = self.ring_keys.read().expect("Ring keys lock poisoned");
+        message::generate_wire(payload,
+                               ring_keys.as_ref().map(RingKeys::primary),
+                               self.member_authenticator.as_ref())
+    }
+
+    /// Unwrap a message received from another member, returning its payload along with the
+    /// member that sent it if gossip is authenticated.
+    fn unwrap_wire(&self, payload: &[u8]) -> Result<(Vec<u8>, Option<AuthenticatedMember>)> {
+        let ring_keys = self.ring_keys.read().expect("Ring keys lock poisoned");
+        message::unwrap_wire(payload,
+                             ring_keys.as_ref(),
+                             self.member_authenticator.as_ref())
+    }
+
+    /// The authenticator this server signs and authenticates its traffic with, if gossip members
+    /// are authenticated with certificates.
+    pub fn member_authenticator(&self) -> Option<&MemberAuthenticator> {
+        self.member_authenticator.as_ref()
+    }
+
+    /// The ring keys this server encrypts and decrypts its traffic with, if it is encrypted.
+    pub fn ring_keys(&self) -> Option<RingKeys> {
+        self.ring_keys
+            .read()
+            .expect("Ring keys lock poisoned")
+            .clone()
     }
 
-    fn unwrap_wire(&self, payload: &[u8]) -> Result<Vec<u8>> {
-        message::unwrap_wire(payload, (*self.ring_key).as_ref())
+    /// Start accepting traffic encrypted with `key`, returning the updated ring keys.
+    pub fn stage_ring_key(&self, key: RingKey) -> Result<RingKeys> {
+        self.update_ring_keys(|ring_keys| ring_keys.stage(key))
+    }
+
+    /// Start encrypting traffic with the staged key `named_revision`, returning the updated ring
+    /// keys.
+    pub fn activate_ring_key(&self, named_revision: &NamedRevision) -> Result<RingKeys> {
+        self.update_ring_keys(|ring_keys| ring_keys.activate(named_revision))
+    }
+
+    /// Stop accepting traffic encrypted with `named_revision`, returning the updated ring keys.
+    pub fn retire_ring_key(&self, named_revision: &NamedRevision) -> Result<RingKeys> {
+        self.update_ring_keys(|ring_keys| ring_keys.retire(named_revision))
+    }
+
+    fn update_ring_keys<F>(&self, update: F) -> Result<RingKeys>
+        where F: FnOnce(&mut RingKeys) -> Result<()>
+    {
+        let mut ring_keys = self.ring_keys.write().expect("Ring keys lock poisoned");
+        let ring_keys =
+            ring_keys.as_mut().ok_or_else(|| {
+                                   Error::RingKeyRotation("gossip is not encrypted".to_string())
+                               })?;
+        update(ring_keys)?;
+        Ok(ring_keys.clone())
+    }
+
+    /// Purge the members that have been `Departed` for longer than `ttl`, along with their
+    /// `Service` and `Departure` rumors. Returns the IDs of the purged members.
+    ///
+    /// # Locking (see locking.md)
+    /// * `RumorStore::list` (write)
+    /// * `RumorStore::tombstones` (write)
+    /// * `MemberList::entries` (write)
+    /// * `MemberList::purged` (write)
+    /// * `RumorHeat::inner` (write)
+    pub fn purge_departed_members_rsw_mlw_rhw(&self, ttl: Duration) -> Vec<String> {
+        let purged = self.member_list.purge_departed_mlw(ttl);
+        for member_id in &purged {
+            let service_groups = self.service_store
+                                     .lock_rsr()
+                                     .iter()
+                                     .filter(|(_, rumors)| rumors.contains_key(member_id))
+                                     .map(|(service_group, _)| service_group.clone())
+                                     .collect::<Vec<_>>();
+            for service_group in service_groups {
+                self.service_store.purge_rsw(&service_group, member_id);
+            }
+            self.departure_store
+                .purge_rsw(Departure::const_key(), member_id);
+
+            let mut rumor_heat = self.rumor_heat.lock_rhw();
+            rumor_heat.purge(member_id);
+            rumor_heat.forget(&RumorKey::new(RumorType::Member, member_id, ""));
+            rumor_heat.forget(&RumorKey::new(RumorType::Departure,
+                                             member_id,
+                                             Departure::const_key()));
+        }
+        purged
+    }
+
+    /// The rumors that no longer describe anything in the ring: `Service` and `Departure` rumors
+    /// for members that aren't in the member list, `Service` rumors for services their member
+    /// unloaded, and election rumors for service groups that no member runs.
+    ///
+    /// `ServiceConfig` and `ServiceFile` rumors are never orphaned: they are applied with `hab
+    /// config apply` and `hab file upload` rather than by running the service, and are still
+    /// needed by members that load the service group again later.
+    ///
+    /// # Locking (see locking.md)
+    /// * `RumorStore::list` (read)
+    /// * `MemberList::entries` (read)
+    pub fn orphaned_rumors_rsr_mlr(&self) -> Vec<RumorKey> {
+        let is_known = |member_id: &str| {
+            member_id == self.member_id() || self.member_list.contains_member_mlr(member_id)
+        };
+        let mut orphaned = Vec::new();
+        for service in self.service_store.lock_rsr().rumors() {
+            if service.unloaded || !is_known(service.id()) {
+                orphaned.push(RumorKey::from(service));
+            }
+        }
+        for departure in self.departure_store.lock_rsr().rumors() {
+            if !is_known(departure.id()) {
+                orphaned.push(RumorKey::from(departure));
+            }
+        }
+
+        let service_groups = self.service_store
+                                 .lock_rsr()
+                                 .rumors()
+                                 .filter(|service| !service.unloaded)
+                                 .map(|service| service.service_group.to_string())
+                                 .collect::<HashSet<_>>();
+        let is_orphaned = |rumor_key: &RumorKey| !service_groups.contains(&rumor_key.key);
+        orphaned.extend(self.election_store
+                            .lock_rsr()
+                            .rumors()
+                            .map(RumorKey::from)
+                            .filter(is_orphaned));
+        orphaned.extend(self.update_store
+                            .lock_rsr()
+                            .rumors()
+                            .map(RumorKey::from)
+                            .filter(is_orphaned));
+        orphaned
+    }
+
+    /// Remove a rumor from its store and stop sharing it. Returns true if the rumor was present.
+    ///
+    /// # Locking (see locking.md)
+    /// * `RumorStore::list` (write)
+    /// * `RumorStore::tombstones` (write)
+    /// * `RumorHeat::inner` (write)
+    pub fn purge_rumor_rsw_rhw(&self, rumor_key: &RumorKey) -> bool {
+        let RumorKey { kind, key, id } = rumor_key;
+        let purged = match kind {
+            RumorType::Service => self.service_store.purge_rsw(key, id),
+            RumorType::ServiceConfig => self.service_config_store.purge_rsw(key, id),
+            RumorType::ServiceFile => self.service_file_store.purge_rsw(key, id),
+            RumorType::Election => self.election_store.purge_rsw(key, id),
+            RumorType::ElectionUpdate => self.update_store.purge_rsw(key, id),
+            RumorType::Departure => self.departure_store.purge_rsw(key, id),
+            _ => false,
+        };
+        if purged {
+            self.rumor_heat.lock_rhw().forget(rumor_key);
+        }
+        purged
+    }
+
+    /// Forget the rumors that were purged longer than `ttl` ago, so that they can be accepted
+    /// again.
+    ///
+    /// # Locking (see locking.md)
+    /// * `RumorStore::tombstones` (write)
+    pub fn expire_rumor_tombstones_rsw(&self, ttl: Duration) {
+        self.service_store.expire_tombstones_rsw(ttl);
+        self.service_config_store.expire_tombstones_rsw(ttl);
+        self.service_file_store.expire_tombstones_rsw(ttl);
+        self.election_store.expire_tombstones_rsw(ttl);
+        self.update_store.expire_tombstones_rsw(ttl);
+        self.departure_store.expire_tombstones_rsw(ttl);
     }
 
     /// # Locking (see locking.md)
@@ -1557,7 +1963,9 @@ mod tests {
                   initialized:     Default::default(),
                   pkg:             Default::default(),
                   cfg:             Default::default(),
-                  sys:             Default::default(), }
+                  sys:             Default::default(),
+                  ready:           true,
+                  unloaded:        false, }
     }
 
     #[test]
@@ -1801,9 +2209,12 @@ mod tests {
     mod server {
         use super::*;
         use crate::{member::Member,
+                    rumor::{ServiceConfig,
+                            ServiceFile},
                     server::{Server,
                              Suitability,
                              timing::Timing}};
+        use habitat_core::package::PackageIdent;
         use std::{fs::File,
                   io::prelude::*,
                   net::{IpAddr,
@@ -1847,6 +2258,7 @@ mod tests {
                         None,
                         None,
                         None,
+                        None,
                         Arc::new(ZeroSuitability)).unwrap()
         }
 
@@ -1877,6 +2289,7 @@ mod tests {
                         member,
                         None,
                         None,
+                        None,
                         Some(tmpdir.path()),
                         Arc::new(ZeroSuitability)).unwrap()
         }
@@ -1899,5 +2312,75 @@ mod tests {
             server.start_rsw_mlw_smw_rhw_msr(&Timing::default())
                   .expect("Server failed to start");
         }
+
+        #[test]
+        fn service_config_incarnation_is_the_gossiped_one() {
+            let server = start_server();
+            assert_eq!(server.service_config_incarnation_rsr("redis.default"), None);
+
+            let mut service_config = ServiceConfig::new("member",
+                                                        ServiceGroup::new("redis", "default",
+                                                                          None).unwrap(),
+                                                        b"port = 6379".to_vec());
+            service_config.incarnation = 5;
+            server.insert_service_config_rsw_rhw(service_config);
+            assert_eq!(server.service_config_incarnation_rsr("redis.default"),
+                       Some(5));
+        }
+
+        fn service_rumor(server: &Server) -> Service {
+            let mut service = Service::new(server.member_id(),
+                                           &PackageIdent::from_str("core/redis/1.0.0/\
+                                                                    20200101000000").unwrap(),
+                                           ServiceGroup::new("redis", "default", None).unwrap(),
+                                           Default::default(),
+                                           None);
+            service.incarnation = 1;
+            service
+        }
+
+        #[test]
+        fn services_unloaded_by_live_members_are_orphaned() {
+            let server = start_server();
+            server.insert_service_rsw_mlw_rhw(service_rumor(&server));
+            assert!(server.orphaned_rumors_rsr_mlr().is_empty());
+
+            server.unload_service_rsw_rhw("redis.default");
+            let orphaned = server.orphaned_rumors_rsr_mlr();
+            assert_eq!(orphaned,
+                       vec![RumorKey::new(RumorType::Service,
+                                          server.member_id(),
+                                          "redis.default")]);
+            assert!(server.purge_rumor_rsw_rhw(&orphaned[0]));
+            assert_eq!(server.service_store
+                             .purged_incarnation_rsr("redis.default", server.member_id()),
+                       Some(2));
+
+            // Loading the service again gossips a new rumor, which isn't orphaned
+            let mut service = service_rumor(&server);
+            service.incarnation = 3;
+            server.insert_service_rsw_mlw_rhw(service);
+            assert!(server.orphaned_rumors_rsr_mlr().is_empty());
+        }
+
+        #[test]
+        fn service_config_and_file_rumors_are_never_orphaned() {
+            let server = start_server();
+            let service_group = ServiceGroup::new("redis", "default", None).unwrap();
+            server.insert_service_config_rsw_rhw(ServiceConfig::new("member",
+                                                                    service_group.clone(),
+                                                                    b"port = 6379".to_vec()));
+            server.insert_service_file_rsw_rhw(ServiceFile::new("member",
+                                                                service_group,
+                                                                "redis.conf",
+                                                                b"port 6379".to_vec()));
+            assert!(server.orphaned_rumors_rsr_mlr().is_empty());
+
+            server.insert_service_rsw_mlw_rhw(service_rumor(&server));
+            server.unload_service_rsw_rhw("redis.default");
+            assert!(server.orphaned_rumors_rsr_mlr()
+                          .iter()
+                          .all(|rumor_key| rumor_key.kind == RumorType::Service));
+        }
     }
 }
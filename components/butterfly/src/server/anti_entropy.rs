@@ -0,0 +1,191 @@
+//! The anti-entropy thread.
+//!
+//! Periodically exchanges rumor digests with a random member, so that rumors which cooled off
+//! before reaching every member still make it around the ring. See `rumor::digest` for how the
+//! exchange works.
+
+use crate::{member::Member,
+            rumor::{Digest,
+                    RumorType,
+                    digest::StoreDigest},
+            server::{Server,
+                     push,
+                     timing::Timing}};
+use habitat_common::liveliness_checker;
+use lazy_static::lazy_static;
+use log::{debug,
+          error,
+          trace};
+use prometheus::{IntCounter,
+                 IntCounterVec,
+                 register_int_counter,
+                 register_int_counter_vec};
+use std::{sync::mpsc::{self,
+                       Receiver,
+                       RecvTimeoutError,
+                       SyncSender,
+                       TrySendError},
+          thread,
+          time::{Duration,
+                 Instant}};
+
+/// How many received digests may wait for the worker thread before further ones are dropped.
+const DIGEST_QUEUE_SIZE: usize = 16;
+
+pub type DigestSender = SyncSender<(Member, Digest)>;
+
+lazy_static! {
+    static ref ANTI_ENTROPY_RUMORS_SENT: IntCounterVec =
+        register_int_counter_vec!("hab_butterfly_anti_entropy_rumors_sent_total",
+                                  "Total number of rumors sent to members that were missing them",
+                                  &["type"]).unwrap();
+    static ref ANTI_ENTROPY_DIGESTS_DROPPED: IntCounter =
+        register_int_counter!("hab_butterfly_anti_entropy_digests_dropped_total",
+                              "Total number of received digests dropped because too many were \
+                               queued").unwrap();
+}
+
+pub fn spawn_thread(name: String, server: Server, timing: Timing) -> std::io::Result<()> {
+    thread::Builder::new().name(name)
+                          .spawn(move || -> ! { run_loop(&server, &timing) })
+                          .map(|_| ())
+}
+
+/// Every `Timing::anti_entropy_interval`, start an exchange with the first member of a fresh
+/// check list that we can currently gossip with.
+fn run_loop(server: &Server, timing: &Timing) -> ! {
+    loop {
+        liveliness_checker::mark_thread_alive().and_divergent();
+
+        if server.paused() {
+            thread::sleep(Duration::from_millis(100));
+            continue;
+        }
+
+        let start_time = Instant::now();
+        let myself = server.myself().lock_smr().to_member();
+        let member = server.member_list
+                           .check_list_mlr(&myself)
+                           .into_iter()
+                           .find(|member| {
+                               !server.is_member_blocked_sblr(&member.id)
+                               && server.member_list.pingable_mlr(member)
+                               && !server.member_list.persistent_and_confirmed_mlr(member)
+                           });
+        if let Some(member) = member {
+            start_exchange_rsr(server, &member);
+        }
+        timing.sleep_for_remaining_anti_entropy_interval(start_time);
+    }
+}
+
+/// Start an anti-entropy exchange with `member` by sending it the summaries of our rumor stores.
+///
+/// # Locking (see locking.md)
+/// * `RumorStore::list` (read)
+pub(super) fn start_exchange_rsr(server: &Server, member: &Member) {
+    trace!("Starting an anti-entropy exchange with {}", member.id);
+    push::send_digest(server, member, Digest::summary(&store_digests_rsr(server)));
+}
+
+/// Queue a digest received from the member `from_id` for the worker thread, so that sending the
+/// reply doesn't hold up the thread the digest was received on. If the worker is already behind
+/// by `DIGEST_QUEUE_SIZE` digests, the digest is dropped; its sender will start another exchange
+/// on its next anti-entropy round.
+pub(super) fn receive_digest(server: &Server,
+                             digests: &DigestSender,
+                             from_id: &str,
+                             digest: Digest) {
+    let Some(member) = server.member_list.get_cloned_mlr(from_id) else {
+        debug!("Ignoring digest from unknown member {}", from_id);
+        return;
+    };
+    match digests.try_send((member, digest)) {
+        Ok(()) => {}
+        Err(TrySendError::Full(_)) => {
+            debug!("Dropping digest from {}, too many digests are queued",
+                   from_id);
+            ANTI_ENTROPY_DIGESTS_DROPPED.inc();
+        }
+        Err(TrySendError::Disconnected(_)) => {
+            error!("Dropping digest from {}, the anti-entropy worker has exited",
+                   from_id);
+        }
+    }
+}
+
+/// Start the thread which acts on the digests passed to `receive_digest`.
+pub fn spawn_worker_thread(name: String, server: Server) -> std::io::Result<DigestSender> {
+    let (tx, rx) = mpsc::sync_channel(DIGEST_QUEUE_SIZE);
+    thread::Builder::new().name(name)
+                          .spawn(move || run_worker_loop(&server, &rx))?;
+    Ok(tx)
+}
+
+fn run_worker_loop(server: &Server, digests: &Receiver<(Member, Digest)>) {
+    let _: liveliness_checker::ThreadUnregistered = loop {
+        let checked_thread = liveliness_checker::mark_thread_alive();
+        match digests.recv_timeout(Duration::from_secs(1)) {
+            Ok((member, digest)) => reconcile_rsr_mlr_rhw(server, &member, &digest),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break checked_thread.unregister(Ok(())),
+        }
+    };
+}
+
+/// Reply to a summary with a detailed digest of the stores that differ from ours, or to a
+/// detailed digest with the rumors the sender is missing.
+///
+/// # Locking (see locking.md)
+/// * `RumorStore::list` (read)
+/// * `MemberList::entries` (read)
+/// * `RumorHeat::inner` (write)
+fn reconcile_rsr_mlr_rhw(server: &Server, member: &Member, digest: &Digest) {
+    let store_digests = store_digests_rsr(server);
+    match &digest.entries {
+        None => {
+            let differing = store_digests.into_iter()
+                                         .filter(|store| {
+                                             digest.summary_of(store.kind())
+                                             != Some(store.summary().as_slice())
+                                         })
+                                         .collect::<Vec<_>>();
+            if !differing.is_empty() {
+                debug!("{} rumor stores differ from those of {}",
+                       differing.len(),
+                       member.id);
+                push::send_digest(server, member, Digest::detailed(&differing, true));
+            }
+        }
+        Some(entries) => {
+            let stores = store_digests.into_iter()
+                                      .filter(|store| digest.summary_of(store.kind()).is_some())
+                                      .collect::<Vec<_>>();
+            let rumors = stores.iter()
+                               .flat_map(|store| store.differing_from(entries))
+                               .collect::<Vec<_>>();
+            if !rumors.is_empty() {
+                debug!("Sending {} rumors {} is missing", rumors.len(), member.id);
+                for rumor in &rumors {
+                    ANTI_ENTROPY_RUMORS_SENT.with_label_values(&[&rumor.kind.to_string()])
+                                            .inc();
+                }
+                push::send_rumors_rsr_mlr_rhw(server, member, &rumors);
+            }
+            if digest.reply {
+                push::send_digest(server, member, Digest::detailed(&stores, false));
+            }
+        }
+    }
+}
+
+/// # Locking (see locking.md)
+/// * `RumorStore::list` (read)
+fn store_digests_rsr(server: &Server) -> Vec<StoreDigest> {
+    vec![StoreDigest::of_rsr(RumorType::Service, &server.service_store),
+         StoreDigest::of_rsr(RumorType::ServiceConfig, &server.service_config_store),
+         StoreDigest::of_rsr(RumorType::ServiceFile, &server.service_file_store),
+         StoreDigest::of_rsr(RumorType::Election, &server.election_store),
+         StoreDigest::of_rsr(RumorType::ElectionUpdate, &server.update_store),
+         StoreDigest::of_rsr(RumorType::Departure, &server.departure_store),]
+}
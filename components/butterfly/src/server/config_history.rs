@@ -0,0 +1,226 @@
+//! Keep a bounded history of the configurations applied to each service group through gossip.
+//!
+//! The `ServiceConfig` rumor store only ever holds the latest incarnation for a service group, so
+//! without this history a bad `hab config apply` could only be reverted by someone who kept the
+//! previous TOML around. The history is persisted alongside the rumor data file so that it
+//! survives restarts.
+
+use crate::{error::{Error,
+                    Result},
+            rumor::service_config::ServiceConfig};
+use habitat_core::fs::atomic_write;
+use serde::{Deserialize,
+            Serialize};
+use std::{collections::BTreeMap,
+          fs,
+          io,
+          path::PathBuf,
+          str,
+          time::{SystemTime,
+                 UNIX_EPOCH}};
+
+habitat_core::env_config_int!(/// The number of configurations kept for each service group.
+                              #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+                              ConfigHistoryLimit,
+                              usize,
+                              HAB_CONFIG_HISTORY_LIMIT,
+                              10);
+
+/// A configuration that was applied to a service group.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct ConfigHistoryEntry {
+    pub incarnation: u64,
+    /// The member that gossiped this configuration.
+    pub from_id:     String,
+    pub encrypted:   bool,
+    /// The configuration exactly as it was gossiped; encrypted configurations stay encrypted.
+    pub config:      String,
+    /// When this Supervisor first saw this incarnation, in seconds since the Unix epoch.
+    pub recorded_at: u64,
+}
+
+#[derive(Debug)]
+pub struct ConfigHistory {
+    /// Path to the file that backs this history, if any.
+    path:   Option<PathBuf>,
+    limit:  usize,
+    /// The entries for each service group, ordered by incarnation.
+    groups: BTreeMap<String, Vec<ConfigHistoryEntry>>,
+}
+
+impl Default for ConfigHistory {
+    fn default() -> Self { ConfigHistory::new(None, ConfigHistoryLimit::configured_value().into()) }
+}
+
+impl ConfigHistory {
+    /// Create an empty history backed by the file at `path`, keeping as many entries for each
+    /// service group as `HAB_CONFIG_HISTORY_LIMIT` allows.
+    pub fn with_configured_limit(path: PathBuf) -> Self {
+        ConfigHistory::new(Some(path), ConfigHistoryLimit::configured_value().into())
+    }
+
+    /// Create an empty history keeping at most `limit` entries for each service group, backed by
+    /// the file at `path` if one is given.
+    pub fn new(path: Option<PathBuf>, limit: usize) -> Self {
+        ConfigHistory { path,
+                        limit: limit.max(1),
+                        groups: BTreeMap::new() }
+    }
+
+    /// Replace the contents of this history with whatever was persisted to its backing file. A
+    /// missing file is an empty history.
+    pub fn load(&mut self) -> Result<()> {
+        let path = match self.path {
+            Some(ref path) => path,
+            None => return Ok(()),
+        };
+        let contents = match fs::read(path) {
+            Ok(contents) => contents,
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(Error::ConfigHistoryIO(path.clone(), err)),
+        };
+        let mut groups: BTreeMap<String, Vec<ConfigHistoryEntry>> =
+            serde_json::from_slice(&contents).map_err(|e| {
+                                                 Error::ConfigHistoryParse(path.clone(), e)
+                                             })?;
+        for entries in groups.values_mut() {
+            entries.sort_by_key(|e| e.incarnation);
+            entries.dedup_by_key(|e| e.incarnation);
+            truncate_front(entries, self.limit);
+        }
+        self.groups = groups;
+        Ok(())
+    }
+
+    /// Add `service_config` to the history of its service group, dropping the oldest entry if
+    /// the group is over the limit, and persist the result.
+    ///
+    /// Returns `false` if the incarnation was already recorded. Configurations that aren't UTF-8
+    /// can never be applied, so they aren't recorded either.
+    pub fn record(&mut self, service_config: &ServiceConfig) -> Result<bool> {
+        let config = match str::from_utf8(&service_config.config) {
+            Ok(config) => config.to_string(),
+            Err(_) => return Ok(false),
+        };
+        let entries = self.groups
+                          .entry(service_config.service_group.to_string())
+                          .or_default();
+        let index =
+            match entries.binary_search_by_key(&service_config.incarnation, |e| e.incarnation) {
+                Ok(_) => return Ok(false),
+                Err(index) => index,
+            };
+        let recorded_at = SystemTime::now().duration_since(UNIX_EPOCH)
+                                           .map(|d| d.as_secs())
+                                           .unwrap_or_default();
+        entries.insert(index,
+                       ConfigHistoryEntry { incarnation: service_config.incarnation,
+                                            from_id: service_config.from_id.clone(),
+                                            encrypted: service_config.encrypted,
+                                            config,
+                                            recorded_at });
+        truncate_front(entries, self.limit);
+        self.persist()?;
+        Ok(true)
+    }
+
+    /// The recorded configurations of `service_group`, oldest first.
+    pub fn entries(&self, service_group: &str) -> &[ConfigHistoryEntry] {
+        self.groups
+            .get(service_group)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    fn persist(&self) -> Result<()> {
+        if let Some(ref path) = self.path {
+            let contents =
+                serde_json::to_vec(&self.groups).map_err(|e| {
+                                                    Error::ConfigHistoryParse(path.clone(), e)
+                                                })?;
+            atomic_write(path, contents).map_err(|e| Error::ConfigHistoryIO(path.clone(), e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Drop the oldest entries so that at most `limit` remain.
+fn truncate_front(entries: &mut Vec<ConfigHistoryEntry>, limit: usize) {
+    if entries.len() > limit {
+        entries.drain(..entries.len() - limit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use habitat_core::service::ServiceGroup;
+    use mktemp::Temp;
+
+    fn service_config(incarnation: u64, config: &str) -> ServiceConfig {
+        let mut service_config =
+            ServiceConfig::new("member",
+                               ServiceGroup::new("redis", "default", None).unwrap(),
+                               config.as_bytes().to_vec());
+        service_config.incarnation = incarnation;
+        service_config
+    }
+
+    fn incarnations(history: &ConfigHistory) -> Vec<u64> {
+        history.entries("redis.default")
+               .iter()
+               .map(|e| e.incarnation)
+               .collect()
+    }
+
+    #[test]
+    fn keeps_the_newest_entries_up_to_the_limit() {
+        let mut history = ConfigHistory::new(None, 2);
+        for incarnation in [1, 3, 2] {
+            assert!(history.record(&service_config(incarnation, "port = 6379"))
+                           .unwrap());
+        }
+        assert_eq!(incarnations(&history), vec![2, 3]);
+        assert!(history.entries("redis.prod").is_empty());
+    }
+
+    #[test]
+    fn ignores_incarnations_it_already_has_and_configs_that_are_not_utf8() {
+        let mut history = ConfigHistory::new(None, 10);
+        assert!(history.record(&service_config(1, "port = 6379")).unwrap());
+        assert!(!history.record(&service_config(1, "port = 6380")).unwrap());
+
+        let mut not_utf8 = service_config(2, "");
+        not_utf8.config = vec![0xff, 0xfe];
+        assert!(!history.record(&not_utf8).unwrap());
+
+        assert_eq!(incarnations(&history), vec![1]);
+        assert_eq!(history.entries("redis.default")[0].config, "port = 6379");
+    }
+
+    #[test]
+    fn survives_a_reload_from_disk() {
+        let dir = Temp::new_dir().expect("Could not create temp dir");
+        let path = dir.as_ref().join("history");
+
+        let mut history = ConfigHistory::new(Some(path.clone()), 10);
+        history.load().expect("a missing file is an empty history");
+        history.record(&service_config(1, "port = 6379")).unwrap();
+        history.record(&service_config(2, "port = 6380")).unwrap();
+
+        let mut reloaded = ConfigHistory::new(Some(path), 1);
+        reloaded.load().unwrap();
+        assert_eq!(incarnations(&reloaded), vec![2]);
+        assert_eq!(reloaded.entries("redis.default"),
+                   &history.entries("redis.default")[1..]);
+    }
+
+    #[test]
+    fn unparseable_file_is_an_error() {
+        let path = Temp::new_file().expect("Could not create temp file");
+        fs::write(&path, "not json").unwrap();
+
+        let mut history = ConfigHistory::new(Some(path.as_ref().to_path_buf()), 10);
+        assert!(matches!(history.load(), Err(Error::ConfigHistoryParse(..))));
+    }
+}
@@ -1,17 +1,45 @@
 //! Periodically check membership rumors to automatically "time out"
 //! `Suspect` rumors to `Confirmed`, and `Confirmed` rumors to
 //! `Departed`.
+//!
+//! Less often, purge members that have been `Departed` for longer
+//! than `HAB_DEPARTED_MEMBER_TTL_SECS`, and rumors that have been
+//! orphaned for longer than `HAB_ORPHANED_RUMOR_TTL_SECS`, so that
+//! long-lived rings don't accumulate them forever.
 
 use crate::{rumor::{RumorKey,
                     RumorType},
             server::{Server,
                      timing::Timing}};
 use habitat_common::liveliness_checker;
-use log::trace;
-use std::{thread,
-          time::Duration};
+use lazy_static::lazy_static;
+use log::{debug,
+          trace};
+use prometheus::{IntCounterVec,
+                 register_int_counter_vec};
+use std::{collections::{HashMap,
+                        HashSet},
+          thread,
+          time::{Duration,
+                 Instant}};
 
 const LOOP_DELAY_MS: u64 = 500;
+const PURGE_INTERVAL: Duration = Duration::from_secs(60);
+
+habitat_core::env_config_duration!(DepartedMemberTtl,
+                                   HAB_DEPARTED_MEMBER_TTL_SECS => from_secs,
+                                   Duration::from_secs(3 * 24 * 60 * 60));
+
+habitat_core::env_config_duration!(OrphanedRumorTtl,
+                                   HAB_ORPHANED_RUMOR_TTL_SECS => from_secs,
+                                   Duration::from_secs(3 * 24 * 60 * 60));
+
+lazy_static! {
+    static ref PURGED_TOTAL: IntCounterVec =
+        register_int_counter_vec!("hab_butterfly_purged_total",
+                                  "Total number of departed members and orphaned rumors purged",
+                                  &["type"]).unwrap();
+}
 
 pub fn spawn_thread(name: String, server: Server, timing: Timing) -> std::io::Result<()> {
     thread::Builder::new().name(name)
@@ -20,6 +48,11 @@ pub fn spawn_thread(name: String, server: Server, timing: Timing) -> std::io::Re
 }
 
 fn run_loop(server: &Server, timing: &Timing) -> ! {
+    let departed_member_ttl: Duration = DepartedMemberTtl::configured_value().into();
+    let orphaned_rumor_ttl: Duration = OrphanedRumorTtl::configured_value().into();
+    let mut orphaned_since = HashMap::new();
+    let mut last_purge = Instant::now();
+
     loop {
         liveliness_checker::mark_thread_alive().and_divergent();
 
@@ -43,6 +76,47 @@ fn run_loop(server: &Server, timing: &Timing) -> ! {
                   .start_hot_rumor(RumorKey::new(RumorType::Member, &id, ""));
         }
 
+        if last_purge.elapsed() >= PURGE_INTERVAL {
+            purge_departed_members(server, departed_member_ttl);
+            purge_orphaned_rumors(server, &mut orphaned_since, orphaned_rumor_ttl);
+            // Purged rumors are remembered until every member has had the chance to purge them
+            // too, whichever the reason they were purged for.
+            server.expire_rumor_tombstones_rsw(departed_member_ttl.max(orphaned_rumor_ttl));
+            last_purge = Instant::now();
+        }
+
         thread::sleep(Duration::from_millis(LOOP_DELAY_MS));
     }
 }
+
+fn purge_departed_members(server: &Server, ttl: Duration) {
+    for id in server.purge_departed_members_rsw_mlw_rhw(ttl) {
+        debug!("Purged member {}, departed for longer than {:?}", id, ttl);
+        PURGED_TOTAL.with_label_values(&[&RumorType::Member.to_string()])
+                    .inc();
+    }
+}
+
+/// Purge the rumors that have been orphaned for longer than `ttl`.
+///
+/// `orphaned_since` records when we first found each rumor orphaned; rumors that are no longer
+/// orphaned are dropped from it.
+fn purge_orphaned_rumors(server: &Server,
+                         orphaned_since: &mut HashMap<RumorKey, Instant>,
+                         ttl: Duration) {
+    let now = Instant::now();
+    let orphaned = server.orphaned_rumors_rsr_mlr()
+                         .into_iter()
+                         .collect::<HashSet<_>>();
+    orphaned_since.retain(|rumor_key, _| orphaned.contains(rumor_key));
+    for rumor_key in orphaned {
+        let since = *orphaned_since.entry(rumor_key.clone()).or_insert(now);
+        if now >= since + ttl && server.purge_rumor_rsw_rhw(&rumor_key) {
+            debug!("Purged {} rumor {}, orphaned for longer than {:?}",
+                   rumor_key.kind, rumor_key, ttl);
+            PURGED_TOTAL.with_label_values(&[&rumor_key.kind.to_string()])
+                        .inc();
+            orphaned_since.remove(&rumor_key);
+        }
+    }
+}
@@ -13,11 +13,13 @@ use crate::{member::Health,
                    Swim,
                    SwimKind}};
 use habitat_common::liveliness_checker;
-use habitat_core::util::ToI64;
+use habitat_core::{tls::gossip::AuthenticatedMember,
+                   util::ToI64};
 use lazy_static::lazy_static;
 use log::{debug,
           error,
-          trace};
+          trace,
+          warn};
 use prometheus::{IntCounterVec,
                  IntGaugeVec,
                  register_int_counter_vec,
@@ -48,10 +50,12 @@ pub fn spawn_thread(name: String,
                           .map(|_| ())
 }
 
-/// Run the thread. Listens for messages up to 1k in size, and then processes them accordingly.
+/// Run the thread. Listens for messages up to 8k in size, and then processes them accordingly.
 /// Takes the Server and a channel to send received Acks to the outbound thread.
 pub fn run_loop(server: &Server, socket: &UdpSocket, tx_outbound: &AckSender) -> ! {
-    let mut recv_buffer: Vec<u8> = vec![0; 1024];
+    // Messages carry the sender's certificate chain when gossip members are authenticated, which
+    // doesn't leave room for much else in 1k
+    let mut recv_buffer: Vec<u8> = vec![0; 8192];
 
     loop {
         liveliness_checker::mark_thread_alive().and_divergent();
@@ -63,8 +67,8 @@ pub fn run_loop(server: &Server, socket: &UdpSocket, tx_outbound: &AckSender) ->
 
         match socket.recv_from(&mut recv_buffer[..]) {
             Ok((length, addr)) => {
-                let swim_payload = match server.unwrap_wire(&recv_buffer[0..length]) {
-                    Ok(swim_payload) => swim_payload,
+                let (swim_payload, sender) = match server.unwrap_wire(&recv_buffer[0..length]) {
+                    Ok(unwrapped) => unwrapped,
                     Err(e) => {
                         // NOTE: In the future, we might want to block people who send us
                         // garbage all the time.
@@ -92,6 +96,19 @@ pub fn run_loop(server: &Server, socket: &UdpSocket, tx_outbound: &AckSender) ->
                     }
                 };
 
+                if let Some(sender) = sender
+                   && !is_sent_by(&msg.kind, &sender)
+                {
+                    warn!("Not processing message from {} - it was sent by a member with a \
+                           certificate for another member ID",
+                          msg.kind.from().id);
+                    let label_values = &["unauthenticated", "failure"];
+                    SWIM_BYTES_RECEIVED.with_label_values(label_values)
+                                       .set(bytes_received.to_i64());
+                    SWIM_MESSAGES_RECEIVED.with_label_values(label_values).inc();
+                    continue;
+                }
+
                 // Setting a label_values variable here throws errors about moving borrowed
                 // content that I couldn't solve w/o clones. Leaving this for now. I'm sure
                 // there's a better way.
@@ -157,6 +174,16 @@ pub fn run_loop(server: &Server, socket: &UdpSocket, tx_outbound: &AckSender) ->
     }
 }
 
+/// Whether the authenticated `sender` is the member the message claims to be from. Acks that are
+/// forwarded on behalf of another member are sent by the member forwarding them, so only their
+/// sender's certificate is checked.
+fn is_sent_by(kind: &SwimKind, sender: &AuthenticatedMember) -> bool {
+    match kind {
+        SwimKind::Ack(ack) if ack.forward_to.is_some() => true,
+        kind => sender.is(&kind.from().id),
+    }
+}
+
 /// Process pingreq messages.
 ///
 /// # Locking (see locking.md)
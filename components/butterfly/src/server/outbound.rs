@@ -128,7 +128,8 @@ fn run_loop(server: &Server, socket: &UdpSocket, rx_inbound: &AckReceiver, timin
                                      .members_write()
                                      .drain()
                                      .collect::<Vec<_>>();
-        let mut check_list = server.member_list.check_list_mlr(&server.member_id);
+        let myself = server.myself.lock_smr().to_member();
+        let mut check_list = server.member_list.check_list_mlr(&myself);
         if !members_to_probe.is_empty() {
             debug!("Probing {} members in the Probe List.",
                    members_to_probe.len());
@@ -225,13 +226,14 @@ fn probe_mlw_smr_rhw(server: &Server,
         return;
     }
 
+    let myself = server.myself.lock_smr().to_member();
     let pingreq_message = PingReq { membership: vec![],
-                                    from:       server.myself.lock_smr().to_member(),
+                                    from:       myself.clone(),
                                     target:     member.clone(), };
     let swim = populate_membership_rumors_mlr_rhw(server, &member, pingreq_message);
 
     server.member_list
-          .with_pingreq_targets_mlr(server.member_id(), &member.id, |pingreq_target| {
+          .with_pingreq_targets_mlr(&myself, &member, |pingreq_target| {
               SWIM_PROBES_SENT.with_label_values(&["pingreq"]).inc();
               pr_timer = Some(SWIM_PROBE_DURATION.with_label_values(&["pingreq/ack"])
                                                  .start_timer());
@@ -5,7 +5,8 @@
 use crate::{ZMQ_CONTEXT,
             rumor::{RumorEnvelope,
                     RumorKind},
-            server::Server};
+            server::{Server,
+                     anti_entropy}};
 use habitat_common::liveliness_checker;
 use habitat_core::util::ToI64;
 use lazy_static::lazy_static;
@@ -30,13 +31,16 @@ lazy_static! {
                                 &["type", "mode", "blocked"]).unwrap();
 }
 
-pub fn spawn_thread(name: String, server: Server) -> std::io::Result<()> {
+pub fn spawn_thread(name: String,
+                    server: Server,
+                    digests: anti_entropy::DigestSender)
+                    -> std::io::Result<()> {
     thread::Builder::new().name(name)
-                          .spawn(move || -> ! { run_loop(&server) })
+                          .spawn(move || -> ! { run_loop(&server, &digests) })
                           .map(|_| ())
 }
 
-fn run_loop(server: &Server) -> ! {
+fn run_loop(server: &Server, digests: &anti_entropy::DigestSender) -> ! {
     habitat_core::env_config_int!(RecvTimeoutMillis, i32, HAB_PULL_RECV_TIMEOUT_MS, 5_000);
 
     let socket = (**ZMQ_CONTEXT).as_mut()
@@ -74,8 +78,8 @@ fn run_loop(server: &Server) -> ! {
             }
         };
 
-        let payload = match server.unwrap_wire(&msg) {
-            Ok(payload) => payload,
+        let (payload, sender) = match server.unwrap_wire(&msg) {
+            Ok(unwrapped) => unwrapped,
             Err(e) => {
                 // NOTE: In the future, we might want to block people who send us
                 // garbage all the time.
@@ -102,6 +106,23 @@ fn run_loop(server: &Server) -> ! {
             }
         };
 
+        // Membership rumors and digests are always sent by the member they are from, unlike other
+        // rumors, which are passed along from the member that started them.
+        if let (Some(sender), RumorKind::Membership(_) | RumorKind::Digest(_)) =
+            (&sender, &proto.kind)
+           && !sender.is(&proto.from_id)
+        {
+            warn!("Not processing message from {} - it was sent by a member with a certificate \
+                   for another member ID",
+                  proto.from_id);
+            let label_values = &["unauthenticated", "failure", "unknown"];
+            GOSSIP_BYTES_RECEIVED.with_label_values(label_values)
+                                 .set(payload.len().to_i64());
+            GOSSIP_MESSAGES_RECEIVED.with_label_values(label_values)
+                                    .inc();
+            continue 'recv;
+        }
+
         let blocked = server.is_member_blocked_sblr(&proto.from_id);
         let blocked_label = if blocked { "true" } else { "false" };
         let label_values = &[&proto.r#type.to_string(), "success", blocked_label];
@@ -137,6 +158,9 @@ fn run_loop(server: &Server) -> ! {
             RumorKind::Departure(departure) => {
                 server.insert_departure_rsw_mlw_rhw(departure);
             }
+            RumorKind::Digest(digest) => {
+                anti_entropy::receive_digest(server, digests, &proto.from_id, digest);
+            }
         }
     }
 }
@@ -6,7 +6,8 @@
 use crate::{ZMQ_CONTEXT,
             member::{Member,
                      Membership},
-            rumor::{RumorEnvelope,
+            rumor::{Digest,
+                    RumorEnvelope,
                     RumorKey,
                     RumorKind,
                     RumorType},
@@ -22,7 +23,8 @@ use prometheus::{IntCounterVec,
                  IntGaugeVec,
                  register_int_counter_vec,
                  register_int_gauge_vec};
-use std::{thread,
+use std::{collections::HashMap,
+          thread,
           time::{Duration,
                  Instant}};
 
@@ -37,8 +39,22 @@ lazy_static! {
         register_int_gauge_vec!("hab_butterfly_gossip_sent_bytes",
                                 "Gossip message size sent in bytes",
                                 &["type", "mode"]).unwrap();
+    static ref HOT_RUMORS: IntGaugeVec =
+        register_int_gauge_vec!("hab_butterfly_hot_rumors",
+                                "Number of rumors that were still hot for the members gossiped \
+                                 to in the last gossip round, summed across members",
+                                &["type"]).unwrap();
 }
 
+/// The rumor types reported by the `hab_butterfly_hot_rumors` metric.
+const HOT_RUMOR_TYPES: [RumorType; 7] = [RumorType::Member,
+                                         RumorType::Service,
+                                         RumorType::Election,
+                                         RumorType::ElectionUpdate,
+                                         RumorType::ServiceConfig,
+                                         RumorType::ServiceFile,
+                                         RumorType::Departure];
+
 pub fn spawn_thread(name: String, server: Server, timing: Timing) -> std::io::Result<()> {
     thread::Builder::new().name(name)
                           .spawn(move || -> ! { run_loop(&server, &timing) })
@@ -60,8 +76,10 @@ fn run_loop(server: &Server, timing: &Timing) -> ! {
 
         server.update_gossip_round();
 
-        let mut check_list = server.member_list.check_list_mlr(server.member_id());
+        let myself = server.myself().lock_smr().to_member();
+        let mut check_list = server.member_list.check_list_mlr(&myself);
         let fanout_loop_start_time = Instant::now();
+        let mut hot_rumors: HashMap<RumorType, i64> = HashMap::new();
 
         'fanout: loop {
             let mut thread_list = Vec::with_capacity(FANOUT);
@@ -85,6 +103,9 @@ fn run_loop(server: &Server, timing: &Timing) -> ! {
                     let rumors = server.rumor_heat
                                        .lock_rhr()
                                        .currently_hot_rumors(&member.id);
+                    for rumor in &rumors {
+                        *hot_rumors.entry(rumor.kind).or_insert(0) += 1;
+                    }
                     if !rumors.is_empty() {
                         let sc = server.clone();
                         let guard = match thread::Builder::new().name(String::from("push-worker"))
@@ -113,6 +134,11 @@ fn run_loop(server: &Server, timing: &Timing) -> ! {
             timing.sleep_for_remaining_gossip_interval(gossip_start_time);
         }
 
+        for rumor_type in HOT_RUMOR_TYPES.iter() {
+            HOT_RUMORS.with_label_values(&[&rumor_type.to_string()])
+                      .set(*hot_rumors.get(rumor_type).unwrap_or(&0));
+        }
+
         // If we've still got any time left in the gossip interval, sleep
         // for that long.
         //
@@ -151,31 +177,10 @@ fn run_loop(server: &Server, timing: &Timing) -> ! {
 // but changing it in the absence of other necessity seems like too much risk for the
 // expected reward.
 #[allow(clippy::cognitive_complexity)]
-fn send_rumors_rsr_mlr_rhw(server: &Server, member: &Member, rumors: &[RumorKey]) {
-    let socket = (**ZMQ_CONTEXT).as_mut()
-                                .socket(zmq::PUSH)
-                                .expect("Failure to create the ZMQ push socket");
-    socket.set_linger(1000)
-          .expect("Failure to set the ZMQ push socket to not linger");
-    socket.set_tcp_keepalive(0)
-          .expect("Failure to set the ZMQ push socket to not use keepalive");
-    socket.set_immediate(true)
-          .expect("Failure to set the ZMQ push socket to immediate");
-    socket.set_sndhwm(1000)
-          .expect("Failure to set the ZMQ push socket hwm");
-    socket.set_sndtimeo(500)
-          .expect("Failure to set the ZMQ send timeout");
-    let to_addr = format!("{}:{}", member.address, member.gossip_port);
-    match socket.connect(&format!("tcp://{}", to_addr)) {
-        Ok(()) => debug!("Connected push socket to {:?}", member),
-        Err(e) => {
-            error!("Cannot connect push socket to {:?}: {:?}", member, e);
-            let label_values = &["socket_connect", "failure"];
-            GOSSIP_MESSAGES_SENT.with_label_values(label_values).inc();
-            GOSSIP_BYTES_SENT.with_label_values(label_values).set(0);
-            return;
-        }
-    }
+pub(super) fn send_rumors_rsr_mlr_rhw(server: &Server, member: &Member, rumors: &[RumorKey]) {
+    let Some(socket) = connect(member) else {
+        return;
+    };
     'rumorlist: for rumor_key in rumors.iter() {
         let rumor_as_bytes = match rumor_key.kind {
             RumorType::Member => {
@@ -289,6 +294,10 @@ fn send_rumors_rsr_mlr_rhw(server: &Server, member: &Member, rumors: &[RumorKey]
                     }
                 }
             }
+            RumorType::Digest => {
+                debug!("Digests are not rumors; not sending one as such");
+                continue 'rumorlist;
+            }
             RumorType::Fake | RumorType::Fake2 => {
                 debug!("You have fake rumors; how odd!");
                 continue 'rumorlist;
@@ -317,8 +326,8 @@ fn send_rumors_rsr_mlr_rhw(server: &Server, member: &Member, rumors: &[RumorKey]
                 debug!("Sent rumor {:?} to {:?}", rumor_key, member);
             }
             Err(e) => {
-                warn!("Could not send rumor to {:?} @ {:?}; ZMQ said: {:?}",
-                      member.id, to_addr, e)
+                warn!("Could not send rumor to {:?} @ {}:{}; ZMQ said: {:?}",
+                      member.id, member.address, member.gossip_port, e)
             }
         }
     }
@@ -326,6 +335,72 @@ fn send_rumors_rsr_mlr_rhw(server: &Server, member: &Member, rumors: &[RumorKey]
     server.rumor_heat.lock_rhw().cool_rumors(&member.id, rumors);
 }
 
+/// Send a digest of our rumor stores to a given member, as part of an anti-entropy exchange.
+pub(super) fn send_digest(server: &Server, member: &Member, digest: Digest) {
+    let Some(socket) = connect(member) else {
+        return;
+    };
+    let rumor = RumorEnvelope { r#type:  RumorType::Digest,
+                                from_id: server.member_id().to_string(),
+                                kind:    RumorKind::Digest(digest), };
+    let payload = match rumor.encode().and_then(|bytes| server.generate_wire(bytes)) {
+        Ok(payload) => payload,
+        Err(e) => {
+            error!("Could not write our digest to bytes; abandoning sending it: {:?}",
+                   e);
+            let label_values = &["digest_encode", "failure"];
+            GOSSIP_MESSAGES_SENT.with_label_values(label_values).inc();
+            GOSSIP_BYTES_SENT.with_label_values(label_values).set(0);
+            return;
+        }
+    };
+    match socket.send(&payload, 0) {
+        Ok(()) => {
+            let label_values = &[&RumorType::Digest.to_string(), "success"];
+            GOSSIP_MESSAGES_SENT.with_label_values(label_values).inc();
+            GOSSIP_BYTES_SENT.with_label_values(label_values)
+                             .set(payload.len().to_i64());
+            debug!("Sent digest to {:?}", member);
+        }
+        Err(e) => {
+            warn!("Could not send digest to {:?}; ZMQ said: {:?}",
+                  member.id, e)
+        }
+    }
+}
+
+/// Create an outbound socket connected to the gossip port of `member`. ZeroMQ may choose to keep
+/// the connection open for 1 second after the socket is dropped.
+fn connect(member: &Member) -> Option<zmq::Socket> {
+    let socket = (**ZMQ_CONTEXT).as_mut()
+                                .socket(zmq::PUSH)
+                                .expect("Failure to create the ZMQ push socket");
+    socket.set_linger(1000)
+          .expect("Failure to set the ZMQ push socket to not linger");
+    socket.set_tcp_keepalive(0)
+          .expect("Failure to set the ZMQ push socket to not use keepalive");
+    socket.set_immediate(true)
+          .expect("Failure to set the ZMQ push socket to immediate");
+    socket.set_sndhwm(1000)
+          .expect("Failure to set the ZMQ push socket hwm");
+    socket.set_sndtimeo(500)
+          .expect("Failure to set the ZMQ send timeout");
+    let to_addr = format!("{}:{}", member.address, member.gossip_port);
+    match socket.connect(&format!("tcp://{}", to_addr)) {
+        Ok(()) => {
+            debug!("Connected push socket to {:?}", member);
+            Some(socket)
+        }
+        Err(e) => {
+            error!("Cannot connect push socket to {:?}: {:?}", member, e);
+            let label_values = &["socket_connect", "failure"];
+            GOSSIP_MESSAGES_SENT.with_label_values(label_values).inc();
+            GOSSIP_BYTES_SENT.with_label_values(label_values).set(0);
+            None
+        }
+    }
+}
+
 /// Given a rumorkey, creates a protobuf rumor for sharing.
 ///
 /// # Locking (see locking.md)
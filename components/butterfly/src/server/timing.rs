@@ -10,6 +10,8 @@ const PINGREQ_TIMING_DEFAULT_MS: u64 = 2100;
 const SUSPICION_TIMEOUT_DEFAULT_PROTOCOL_PERIODS: u64 = 3;
 /// How long to wait between each time we send rumors out.
 const GOSSIP_INTERVAL_DEFAULT_MS: u64 = 1000;
+/// How long to wait between each time we exchange rumor digests with a member.
+const ANTI_ENTROPY_INTERVAL_DEFAULT_MS: u64 = 30_000;
 /// How long before we set a confirmed member to a departed member, removing them from quorums
 ///   just for your own sanity - this is 3 days.
 const DEPARTURE_TIMEOUT_DEFAULT_MS: u64 = 259_200_000;
@@ -23,8 +25,9 @@ pub struct Timing {
     confirm:   Duration,
     departure: Duration,
 
-    gossip_interval:     Duration,
-    swim_probe_interval: Duration,
+    gossip_interval:       Duration,
+    swim_probe_interval:   Duration,
+    anti_entropy_interval: Duration,
 }
 
 impl Default for Timing {
@@ -32,12 +35,13 @@ impl Default for Timing {
         let swim_interval_ms = PING_TIMING_DEFAULT_MS + PINGREQ_TIMING_DEFAULT_MS;
         let confirm_ms = swim_interval_ms * SUSPICION_TIMEOUT_DEFAULT_PROTOCOL_PERIODS;
 
-        Timing { ping:                Duration::from_millis(PING_TIMING_DEFAULT_MS),
-                 pingreq:             Duration::from_millis(PINGREQ_TIMING_DEFAULT_MS),
-                 confirm:             Duration::from_millis(confirm_ms),
-                 departure:           Duration::from_millis(DEPARTURE_TIMEOUT_DEFAULT_MS),
-                 gossip_interval:     Duration::from_millis(GOSSIP_INTERVAL_DEFAULT_MS),
-                 swim_probe_interval: Duration::from_millis(swim_interval_ms), }
+        Timing { ping:                  Duration::from_millis(PING_TIMING_DEFAULT_MS),
+                 pingreq:               Duration::from_millis(PINGREQ_TIMING_DEFAULT_MS),
+                 confirm:               Duration::from_millis(confirm_ms),
+                 departure:             Duration::from_millis(DEPARTURE_TIMEOUT_DEFAULT_MS),
+                 gossip_interval:       Duration::from_millis(GOSSIP_INTERVAL_DEFAULT_MS),
+                 swim_probe_interval:   Duration::from_millis(swim_interval_ms),
+                 anti_entropy_interval: Duration::from_millis(ANTI_ENTROPY_INTERVAL_DEFAULT_MS), }
     }
 }
 
@@ -68,6 +72,12 @@ impl Timing {
     pub fn sleep_for_remaining_swim_protocol_interval(&self, starting_point: Instant) {
         maybe_sleep(starting_point, self.swim_probe_interval)
     }
+
+    /// If the amount of time since `starting_point` is less than an
+    /// anti-entropy interval, sleep for the remainder of that interval.
+    pub fn sleep_for_remaining_anti_entropy_interval(&self, starting_point: Instant) {
+        maybe_sleep(starting_point, self.anti_entropy_interval)
+    }
 }
 
 /// If the amount of time elapsed from `start` is less than `timeout`,
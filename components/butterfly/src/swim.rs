@@ -296,6 +296,16 @@ impl SwimKind {
             SwimKind::ProbePing(_) => "probeping",
         }
     }
+
+    /// The member the message is from.
+    pub fn from(&self) -> &Member {
+        match self {
+            SwimKind::Ping(ping) => &ping.from,
+            SwimKind::Ack(ack) => &ack.from,
+            SwimKind::PingReq(pingreq) => &pingreq.from,
+            SwimKind::ProbePing(probe_ping) => &probe_ping.from,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -0,0 +1,54 @@
+use crate::btest;
+use habitat_butterfly::{member::Health,
+                        rumor::{ConstIdRumor as _,
+                                ServiceConfig}};
+use habitat_core::service::ServiceGroup;
+
+/// Store a service config on `member` without making it a hot rumor, as if it had cooled off
+/// before reaching the other members.
+fn insert_cold_service_config(net: &btest::SwimNet, member: usize, incarnation: u64) {
+    let mut service_config =
+        ServiceConfig::new(net[member].member_id(),
+                           ServiceGroup::new("witcher", "prod", None).unwrap(),
+                           Vec::from("tcp-backlog = 128"));
+    service_config.incarnation = incarnation;
+    net[member].service_config_store.insert_rsw(service_config);
+}
+
+fn service_config_incarnation(net: &btest::SwimNet, member: usize) -> Option<u64> {
+    net[member].service_config_store
+               .lock_rsr()
+               .service_group("witcher.prod")
+               .map_rumor(ServiceConfig::const_id(), |rumor| rumor.incarnation)
+}
+
+#[test]
+fn members_catch_up_on_missed_rumors() {
+    let mut net = btest::SwimNet::new_rhw(2);
+    net.mesh_mlw_smr();
+    assert_wait_for_health_of_mlr!(net, [0..2, 0..2], Health::Alive);
+    // Let the exchanges every member starts with go by, so that only the exchange below can
+    // deliver the rumor.
+    net.wait_for_gossip_rounds(1);
+    insert_cold_service_config(&net, 0, 1);
+    net.wait_for_gossip_rounds(2);
+    assert_eq!(service_config_incarnation(&net, 1), None);
+
+    net[1].exchange_digests_mlr_rsr(net[0].member_id());
+    net.wait_for_gossip_rounds(2);
+    assert_eq!(service_config_incarnation(&net, 1), Some(1));
+}
+
+#[test]
+fn members_catch_up_on_newer_rumors() {
+    let mut net = btest::SwimNet::new_rhw(2);
+    net.mesh_mlw_smr();
+    assert_wait_for_health_of_mlr!(net, [0..2, 0..2], Health::Alive);
+    insert_cold_service_config(&net, 0, 1);
+    insert_cold_service_config(&net, 1, 2);
+
+    net[0].exchange_digests_mlr_rsr(net[1].member_id());
+    net.wait_for_gossip_rounds(2);
+    assert_eq!(service_config_incarnation(&net, 0), Some(2));
+    assert_eq!(service_config_incarnation(&net, 1), Some(2));
+}
@@ -0,0 +1,64 @@
+use crate::btest::{self,
+                   CertificateAuthority};
+use habitat_butterfly::{error::Error,
+                        member::{Health,
+                                 Member},
+                        server::{Server,
+                                 Suitability}};
+use std::{net::{IpAddr,
+                Ipv4Addr,
+                SocketAddr},
+          sync::Arc};
+
+#[derive(Debug)]
+struct ZeroSuitability;
+impl Suitability for ZeroSuitability {
+    fn suitability_for_msr(&self, _service_group: &str) -> u64 { 0 }
+}
+
+#[test]
+fn members_with_certificates_from_a_trusted_authority_gossip() {
+    let ca = CertificateAuthority::generate();
+    let mut net = btest::SwimNet::new_member_authentication_rhw(&[&ca, &ca]);
+    net.connect_smr(0, 1);
+    assert_wait_for_health_of_mlr!(net, [0..2, 0..2], Health::Alive);
+    net.add_service(0, "core/beast/1.2.3/20161208121212");
+    net.wait_for_gossip_rounds(2);
+    assert!(net[1].service_store
+                  .lock_rsr()
+                  .service_group("beast.prod")
+                  .contains_id(net[0].member_id()));
+}
+
+#[test]
+fn members_with_certificates_from_an_untrusted_authority_are_rejected() {
+    let ca = CertificateAuthority::generate();
+    let other_ca = CertificateAuthority::generate();
+    let mut net = btest::SwimNet::new_member_authentication_rhw(&[&ca, &ca, &other_ca]);
+    net.connect_smr(0, 1);
+    net.connect_smr(2, 0);
+    assert_wait_for_health_of_mlr!(net, [0..2, 0..2], Health::Alive);
+    net.add_service(2, "core/beast/1.2.3/20161208121212");
+    net.wait_for_gossip_rounds(2);
+    assert_eq!(net.health_of_mlr(0, 2), None);
+    assert_eq!(net.health_of_mlr(1, 2), None);
+    assert!(!net[0].service_store
+                   .lock_rsr()
+                   .service_group("beast.prod")
+                   .contains_id(net[2].member_id()));
+}
+
+#[test]
+fn members_need_a_certificate_for_their_own_member_id() {
+    let ca = CertificateAuthority::generate();
+    let listen = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0);
+    let result = Server::new(listen,
+                             listen,
+                             Member::default(),
+                             None,
+                             Some(ca.authenticator_for("someone-else")),
+                             None,
+                             None,
+                             Arc::new(ZeroSuitability));
+    assert!(matches!(result, Err(Error::MemberCertificateMismatch(_))));
+}
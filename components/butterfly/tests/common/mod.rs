@@ -1,6 +1,8 @@
 use habitat_butterfly::{error::Error,
-                        member::{Health,
+                        member::{DEFAULT_SEGMENT,
+                                 Health,
                                  Member},
+                        ring_keys::RingKeys,
                         rumor::{ConstIdRumor as _,
                                 Election,
                                 departure::Departure,
@@ -15,7 +17,16 @@ use habitat_butterfly::{error::Error,
 use habitat_core::{crypto::keys::RingKey,
                    package::{Identifiable,
                              PackageIdent},
-                   service::ServiceGroup};
+                   service::ServiceGroup,
+                   tls::gossip::MemberAuthenticator};
+use rcgen::{BasicConstraints,
+            CertificateParams,
+            IsCa,
+            Issuer,
+            KeyPair};
+use rustls::{RootCertStore,
+             pki_types::{CertificateDer,
+                         PrivatePkcs8KeyDer}};
 use std::{net::{IpAddr,
                 Ipv4Addr,
                 SocketAddr},
@@ -38,10 +49,54 @@ impl Suitability for NSuitability {
     fn suitability_for_msr(&self, _service_group: &str) -> u64 { self.0 }
 }
 
+/// A certificate authority that issues the certificates gossip members authenticate with.
+pub struct CertificateAuthority {
+    certificate: CertificateDer<'static>,
+    issuer:      Issuer<'static, KeyPair>,
+}
+
+impl CertificateAuthority {
+    pub fn generate() -> Self {
+        let mut params =
+            CertificateParams::new(Vec::<String>::new()).expect("Cannot create CA parameters");
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let key_pair = KeyPair::generate().expect("Cannot generate CA key");
+        let certificate = params.self_signed(&key_pair)
+                                .expect("Cannot create CA certificate")
+                                .der()
+                                .clone();
+        CertificateAuthority { certificate,
+                               issuer: Issuer::new(params, key_pair) }
+    }
+
+    /// Issue a certificate for `member_id`, returning an authenticator that trusts this
+    /// certificate authority.
+    pub fn authenticator_for(&self, member_id: &str) -> MemberAuthenticator {
+        let params =
+            CertificateParams::new(vec![member_id.to_string()]).expect("Cannot create member \
+                                                                        parameters");
+        let key_pair = KeyPair::generate().expect("Cannot generate member key");
+        let certificate = params.signed_by(&key_pair, &self.issuer)
+                                .expect("Cannot create member certificate");
+        let mut roots = RootCertStore::empty();
+        roots.add(self.certificate.clone())
+             .expect("Cannot trust CA certificate");
+        MemberAuthenticator::new(vec![certificate.der().clone()],
+                                 PrivatePkcs8KeyDer::from(key_pair.serialize_der()),
+                                 roots).expect("Cannot create member authenticator")
+    }
+}
+
 /// # Locking (see locking.md)
 /// * `Server::member` (write)
 /// * `RumorHeat::inner` (write)
-pub fn start_server_smw_rhw(name: &str, ring_key: Option<RingKey>, suitability: u64) -> Server {
+pub fn start_server_smw_rhw(name: &str,
+                            ring_key: Option<RingKey>,
+                            ca: Option<&CertificateAuthority>,
+                            segment: &str,
+                            gateway: bool,
+                            suitability: u64)
+                            -> Server {
     let swim_port;
     let gossip_port;
     {
@@ -55,12 +110,16 @@ pub fn start_server_smw_rhw(name: &str, ring_key: Option<RingKey>, suitability:
     let listen_gossip = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), gossip_port);
     let member = Member { swim_port,
                           gossip_port,
+                          segment: String::from(segment),
+                          gateway,
                           ..Default::default() };
+    let member_id = member.id.clone();
 
     let mut server = Server::new(listen_swim,
                                  listen_gossip,
                                  member,
-                                 ring_key,
+                                 ring_key.map(RingKeys::new),
+                                 ca.map(|ca| ca.authenticator_for(&member_id)),
                                  Some(String::from(name)),
                                  None,
                                  Arc::new(NSuitability(suitability))).unwrap();
@@ -108,6 +167,9 @@ impl SwimNet {
                                         .map(|(x, suitability)| {
                                             start_server_smw_rhw(&format!("{}", x),
                                                                  None,
+                                                                 None,
+                                                                 DEFAULT_SEGMENT,
+                                                                 false,
                                                                  suitability)
                                         })
                                         .collect(), }
@@ -126,11 +188,54 @@ impl SwimNet {
         let mut members = Vec::with_capacity(count);
         for x in 0..count {
             let rk = ring_key.clone();
-            members.push(start_server_smw_rhw(&format!("{}", x), Some(rk), 0));
+            members.push(start_server_smw_rhw(&format!("{}", x),
+                                              Some(rk),
+                                              None,
+                                              DEFAULT_SEGMENT,
+                                              false,
+                                              0));
         }
         SwimNet { members }
     }
 
+    /// Start a network whose members authenticate each other with certificates issued by the
+    /// certificate authority of the same index in `cas`.
+    ///
+    /// # Locking (see locking.md)
+    /// * `RumorHeat::inner` (write)
+    pub fn new_member_authentication_rhw(cas: &[&CertificateAuthority]) -> SwimNet {
+        SwimNet { members: cas.iter()
+                              .enumerate()
+                              .map(|(x, ca)| {
+                                  start_server_smw_rhw(&format!("{}", x),
+                                                       None,
+                                                       Some(ca),
+                                                       DEFAULT_SEGMENT,
+                                                       false,
+                                                       0)
+                              })
+                              .collect(), }
+    }
+
+    /// Start a network whose members are in the gossip segment of the same index in `segments`,
+    /// and are gateways if the segment is paired with `true`.
+    ///
+    /// # Locking (see locking.md)
+    /// * `RumorHeat::inner` (write)
+    pub fn new_segments_rhw(segments: &[(&str, bool)]) -> SwimNet {
+        SwimNet { members: segments.iter()
+                                   .enumerate()
+                                   .map(|(x, (segment, gateway))| {
+                                       start_server_smw_rhw(&format!("{}", x),
+                                                            None,
+                                                            None,
+                                                            segment,
+                                                            *gateway,
+                                                            0)
+                                   })
+                                   .collect(), }
+    }
+
     /// # Locking (see locking.md)
     /// * `Server::member` (read)
     pub fn connect_smr(&mut self, from_entry: usize, to_entry: usize) {
@@ -1,6 +1,9 @@
 use crate::btest;
 use habitat_butterfly::member::Health;
-use habitat_core::crypto::keys::RingKey;
+use habitat_core::crypto::keys::{Key,
+                                 RingKey};
+use std::{thread,
+          time::Duration};
 
 #[test]
 fn symmetric_encryption_of_wire_payloads() {
@@ -15,3 +18,36 @@ fn symmetric_encryption_of_wire_payloads() {
                   .service_group("beast.prod")
                   .contains_id(net[0].member_id()));
 }
+
+#[test]
+fn ring_key_rotation_does_not_partition_the_ring() {
+    let old_key = RingKey::new("wolverine");
+    // Ring key revisions are timestamped to the second
+    thread::sleep(Duration::from_secs(1));
+    let new_key = RingKey::new("wolverine");
+    let mut net = btest::SwimNet::new_ring_encryption_rhw(2, &old_key);
+    net.connect_smr(0, 1);
+    assert_wait_for_health_of_mlr!(net, [0..2, 0..2], Health::Alive);
+
+    net[0].stage_ring_key(new_key.clone()).unwrap();
+    net[1].stage_ring_key(new_key.clone()).unwrap();
+    // Only one member encrypts with the new key
+    net[0].activate_ring_key(new_key.named_revision()).unwrap();
+    net.add_service(0, "core/beast/1.2.3/20161208121212");
+    net.wait_for_gossip_rounds(2);
+    assert!(net[1].service_store
+                  .lock_rsr()
+                  .service_group("beast.prod")
+                  .contains_id(net[0].member_id()));
+
+    net[1].activate_ring_key(new_key.named_revision()).unwrap();
+    net[0].retire_ring_key(old_key.named_revision()).unwrap();
+    net[1].retire_ring_key(old_key.named_revision()).unwrap();
+    net.add_service(1, "core/storm/1.2.3/20161208121212");
+    net.wait_for_gossip_rounds(2);
+    assert!(net[0].service_store
+                  .lock_rsr()
+                  .service_group("storm.prod")
+                  .contains_id(net[1].member_id()));
+    assert_wait_for_health_of_mlr!(net, [0..2, 0..2], Health::Alive);
+}
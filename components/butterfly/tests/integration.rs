@@ -1,7 +1,10 @@
 #[macro_use]
 mod common;
+mod anti_entropy;
+mod authentication;
 mod encryption;
 mod rumor;
+mod segments;
 
 use common as btest;
 use habitat_butterfly::{self,
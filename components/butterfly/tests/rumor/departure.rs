@@ -20,8 +20,8 @@ fn departure_via_client() {
 
     net.wait_for_gossip_rounds(1);
     let mut client =
-        Client::new(&net[0].gossip_addr().to_string(), None).expect("Cannot create Butterfly \
-                                                                     Client");
+        Client::new(&net[0].gossip_addr().to_string(), None, None).expect("Cannot create \
+                                                                           Butterfly Client");
     client.send_departure(net[1].member_id())
           .expect("Cannot send the departure");
     net.wait_for_gossip_rounds(1);
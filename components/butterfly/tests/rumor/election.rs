@@ -280,3 +280,56 @@ fn five_persistent_members_same_leader_multiple_non_quorum_partitions() {
                "OLD: {:?}, NEW: {:?}",
                leader_id, new_leader_id);
 }
+
+fn leader_of(net: &btest::SwimNet, member: usize, service_group: &str) -> Option<(u64, String)> {
+    net[member].election_store
+               .lock_rsr()
+               .service_group(service_group)
+               .map_rumor(Election::const_id(), |e| (e.term, e.member_id.clone()))
+}
+
+#[test]
+fn three_members_elect_the_next_most_suitable_when_the_leader_steps_down() {
+    let mut net = btest::SwimNet::new_with_suitability_rhw(vec![2, 1, 0]);
+    net.mesh_mlw_smr();
+    net.add_service(0, "core/witcher/1.2.3/20161208121212");
+    net.add_service(1, "core/witcher/1.2.3/20161208121212");
+    net.add_service(2, "core/witcher/1.2.3/20161208121212");
+    net.add_election(0, "witcher");
+    assert_wait_for_election_status!(net, [0..3], "witcher.prod", ElectionStatus::Finished);
+    assert_wait_for_equal_election!(net, [0..3, 0..3], "witcher.prod");
+    assert_eq!(leader_of(&net, 2, "witcher.prod"),
+               Some((0, net[0].member_id().to_string())));
+
+    let stepped_down = net[2].hand_off_leadership_rsw_mlr_rhw_msr("witcher.prod", None)
+                             .expect("the leader can step down");
+    assert_eq!(stepped_down, net[0].member_id());
+
+    assert_wait_for_election_status!(net, [0..3], "witcher.prod", ElectionStatus::Finished);
+    assert_wait_for_equal_election!(net, [0..3, 0..3], "witcher.prod");
+    assert_eq!(leader_of(&net, 0, "witcher.prod"),
+               Some((1, net[1].member_id().to_string())));
+}
+
+#[test]
+fn three_members_elect_the_member_leadership_is_transferred_to() {
+    let mut net = btest::SwimNet::new_with_suitability_rhw(vec![2, 1, 0]);
+    net.mesh_mlw_smr();
+    net.add_service(0, "core/witcher/1.2.3/20161208121212");
+    net.add_service(1, "core/witcher/1.2.3/20161208121212");
+    net.add_service(2, "core/witcher/1.2.3/20161208121212");
+    net.add_election(0, "witcher");
+    assert_wait_for_election_status!(net, [0..3], "witcher.prod", ElectionStatus::Finished);
+    assert_wait_for_equal_election!(net, [0..3, 0..3], "witcher.prod");
+
+    let least_suitable = net[2].member_id().to_string();
+    assert!(net[0].hand_off_leadership_rsw_mlr_rhw_msr("witcher.prod", Some("nobody"))
+                  .is_err());
+    net[0].hand_off_leadership_rsw_mlr_rhw_msr("witcher.prod", Some(&least_suitable))
+          .expect("leadership can be transferred to an alive member");
+
+    assert_wait_for_election_status!(net, [0..3], "witcher.prod", ElectionStatus::Finished);
+    assert_wait_for_equal_election!(net, [0..3, 0..3], "witcher.prod");
+    assert_eq!(leader_of(&net, 1, "witcher.prod"),
+               Some((1, least_suitable)));
+}
@@ -0,0 +1,60 @@
+use crate::btest;
+use habitat_butterfly::member::Health;
+
+/// Start a network of two segments of three members each, where the first member of each segment
+/// is a gateway.
+fn two_segments_rhw() -> btest::SwimNet {
+    let mut net = btest::SwimNet::new_segments_rhw(&[("east", true),
+                                                     ("east", false),
+                                                     ("east", false),
+                                                     ("west", true),
+                                                     ("west", false),
+                                                     ("west", false)]);
+    net.mesh_mlw_smr();
+    net
+}
+
+/// Start the network of `two_segments_rhw` and block all traffic between the segments except
+/// between the gateways.
+fn blocked_two_segments_rhw() -> btest::SwimNet {
+    let net = two_segments_rhw();
+    for east in 0..3 {
+        for west in 3..6 {
+            if (east, west) != (0, 3) {
+                net.block(east, west);
+                net.block(west, east);
+            }
+        }
+    }
+    net
+}
+
+#[test]
+fn members_only_probe_their_own_segment() {
+    let net = blocked_two_segments_rhw();
+    net.wait_for_rounds(4);
+    for member in 0..6 {
+        assert_wait_for_health_of_mlr!(net, member, Health::Alive);
+    }
+}
+
+#[test]
+fn rumors_cross_segments_through_gateways() {
+    // Blocking drops rumors by the member that started them rather than the one passing them
+    // along, so the segments are left unblocked here; members only send rumors to the members
+    // they talk to, which leaves the gateways as the only way across.
+    let mut net = two_segments_rhw();
+    net.add_service(1, "core/beast/1.2.3/20161208121212");
+    net.wait_for_gossip_rounds(4);
+    assert!(net[4].service_store
+                  .lock_rsr()
+                  .service_group("beast.prod")
+                  .contains_id(net[1].member_id()));
+}
+
+#[test]
+fn failures_are_detected_in_the_segment_and_relayed_to_the_others() {
+    let mut net = blocked_two_segments_rhw();
+    net[4].pause();
+    assert_wait_for_health_of_mlr!(net, 4, Health::Confirmed);
+}
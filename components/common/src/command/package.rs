@@ -1,3 +1,4 @@
 pub mod binds;
 pub mod config;
 pub mod install;
+pub mod lockfile;
@@ -29,12 +29,16 @@ use crate::{api_client::{self,
                          Error::{APIClientError,
                                  APIError},
                          retry_builder_api},
+            command::package::lockfile::{LockedPackage,
+                                         Lockfile},
             error::{Error,
                     Result},
             templating::hooks::{InstallHook,
                                 PackageMaintenanceHookExt},
             ui::{Status,
                  UIWriter}};
+use futures::stream::{self,
+                      StreamExt};
 use habitat_core::{self,
                    ChannelIdent,
                    crypto::{artifact,
@@ -53,11 +57,13 @@ use habitat_core::{self,
                              PackageInstall,
                              PackageTarget,
                              list::temp_package_directory}};
-use log::debug;
+use log::{debug,
+          warn};
 use reqwest::StatusCode;
 use serde::{Deserialize,
             Serialize};
-use std::{collections::VecDeque,
+use std::{collections::{HashSet,
+                        VecDeque},
           convert::TryFrom,
           fs::{self,
                File},
@@ -75,6 +81,13 @@ pub const RETRIES: usize = 5;
 pub const RETRY_WAIT: Duration = Duration::from_millis(3000);
 pub const DEFAULT_RENAME_TIMEOUT_SECS: u64 = 5;
 
+habitat_core::env_config_int!(/// The number of artifacts downloaded at once when installing a
+                              /// package's dependencies.
+                              DownloadConcurrency,
+                              usize,
+                              HAB_PKG_INSTALL_DOWNLOAD_CONCURRENCY,
+                              4);
+
 /// Represents a locally-available `.hart` file for package
 /// installation purposes only.
 ///
@@ -308,19 +321,15 @@ pub async fn start<U>(ui: &mut U,
                       -> Result<PackageInstall>
     where U: UIWriter
 {
-    let key_cache = KeyCache::new(cache_key_path(fs_root_path));
-    key_cache.setup()?;
-    debug!("install key cache: {}", key_cache.as_ref().display());
-
-    let api_client = Client::new(url, product, version, Some(fs_root_path))?;
-    let task = InstallTask { install_mode,
-                             local_package_usage,
-                             api_client,
-                             channel,
-                             fs_root_path,
-                             artifact_cache_path,
-                             key_cache,
-                             install_hook_mode };
+    let task = InstallTask::new(url,
+                                product,
+                                version,
+                                channel,
+                                fs_root_path,
+                                artifact_cache_path,
+                                install_mode,
+                                local_package_usage,
+                                install_hook_mode)?;
 
     match *install_source {
         InstallSource::Ident(ref ident, target) => {
@@ -332,6 +341,79 @@ pub async fn start<U>(ui: &mut U,
     }
 }
 
+/// Install exactly the packages recorded in `lockfile`.
+///
+/// Every artifact is retrieved by its fully-qualified identifier,
+/// from the local cache or from the Builder at `url`, and must match
+/// the checksum recorded for it; no channel is consulted. Packages
+/// that are already installed are used as-is, though their cached
+/// artifact, if any, is still checked against the lockfile.
+///
+/// Returns the installed root packages of the lockfile.
+#[allow(clippy::too_many_arguments)]
+pub async fn start_from_lockfile<U>(ui: &mut U,
+                                    url: &str,
+                                    lockfile: &Lockfile,
+                                    product: &str,
+                                    version: &str,
+                                    fs_root_path: &Path,
+                                    artifact_cache_path: &Path,
+                                    token: Option<&str>,
+                                    install_mode: &InstallMode,
+                                    install_hook_mode: InstallHookMode)
+                                    -> Result<Vec<PackageInstall>>
+    where U: UIWriter
+{
+    let channel = ChannelIdent::default();
+    let local_package_usage = LocalPackageUsage::default();
+    let task = InstallTask::new(url,
+                                product,
+                                version,
+                                &channel,
+                                fs_root_path,
+                                artifact_cache_path,
+                                install_mode,
+                                &local_package_usage,
+                                install_hook_mode)?;
+    task.with_lockfile(ui, lockfile, token).await
+}
+
+/// Record the given installed packages, and all their dependencies,
+/// in a lockfile.
+///
+/// Checksums are taken from the cached artifacts of the packages;
+/// artifacts missing from the cache are downloaded again.
+#[allow(clippy::too_many_arguments)]
+pub async fn lock<U>(ui: &mut U,
+                     url: &str,
+                     packages: &[PackageInstall],
+                     product: &str,
+                     version: &str,
+                     fs_root_path: &Path,
+                     artifact_cache_path: &Path,
+                     token: Option<&str>,
+                     install_mode: &InstallMode)
+                     -> Result<Lockfile>
+    where U: UIWriter
+{
+    let channel = ChannelIdent::default();
+    let local_package_usage = LocalPackageUsage::default();
+    let task = InstallTask::new(url,
+                                product,
+                                version,
+                                &channel,
+                                fs_root_path,
+                                artifact_cache_path,
+                                install_mode,
+                                &local_package_usage,
+                                InstallHookMode::Ignore)?;
+    let mut lockfile = Lockfile::default();
+    for package in packages {
+        task.lock_package(ui, package, &mut lockfile, token).await?;
+    }
+    Ok(lockfile)
+}
+
 // This is needed because `start` is called asynchronously which requires boxing the future.
 #[allow(clippy::too_many_arguments)]
 pub fn type_erased_start<'a, U>(
@@ -417,6 +499,16 @@ fn read_install_hook_status(path: PathBuf) -> Result<Option<i32>> {
     }
 }
 
+fn download_failed(ident: &FullyQualifiedPackageIdent,
+                   target: PackageTarget,
+                   err: api_client::Error)
+                   -> api_client::Error {
+    APIClientError(APIFailure::DownloadPackageFailed(API_RETRY_COUNT,
+                                                     PackageIdent::from(ident.clone()),
+                                                     target,
+                                                     Box::new(err)))
+}
+
 struct InstallTask<'a> {
     install_mode:        &'a InstallMode,
     local_package_usage: &'a LocalPackageUsage,
@@ -429,6 +521,34 @@ struct InstallTask<'a> {
     install_hook_mode:   InstallHookMode,
 }
 
+impl<'a> InstallTask<'a> {
+    #[allow(clippy::too_many_arguments)]
+    fn new(url: &str,
+           product: &str,
+           version: &str,
+           channel: &'a ChannelIdent,
+           fs_root_path: &'a Path,
+           artifact_cache_path: &'a Path,
+           install_mode: &'a InstallMode,
+           local_package_usage: &'a LocalPackageUsage,
+           install_hook_mode: InstallHookMode)
+           -> Result<Self> {
+        let key_cache = KeyCache::new(cache_key_path(fs_root_path));
+        key_cache.setup()?;
+        debug!("install key cache: {}", key_cache.as_ref().display());
+
+        let api_client = Client::new(url, product, version, Some(fs_root_path))?;
+        Ok(InstallTask { install_mode,
+                         local_package_usage,
+                         api_client,
+                         channel,
+                         fs_root_path,
+                         artifact_cache_path,
+                         key_cache,
+                         install_hook_mode })
+    }
+}
+
 impl InstallTask<'_> {
     /// Install a package from the Depot, based on a given identifier.
     ///
@@ -609,20 +729,30 @@ impl InstallTask<'_> {
             }
         };
 
-        // TODO fn: I'd prefer this list to be a `Vec<FullyQualifiedPackageIdent>` but that
-        // requires a conversion that could fail (i.e. returns a `Result<...>`). Should be
-        // possible though.
+        let dependencies = dependencies.iter()
+                                       .map(FullyQualifiedPackageIdent::try_from)
+                                       .collect::<StdResult<Vec<_>, _>>()?;
+
+        // Download all missing dependencies up front, several at a time. They are still verified
+        // and unpacked one at a time below, in dependency order.
+        if !self.is_offline() {
+            let missing = dependencies.iter()
+                                      .filter(|dependency| {
+                                          self.installed_package(dependency).is_none()
+                                          && !self.is_artifact_cached(dependency)
+                                      })
+                                      .collect::<Vec<_>>();
+            self.fetch_artifacts(ui, &missing, target, token).await?;
+        }
+
         for dependency in dependencies.iter() {
-            if self.installed_package(&FullyQualifiedPackageIdent::try_from(dependency)?)
-                   .is_some()
-            {
+            if self.installed_package(dependency).is_some() {
                 ui.status(Status::Using, dependency)?;
             } else {
-                artifacts_to_install.push_front(self.get_cached_artifact(
-                    ui,
-                    (&FullyQualifiedPackageIdent::try_from(dependency)?, target),
-                    token,
-                ).await?);
+                artifacts_to_install.push_front(self.get_cached_artifact(ui,
+                                                                         (dependency, target),
+                                                                         token)
+                                                    .await?);
             }
         }
 
@@ -646,6 +776,112 @@ impl InstallTask<'_> {
         PackageInstall::load(ident.as_ref(), Some(self.fs_root_path)).map_err(Error::from)
     }
 
+    /// Install the packages of a lockfile, in the order they are
+    /// recorded, verifying each artifact against its checksum.
+    async fn with_lockfile<T>(&self,
+                              ui: &mut T,
+                              lockfile: &Lockfile,
+                              token: Option<&str>)
+                              -> Result<Vec<PackageInstall>>
+        where T: UIWriter
+    {
+        let roots = lockfile.roots
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>();
+        ui.begin(format!("Installing {} from lockfile", roots.join(", ")))?;
+
+        // Download all missing artifacts up front, as `install_package` does for dependencies.
+        if !self.is_offline() {
+            let targets = lockfile.packages
+                                  .iter()
+                                  .map(|package| package.target)
+                                  .collect::<HashSet<_>>();
+            for target in targets {
+                let missing = lockfile.packages
+                                      .iter()
+                                      .filter(|package| {
+                                          package.target == target
+                                          && self.installed_package(&package.ident).is_none()
+                                          && !self.is_artifact_cached(&package.ident)
+                                      })
+                                      .map(|package| &package.ident)
+                                      .collect::<Vec<_>>();
+                self.fetch_artifacts(ui, &missing, target, token).await?;
+            }
+        }
+
+        let mut artifacts_to_install = Vec::with_capacity(lockfile.packages.len());
+        for package in lockfile.packages.iter() {
+            if self.installed_package(&package.ident).is_some() {
+                if self.is_artifact_cached(&package.ident) {
+                    package.verify(&PackageArchive::new(self.cached_artifact_path(&package.ident))?)?;
+                }
+                ui.status(Status::Using, &package.ident)?;
+            } else {
+                let artifact =
+                    self.get_cached_artifact(ui, (&package.ident, package.target), token)
+                        .await?;
+                if let Err(err) = package.verify(&artifact) {
+                    self.discard_cached_artifact(&package.ident);
+                    return Err(err);
+                }
+                artifacts_to_install.push(artifact);
+            }
+        }
+
+        for artifact in artifacts_to_install.iter_mut() {
+            self.unpack_artifact(ui, artifact)?;
+        }
+
+        let mut installed = Vec::with_capacity(lockfile.roots.len());
+        for root in lockfile.roots.iter() {
+            let package = PackageInstall::load(root.as_ref(), Some(self.fs_root_path))?;
+            if self.install_hook_mode != InstallHookMode::Ignore {
+                check_install_hooks(ui, &package, self.fs_root_path, token).await?;
+            }
+            installed.push(package);
+        }
+
+        ui.end(format!("Install of {} complete with {} new packages installed.",
+                       roots.join(", "),
+                       artifacts_to_install.len()))?;
+        Ok(installed)
+    }
+
+    /// Add an installed package and its dependencies to `lockfile`.
+    async fn lock_package<T>(&self,
+                             ui: &mut T,
+                             package: &PackageInstall,
+                             lockfile: &mut Lockfile,
+                             token: Option<&str>)
+                             -> Result<()>
+        where T: UIWriter
+    {
+        let root = FullyQualifiedPackageIdent::try_from(package.ident())?;
+        // tdeps are ordered high to low so we reverse the list to lock lower level deps first
+        let mut idents = package.tdeps()?
+                                .iter()
+                                .rev()
+                                .map(FullyQualifiedPackageIdent::try_from)
+                                .collect::<StdResult<Vec<_>, _>>()?;
+        idents.push(root.clone());
+
+        for ident in idents {
+            if lockfile.contains(&ident) {
+                continue;
+            }
+            let artifact =
+                self.get_cached_artifact(ui, (&ident, PackageTarget::active_target()), token)
+                    .await?;
+            lockfile.add(LockedPackage { target: artifact.target()?,
+                                         checksum: artifact.checksum()?,
+                                         ident });
+        }
+        lockfile.add_root(root);
+        Ok(())
+    }
+
     /// This ensures the identified package is in the local cache,
     /// verifies it, and returns a handle to the package's metadata.
     async fn get_cached_artifact<T>(&self,
@@ -839,6 +1075,21 @@ impl InstallTask<'_> {
         self.artifact_cache_path.join(ident.archive_name())
     }
 
+    /// Remove the cached artifact of a package which failed verification, so that the next
+    /// install downloads it again instead of reusing it. Offline installs could not download
+    /// it again, so they leave it in place.
+    fn discard_cached_artifact(&self, ident: &FullyQualifiedPackageIdent) {
+        if self.is_offline() {
+            return;
+        }
+        let path = self.cached_artifact_path(ident);
+        warn!("Removing {} from the artifact cache as it failed verification",
+              path.display());
+        if let Err(err) = fs::remove_file(&path) {
+            debug!("Failed to remove {}: {}", path.display(), err);
+        }
+    }
+
     async fn fetch_latest_pkg_ident_for(&self,
                                         (ident, target): (&PackageIdent, PackageTarget),
                                         token: Option<&str>)
@@ -877,12 +1128,67 @@ impl InstallTask<'_> {
                                ui.progress())
                 .await
         }).await
-          .map_err(|e| {
-              APIClientError(APIFailure::DownloadPackageFailed(API_RETRY_COUNT,
-                                                               PackageIdent::from(ident.clone()),
-                                                               target,
-                                                               Box::new(e)))
-          })?;
+          .map_err(|e| download_failed(ident, target, e))?;
+
+        Ok(())
+    }
+
+    /// Retrieve the identified packages from the depot, up to
+    /// `DownloadConcurrency` of them at a time, ensuring that the
+    /// artifacts are cached locally.
+    async fn fetch_artifacts<T>(&self,
+                                ui: &mut T,
+                                idents: &[&FullyQualifiedPackageIdent],
+                                target: PackageTarget,
+                                token: Option<&str>)
+                                -> Result<()>
+        where T: UIWriter
+    {
+        let concurrency = usize::from(DownloadConcurrency::configured_value()).max(1);
+        if concurrency == 1 || idents.len() < 2 {
+            for &ident in idents {
+                self.fetch_artifact(ui, (ident, target), token).await?;
+            }
+            return Ok(());
+        }
+
+        ui.status(Status::Downloading,
+                  format!("{} dependencies for {}, {} at a time",
+                          idents.len(),
+                          target,
+                          concurrency))?;
+        // Progress bars can't be shown for several downloads at once, so
+        // each artifact is reported once it is in the cache instead.
+        let downloads =
+            idents.iter()
+                  .map(|&ident| {
+                      async move { (ident, self.download_artifact((ident, target), token).await) }
+                  })
+                  .collect::<Vec<_>>();
+        let mut downloads = stream::iter(downloads).buffer_unordered(concurrency);
+        while let Some((ident, result)) = downloads.next().await {
+            result?;
+            ui.status(Status::Cached, ident)?;
+        }
+        Ok(())
+    }
+
+    /// Retrieve the identified package from the depot without
+    /// reporting progress, ensuring that the artifact is cached
+    /// locally.
+    async fn download_artifact(&self,
+                               (ident, target): (&FullyQualifiedPackageIdent, PackageTarget),
+                               token: Option<&str>)
+                               -> Result<()> {
+        retry_builder_api!(async {
+            self.api_client
+                .fetch_package((ident.as_ref(), target),
+                               token,
+                               self.artifact_cache_path,
+                               None)
+                .await
+        }).await
+          .map_err(|e| download_failed(ident, target, e))?;
 
         Ok(())
     }
@@ -989,7 +1295,10 @@ impl InstallTask<'_> {
             self.fetch_origin_key(ui, &named_revision, token).await?;
         };
 
-        artifact::verify(&artifact.path, &self.key_cache)?;
+        if let Err(err) = artifact::verify(&artifact.path, &self.key_cache) {
+            self.discard_cached_artifact(ident);
+            return Err(err.into());
+        }
 
         debug!("Verified {} signed by {}", ident, named_revision);
         Ok(())
@@ -1070,3 +1379,85 @@ impl InstallTask<'_> {
         Ok(res)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ui::NullUi;
+    use habitat_core::{fs::cache_artifact_path,
+                       package::metadata::MetaFile};
+    use tempfile::TempDir;
+
+    const IDENT: &str = "happyhumans/possums/8.1.4/20160427165340";
+    const ARTIFACT: &str = "happyhumans-possums-8.1.4-20160427165340-x86_64-linux.hart";
+    const PUBLIC_KEY: &str = "happyhumans-20160424223347.pub";
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests")
+                                                 .join("fixtures")
+                                                 .join(name)
+    }
+
+    /// A lockfile of the fixture artifact, with a checksum that doesn't match it.
+    fn lockfile_with_wrong_checksum() -> Lockfile {
+        let ident: FullyQualifiedPackageIdent = IDENT.parse().unwrap();
+        let mut lockfile = Lockfile::default();
+        lockfile.add(LockedPackage { ident:    ident.clone(),
+                                     target:   PackageTarget::active_target(),
+                                     checksum: String::from("0123456789abcdef"), });
+        lockfile.add_root(ident);
+        lockfile
+    }
+
+    /// Puts the fixture artifact in the artifact cache of `fs_root`.
+    fn cache_fixture_artifact(fs_root: &Path) {
+        let ident: FullyQualifiedPackageIdent = IDENT.parse().unwrap();
+        let artifact_cache = cache_artifact_path(Some(fs_root));
+        fs::create_dir_all(&artifact_cache).unwrap();
+        fs::copy(fixture(ARTIFACT), artifact_cache.join(ident.archive_name())).unwrap();
+    }
+
+    async fn install_offline(fs_root: &Path, lockfile: &Lockfile) -> Result<Vec<PackageInstall>> {
+        start_from_lockfile(&mut NullUi::new(),
+                            "https://bldr.habitat.sh",
+                            lockfile,
+                            "hab-test",
+                            "0.0.0",
+                            fs_root,
+                            &cache_artifact_path(Some(fs_root)),
+                            None,
+                            &InstallMode::Offline,
+                            InstallHookMode::Ignore).await
+    }
+
+    // The fixture artifact must be for the active target to be installed
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    #[tokio::test]
+    async fn install_from_lockfile_fails_on_checksum_mismatch() {
+        let fs_root = TempDir::new().unwrap();
+        cache_fixture_artifact(fs_root.path());
+        let key_cache = cache_key_path(fs_root.path());
+        fs::create_dir_all(&key_cache).unwrap();
+        fs::copy(fixture(PUBLIC_KEY), key_cache.join(PUBLIC_KEY)).unwrap();
+
+        let result = install_offline(fs_root.path(), &lockfile_with_wrong_checksum()).await;
+        assert!(matches!(result, Err(Error::LockfileChecksumMismatch(..))));
+        let ident: PackageIdent = IDENT.parse().unwrap();
+        assert!(!pkg_install_path(&ident, Some(fs_root.path())).exists());
+    }
+
+    #[tokio::test]
+    async fn install_from_lockfile_fails_when_cached_artifact_of_installed_package_mismatches() {
+        let fs_root = TempDir::new().unwrap();
+        cache_fixture_artifact(fs_root.path());
+        let ident: PackageIdent = IDENT.parse().unwrap();
+        let install_path = pkg_install_path(&ident, Some(fs_root.path()));
+        fs::create_dir_all(&install_path).unwrap();
+        fs::write(install_path.join(MetaFile::Ident.to_string()), IDENT).unwrap();
+        fs::write(install_path.join(MetaFile::Target.to_string()),
+                  PackageTarget::active_target().to_string()).unwrap();
+
+        let result = install_offline(fs_root.path(), &lockfile_with_wrong_checksum()).await;
+        assert!(matches!(result, Err(Error::LockfileChecksumMismatch(..))));
+    }
+}
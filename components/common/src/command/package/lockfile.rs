@@ -0,0 +1,207 @@
+//! Lockfiles record the exact set of packages an install resolved to.
+//!
+//! Installing a partially-qualified identifier resolves it (and its
+//! transitive dependencies) against a channel, which changes over
+//! time. A lockfile pins the result of one such resolution: the
+//! fully-qualified identifier, target and Blake2b checksum of every
+//! package involved, in the order they must be installed. Installing
+//! from a lockfile installs exactly those artifacts, or fails.
+//!
+//! # Examples
+//!
+//! ```toml
+//! version = 1
+//! roots = ["core/redis/4.0.14/20190319155852"]
+//!
+//! [[package]]
+//! ident = "core/glibc/2.27/20190115002733"
+//! target = "x86_64-linux"
+//! checksum = "3d5a0b1d0b8cf0d4b8ae9d7ab2a8f8c3e8b5b7a0e2c4d6f8a1b3c5d7e9f1a3b5"
+//!
+//! [[package]]
+//! ident = "core/redis/4.0.14/20190319155852"
+//! target = "x86_64-linux"
+//! checksum = "9e1f0ac2b4d6f8a0c2e4f6a8b0d2f4a6c8e0a2c4e6a8c0e2a4c6e8a0c2e4f6a8"
+//! ```
+
+use crate::error::{Error,
+                   Result};
+use habitat_core::{package::{FullyQualifiedPackageIdent,
+                             PackageArchive,
+                             PackageTarget},
+                   util};
+use serde::{Deserialize,
+            Serialize};
+use std::{fs,
+          path::Path,
+          str::FromStr};
+
+/// The version of the lockfile format written by this release.
+pub const LOCKFILE_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Lockfile {
+    pub version:  u32,
+    /// The packages that were asked for, as opposed to the
+    /// dependencies they pulled in.
+    #[serde(with = "util::serde::string_vec")]
+    pub roots:    Vec<FullyQualifiedPackageIdent>,
+    /// Every package of the resolved set, dependencies before the
+    /// packages that depend on them.
+    #[serde(default, rename = "package")]
+    pub packages: Vec<LockedPackage>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct LockedPackage {
+    #[serde(with = "util::serde::string")]
+    pub ident:    FullyQualifiedPackageIdent,
+    pub target:   PackageTarget,
+    /// The Blake2b checksum of the package's `.hart` file
+    pub checksum: String,
+}
+
+impl LockedPackage {
+    /// Fails unless `artifact` is the exact artifact that was locked.
+    pub fn verify(&self, artifact: &PackageArchive) -> Result<()> {
+        let checksum = artifact.checksum()?;
+        if checksum == self.checksum {
+            Ok(())
+        } else {
+            Err(Error::LockfileChecksumMismatch(Box::new(self.ident
+                                                             .clone()),
+                                                self.checksum.clone(),
+                                                checksum))
+        }
+    }
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Lockfile { version:  LOCKFILE_VERSION,
+                   roots:    Vec::new(),
+                   packages: Vec::new(), }
+    }
+}
+
+impl Lockfile {
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        fs::read_to_string(path.as_ref())?.parse()
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path.as_ref(), self.to_toml_string()?)?;
+        Ok(())
+    }
+
+    pub fn to_toml_string(&self) -> Result<String> { Ok(toml::to_string(self)?) }
+
+    /// Records `ident` as one of the packages that were asked for.
+    pub fn add_root(&mut self, ident: FullyQualifiedPackageIdent) {
+        if !self.roots.contains(&ident) {
+            self.roots.push(ident);
+        }
+    }
+
+    /// Appends `package` to the resolved set, unless it is already
+    /// part of it. Callers add dependencies before their dependents.
+    pub fn add(&mut self, package: LockedPackage) {
+        if !self.contains(&package.ident) {
+            self.packages.push(package);
+        }
+    }
+
+    pub fn contains(&self, ident: &FullyQualifiedPackageIdent) -> bool {
+        self.packages.iter().any(|package| &package.ident == ident)
+    }
+}
+
+impl FromStr for Lockfile {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let lockfile: Lockfile = toml::from_str(s).map_err(Error::TomlParser)?;
+        if lockfile.version != LOCKFILE_VERSION {
+            return Err(Error::LockfileVersionUnsupported(lockfile.version));
+        }
+        if let Some(root) = lockfile.roots.iter().find(|root| !lockfile.contains(root)) {
+            return Err(Error::LockfileRootNotLocked(root.clone()));
+        }
+        Ok(lockfile)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn locked(ident: &str) -> LockedPackage {
+        LockedPackage { ident:    ident.parse().unwrap(),
+                        target:   PackageTarget::active_target(),
+                        checksum: String::from("0123456789abcdef"), }
+    }
+
+    const ARTIFACT: &str = "happyhumans-possums-8.1.4-20160427165340-x86_64-linux.hart";
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests")
+                                                 .join("fixtures")
+                                                 .join(name)
+    }
+
+    #[test]
+    fn verify_fails_on_checksum_mismatch() {
+        let artifact = PackageArchive::new(fixture(ARTIFACT)).unwrap();
+        let mut package = locked("happyhumans/possums/8.1.4/20160427165340");
+        assert!(matches!(package.verify(&artifact),
+                         Err(Error::LockfileChecksumMismatch(..))));
+
+        package.checksum = artifact.checksum().unwrap();
+        assert!(package.verify(&artifact).is_ok());
+    }
+
+    #[test]
+    fn lockfile_round_trips_through_toml() {
+        let mut lockfile = Lockfile::default();
+        lockfile.add(locked("core/glibc/2.27/20190115002733"));
+        lockfile.add(locked("core/redis/4.0.14/20190319155852"));
+        lockfile.add_root("core/redis/4.0.14/20190319155852".parse().unwrap());
+
+        let toml = lockfile.to_toml_string().unwrap();
+        assert!(toml.contains("roots = [\"core/redis/4.0.14/20190319155852\"]"));
+        assert!(toml.contains("[[package]]"));
+        assert_eq!(toml.parse::<Lockfile>().unwrap(), lockfile);
+    }
+
+    #[test]
+    fn add_skips_packages_already_locked() {
+        let mut lockfile = Lockfile::default();
+        lockfile.add(locked("core/glibc/2.27/20190115002733"));
+        lockfile.add(locked("core/redis/4.0.14/20190319155852"));
+        lockfile.add(locked("core/glibc/2.27/20190115002733"));
+        assert_eq!(lockfile.packages.len(), 2);
+        assert_eq!(lockfile.packages[0].ident.to_string(),
+                   "core/glibc/2.27/20190115002733");
+    }
+
+    #[test]
+    fn parse_rejects_unknown_versions() {
+        let toml = "version = 2\nroots = []\n";
+        assert!(matches!(toml.parse::<Lockfile>(),
+                         Err(Error::LockfileVersionUnsupported(2))));
+    }
+
+    #[test]
+    fn parse_rejects_roots_that_are_not_locked() {
+        let toml = "version = 1\nroots = [\"core/redis/4.0.14/20190319155852\"]\n";
+        assert!(matches!(toml.parse::<Lockfile>(),
+                         Err(Error::LockfileRootNotLocked(_))));
+    }
+
+    #[test]
+    fn parse_rejects_partially_qualified_idents() {
+        let toml = "version = 1\nroots = [\"core/redis\"]\n";
+        assert!(toml.parse::<Lockfile>().is_err());
+    }
+}
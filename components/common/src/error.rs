@@ -83,6 +83,11 @@ pub enum Error {
     /// Errors when joining paths :)
     JoinPathsError(env::JoinPathsError),
     ListenCtlResolutionError(String, io::Error),
+    /// Occurs when an artifact does not match the checksum a lockfile
+    /// recorded for it.
+    LockfileChecksumMismatch(Box<FullyQualifiedPackageIdent>, String, String),
+    LockfileRootNotLocked(FullyQualifiedPackageIdent),
+    LockfileVersionUnsupported(u32),
     MissingCLIInputError(String),
     NamedPipeTimeoutOnStart(String, String, io::Error),
     NativeTls(native_tls::Error),
@@ -207,6 +212,19 @@ impl fmt::Display for Error {
             Error::ListenCtlResolutionError(ref sup_addr, ref err) => {
                 format!("Failed to resolve ctl address '{}': {}", sup_addr, err,)
             }
+            Error::LockfileChecksumMismatch(ref ident, ref expected, ref actual) => {
+                format!("Checksum mismatch for {}: the lockfile expects {} but the artifact is {}",
+                        ident, expected, actual)
+            }
+            Error::LockfileRootNotLocked(ref ident) => {
+                format!("Lockfile lists {} as a root package but does not lock it",
+                        ident)
+            }
+            Error::LockfileVersionUnsupported(version) => {
+                format!("Unsupported lockfile version {}, expected {}",
+                        version,
+                        crate::command::package::lockfile::LOCKFILE_VERSION)
+            }
             Error::RootRequired => {
                 "Root or administrator permissions required to complete operation".to_string()
             }
@@ -203,6 +203,19 @@ impl PackageGraph {
     pub fn rdeps(&self, package: &PackageIdent) -> Vec<&PackageIdent> {
         self.neighbours(package, petgraph::Incoming)
     }
+
+    /// Returns every package in the graph, ordered so that each package comes after all of its
+    /// dependencies.
+    ///
+    /// Returns `Err` with one of the packages in a dependency cycle if the graph has one.
+    pub fn dependency_order(&self) -> std::result::Result<Vec<&PackageIdent>, &PackageIdent> {
+        let weight = |idx: NodeIndex| self.graph.node_weight(idx).unwrap();
+        match petgraph::algo::toposort(&self.graph, None) {
+            // `toposort` places each package before the packages it depends on, so reverse it
+            Ok(order) => Ok(order.into_iter().rev().map(weight).collect()),
+            Err(cycle) => Err(weight(cycle.node_id())),
+        }
+    }
 }
 
 impl Default for PackageGraph {
@@ -560,4 +573,39 @@ mod tests {
         let expected = vec![&b, &c, &d];
         assert_eq!(expected, odeps);
     }
+
+    #[test]
+    fn dependency_order_places_deps_first() {
+        let a = PackageIdent::from_str("core/redis/2.1.0/20180704142101").unwrap();
+        let b = PackageIdent::from_str("core/foo/1.0/20180704142702").unwrap();
+        let c = PackageIdent::from_str("core/bar/1.0/20180704142805").unwrap();
+        let d = PackageIdent::from_str("core/baz/1.0/20180704142805").unwrap();
+        let packages = vec![package_deps(d.clone(), &[b.clone(), c.clone()]),
+                            package_deps(c.clone(), std::slice::from_ref(&a)),
+                            package_deps(b.clone(), std::slice::from_ref(&a)),
+                            empty_package_deps(a.clone()),];
+
+        let graph = build(&packages);
+        let order = graph.dependency_order().unwrap();
+        assert_eq!(order.len(), 4);
+        let position = |p: &PackageIdent| order.iter().position(|&o| o == p).unwrap();
+        assert!(position(&a) < position(&b));
+        assert!(position(&a) < position(&c));
+        assert!(position(&b) < position(&d));
+        assert!(position(&c) < position(&d));
+    }
+
+    #[test]
+    fn dependency_order_detects_cycles() {
+        let a = PackageIdent::from_str("core/redis/2.1.0/20180704142101").unwrap();
+        let b = PackageIdent::from_str("core/foo/1.0/20180704142702").unwrap();
+        let c = PackageIdent::from_str("core/bar/1.0/20180704142805").unwrap();
+        let packages = vec![package_deps(a.clone(), std::slice::from_ref(&b)),
+                            package_deps(b.clone(), std::slice::from_ref(&a)),
+                            empty_package_deps(c),];
+
+        let graph = build(&packages);
+        let in_cycle = graph.dependency_order().unwrap_err();
+        assert!(in_cycle == &a || in_cycle == &b);
+    }
 }
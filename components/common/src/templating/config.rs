@@ -356,6 +356,11 @@ impl CfgRenderer {
         Ok(CfgRenderer(renderer))
     }
 
+    /// The paths of the configuration files that `compile` writes, relative to its render path.
+    pub fn rendered_files(&self) -> impl Iterator<Item = &str> {
+        self.0.get_templates().keys().map(String::as_str)
+    }
+
     /// Compile and write all configuration files to the configuration directory.
     ///
     /// Returns `true` if the configuration has changed.
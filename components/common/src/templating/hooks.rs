@@ -122,6 +122,17 @@ pub trait Hook: fmt::Debug + Sized + Send {
         }
     }
 
+    /// Render a hook without writing it to its destination service directory.
+    ///
+    /// Returns `true` if compiling the hook would change it.
+    fn would_change<T>(&self, ctx: &T) -> Result<bool>
+        where T: Serialize
+    {
+        let content = self.renderer().0.render(Self::FILE_NAME, ctx)?;
+        let path = self.path().with_file_name(Self::FILE_NAME);
+        Ok(hash_content(&path)? != Some(Blake2bHash::from_bytes(&content)))
+    }
+
     #[cfg(not(windows))]
     fn set_permissions<T: AsRef<Path>>(path: T) -> habitat_core::error::Result<()> {
         use habitat_core::util::posix_perm;
@@ -178,12 +189,27 @@ pub trait Hook: fmt::Debug + Sized + Send {
                    svc_encrypted_password: Option<T>)
                    -> Result<Self::ExitValue>
         where T: ToString
+    {
+        self.run_reporting_pid(service_group, pkg, svc_encrypted_password, |_| {})
+    }
+
+    /// Run a compiled hook, passing the ID of its process to `spawned` as soon as it is started,
+    /// so that the caller can terminate it if it stops waiting for the hook.
+    fn run_reporting_pid<T, F>(&self,
+                               service_group: &str,
+                               pkg: &Pkg,
+                               svc_encrypted_password: Option<T>,
+                               spawned: F)
+                               -> Result<Self::ExitValue>
+        where T: ToString,
+              F: FnOnce(u32)
     {
         let mut child = Self::exec(self.path(), pkg, svc_encrypted_password).map_err(|err| {
                             outputln!(preamble service_group,
                                       "Hook failed to run, {}, {}", Self::FILE_NAME, err);
                             err
                         })?;
+        spawned(child.id());
         let mut hook_output = HookOutput::new(self.stdout_log_path(), self.stderr_log_path());
         hook_output.output_standard_streams::<Self>(service_group, &mut child);
         Ok(child.wait()
@@ -214,30 +240,10 @@ pub trait Hook: fmt::Debug + Sized + Send {
         where T: ToString,
               S: AsRef<OsStr>
     {
-        use habitat_core::os::{process,
-                               users};
-        use nix::unistd::{Gid,
-                          Uid};
+        use habitat_core::os::process;
         use std::ops::Deref;
 
-        let ids = if process::can_run_services_as_svc_user() {
-            // If we can SETUID/SETGID, then run the script as the service
-            // user; otherwise, we'll just run it as ourselves.
-            let uid = users::get_uid_by_name(&pkg.svc_user)?
-                .map(Uid::from_raw)
-                .ok_or_else(|| {Error::PermissionFailed(format!("No uid for user '{}' could be found", &pkg.svc_user))})?;
-            let gid = users::get_gid_by_name(&pkg.svc_group)?
-                .map(Gid::from_raw)
-                .ok_or_else(|| {Error::PermissionFailed(format!("No gid for group '{}' could be found", &pkg.svc_group))})?;
-            Some((uid, gid))
-        } else {
-            debug!("Current user lacks sufficient capabilites to run {:?} as \"{}\"; running as \
-                    self!",
-                   path.as_ref(),
-                   &pkg.svc_user);
-            None
-        };
-
+        let ids = svc_ids(pkg, path.as_ref())?;
         let mut cmd = process::exec::unix::hook_command(path, pkg.env.deref(), ids);
         Ok(cmd.spawn()?)
     }
@@ -256,6 +262,79 @@ pub trait Hook: fmt::Debug + Sized + Send {
     fn stderr_log_path(&self) -> &Path;
 }
 
+/// Run the shell command `command` in place of the hook `H`, with the service's environment and
+/// as the service's user. Its output is streamed and logged just like that of `H`.
+pub fn run_command<H, T>(command: &str,
+                         service_group: &str,
+                         pkg: &Pkg,
+                         svc_encrypted_password: Option<T>)
+                         -> Result<ExitStatus>
+    where H: Hook,
+          T: ToString
+{
+    let mut child = spawn_command(command, pkg, svc_encrypted_password)?;
+    let stdout_log_path = stdout_log_path::<H>(&pkg.name);
+    let stderr_log_path = stderr_log_path::<H>(&pkg.name);
+    let mut output = HookOutput::new(&stdout_log_path, &stderr_log_path);
+    output.output_standard_streams::<H>(service_group, &mut child);
+    Ok(child.wait()?)
+}
+
+/// Spawn the shell command `command` with the service's environment, running as the service's
+/// user the same way its hooks are.
+#[cfg(windows)]
+fn spawn_command<T>(command: &str, pkg: &Pkg, svc_encrypted_password: Option<T>) -> Result<Child>
+    where T: ToString
+{
+    use habitat_core::util;
+
+    Ok(util::spawn_pwsh(command,
+                        &pkg.env.to_hash_map(),
+                        &pkg.svc_user,
+                        svc_encrypted_password)?)
+}
+
+/// Spawn the shell command `command` with the service's environment, running as the service's
+/// user the same way its hooks are.
+#[cfg(unix)]
+fn spawn_command<T>(command: &str, pkg: &Pkg, _: Option<T>) -> Result<Child>
+    where T: ToString
+{
+    use habitat_core::os::process;
+    use std::ops::Deref;
+
+    let ids = svc_ids(pkg, OsStr::new(command))?;
+    let mut cmd = process::exec::unix::hook_command("/bin/sh", pkg.env.deref(), ids);
+    cmd.arg("-c").arg(command);
+    Ok(cmd.spawn()?)
+}
+
+/// The user and group that `program` should be run as on behalf of the service, if we are able
+/// to switch to them.
+#[cfg(unix)]
+fn svc_ids(pkg: &Pkg, program: &OsStr) -> Result<Option<(nix::unistd::Uid, nix::unistd::Gid)>> {
+    use habitat_core::os::{process,
+                           users};
+    use nix::unistd::{Gid,
+                      Uid};
+
+    if process::can_run_services_as_svc_user() {
+        // If we can SETUID/SETGID, then run the script as the service
+        // user; otherwise, we'll just run it as ourselves.
+        let uid = users::get_uid_by_name(&pkg.svc_user)?
+            .map(Uid::from_raw)
+            .ok_or_else(|| {Error::PermissionFailed(format!("No uid for user '{}' could be found", &pkg.svc_user))})?;
+        let gid = users::get_gid_by_name(&pkg.svc_group)?
+            .map(Gid::from_raw)
+            .ok_or_else(|| {Error::PermissionFailed(format!("No gid for group '{}' could be found", &pkg.svc_group))})?;
+        Ok(Some((uid, gid)))
+    } else {
+        debug!("Current user lacks sufficient capabilites to run {:?} as \"{}\"; running as self!",
+               program, &pkg.svc_user);
+        Ok(None)
+    }
+}
+
 /// A trait that adds a convenient method for executing one-off hooks
 ///
 /// This trait unifies the logic the `install` and `uninstall` hooks use to execute. These hooks
@@ -7,10 +7,13 @@ use crate::{error::{Error,
                     package::{FullyQualifiedPackageIdent,
                               PackageIdent,
                               PackageInstall},
+                    service::{HealthCheck,
+                              Schedule},
                     util},
             util::path};
 use habitat_core::package::metadata::PackageType;
-use log::debug;
+use log::{debug,
+          warn};
 use serde::{Deserialize,
             Serialize};
 use std::{collections::{BTreeMap,
@@ -101,6 +104,10 @@ pub struct Pkg {
     pub svc_group:               String,
     pub shutdown_signal:         ShutdownSignal,
     pub shutdown_timeout:        ShutdownTimeout,
+    /// Run by the Supervisor in place of the package's `health-check` hook
+    pub health_check:            Option<HealthCheck>,
+    /// When the Supervisor runs the package's `scheduled` hook
+    pub schedule:                Option<Schedule>,
 }
 
 impl Pkg {
@@ -130,6 +137,14 @@ impl Pkg {
                         release: String::from(ident.release()),
                         shutdown_signal: package.shutdown_signal()?.unwrap_or_default(),
                         shutdown_timeout: package.shutdown_timeout()?.unwrap_or_default(),
+                        health_check: package.health_check().unwrap_or_else(|err| {
+                                                                warn!("Ignoring the HEALTH_CHECK \
+                                                                       of {}, the health-check \
+                                                                       hook is run instead: {}",
+                                                                      package.ident, err);
+                                                                None
+                                                            }),
+                        schedule: package.schedule()?,
                         ident };
         Ok(pkg)
     }
@@ -278,8 +293,10 @@ fn current_user_and_group() -> Result<(String, String)> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use habitat_core::AUTH_TOKEN_ENVVAR;
+    use habitat_core::{AUTH_TOKEN_ENVVAR,
+                       package::metadata::MetaFile};
     use std::collections::BTreeMap;
+    use tempfile::TempDir;
 
     #[test]
     fn with_additional_env_adds_new_variable() {
@@ -315,4 +332,31 @@ mod tests {
         assert_eq!(env.get(AUTH_TOKEN_ENVVAR).map(String::as_str),
                    Some("new_token"));
     }
+
+    #[tokio::test]
+    async fn malformed_health_check_falls_back_to_the_health_check_hook() {
+        let root = TempDir::new().expect("create temp dir");
+        let pkg_dir = root.path().join("pkg/testing/test");
+        std::fs::create_dir_all(&pkg_dir).expect("create pkg dir");
+        let ident = PackageIdent::new("testing", "test", Some("1.0.0"), Some("20170712000000"));
+        let package = PackageInstall::new_from_parts(ident,
+                                                     pkg_dir.clone(),
+                                                     pkg_dir.clone(),
+                                                     pkg_dir.clone());
+        std::fs::write(pkg_dir.join(MetaFile::HealthCheck.to_string()),
+                       "ftp://localhost/").expect("write HEALTH_CHECK");
+
+        // Platforms without standard package support require all packages to be native packages
+        #[cfg(not(any(all(target_os = "linux",
+                          any(target_arch = "x86_64", target_arch = "aarch64")),
+                      all(target_os = "windows", target_arch = "x86_64"))))]
+        {
+            std::fs::write(pkg_dir.join(MetaFile::PackageType.to_string()), "native")
+                .expect("write PACKAGE_TYPE");
+        }
+
+        let pkg = Pkg::from_install(&package, None).await
+                                                   .expect("malformed HEALTH_CHECK is ignored");
+        assert!(pkg.health_check.is_none());
+    }
 }
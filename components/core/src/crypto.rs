@@ -112,17 +112,18 @@
 //! A signed Habitat artifact (a file with the extension `.hart`) has 5 plaintext lines followed by
 //! a binary blob of data, which is an unsigned, compressed tarfile. The lines are as follows:
 //!
-//! 1. The artifact format version
+//! 1. The artifact format version, which also tells how the tarball is compressed
 //! 1. The name with revision of the origin key which was used to sign the artifact
 //! 1. The hashing algorithm used, which at present is only `BLAKE2b`, but may expand in the future
 //! 1. A Base64 *signed* value of the binary blob's Base64 file hash
 //! 1. The last line is left empty, meaning that 2 newline characters (`\n`) separate the header
 //!    from the payload
 //!
-//! The remainder of the file is a compressed tarball of the contents to be extracted on disk. At
-//! present, the tarball is compressed using `xz` but is considered an implementation detail. Also
-//! note unlike the format of keys, the compressed tarball is **not** Base64 encoded--it is the
-//! compressed tarball itself.
+//! The remainder of the file is a compressed tarball of the contents to be extracted on disk.
+//! `HART-1` artifacts compress the tarball using `xz`, and `HART-2` artifacts compress it using
+//! `zstd`, which decompresses considerably faster. A `HART-2` tarball may have been compressed
+//! with a long window (`zstd --long`), which readers accept. Also note unlike the format of keys,
+//! the compressed tarball is **not** Base64 encoded--it is the compressed tarball itself.
 //!
 //! Note that the BLAKE2b hash functions use a digest length of 32 bytes (256 bits!). More details
 //! about the hashing strategy can be found in the [libsodium hashing
@@ -172,6 +173,8 @@
 //! tail -n +6 /tmp/somefile.hart | xzcat | tar x -C /
 //! ```
 //!
+//! For a `HART-2` artifact, use `zstdcat --long=27` in place of `xzcat`.
+//!
 //! **Caution!** Working with Habitat artifacts in this manner this is not normally recommended and
 //! is **not** a supported workflow for working with Habitat artifacts--they are signed for very
 //! important reasons.
@@ -229,6 +232,7 @@ pub const SIG_HASH_TYPE: &str = "BLAKE2b";
 /// at runtime. This is useful for testing.
 pub const CACHE_KEY_PATH_ENV_VAR: &str = "HAB_CACHE_KEY_PATH";
 pub const HART_FORMAT_VERSION: &str = "HART-1";
+pub const HART_ZSTD_FORMAT_VERSION: &str = "HART-2";
 pub const BOX_FORMAT_VERSION: &str = "BOX-1";
 pub const ANONYMOUS_BOX_FORMAT_VERSION: &str = "ANONYMOUS-BOX-1";
 
@@ -1,5 +1,6 @@
 use crate::{crypto::{Blake2bHash,
                      HART_FORMAT_VERSION,
+                     HART_ZSTD_FORMAT_VERSION,
                      SIG_HASH_TYPE,
                      keys::{Key,
                             KeyCache,
@@ -7,24 +8,151 @@ use crate::{crypto::{Blake2bHash,
                             SecretOriginSigningKey}},
             error::{Error,
                     Result}};
-use std::{fs::File,
+use std::{fmt,
+          fs::File,
           io::{self,
                BufRead,
                BufReader,
                BufWriter,
                prelude::*},
-          path::Path};
+          path::Path,
+          str::FromStr};
+use tempfile::NamedTempFile;
+use xz2::{read::XzDecoder,
+          write::XzEncoder};
+
+/// The compression level of xz-compressed artifacts, matching what `hab pkg build` uses.
+const XZ_LEVEL: u32 = 6;
+/// The compression level of zstd-compressed artifacts. Higher levels only slow down
+/// compression; decompression is equally fast at every level.
+const ZSTD_LEVEL: i32 = 19;
+/// The window of zstd-compressed artifacts created with a long window, the same one
+/// `zstd --long` uses. Readers allow windows up to this size.
+pub const ZSTD_LONG_WINDOW_LOG: u32 = 27;
+
+/// How the tarball of an artifact is compressed. Each compression has its own artifact format
+/// version, so that readers know how to decompress the tarball before opening it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HartFormat {
+    /// `HART-1`, which every Habitat release can read
+    #[default]
+    Xz,
+    /// `HART-2`, which decompresses considerably faster than `HART-1`
+    Zstd,
+}
+
+impl HartFormat {
+    /// The format version at the top of the artifact's header.
+    pub fn version(self) -> &'static str {
+        match self {
+            HartFormat::Xz => HART_FORMAT_VERSION,
+            HartFormat::Zstd => HART_ZSTD_FORMAT_VERSION,
+        }
+    }
+
+    fn from_version(version: &str) -> Option<Self> {
+        match version {
+            HART_FORMAT_VERSION => Some(HartFormat::Xz),
+            HART_ZSTD_FORMAT_VERSION => Some(HartFormat::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Identifies the compression of a file by its magic number. Returns `None` for files that
+    /// are neither xz- nor zstd-compressed.
+    pub fn detect<P>(path: P) -> Result<Option<Self>>
+        where P: AsRef<Path>
+    {
+        let mut magic = Vec::with_capacity(6);
+        File::open(path)?.take(6).read_to_end(&mut magic)?;
+        if magic.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+            Ok(Some(HartFormat::Xz))
+        } else if magic.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Ok(Some(HartFormat::Zstd))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn decoder<'a, R>(self, reader: R) -> Result<Box<dyn Read + 'a>>
+        where R: BufRead + 'a
+    {
+        match self {
+            HartFormat::Xz => Ok(Box::new(XzDecoder::new(reader))),
+            HartFormat::Zstd => {
+                let mut decoder = zstd::stream::read::Decoder::with_buffer(reader)?;
+                decoder.window_log_max(ZSTD_LONG_WINDOW_LOG)?;
+                Ok(Box::new(decoder))
+            }
+        }
+    }
+
+    /// Compresses `tarball` into `dst`. A long window improves the compression of large
+    /// packages with zstd, and is ignored with xz.
+    pub fn compress<R, W>(self, tarball: &mut R, dst: W, long_window: bool) -> Result<()>
+        where R: Read,
+              W: Write
+    {
+        match self {
+            HartFormat::Xz => {
+                let mut encoder = XzEncoder::new(dst, XZ_LEVEL);
+                io::copy(tarball, &mut encoder)?;
+                encoder.finish()?.flush()?;
+            }
+            HartFormat::Zstd => {
+                let mut encoder = zstd::stream::write::Encoder::new(dst, ZSTD_LEVEL)?;
+                encoder.include_checksum(true)?;
+                if long_window {
+                    encoder.long_distance_matching(true)?;
+                    encoder.window_log(ZSTD_LONG_WINDOW_LOG)?;
+                }
+                io::copy(tarball, &mut encoder)?;
+                encoder.finish()?.flush()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for HartFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HartFormat::Xz => write!(f, "xz"),
+            HartFormat::Zstd => write!(f, "zstd"),
+        }
+    }
+}
+
+impl FromStr for HartFormat {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_ref() {
+            "xz" => Ok(HartFormat::Xz),
+            "zstd" => Ok(HartFormat::Zstd),
+            _ => {
+                Err(Error::CryptoError(format!("Unsupported artifact \
+                                                compression: {}",
+                                               value)))
+            }
+        }
+    }
+}
 
 pub struct ArtifactHeader {
-    format:    String,
-    signer:    NamedRevision,
-    hash_type: String,
-    signature: Vec<u8>,
+    format:      String,
+    hart_format: HartFormat,
+    signer:      NamedRevision,
+    hash_type:   String,
+    signature:   Vec<u8>,
 }
 
 impl ArtifactHeader {
     pub fn format(&self) -> &String { &self.format }
 
+    /// The compression of the artifact's tarball, as implied by its format version.
+    pub fn hart_format(&self) -> HartFormat { self.hart_format }
+
     pub fn signer(&self) -> &NamedRevision { &self.signer }
 
     pub fn hash_type(&self) -> &String { &self.hash_type }
@@ -39,13 +167,25 @@ impl ArtifactHeader {
 pub fn sign<P1, P2>(src: &P1, dst: &P2, key: &SecretOriginSigningKey) -> Result<()>
     where P1: ?Sized + AsRef<Path>,
           P2: ?Sized + AsRef<Path>
+{
+    sign_with_format(src, dst, key, HartFormat::Xz)
+}
+
+/// Generate and sign a package whose payload `src` is already compressed as `format` requires.
+pub fn sign_with_format<P1, P2>(src: &P1,
+                                dst: &P2,
+                                key: &SecretOriginSigningKey,
+                                format: HartFormat)
+                                -> Result<()>
+    where P1: ?Sized + AsRef<Path>,
+          P2: ?Sized + AsRef<Path>
 {
     let signature = key.sign(src)?;
     let output_file = File::create(dst)?;
     let mut writer = BufWriter::new(&output_file);
     write!(writer,
            "{}\n{}\n{}\n{}\n\n",
-           HART_FORMAT_VERSION,
+           format.version(),
            key.named_revision(),
            SIG_HASH_TYPE,
            crate::base64::encode(signature))?;
@@ -54,7 +194,73 @@ pub fn sign<P1, P2>(src: &P1, dst: &P2, key: &SecretOriginSigningKey) -> Result<
     Ok(())
 }
 
-/// return a BufReader to the .tar bytestream, skipping the signed header
+/// Generate and sign a package from the tarball `src`, compressed as `format` requires. A
+/// tarball that is compressed differently is recompressed first.
+///
+/// Uncompressed sources are signed as they are for `HartFormat::Xz`, which is how signed
+/// metadata such as a package's `FILES` has always been produced, and compressed for
+/// `HartFormat::Zstd`.
+pub fn compress_and_sign<P1, P2>(src: &P1,
+                                 dst: &P2,
+                                 key: &SecretOriginSigningKey,
+                                 format: HartFormat,
+                                 long_window: bool)
+                                 -> Result<()>
+    where P1: ?Sized + AsRef<Path>,
+          P2: ?Sized + AsRef<Path>
+{
+    match HartFormat::detect(src)? {
+        Some(current) if current == format => sign_with_format(src, dst, key, format),
+        Some(current) => {
+            let reader = BufReader::new(File::open(src)?);
+            let mut tarball = current.decoder(reader)?;
+            sign_tarball(&mut tarball, dst.as_ref(), key, format, long_window)
+        }
+        None if format == HartFormat::Xz => sign_with_format(src, dst, key, format),
+        None => {
+            sign_tarball(&mut File::open(src)?,
+                         dst.as_ref(),
+                         key,
+                         format,
+                         long_window)
+        }
+    }
+}
+
+/// Rewrite the artifact `src` as `format`, signed with `key`. The artifact's signature covers
+/// its compressed tarball, so converting an artifact means signing it again; callers should
+/// verify `src` before trusting its contents with their key.
+pub fn convert<P1, P2>(src: &P1,
+                       dst: &P2,
+                       key: &SecretOriginSigningKey,
+                       format: HartFormat,
+                       long_window: bool)
+                       -> Result<()>
+    where P1: ?Sized + AsRef<Path>,
+          P2: ?Sized + AsRef<Path>
+{
+    let mut tarball = get_archive_decoder(src)?;
+    sign_tarball(&mut tarball, dst.as_ref(), key, format, long_window)
+}
+
+fn sign_tarball<R>(tarball: &mut R,
+                   dst: &Path,
+                   key: &SecretOriginSigningKey,
+                   format: HartFormat,
+                   long_window: bool)
+                   -> Result<()>
+    where R: Read
+{
+    let dir = match dst.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let compressed = NamedTempFile::new_in(dir)?;
+    format.compress(tarball, BufWriter::new(compressed.as_file()), long_window)?;
+    sign_with_format(compressed.path(), dst, key, format)
+}
+
+/// return a BufReader to the compressed .tar bytestream, skipping the signed header
 pub fn get_archive_reader<P>(src: P) -> Result<impl BufRead>
     where P: AsRef<Path>
 {
@@ -62,6 +268,14 @@ pub fn get_archive_reader<P>(src: P) -> Result<impl BufRead>
     Ok(reader)
 }
 
+/// return a reader of the decompressed .tar bytestream, skipping the signed header
+pub fn get_archive_decoder<P>(src: P) -> Result<Box<dyn Read>>
+    where P: AsRef<Path>
+{
+    let (header, reader) = artifact_header_and_archive(src)?;
+    header.hart_format.decoder(reader)
+}
+
 /// Read only the header of the artifact, fails if any of the components
 /// are invalid/missing. Each component of the header has it's whitespace
 /// stripped before returning in an `ArtifactHeader` struct
@@ -72,7 +286,7 @@ pub fn get_artifact_header<P>(src: P) -> Result<ArtifactHeader>
     Ok(header)
 }
 
-fn artifact_header_and_archive<P>(path: P) -> Result<(ArtifactHeader, impl BufRead)>
+fn artifact_header_and_archive<P>(path: P) -> Result<(ArtifactHeader, BufReader<File>)>
     where P: AsRef<Path>
 {
     let f = File::open(path)?;
@@ -80,18 +294,19 @@ fn artifact_header_and_archive<P>(path: P) -> Result<(ArtifactHeader, impl BufRe
 
     // First line is HART format line.
     let mut line = String::new();
-    let format = if reader.read_line(&mut line)? == 0 {
+    let (format, hart_format) = if reader.read_line(&mut line)? == 0 {
         Err(Error::CryptoError("Corrupt payload, can't read format \
                                 version"
                                         .to_string()))
     } else {
         let line = line.trim();
-        if line != HART_FORMAT_VERSION {
-            Err(Error::CryptoError(format!("Unsupported format version: \
-                                            {}",
-                                           line)))
-        } else {
-            Ok(line.to_string())
+        match HartFormat::from_version(line) {
+            Some(hart_format) => Ok((line.to_string(), hart_format)),
+            None => {
+                Err(Error::CryptoError(format!("Unsupported format version: \
+                                                {}",
+                                               line)))
+            }
         }
     }?;
 
@@ -154,6 +369,7 @@ fn artifact_header_and_archive<P>(path: P) -> Result<(ArtifactHeader, impl BufRe
     // of the file for further processing (either signature
     // verification or decompression).
     let header = ArtifactHeader { format,
+                                  hart_format,
                                   signer: named_revision,
                                   hash_type,
                                   signature };
@@ -184,6 +400,7 @@ pub fn artifact_signer<P>(hart_file_path: P) -> Result<NamedRevision>
 #[cfg(test)]
 mod tests {
     use super::{super::{HART_FORMAT_VERSION,
+                        HART_ZSTD_FORMAT_VERSION,
                         SIG_HASH_TYPE,
                         test_support::*},
                 *};
@@ -364,6 +581,89 @@ mod tests {
         assert_eq!(buffer.as_bytes(), b"hearty goodness");
     }
 
+    fn decompressed(hart: &Path) -> Vec<u8> {
+        let mut tarball = Vec::new();
+        get_archive_decoder(hart).unwrap()
+                                 .read_to_end(&mut tarball)
+                                 .unwrap();
+        tarball
+    }
+
+    #[test]
+    fn compress_and_sign_zstd() {
+        let (cache, dir) = new_cache();
+        let origin = "unicorn".parse().unwrap();
+        let (_public, secret) = cache.new_signing_pair(&origin).unwrap();
+
+        let src = dir.path().join("src.tar");
+        let dst = dir.path().join("src.hart");
+        let mut f = File::create(&src).unwrap();
+        f.write_all(b"hearty goodness").unwrap();
+        compress_and_sign(&src, &dst, &secret, HartFormat::Zstd, true).unwrap();
+
+        let header = get_artifact_header(&dst).unwrap();
+        assert_eq!(HART_ZSTD_FORMAT_VERSION, header.format());
+        assert_eq!(HartFormat::Zstd, header.hart_format());
+        verify(&dst, &cache).unwrap();
+        assert_eq!(decompressed(&dst), b"hearty goodness");
+    }
+
+    #[test]
+    fn compress_and_sign_xz_keeps_uncompressed_payload() {
+        let (cache, dir) = new_cache();
+        let origin = "unicorn".parse().unwrap();
+        let (_public, secret) = cache.new_signing_pair(&origin).unwrap();
+
+        let src = dir.path().join("src.in");
+        let dst = dir.path().join("src.signed");
+        let mut f = File::create(&src).unwrap();
+        f.write_all(b"hearty goodness").unwrap();
+        compress_and_sign(&src, &dst, &secret, HartFormat::Xz, false).unwrap();
+
+        let mut buffer = String::new();
+        let mut reader = get_archive_reader(&dst).unwrap();
+        reader.read_to_string(&mut buffer).unwrap();
+        assert_eq!(buffer.as_bytes(), b"hearty goodness");
+    }
+
+    #[test]
+    fn convert_between_formats() {
+        let (cache, dir) = new_cache();
+        let origin = "unicorn".parse().unwrap();
+        let (_public, secret) = cache.new_signing_pair(&origin).unwrap();
+
+        let src = fixture("happyhumans-possums-8.1.4-20160427165340-x86_64-linux.hart");
+        let zstd = dir.path().join("zstd.hart");
+        convert(&src, &zstd, &secret, HartFormat::Zstd, true).unwrap();
+        assert_eq!(HartFormat::Zstd,
+                   get_artifact_header(&zstd).unwrap().hart_format());
+        verify(&zstd, &cache).unwrap();
+
+        let xz = dir.path().join("xz.hart");
+        convert(&zstd, &xz, &secret, HartFormat::Xz, false).unwrap();
+        assert_eq!(HART_FORMAT_VERSION,
+                   get_artifact_header(&xz).unwrap().format());
+        verify(&xz, &cache).unwrap();
+
+        let tarball = decompressed(&src);
+        assert_eq!(decompressed(&zstd), tarball);
+        assert_eq!(decompressed(&xz), tarball);
+    }
+
+    #[test]
+    fn hart_format_detects_compression() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("payload");
+        for format in [HartFormat::Xz, HartFormat::Zstd] {
+            let f = File::create(&path).unwrap();
+            format.compress(&mut &b"hearty goodness"[..], f, false)
+                  .unwrap();
+            assert_eq!(HartFormat::detect(&path).unwrap(), Some(format));
+        }
+        std::fs::write(&path, b"hearty goodness").unwrap();
+        assert_eq!(HartFormat::detect(&path).unwrap(), None);
+    }
+
     #[test]
     fn verify_get_artifact_header() {
         let (cache, dir) = new_cache();
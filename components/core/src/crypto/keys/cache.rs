@@ -170,6 +170,11 @@ impl KeyCache {
         self.fetch_latest_revision::<RingKey>(name)
     }
 
+    /// Retrieve the ring key with the specified revision.
+    pub fn ring_key(&self, named_revision: &NamedRevision) -> Result<RingKey> {
+        self.fetch_specific_revision::<RingKey>(named_revision)
+    }
+
     pub fn latest_secret_origin_signing_key(&self,
                                             origin: &Origin)
                                             -> Result<SecretOriginSigningKey> {
@@ -88,8 +88,13 @@ pub enum Error {
     /// but a non-qualified identifier (e.g. "foo/bar" or
     /// "foo/bar/1.0.0") was given instead.
     FullyQualifiedPackageIdentRequired(String),
+    /// Occurs when the files of an installed package differ from its signed `FILES` metafile,
+    /// with the numbers of added, removed and modified files.
+    InstalledPackageModified(Box<package::PackageIdent>, usize, usize, usize),
     /// Occurs when a service binding cannot be successfully parsed.
     InvalidBinding(String),
+    /// Occurs when a health check string cannot be successfully parsed.
+    InvalidHealthCheck(String),
     /// Occurs when an origin is in an invalid format
     InvalidOrigin(String),
     /// Occurs when a package identifier string cannot be successfully parsed.
@@ -102,6 +107,8 @@ pub enum Error {
     InvalidPort(ParseIntError),
     /// Occurs when an OsString path cannot be converted to a String
     InvalidPathString(ffi::OsString),
+    /// Occurs when a hook schedule cannot be successfully parsed.
+    InvalidSchedule(String),
     /// Occurs when a service group string cannot be successfully parsed.
     InvalidServiceGroup(String),
     /// Occurs when a Url is in an invalid format.
@@ -289,11 +296,22 @@ impl fmt::Display for Error {
                 format!("Fully-qualified package identifier was expected, but found: {:?}",
                         ident)
             }
+            Error::InstalledPackageModified(ref ident, added, removed, modified) => {
+                format!("Installed package {} doesn't match its signed FILES metafile: {} added, \
+                         {} removed and {} modified files",
+                        ident, added, removed, modified)
+            }
             Error::InvalidBinding(ref binding) => {
                 format!("Invalid binding '{}', must be of the form <NAME>:<SERVICE_GROUP> where \
                          <NAME> is a service name, and <SERVICE_GROUP> is a valid service group",
                         binding)
             }
+            Error::InvalidHealthCheck(ref check) => {
+                format!("Invalid health check '{}', must be of the form \
+                         http(s)://<HOST>[:<PORT>]/<PATH> [status=<CODE>] [body=<REGEX>], \
+                         tcp://<HOST>:<PORT> or exec:<COMMAND>",
+                        check)
+            }
             Error::InvalidOrigin(ref origin) => {
                 format!("Invalid origin: {}. Origins must begin with a lowercase letter or \
                          number. Allowed characters include lowercase letters, numbers, -, and _. \
@@ -315,6 +333,12 @@ impl fmt::Display for Error {
                 format!("Could not generate String from path: {:?}", s)
             }
             Error::InvalidPort(ref e) => format!("Invalid port: {}.", e),
+            Error::InvalidSchedule(ref schedule) => {
+                format!("Invalid schedule '{}', must be a cron expression of the form '<MINUTE> \
+                         <HOUR> <DAY OF MONTH> <MONTH> <DAY OF WEEK>', or one of @hourly, @daily, \
+                         @weekly, @monthly or @yearly",
+                        schedule)
+            }
             Error::InvalidServiceGroup(ref e) => {
                 format!("Invalid service group: {}. A valid service group string is in the form \
                          service.group (example: redis.production)",
@@ -157,6 +157,22 @@ pub enum Permissions {
     Explicit(u32),
 }
 
+impl Permissions {
+    /// Set these permissions on the file at `path`. Standard
+    /// permissions leave the file as it is.
+    pub fn apply(&self, path: &Path) -> io::Result<()> {
+        if let Permissions::Explicit(permissions) = self {
+            // This is not my proudest moment, but it does the trick
+            // with a minimum amount of fuss :/
+            #[cfg(not(windows))]
+            let permissions = *permissions;
+
+            set_permissions(path, permissions).map_err(|e| io::Error::other(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
 // Explicitly implementing this so we can get octal formatting on
 // Linux. Otherwise, it would just be a regular decimal number, which
 // isn't very helpful when dealing with permission bits.
@@ -867,16 +883,7 @@ impl AtomicWriter {
     fn finish(self) -> io::Result<()> {
         // Note that we only set permissions if given explicit ones to
         // override whatever permissions the file was created with.
-        if let Permissions::Explicit(ref permissions) = self.permissions {
-            // This is not my proudest moment, but it does the trick
-            // with a minimum amount of fuss :/
-            #[cfg(not(windows))]
-            let permissions = *permissions;
-
-            set_permissions(self.tempfile.path(), permissions).map_err(|e| {
-                                                                  io::Error::other(e.to_string())
-                                                              })?;
-        }
+        self.permissions.apply(self.tempfile.path())?;
         self.tempfile.as_file().sync_all()?;
 
         atomic_rename(self.tempfile.into_temp_path(), self.dest.as_path())?;
@@ -24,6 +24,29 @@ pub fn hook_command<X, I, K, V>(executable: X, env: I, ids: Option<(Uid, Gid)>)
           I: IntoIterator<Item = (K, V)>,
           K: AsRef<OsStr>,
           V: AsRef<OsStr>
+{
+    unsafe { hook_command_with_setup(executable, env, ids, || Ok(())) }
+}
+
+/// Prepare a `Command` to execute a lifecycle hook, as `hook_command`
+/// does, running `setup` in the new process before it switches to the
+/// user and group in `ids`. `setup` thus still has the privileges of
+/// the spawning process.
+///
+/// # Safety
+///
+/// `setup` runs between `fork` and `exec`, and is subject to the same
+/// restrictions as a `CommandExt::pre_exec` callback.
+pub unsafe fn hook_command_with_setup<X, I, K, V, F>(executable: X,
+                                                     env: I,
+                                                     ids: Option<(Uid, Gid)>,
+                                                     setup: F)
+                                                     -> Command
+    where X: AsRef<OsStr>,
+          I: IntoIterator<Item = (K, V)>,
+          K: AsRef<OsStr>,
+          V: AsRef<OsStr>,
+          F: FnMut() -> io::Result<()> + Send + Sync + 'static
 {
     let mut cmd = Command::new(executable);
 
@@ -36,6 +59,9 @@ pub fn hook_command<X, I, K, V>(executable: X, env: I, ids: Option<(Uid, Gid)>)
        .envs(env);
 
     with_own_process_group(&mut cmd);
+    unsafe {
+        cmd.pre_exec(setup);
+    }
     if let Some((uid, gid)) = ids {
         with_user_and_group_information(&mut cmd, uid, gid);
     }
@@ -1,4 +1,5 @@
 pub mod archive;
+pub mod files;
 pub mod ident;
 pub mod install;
 pub mod list;
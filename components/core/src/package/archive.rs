@@ -22,7 +22,6 @@ use std::{collections::HashMap,
                 FromStr},
           string::ToString};
 use tar::Archive;
-use xz2::read::XzDecoder;
 
 lazy_static::lazy_static! {
     static ref METAFILE_REGXS: HashMap<MetaFile, Regex> = {
@@ -301,8 +300,7 @@ impl PackageArchive {
     /// * If the package cannot be unpacked
     pub fn unpack(&self, fs_root_path: Option<&Path>) -> Result<()> {
         let root = fs_root_path.unwrap_or_else(|| Path::new("/"));
-        let hart_payload_tar_xz = artifact::get_archive_reader(&self.path)?;
-        let decoder = XzDecoder::new(hart_payload_tar_xz);
+        let decoder = artifact::get_archive_decoder(&self.path)?;
         let mut tar = Archive::new(decoder);
         tar.set_preserve_permissions(true);
         tar.set_preserve_mtime(true);
@@ -332,8 +330,7 @@ impl PackageArchive {
 
     fn get_all_metadata(path: impl AsRef<Path>) -> Result<Metadata> {
         let mut metadata = Metadata::new();
-        let hart_payload_tar_xz = artifact::get_archive_reader(path)?;
-        let decoder = XzDecoder::new(hart_payload_tar_xz);
+        let decoder = artifact::get_archive_decoder(path)?;
         let mut tar = Archive::new(decoder);
 
         // Check all entries in the tar archive for metafiles and add them to the `Metadata` store
@@ -0,0 +1,228 @@
+//! Verifies the files of installed packages against their `FILES` metafile.
+//!
+//! When it builds a package, `hab pkg build` records the Blake2b checksum of every file in the
+//! package in `FILES`, which it signs with the origin key the same way it signs the artifact:
+//!
+//! ```text
+//! HART-1
+//! core-20180119235000
+//! BLAKE2b
+//! <signature>
+//!
+//! 4ab174a388f8743f56b2e3669b6f4d27e72591daa174029fd80a745cd511a34f  /hab/pkgs/core/tree/1.7.0/20180609045201/BUILD_DEPS
+//! 65b742f99a888111d16f171c4f23bd5a64f7ef8486f294a2a0967c5312b1399d  /hab/pkgs/core/tree/1.7.0/20180609045201/bin/tree
+//! ```
+//!
+//! Verifying an installed package checks that signature, then compares the files under the
+//! package's directory to the recorded checksums, so that tampering or accidental edits after
+//! the package was installed are detected.
+
+use super::{PackageInstall,
+            metadata::MetaFile};
+use crate::{crypto::{Blake2bHash,
+                     artifact,
+                     keys::{KeyCache,
+                            NamedRevision}},
+            error::{Error,
+                    Result},
+            fs};
+use std::{collections::BTreeMap,
+          fs as stdfs,
+          io::Read,
+          path::{Path,
+                 PathBuf}};
+
+/// Files Habitat writes to a package's directory after `FILES` was signed, which are thus not
+/// listed in it.
+const UNSIGNED_FILES: &[&str] = &["FILES",
+                                  "MANIFEST",
+                                  "INSTALL_HOOK_STATUS",
+                                  "UNINSTALL_HOOK_STATUS"];
+
+/// The differences between the files of an installed package and its `FILES` metafile. Paths
+/// are relative to the package's directory.
+#[derive(Debug)]
+pub struct FilesReport {
+    /// The origin key revision that signed `FILES`
+    pub signer:   NamedRevision,
+    /// Files of the package that `FILES` doesn't list
+    pub added:    Vec<PathBuf>,
+    /// Files listed in `FILES` that are missing from the package
+    pub removed:  Vec<PathBuf>,
+    /// Files whose contents don't match their checksum in `FILES`
+    pub modified: Vec<PathBuf>,
+}
+
+impl FilesReport {
+    /// Whether the installed files are exactly the ones that were signed.
+    pub fn is_unmodified(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+
+    /// Fails if the installed files of `package` differ from the ones that were signed.
+    pub fn into_result(self, package: &PackageInstall) -> Result<Self> {
+        if self.is_unmodified() {
+            Ok(self)
+        } else {
+            Err(Error::InstalledPackageModified(Box::new(package.ident()
+                                                                .clone()),
+                                                self.added.len(),
+                                                self.removed.len(),
+                                                self.modified.len()))
+        }
+    }
+}
+
+/// Compares the files of an installed package to its `FILES` metafile, after verifying the
+/// signature of `FILES` with a public origin key from `cache`.
+pub fn verify(package: &PackageInstall, cache: &KeyCache) -> Result<FilesReport> {
+    let files_path = package.installed_path().join(MetaFile::Files.to_string());
+    if !files_path.is_file() {
+        return Err(Error::MetaFileNotFound(MetaFile::Files));
+    }
+    let (signer, _) = artifact::verify(&files_path, cache)?;
+    let mut signed = signed_checksums(package, &files_path)?;
+
+    let mut report = FilesReport { signer,
+                                   added: Vec::new(),
+                                   removed: Vec::new(),
+                                   modified: Vec::new() };
+    for path in installed_files(package.installed_path())? {
+        match signed.remove(&path) {
+            Some(checksum) => {
+                let actual = Blake2bHash::from_file(package.installed_path().join(&path))?;
+                if actual.to_string() != checksum {
+                    report.modified.push(path);
+                }
+            }
+            None if UNSIGNED_FILES.iter().any(|name| path == Path::new(name)) => {}
+            None => report.added.push(path),
+        }
+    }
+    report.removed = signed.into_keys().collect();
+    Ok(report)
+}
+
+/// Reads the checksums of `FILES`, keyed by paths relative to the package's directory.
+fn signed_checksums(package: &PackageInstall,
+                    files_path: &Path)
+                    -> Result<BTreeMap<PathBuf, String>> {
+    let mut contents = String::new();
+    artifact::get_archive_reader(files_path)?.read_to_string(&mut contents)
+                                             .map_err(|_| {
+                                                 Error::MetaFileMalformed(MetaFile::Files)
+                                             })?;
+
+    // `FILES` lists the paths the package was built at, regardless of the filesystem root it
+    // is installed under.
+    let prefix = fs::pkg_install_path(package.ident(), None::<&Path>);
+    let mut checksums = BTreeMap::new();
+    for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+        let (checksum, path) = line.split_once(char::is_whitespace)
+                                   .ok_or(Error::MetaFileMalformed(MetaFile::Files))?;
+        let path =
+            Path::new(path.trim_start()).strip_prefix(&prefix)
+                                        .map_err(|_| Error::MetaFileMalformed(MetaFile::Files))?;
+        checksums.insert(path.to_path_buf(), checksum.to_string());
+    }
+    Ok(checksums)
+}
+
+/// Lists the regular files under `dir`, relative to it, the same way `FILES` is generated.
+/// Symbolic links are not followed.
+fn installed_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut dirs = vec![PathBuf::new()];
+    while let Some(relative) = dirs.pop() {
+        for entry in stdfs::read_dir(dir.join(&relative))? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let path = relative.join(entry.file_name());
+            if file_type.is_dir() {
+                dirs.push(path);
+            } else if file_type.is_file() {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{crypto::test_support::new_cache,
+                package::test_support::testing_package_install};
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    /// Installs a package with a few files and a `FILES` metafile signed for them.
+    fn signed_package() -> (TempDir, TempDir, KeyCache, PackageInstall) {
+        let fs_root = TempDir::new().unwrap();
+        let (cache, cache_dir) = new_cache();
+        let (_public, secret) = cache.new_signing_pair(&"unicorn".parse().unwrap()).unwrap();
+        let package = testing_package_install("unicorn/possums", fs_root.path());
+        let installed_path = package.installed_path();
+        stdfs::create_dir_all(installed_path.join("bin")).unwrap();
+        stdfs::write(installed_path.join("bin").join("possums"), "#!/bin/sh\n").unwrap();
+
+        let prefix = fs::pkg_install_path(package.ident(), None::<&Path>);
+        let sums = fs_root.path().join("possums_blake2bsums");
+        let mut f = stdfs::File::create(&sums).unwrap();
+        for path in installed_files(installed_path).unwrap() {
+            let checksum = Blake2bHash::from_file(installed_path.join(&path)).unwrap();
+            writeln!(f, "{}  {}", checksum, prefix.join(&path).display()).unwrap();
+        }
+        artifact::sign(&sums, &installed_path.join("FILES"), &secret).unwrap();
+        // Written after signing, as when the package is installed
+        stdfs::write(installed_path.join("INSTALL_HOOK_STATUS"), "0").unwrap();
+        (fs_root, cache_dir, cache, package)
+    }
+
+    #[test]
+    fn unmodified_package_verifies() {
+        let (_fs_root, _cache_dir, cache, package) = signed_package();
+        let report = verify(&package, &cache).unwrap();
+        assert_eq!(report.signer.name(), "unicorn");
+        assert!(report.is_unmodified(), "{:?}", report);
+    }
+
+    #[test]
+    fn reports_added_removed_and_modified_files() {
+        let (_fs_root, _cache_dir, cache, package) = signed_package();
+        let installed_path = package.installed_path();
+        stdfs::write(installed_path.join("bin").join("possums"), "#!/bin/bash\n").unwrap();
+        stdfs::remove_file(installed_path.join("IDENT")).unwrap();
+        stdfs::write(installed_path.join("bin").join("opossums"), "").unwrap();
+
+        let report = verify(&package, &cache).unwrap();
+        assert_eq!(report.added, vec![PathBuf::from("bin").join("opossums")]);
+        assert_eq!(report.removed, vec![PathBuf::from("IDENT")]);
+        assert_eq!(report.modified, vec![PathBuf::from("bin").join("possums")]);
+        assert!(matches!(report.into_result(&package),
+                         Err(Error::InstalledPackageModified(_, 1, 1, 1))));
+    }
+
+    #[test]
+    fn tampered_files_metafile_fails() {
+        let (_fs_root, _cache_dir, cache, package) = signed_package();
+        let files_path = package.installed_path().join("FILES");
+        let mut f = stdfs::OpenOptions::new().append(true)
+                                             .open(&files_path)
+                                             .unwrap();
+        writeln!(f, "0000  /hab/pkgs/unicorn/possums/extra").unwrap();
+
+        assert!(verify(&package, &cache).is_err());
+    }
+
+    #[test]
+    fn package_without_files_metafile_fails() {
+        let fs_root = TempDir::new().unwrap();
+        let (cache, _cache_dir) = new_cache();
+        let package = testing_package_install("unicorn/possums", fs_root.path());
+
+        assert!(matches!(verify(&package, &cache),
+                         Err(Error::MetaFileNotFound(MetaFile::Files))));
+    }
+}
@@ -12,7 +12,9 @@ use crate::{error::{Error,
                     Result},
             fs,
             os::process::{ShutdownSignal,
-                          ShutdownTimeout}};
+                          ShutdownTimeout},
+            service::{HealthCheck,
+                      Schedule}};
 use log::debug;
 use serde::{Deserialize,
             Serialize};
@@ -596,6 +598,26 @@ impl PackageInstall {
         }
     }
 
+    /// Returns the health check that the Supervisor runs in place of a `health-check` hook, or
+    /// None if the package doesn't contain a HEALTH_CHECK Metafile
+    pub fn health_check(&self) -> Result<Option<HealthCheck>> {
+        match self.read_metafile(MetaFile::HealthCheck) {
+            Ok(body) => Ok(Some(body.parse()?)),
+            Err(Error::MetaFileNotFound(MetaFile::HealthCheck)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns when the Supervisor runs the package's `scheduled` hook, or None if the package
+    /// doesn't contain a SCHEDULE Metafile
+    pub fn schedule(&self) -> Result<Option<Schedule>> {
+        match self.read_metafile(MetaFile::Schedule) {
+            Ok(body) => Ok(Some(body.parse()?)),
+            Err(Error::MetaFileNotFound(MetaFile::Schedule)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
     /// Read the contents of a given metafile.
     ///
     /// # Failures
@@ -118,6 +118,9 @@ pub enum MetaFile {
     EnvironmentSep,
     Exports,
     Exposes,
+    /// The signed Blake2b checksums of every file of the package
+    Files,
+    HealthCheck,
     Ident,
     LdFlags,
     LdRunPath,
@@ -126,6 +129,7 @@ pub enum MetaFile {
     RuntimeEnvironment,
     RuntimeEnvironmentPaths,
     RuntimePath,
+    Schedule,
     ShutdownSignal,
     ShutdownTimeout,
     SvcGroup,
@@ -149,6 +153,8 @@ impl fmt::Display for MetaFile {
             MetaFile::EnvironmentSep => "ENVIRONMENT_SEP",
             MetaFile::Exports => "EXPORTS",
             MetaFile::Exposes => "EXPOSES",
+            MetaFile::Files => "FILES",
+            MetaFile::HealthCheck => "HEALTH_CHECK",
             MetaFile::Ident => "IDENT",
             MetaFile::LdFlags => "LDFLAGS",
             MetaFile::LdRunPath => "LD_RUN_PATH",
@@ -157,6 +163,7 @@ impl fmt::Display for MetaFile {
             MetaFile::RuntimeEnvironment => "RUNTIME_ENVIRONMENT",
             MetaFile::RuntimeEnvironmentPaths => "RUNTIME_ENVIRONMENT_PATHS",
             MetaFile::RuntimePath => "RUNTIME_PATH",
+            MetaFile::Schedule => "SCHEDULE",
             MetaFile::ShutdownSignal => "SHUTDOWN_SIGNAL",
             MetaFile::ShutdownTimeout => "SHUTDOWN_TIMEOUT",
             MetaFile::SvcGroup => "SVC_GROUP",
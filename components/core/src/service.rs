@@ -1,3 +1,6 @@
+mod schedule;
+
+pub use self::schedule::Schedule;
 use crate::error::{Error,
                    Result};
 use regex::Regex;
@@ -300,6 +303,114 @@ impl From<Duration> for HealthCheckInterval {
     fn from(d: Duration) -> Self { Self(d) }
 }
 
+/// A health check the Supervisor runs itself, in place of a package's `health-check` hook.
+///
+/// Health checks are written as one of:
+///
+/// * `http(s)://<HOST>[:<PORT>]/<PATH> [status=<CODE>] [body=<REGEX>]`: a GET of the URL must
+///   return the given status, or any 2xx status if there is none, and a body matching the regex. As
+///   the regex may contain whitespace, it extends to the end of the string.
+/// * `tcp://<HOST>:<PORT>`: a connection to the address must be established.
+/// * `exec:<COMMAND>`: the command is run by the shell as the service user, and its exit code is
+///   interpreted like that of a `health-check` hook.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum HealthCheck {
+    Http {
+        url:    String,
+        status: Option<u16>,
+        body:   Option<String>,
+    },
+    Tcp {
+        address: String,
+    },
+    Exec {
+        command: String,
+    },
+}
+
+impl FromStr for HealthCheck {
+    type Err = Error;
+
+    fn from_str(value: &str) -> result::Result<Self, Self::Err> {
+        let invalid = || Error::InvalidHealthCheck(value.to_string());
+        let check = value.trim();
+        if let Some(command) = check.strip_prefix("exec:") {
+            let command = command.trim();
+            if command.is_empty() {
+                return Err(invalid());
+            }
+            return Ok(HealthCheck::Exec { command: command.to_string(), });
+        }
+        if let Some(address) = check.strip_prefix("tcp://") {
+            match address.rsplit_once(':') {
+                Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => {
+                    return Ok(HealthCheck::Tcp { address: address.to_string(), });
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        if !check.starts_with("http://") && !check.starts_with("https://") {
+            return Err(invalid());
+        }
+        let (url, mut options) = check.split_once(char::is_whitespace).unwrap_or((check, ""));
+        url::Url::parse(url).map_err(|_| invalid())?;
+        let mut status = None;
+        let mut body = None;
+        while !options.trim_start().is_empty() {
+            options = options.trim_start();
+            if let Some(regex) = options.strip_prefix("body=") {
+                Regex::new(regex).map_err(|_| invalid())?;
+                body = Some(regex.to_string());
+                break;
+            }
+            let (option, rest) = options.split_once(char::is_whitespace)
+                                        .unwrap_or((options, ""));
+            let code = option.strip_prefix("status=").ok_or_else(invalid)?;
+            status = Some(code.parse().map_err(|_| invalid())?);
+            options = rest;
+        }
+        Ok(HealthCheck::Http { url: url.to_string(),
+                               status,
+                               body })
+    }
+}
+
+impl fmt::Display for HealthCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HealthCheck::Http { url, status, body } => {
+                write!(f, "{}", url)?;
+                if let Some(status) = status {
+                    write!(f, " status={}", status)?;
+                }
+                if let Some(body) = body {
+                    write!(f, " body={}", body)?;
+                }
+                Ok(())
+            }
+            HealthCheck::Tcp { address } => write!(f, "tcp://{}", address),
+            HealthCheck::Exec { command } => write!(f, "exec:{}", command),
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for HealthCheck {
+    fn deserialize<D>(deserializer: D) -> result::Result<Self, D::Error>
+        where D: serde::Deserializer<'de>
+    {
+        String::deserialize(deserializer)?.parse()
+                                          .map_err(serde::de::Error::custom)
+    }
+}
+
+impl serde::Serialize for HealthCheck {
+    fn serialize<S>(&self, serializer: S) -> result::Result<S::Ok, S::Error>
+        where S: serde::Serializer
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -539,6 +650,50 @@ mod tests {
         HealthCheckInterval::from_str("oh-noes").unwrap();
     }
 
+    #[test]
+    fn health_check_from_str() {
+        assert_eq!(HealthCheck::from_str("http://localhost:8080/health").unwrap(),
+                   HealthCheck::Http { url:    String::from("http://localhost:8080/health"),
+                                       status: None,
+                                       body:   None, });
+        assert_eq!(HealthCheck::from_str("https://localhost/ status=204  body=^up and \
+                                          running$").unwrap(),
+                   HealthCheck::Http { url:    String::from("https://localhost/"),
+                                       status: Some(204),
+                                       body:   Some(String::from("^up and running$")), });
+        assert_eq!(HealthCheck::from_str("tcp://127.0.0.1:6379").unwrap(),
+                   HealthCheck::Tcp { address: String::from("127.0.0.1:6379"), });
+        assert_eq!(HealthCheck::from_str("exec: pg_isready -q").unwrap(),
+                   HealthCheck::Exec { command: String::from("pg_isready -q"), });
+    }
+
+    #[test]
+    fn health_check_from_str_invalid() {
+        for check in ["ftp://localhost/",
+                      "http://localhost/ status=ok",
+                      "http://localhost/ timeout=5",
+                      "http://localhost/ body=(",
+                      "tcp://localhost",
+                      "tcp://:6379",
+                      "exec:"]
+        {
+            match HealthCheck::from_str(check) {
+                Err(Error::InvalidHealthCheck(val)) => assert_eq!(check, val),
+                other => panic!("Unexpected result for '{}': {:?}", check, other),
+            }
+        }
+    }
+
+    #[test]
+    fn health_check_display_round_trips() {
+        for check in ["http://localhost:8080/health status=200 body=OK",
+                      "tcp://127.0.0.1:6379",
+                      "exec:pg_isready -q"]
+        {
+            assert_eq!(HealthCheck::from_str(check).unwrap().to_string(), check);
+        }
+    }
+
     #[test]
     fn health_check_interval_display() {
         assert_eq!("(5s)".to_owned(),
@@ -0,0 +1,256 @@
+use crate::error::Error;
+use chrono::{DateTime,
+             Datelike,
+             Duration,
+             NaiveDate,
+             NaiveDateTime,
+             TimeZone,
+             Timelike};
+use std::{fmt,
+          result,
+          str::FromStr};
+
+/// How many years ahead to look for the next run of a schedule before deciding that it never
+/// runs, e.g. `0 0 30 2 *`.
+const MAX_YEARS_AHEAD: i32 = 5;
+
+/// When a service's `scheduled` hook is run, written as a cron expression:
+///
+/// ```text
+/// <MINUTE> <HOUR> <DAY OF MONTH> <MONTH> <DAY OF WEEK>
+/// ```
+///
+/// Each field is `*`, a value, a range `<LOW>-<HIGH>`, any of these followed by a step `/<STEP>`,
+/// or a comma-separated list of them. Days of the week run from 0 (Sunday) to 7 (also Sunday).
+/// As with cron, when both the day of the month and the day of the week are restricted, the
+/// schedule runs on days that match either. `@hourly`, `@daily`, `@weekly`, `@monthly` and
+/// `@yearly` are shorthands for the usual expressions.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Schedule {
+    expression:       String,
+    minutes:          u64,
+    hours:            u64,
+    days_of_month:    u64,
+    months:           u64,
+    days_of_week:     u64,
+    any_day_of_month: bool,
+    any_day_of_week:  bool,
+}
+
+impl Schedule {
+    /// The first time after `after`, to the minute, at which the schedule runs.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let timezone = after.timezone();
+        let mut candidate = after.naive_local();
+        loop {
+            let next = self.next_naive_after(candidate)?;
+            // Times skipped by a daylight saving time change never happen, so carry on from them
+            if let Some(next) = timezone.from_local_datetime(&next).earliest() {
+                return Some(next);
+            }
+            candidate = next;
+        }
+    }
+
+    fn next_naive_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut time = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let last_year = after.year() + MAX_YEARS_AHEAD;
+        while time.year() <= last_year {
+            if !contains(self.months, time.month()) {
+                let (year, month) = if time.month() == 12 {
+                    (time.year() + 1, 1)
+                } else {
+                    (time.year(), time.month() + 1)
+                };
+                time = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.runs_on(time.date()) {
+                time = time.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if !contains(self.hours, time.hour()) {
+                time = time.with_minute(0)? + Duration::hours(1);
+            } else if !contains(self.minutes, time.minute()) {
+                time += Duration::minutes(1);
+            } else {
+                return Some(time);
+            }
+        }
+        None
+    }
+
+    fn runs_on(&self, date: NaiveDate) -> bool {
+        let day_of_month = contains(self.days_of_month, date.day());
+        let day_of_week = contains(self.days_of_week, date.weekday().num_days_from_sunday());
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (true, true) => true,
+            (true, false) => day_of_week,
+            (false, true) => day_of_month,
+            (false, false) => day_of_month || day_of_week,
+        }
+    }
+}
+
+fn contains(set: u64, value: u32) -> bool { set & (1 << value) != 0 }
+
+/// Parse one field of a cron expression into a set of values between `min` and `max`.
+fn parse_field(field: &str, min: u32, max: u32) -> Option<u64> {
+    let mut set = 0;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|s| *s > 0)?),
+            None => (item, 1),
+        };
+        let (low, high) = if range == "*" {
+            (min, max)
+        } else if let Some((low, high)) = range.split_once('-') {
+            (low.parse().ok()?, high.parse().ok()?)
+        } else {
+            let value = range.parse().ok()?;
+            // A single value with a step runs from that value to the end of the field
+            (value, if item.contains('/') { max } else { value })
+        };
+        if low < min || high > max || low > high {
+            return None;
+        }
+        for value in (low..=high).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Some(set)
+}
+
+impl FromStr for Schedule {
+    type Err = Error;
+
+    fn from_str(value: &str) -> result::Result<Self, Self::Err> {
+        let invalid = || Error::InvalidSchedule(value.to_string());
+        let expression = value.trim();
+        let expanded = match expression {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            expression => expression,
+        };
+        let fields = expanded.split_whitespace().collect::<Vec<_>>();
+        let &[minute, hour, day_of_month, month, day_of_week] = fields.as_slice() else {
+            return Err(invalid());
+        };
+        let mut days_of_week = parse_field(day_of_week, 0, 7).ok_or_else(invalid)?;
+        // Both 0 and 7 are Sunday
+        if contains(days_of_week, 7) {
+            days_of_week |= 1;
+        }
+        Ok(Schedule { expression: expression.to_string(),
+                      minutes: parse_field(minute, 0, 59).ok_or_else(invalid)?,
+                      hours: parse_field(hour, 0, 23).ok_or_else(invalid)?,
+                      days_of_month: parse_field(day_of_month, 1, 31).ok_or_else(invalid)?,
+                      months: parse_field(month, 1, 12).ok_or_else(invalid)?,
+                      days_of_week,
+                      any_day_of_month: day_of_month.starts_with('*'),
+                      any_day_of_week: day_of_week.starts_with('*') })
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self.expression) }
+}
+
+impl<'de> serde::Deserialize<'de> for Schedule {
+    fn deserialize<D>(deserializer: D) -> result::Result<Self, D::Error>
+        where D: serde::Deserializer<'de>
+    {
+        String::deserialize(deserializer)?.parse()
+                                          .map_err(serde::de::Error::custom)
+    }
+}
+
+impl serde::Serialize for Schedule {
+    fn serialize<S>(&self, serializer: S) -> result::Result<S::Ok, S::Error>
+        where S: serde::Serializer
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn next(schedule: &str, after: &str) -> Option<String> {
+        let schedule = Schedule::from_str(schedule).unwrap();
+        let after = DateTime::parse_from_rfc3339(after).unwrap()
+                                                       .with_timezone(&Utc);
+        schedule.next_after(&after)
+                .map(|next| next.format("%Y-%m-%d %H:%M").to_string())
+    }
+
+    #[test]
+    fn schedule_from_str() {
+        assert!(Schedule::from_str("*/15 2,14 1-7 * 1-5").is_ok());
+        assert!(Schedule::from_str("@daily").is_ok());
+        assert!(Schedule::from_str("0 0 * * 7").is_ok());
+
+        assert!(Schedule::from_str("").is_err());
+        assert!(Schedule::from_str("* * * *").is_err());
+        assert!(Schedule::from_str("* * * * * *").is_err());
+        assert!(Schedule::from_str("60 * * * *").is_err());
+        assert!(Schedule::from_str("* * 0 * *").is_err());
+        assert!(Schedule::from_str("*/0 * * * *").is_err());
+        assert!(Schedule::from_str("5-1 * * * *").is_err());
+        assert!(Schedule::from_str("@often").is_err());
+    }
+
+    #[test]
+    fn schedule_displays_as_written() {
+        assert_eq!(Schedule::from_str(" 0 3 * * * ").unwrap().to_string(),
+                   "0 3 * * *");
+        assert_eq!(Schedule::from_str("@hourly").unwrap().to_string(),
+                   "@hourly");
+    }
+
+    #[test]
+    fn next_after_is_strictly_later() {
+        assert_eq!(next("* * * * *", "2024-05-01T10:00:00Z"),
+                   Some("2024-05-01 10:01".to_string()));
+        assert_eq!(next("* * * * *", "2024-05-01T10:00:30Z"),
+                   Some("2024-05-01 10:01".to_string()));
+        assert_eq!(next("0 3 * * *", "2024-05-01T03:00:00Z"),
+                   Some("2024-05-02 03:00".to_string()));
+    }
+
+    #[test]
+    fn next_after_with_steps_and_ranges() {
+        assert_eq!(next("*/15 * * * *", "2024-05-01T10:16:00Z"),
+                   Some("2024-05-01 10:30".to_string()));
+        assert_eq!(next("30 9-17/4 * * *", "2024-05-01T14:00:00Z"),
+                   Some("2024-05-01 17:30".to_string()));
+        assert_eq!(next("@monthly", "2024-12-15T00:00:00Z"),
+                   Some("2025-01-01 00:00".to_string()));
+    }
+
+    #[test]
+    fn next_after_with_days_of_week() {
+        // 2024-05-01 is a Wednesday
+        assert_eq!(next("0 0 * * 0", "2024-05-01T00:00:00Z"),
+                   Some("2024-05-05 00:00".to_string()));
+        assert_eq!(next("0 0 * * 7", "2024-05-01T00:00:00Z"),
+                   Some("2024-05-05 00:00".to_string()));
+        // Either the day of the month or the day of the week
+        assert_eq!(next("0 0 10 * 5", "2024-05-01T00:00:00Z"),
+                   Some("2024-05-03 00:00".to_string()));
+        assert_eq!(next("0 0 10 * 5", "2024-05-08T00:00:00Z"),
+                   Some("2024-05-10 00:00".to_string()));
+    }
+
+    #[test]
+    fn next_after_leap_day() {
+        assert_eq!(next("0 12 29 2 *", "2025-03-01T00:00:00Z"),
+                   Some("2028-02-29 12:00".to_string()));
+    }
+
+    #[test]
+    fn next_after_never() {
+        assert_eq!(next("0 0 30 2 *", "2024-01-01T00:00:00Z"), None);
+    }
+}
@@ -4,6 +4,7 @@
 //! `rustls`:
 //!     - http gateway
 //!     - control gateway
+//!     - gossip member authentication
 //! `native-tls`:
 //!     - builder http client
 //!     - event stream
@@ -22,5 +23,6 @@
 //! `rustls_wrapper` modules.
 
 pub mod ctl_gateway;
+pub mod gossip;
 pub mod native_tls_wrapper;
 pub mod rustls_wrapper;
@@ -0,0 +1,226 @@
+//! Utilities for authenticating Supervisor gossip with per-member X.509 certificates.
+//!
+//! Gossip is not sent over a TLS connection, so instead every message a member sends is signed
+//! with the private key of its certificate, and the certificate chain travels with the message.
+//! A receiver accepts the message only if the chain leads to a certificate authority it trusts and
+//! the signature was made with the certificate's key. Each member's certificate has the member's
+//! ID as a DNS subject alternative name, so that members can't pass themselves off as each other.
+
+use rustls::{RootCertStore,
+             SignatureScheme,
+             crypto::aws_lc_rs,
+             pki_types::{CertificateDer,
+                         PrivateKeyDer,
+                         PrivatePkcs8KeyDer,
+                         ServerName,
+                         SignatureVerificationAlgorithm,
+                         UnixTime},
+             server::{VerifierBuilderError,
+                      WebPkiClientVerifier,
+                      danger::ClientCertVerifier},
+             sign::Signer};
+use std::sync::Arc;
+use thiserror::Error;
+use webpki::EndEntityCert;
+
+/// The signature schemes a member's private key may sign with, in order of preference.
+const SIGNATURE_SCHEMES: &[SignatureScheme] = &[SignatureScheme::ECDSA_NISTP256_SHA256,
+                                                SignatureScheme::ECDSA_NISTP384_SHA384,
+                                                SignatureScheme::ED25519,
+                                                SignatureScheme::RSA_PSS_SHA256];
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("gossip member certificate chain is empty")]
+    NoCertificates,
+    #[error("gossip member private key can't be used for signing, err: {0}")]
+    UnusableKey(rustls::Error),
+    #[error("gossip member private key does not support any of the signature schemes {0:?}")]
+    UnsupportedKey(&'static [SignatureScheme]),
+    #[error("gossip certificate authority can't be used, err: {0}")]
+    CertificateAuthority(#[from] VerifierBuilderError),
+    #[error("signing gossip message failed, err: {0}")]
+    Signing(rustls::Error),
+    #[error("gossip member certificate is not trusted, err: {0}")]
+    UntrustedCertificate(rustls::Error),
+    #[error("gossip member certificate can't be parsed, err: {0}")]
+    InvalidCertificate(webpki::Error),
+    #[error("gossip message signature does not match the member's certificate")]
+    InvalidSignature,
+}
+
+/// Signs the gossip a member sends, and authenticates the gossip it receives from other members.
+#[derive(Clone, Debug)]
+pub struct MemberAuthenticator {
+    certificates: Vec<CertificateDer<'static>>,
+    signer:       Arc<dyn Signer>,
+    verifier:     Arc<dyn ClientCertVerifier>,
+    algorithms:   &'static [&'static dyn SignatureVerificationAlgorithm],
+}
+
+impl MemberAuthenticator {
+    /// Create an authenticator for the member with the certificate chain `certificates`, leaf
+    /// first, and its `private_key`, trusting members whose certificates were issued by one of
+    /// `ca_certificates`.
+    pub fn new(certificates: Vec<CertificateDer<'static>>,
+               private_key: PrivatePkcs8KeyDer<'static>,
+               ca_certificates: RootCertStore)
+               -> Result<Self, Error> {
+        if certificates.is_empty() {
+            return Err(Error::NoCertificates);
+        }
+        let provider = Arc::new(aws_lc_rs::default_provider());
+        let signer = provider.key_provider
+                             .load_private_key(PrivateKeyDer::Pkcs8(private_key))
+                             .map_err(Error::UnusableKey)?
+                             .choose_scheme(SIGNATURE_SCHEMES)
+                             .ok_or(Error::UnsupportedKey(SIGNATURE_SCHEMES))?;
+        let algorithms = provider.signature_verification_algorithms.all;
+        let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(ca_certificates),
+                                                                   provider).build()?;
+        Ok(MemberAuthenticator { certificates,
+                                 signer: Arc::from(signer),
+                                 verifier,
+                                 algorithms })
+    }
+
+    /// The member's certificate chain, leaf first.
+    pub fn certificates(&self) -> &[CertificateDer<'static>] { &self.certificates }
+
+    /// Whether the member's own certificate was issued for `member_id`.
+    pub fn is_for(&self, member_id: &str) -> bool {
+        certificate_is_for(&self.certificates[0], member_id)
+    }
+
+    /// Sign `message` with the member's private key.
+    pub fn sign(&self, message: &[u8]) -> Result<Vec<u8>, Error> {
+        self.signer.sign(message).map_err(Error::Signing)
+    }
+
+    /// Authenticate the sender of `message`, checking that its certificate chain `certificates`
+    /// leads to a trusted certificate authority and that `signature` was made with the leaf
+    /// certificate's key.
+    pub fn authenticate(&self,
+                        certificates: &[CertificateDer<'_>],
+                        message: &[u8],
+                        signature: &[u8])
+                        -> Result<AuthenticatedMember, Error> {
+        let (leaf, intermediates) = certificates.split_first().ok_or(Error::NoCertificates)?;
+        self.verifier
+            .verify_client_cert(leaf, intermediates, UnixTime::now())
+            .map_err(Error::UntrustedCertificate)?;
+        let certificate = EndEntityCert::try_from(leaf).map_err(Error::InvalidCertificate)?;
+        if self.algorithms.iter().any(|algorithm| {
+                                     certificate.verify_signature(*algorithm, message, signature)
+                                                .is_ok()
+                                 })
+        {
+            Ok(AuthenticatedMember(leaf.clone().into_owned()))
+        } else {
+            Err(Error::InvalidSignature)
+        }
+    }
+}
+
+/// A member that sent a message signed with the key of a certificate issued by a trusted
+/// certificate authority.
+#[derive(Clone, Debug)]
+pub struct AuthenticatedMember(CertificateDer<'static>);
+
+impl AuthenticatedMember {
+    /// Whether the member's certificate was issued for `member_id`.
+    pub fn is(&self, member_id: &str) -> bool { certificate_is_for(&self.0, member_id) }
+}
+
+fn certificate_is_for(certificate: &CertificateDer<'_>, member_id: &str) -> bool {
+    let Ok(name) = ServerName::try_from(member_id) else {
+        return false;
+    };
+    EndEntityCert::try_from(certificate).and_then(|certificate| {
+                                            certificate.verify_is_valid_for_subject_name(&name)
+                                        })
+                                        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcgen::{BasicConstraints,
+                CertificateParams,
+                IsCa,
+                Issuer,
+                KeyPair};
+
+    struct CertificateAuthority {
+        certificate: CertificateDer<'static>,
+        issuer:      Issuer<'static, KeyPair>,
+    }
+
+    impl CertificateAuthority {
+        fn new() -> Self {
+            let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+            params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+            let key_pair = KeyPair::generate().unwrap();
+            let certificate = params.self_signed(&key_pair).unwrap().der().clone();
+            CertificateAuthority { certificate,
+                                   issuer: Issuer::new(params, key_pair) }
+        }
+
+        fn roots(&self) -> RootCertStore {
+            let mut roots = RootCertStore::empty();
+            roots.add(self.certificate.clone()).unwrap();
+            roots
+        }
+
+        fn authenticator_for(&self, member_id: &str) -> MemberAuthenticator {
+            let params = CertificateParams::new(vec![member_id.to_string()]).unwrap();
+            let key_pair = KeyPair::generate().unwrap();
+            let certificate = params.signed_by(&key_pair, &self.issuer).unwrap();
+            MemberAuthenticator::new(vec![certificate.der().clone()],
+                                     PrivatePkcs8KeyDer::from(key_pair.serialize_der()),
+                                     self.roots()).unwrap()
+        }
+    }
+
+    #[test]
+    fn authenticates_members_of_the_same_authority() {
+        let ca = CertificateAuthority::new();
+        let alice = ca.authenticator_for("alice");
+        let bob = ca.authenticator_for("bob");
+
+        let signature = alice.sign(b"hello").unwrap();
+        let member = bob.authenticate(alice.certificates(), b"hello", &signature)
+                        .unwrap();
+        assert!(member.is("alice"));
+        assert!(!member.is("bob"));
+        assert!(alice.is_for("alice"));
+        assert!(!alice.is_for("bob"));
+    }
+
+    #[test]
+    fn rejects_tampered_messages() {
+        let ca = CertificateAuthority::new();
+        let alice = ca.authenticator_for("alice");
+        let bob = ca.authenticator_for("bob");
+
+        let signature = alice.sign(b"hello").unwrap();
+        assert!(matches!(bob.authenticate(alice.certificates(), b"goodbye", &signature),
+                         Err(Error::InvalidSignature)));
+        // Bob can't sign as Alice
+        let signature = bob.sign(b"hello").unwrap();
+        assert!(matches!(bob.authenticate(alice.certificates(), b"hello", &signature),
+                         Err(Error::InvalidSignature)));
+    }
+
+    #[test]
+    fn rejects_members_of_another_authority() {
+        let alice = CertificateAuthority::new().authenticator_for("alice");
+        let bob = CertificateAuthority::new().authenticator_for("bob");
+
+        let signature = alice.sign(b"hello").unwrap();
+        assert!(matches!(bob.authenticate(alice.certificates(), b"hello", &signature),
+                         Err(Error::UntrustedCertificate(_))));
+        assert!(matches!(bob.authenticate(&[], b"hello", &signature),
+                         Err(Error::NoCertificates)));
+    }
+}
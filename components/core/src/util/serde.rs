@@ -26,6 +26,29 @@ pub mod string {
     }
 }
 
+/// `Serialize` and `Deserialize` a `Vec` as a sequence of strings using the `ToString` and
+/// `FromStr` traits of its elements.
+pub mod string_vec {
+    use super::*;
+
+    pub fn serialize<T, S>(ts: &[T], s: S) -> Result<S::Ok, S::Error>
+        where T: ToString,
+              S: Serializer
+    {
+        s.collect_seq(ts.iter().map(ToString::to_string))
+    }
+
+    pub fn deserialize<'de, T, D>(d: D) -> Result<Vec<T>, D::Error>
+        where T: FromStr,
+              T::Err: Error,
+              D: Deserializer<'de>
+    {
+        Vec::<String>::deserialize(d)?.into_iter()
+                                      .map(|t| t.parse().map_err(de::Error::custom))
+                                      .collect()
+    }
+}
+
 /// `Serialize` and `Deserialize` a type using a proxy type that implements `Serialize` and
 /// `Deserialize`.
 pub mod proxy {
@@ -4,9 +4,13 @@ use clap_v4 as clap;
 use habitat_common::ui::UI;
 
 mod apply;
+mod history;
+mod rollback;
 mod show;
 
 use apply::ConfigApplyOptions;
+use history::ConfigHistoryOptions;
+use rollback::ConfigRollbackOptions;
 use show::ConfigShowOptions;
 
 #[derive(Debug, Clone, Subcommand)]
@@ -18,6 +22,13 @@ pub(crate) enum ConfigCommand {
     /// Apply a configuration to a running service
     Apply(ConfigApplyOptions),
 
+    /// List the configurations recently applied to a service group, or the changes between two
+    /// of them
+    History(ConfigHistoryOptions),
+
+    /// Re-apply a previous configuration to a service group
+    Rollback(ConfigRollbackOptions),
+
     /// Show the current config of a running service
     Show(ConfigShowOptions),
 }
@@ -26,6 +37,8 @@ impl ConfigCommand {
     pub(crate) async fn do_command(&self, ui: &mut UI) -> HabResult<()> {
         match self {
             ConfigCommand::Apply(opts) => opts.do_apply(ui).await,
+            ConfigCommand::History(opts) => opts.do_history().await,
+            ConfigCommand::Rollback(opts) => opts.do_rollback(ui).await,
             ConfigCommand::Show(opts) => opts.do_show().await,
         }
     }
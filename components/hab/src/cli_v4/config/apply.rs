@@ -1,6 +1,7 @@
 use crate::{cli_v4::utils::{CacheKeyPath,
                             RemoteSup},
-            command::config::sub_svc_set,
+            command::config::{sub_svc_preview,
+                              sub_svc_set},
             error::Result as HabResult};
 use clap::Parser;
 use clap_v4 as clap;
@@ -37,6 +38,11 @@ pub(crate) struct ConfigApplyOptions {
     /// Path to local file on disk (ex: /tmp/config.toml, "-" for stdin)
     #[arg(value_parser = FileExistsOrStdinValueParser, value_name = "FILE", default_value = "-")]
     file: String,
+
+    /// Render the service's templates with the configuration on the Supervisor, which must be
+    /// running a member of the service group, and show what would change instead of applying it
+    #[arg(long)]
+    dry_run: bool,
 }
 
 impl ConfigApplyOptions {
@@ -45,6 +51,14 @@ impl ConfigApplyOptions {
                                 .parse()
                                 .expect("Invalid service group identifier");
 
+        if self.dry_run {
+            return sub_svc_preview(ui,
+                                   service_group,
+                                   &self.file,
+                                   self.config_version,
+                                   self.remote_sup.inner()).await;
+        }
+
         sub_svc_set(ui,
                     service_group,
                     &self.file,
@@ -0,0 +1,52 @@
+use crate::{cli_v4::utils::{CacheKeyPath,
+                            RemoteSup},
+            command::config::{sub_svc_history,
+                              sub_svc_history_diff},
+            error::Result as HabResult};
+use clap::Parser;
+use clap_v4 as clap;
+
+#[derive(Debug, Clone, Parser)]
+#[command(arg_required_else_help = true,
+          rename_all = "kebab-case",
+          help_template = "{name} {version} {author-section} {about-section}\n{usage-heading} \
+                           {usage}\n\n{all-args}\n",
+          about = "Lists the configurations recently applied to a Service Group")]
+pub(crate) struct ConfigHistoryOptions {
+    #[command(flatten)]
+    cache_key_path: CacheKeyPath,
+
+    /// Supervisor control address (overrides HAB_SUP_CTL_ADDR)
+    #[command(flatten)]
+    remote_sup: RemoteSup,
+
+    /// Target service group service.group[@organization] (ex: redis.default or
+    /// foo.default@bazcorp)
+    #[arg(value_name = "SERVICE_GROUP")]
+    service_group: String,
+
+    /// Show the changes between two configuration versions instead, the second of which
+    /// defaults to the latest version (ex: --diff 41 42)
+    #[arg(long,
+          value_names = ["FROM", "TO"],
+          num_args = 1..=2,
+          value_parser = clap::value_parser!(u64))]
+    diff: Option<Vec<u64>>,
+}
+
+impl ConfigHistoryOptions {
+    pub(crate) async fn do_history(&self) -> HabResult<()> {
+        let service_group = self.service_group.parse()?;
+
+        match self.diff.as_deref() {
+            Some([from, to @ ..]) => {
+                sub_svc_history_diff(service_group,
+                                     *from,
+                                     to.first().copied(),
+                                     self.remote_sup.inner(),
+                                     (&self.cache_key_path).into()).await
+            }
+            _ => sub_svc_history(service_group, self.remote_sup.inner()).await,
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{cli_v4::utils::RemoteSup,
+            command::config::sub_svc_rollback,
+            error::Result as HabResult};
+use clap::Parser;
+use clap_v4 as clap;
+use habitat_common::ui::UI;
+
+#[derive(Debug, Clone, Parser)]
+#[command(arg_required_else_help = true,
+          rename_all = "kebab-case",
+          help_template = "{name} {version} {author-section} {about-section}\n{usage-heading} \
+                           {usage}\n\n{all-args}\n",
+          about = "Re-applies a configuration from a Service Group's history as a new version")]
+pub(crate) struct ConfigRollbackOptions {
+    /// Supervisor control address (overrides HAB_SUP_CTL_ADDR)
+    #[command(flatten)]
+    remote_sup: RemoteSup,
+
+    /// Target service group service.group[@organization] (ex: redis.default or
+    /// foo.default@bazcorp)
+    #[arg(value_name = "SERVICE_GROUP")]
+    service_group: String,
+
+    /// The version number of the configuration to re-apply (ex: 41)
+    #[arg(value_name = "VERSION_NUMBER", value_parser = clap::value_parser!(u64))]
+    config_version: u64,
+
+    /// The version number to re-apply the configuration as. Defaults to one more than the latest
+    /// version.
+    #[arg(long, value_name = "VERSION_NUMBER", value_parser = clap::value_parser!(u64))]
+    new_version: Option<u64>,
+}
+
+impl ConfigRollbackOptions {
+    pub(crate) async fn do_rollback(&self, ui: &mut UI) -> HabResult<()> {
+        let service_group = self.service_group.parse()?;
+
+        sub_svc_rollback(ui,
+                         service_group,
+                         self.config_version,
+                         self.new_version,
+                         self.remote_sup.inner()).await
+    }
+}
@@ -16,6 +16,7 @@ mod bulk_upload;
 
 mod channels;
 mod config;
+mod convert;
 
 mod delete;
 mod demote;
@@ -38,6 +39,7 @@ mod info;
 mod install;
 
 mod list;
+mod mirror;
 
 mod uninstall;
 mod upload;
@@ -75,6 +77,9 @@ pub(super) enum PkgCommand {
     /// Displays the default configuration options for a service
     Config(config::PkgConfigOptions),
 
+    /// Converts a Habitat Artifact to another compression, signing it again
+    Convert(convert::PkgConvertOptions),
+
     /// Removes a package from Builder
     Delete(delete::PkgDeleteOptions),
 
@@ -117,6 +122,10 @@ pub(super) enum PkgCommand {
     /// List all versions of installed packages
     List(list::PkgListOptions),
 
+    #[clap(subcommand)]
+    /// Maintains a local mirror of Builder for offline installs
+    Mirror(mirror::PkgMirrorCommand),
+
     /// Prints the path to a specific installed release of a package
     Path(path::PkgPathOptions),
 
@@ -155,6 +164,7 @@ impl PkgCommand {
 
             Self::Channels(opts) => opts.do_channels(ui).await,
             Self::Config(opts) => opts.do_config(),
+            Self::Convert(opts) => opts.do_convert(ui),
 
             Self::Delete(opts) => opts.do_delete(ui).await,
             Self::Demote(opts) => opts.do_demote(ui).await,
@@ -176,6 +186,7 @@ impl PkgCommand {
             Self::Install(opts) => opts.do_install(ui, feature_flags).await,
 
             Self::List(opts) => opts.do_list(),
+            Self::Mirror(cmd) => cmd.do_mirror(ui).await,
 
             Self::Path(opts) => opts.do_path(),
             Self::Promote(opts) => opts.do_promote(ui).await,
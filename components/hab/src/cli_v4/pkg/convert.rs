@@ -0,0 +1,59 @@
+// Implementation of `hab pkg convert` command
+
+use clap_v4 as clap;
+
+use std::path::PathBuf;
+
+use clap::{ArgAction,
+           Parser};
+
+use habitat_core::{crypto,
+                   crypto::{artifact::HartFormat,
+                            keys::KeyCache}};
+
+use habitat_common::{cli::clap_validators::FileExistsValueParser,
+                     ui::UI};
+
+use crate::{cli_v4::utils::CacheKeyPath,
+            command::pkg::convert,
+            error::Result as HabResult};
+
+#[derive(Debug, Clone, Parser)]
+#[command(arg_required_else_help = true,
+          help_template = "{name} {version} {author-section} {about-section} \n{usage-heading} \
+                           {usage}\n\n{all-args}\n")]
+pub(crate) struct PkgConvertOptions {
+    /// A path to a Habitat Artifact (ex: /home/acme-redis-3.0.7-21120102031201-x86_64-linux.hart)
+    #[arg(name = "SOURCE", value_parser = FileExistsValueParser)]
+    source: PathBuf,
+
+    /// The destination path to the converted Habitat Artifact (ex:
+    /// /tmp/acme-redis-3.0.7-21120102031201-x86_64-linux.hart)
+    #[arg(name = "DEST")]
+    dest: PathBuf,
+
+    /// The compression to convert to: xz (HART-1) or zstd (HART-2)
+    #[arg(name = "FORMAT", long = "format", default_value = "zstd", value_parser = clap::value_parser!(HartFormat))]
+    format: HartFormat,
+
+    /// Compress with a long window, which shrinks large packages (zstd only)
+    #[arg(name = "ZSTD_LONG", long = "zstd-long", action = ArgAction::SetTrue)]
+    zstd_long: bool,
+
+    #[command(flatten)]
+    cache_key_path: CacheKeyPath,
+}
+
+impl PkgConvertOptions {
+    pub(super) fn do_convert(&self, ui: &mut UI) -> HabResult<()> {
+        crypto::init()?;
+        let key_cache = KeyCache::new::<PathBuf>((&self.cache_key_path).into());
+
+        convert::start(ui,
+                       &self.source,
+                       &self.dest,
+                       self.format,
+                       self.zstd_long,
+                       &key_cache)
+    }
+}
@@ -78,30 +78,11 @@ impl PkgDownloadOptions {
     pub(super) async fn do_download(&self, ui: &mut UI) -> HabResult<()> {
         let auth_token = self.auth_token.try_from_cli_or_config();
 
-        let target = self.pkg_target.unwrap_or_else(|| {
-                                        match PackageTarget::active_target() {
-                                            #[cfg(feature = "supported_targets")]
-                                            target::X86_64_DARWIN => target::X86_64_LINUX,
-                                            t => t,
-                                        }
-                                    });
-
-        let mut package_sets = vec![];
-
-        if !self.pkg_ident.is_empty() {
-            if let Some(ref channel) = self.channel {
-                package_sets.push(PackageSet { target,
-                                               channel: channel.clone(),
-                                               idents: self.pkg_ident.clone() });
-            } else {
-                package_sets.push(PackageSet { target,
-                                               channel: ChannelIdent::default(),
-                                               idents: self.pkg_ident.clone() });
-            }
-        }
-        let mut package_sets_from_file = self.idents_from_file_matches(target)?;
-        package_sets.append(&mut package_sets_from_file);
-        package_sets.retain(|set| !set.idents.is_empty());
+        let target = default_target(self.pkg_target);
+        let package_sets = package_sets(self.channel.as_ref(),
+                                        &self.pkg_ident,
+                                        &self.pkg_ident_file,
+                                        target)?;
 
         download::start(ui,
                         &self.bldr_url.to_string(),
@@ -111,45 +92,69 @@ impl PkgDownloadOptions {
                         self.download_directory.as_ref(),
                         auth_token.as_deref(),
                         self.verify,
-                        self.ignore_missing_seed).await
+                        self.ignore_missing_seed).await?;
+        Ok(())
     }
+}
 
-    fn idents_from_file_matches(&self, target: PackageTarget) -> HabResult<Vec<PackageSet>> {
-        let mut sources: Vec<PackageSet> = Vec::new();
-
-        if !self.pkg_ident_file.is_empty() {
-            for f in &self.pkg_ident_file {
-                if is_toml_file(f) {
-                    let file_data = std::fs::read_to_string(f)?;
-                    let toml_data: PackageSetFile =
-                        toml::from_str(&file_data).map_err(HabitatCommonError::TomlParser)?;
-                    sources.append(&mut toml_data.to_package_sets()?);
-                } else {
-                    let idents_from_file = file_into_idents(f)?;
-                    match self.channel {
-                        Some(ref channel) => {
-                            sources.push(PackageSet { idents: idents_from_file,
-                                                      channel: channel.clone(),
-                                                      target })
-                        }
-                        None => {
-                            sources.push(PackageSet { idents: idents_from_file,
-                                                      channel: ChannelIdent::default(),
-                                                      target });
-                        }
-                    }
-                }
-            }
+/// The target to download packages for when none is given.
+pub(super) fn default_target(pkg_target: Option<PackageTarget>) -> PackageTarget {
+    pkg_target.unwrap_or_else(|| {
+                  match PackageTarget::active_target() {
+                      #[cfg(feature = "supported_targets")]
+                      target::X86_64_DARWIN => target::X86_64_LINUX,
+                      t => t,
+                  }
+              })
+}
+
+/// Collects the package sets given on the command line and in package identifier files.
+pub(super) fn package_sets(channel: Option<&ChannelIdent>,
+                           pkg_idents: &[PackageIdent],
+                           pkg_ident_files: &[String],
+                           target: PackageTarget)
+                           -> HabResult<Vec<PackageSet>> {
+    let mut package_sets = vec![];
+
+    if !pkg_idents.is_empty() {
+        package_sets.push(PackageSet { target,
+                                       channel: channel.cloned().unwrap_or_default(),
+                                       idents: pkg_idents.to_vec() });
+    }
+    let mut package_sets_from_file = idents_from_files(channel, pkg_ident_files, target)?;
+    package_sets.append(&mut package_sets_from_file);
+    package_sets.retain(|set| !set.idents.is_empty());
+    Ok(package_sets)
+}
+
+fn idents_from_files(channel: Option<&ChannelIdent>,
+                     pkg_ident_files: &[String],
+                     target: PackageTarget)
+                     -> HabResult<Vec<PackageSet>> {
+    let mut sources: Vec<PackageSet> = Vec::new();
+
+    for f in pkg_ident_files {
+        if is_toml_file(f) {
+            let file_data = std::fs::read_to_string(f)?;
+            let toml_data: PackageSetFile =
+                toml::from_str(&file_data).map_err(HabitatCommonError::TomlParser)?;
+            sources.append(&mut toml_data.to_package_sets()?);
+        } else {
+            let idents_from_file = file_into_idents(f)?;
+            sources.push(PackageSet { idents: idents_from_file,
+                                      channel: channel.cloned().unwrap_or_default(),
+                                      target });
         }
-        Ok(sources)
     }
+    Ok(sources)
 }
 
 #[cfg(test)]
 mod tests {
     use super::{PackageTarget,
                 Parser,
-                PkgDownloadOptions};
+                PkgDownloadOptions,
+                idents_from_files};
     use std::{collections::HashMap,
               path::Path};
 
@@ -178,7 +183,9 @@ mod tests {
                 assert!(result.is_ok(), "{:#?}", result.err().unwrap());
 
                 let pkg_download = result.unwrap();
-                let result = pkg_download.idents_from_file_matches(PackageTarget::active_target());
+                let result = idents_from_files(pkg_download.channel.as_ref(),
+                                               &pkg_download.pkg_ident_file,
+                                               PackageTarget::active_target());
                 let should_be_ok = toml_files_map[&key];
                 assert_eq!(result.is_ok(), should_be_ok, "{}: {:#?}", key, result.err());
             }
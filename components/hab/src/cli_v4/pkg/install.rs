@@ -19,12 +19,15 @@ use habitat_common::{FEATURE_FLAGS,
                      FeatureFlag,
                      cli::{BINLINK_DIR_ENVVAR,
                            DEFAULT_BINLINK_DIR},
-                     command::package::install::{self,
-                                                 InstallHookMode,
-                                                 InstallMode,
-                                                 InstallSource,
-                                                 LocalPackageUsage},
-                     ui::UI};
+                     command::package::{install::{self,
+                                                  InstallHookMode,
+                                                  InstallMode,
+                                                  InstallSource,
+                                                  LocalPackageUsage},
+                                        lockfile::Lockfile},
+                     ui::{Status,
+                          UI,
+                          UIWriter}};
 
 use crate::{PRODUCT,
             VERSION,
@@ -51,9 +54,20 @@ pub(crate) struct PkgInstallOptions {
 
     /// One or more Habitat package identifiers (ex: acme/redis) and/or filepaths to a Habitat
     /// Artifact (ex: /home/acme-redis-3.0.7-21120102031201-x86_64-linux.hart)
-    #[arg(required = true)]
+    #[arg(required_unless_present = "lockfile", conflicts_with = "lockfile")]
     pkg_ident_or_artifact: Vec<InstallSource>,
 
+    /// Install exactly the packages recorded in a lockfile, failing if any artifact does not
+    /// match its recorded checksum
+    #[arg(long = "lockfile", value_name = "LOCKFILE")]
+    lockfile: Option<PathBuf>,
+
+    /// Write a lockfile recording the installed package(s) and all their dependencies
+    #[arg(long = "write-lockfile",
+          value_name = "LOCKFILE",
+          conflicts_with = "lockfile")]
+    write_lockfile: Option<PathBuf>,
+
     /// Binlink all binaries from installed package(s) into BINLINK_DIR
     #[arg(short = 'b', long = "binlink")]
     binlink: bool,
@@ -122,28 +136,48 @@ impl PkgInstallOptions {
             _ => self.binlink,
         };
 
-        for install_source in &self.pkg_ident_or_artifact {
-            let channel = if let Some(ref channel) = self.channel {
-                channel.clone()
-            } else {
-                ChannelIdent::default()
-            };
-
-            let pkg_install = install::start(ui,
-                                             &self.bldr_url.to_string(),
-                                             &channel,
-                                             install_source,
-                                             PRODUCT,
-                                             VERSION,
-                                             &FS_ROOT_PATH,
-                                             &cache_artifact_path(Some(FS_ROOT_PATH.as_path())),
-                                             auth_token.as_deref(),
-                                             &install_mode,
-                                             &local_package_usage,
-                                             install_hook_mode).await?;
-
-            if do_binlink {
-                let binlink_dir = PathBuf::from(&self.binlink_dir);
+        let fs_root_path = FS_ROOT_PATH.as_path();
+        let artifact_cache_path = cache_artifact_path(Some(fs_root_path));
+
+        let pkg_installs = if let Some(ref lockfile) = self.lockfile {
+            install::start_from_lockfile(ui,
+                                         &self.bldr_url.to_string(),
+                                         &Lockfile::read(lockfile)?,
+                                         PRODUCT,
+                                         VERSION,
+                                         fs_root_path,
+                                         &artifact_cache_path,
+                                         auth_token.as_deref(),
+                                         &install_mode,
+                                         install_hook_mode).await?
+        } else {
+            let mut pkg_installs = Vec::with_capacity(self.pkg_ident_or_artifact.len());
+            for install_source in &self.pkg_ident_or_artifact {
+                let channel = if let Some(ref channel) = self.channel {
+                    channel.clone()
+                } else {
+                    ChannelIdent::default()
+                };
+
+                pkg_installs.push(install::start(ui,
+                                                 &self.bldr_url.to_string(),
+                                                 &channel,
+                                                 install_source,
+                                                 PRODUCT,
+                                                 VERSION,
+                                                 fs_root_path,
+                                                 &artifact_cache_path,
+                                                 auth_token.as_deref(),
+                                                 &install_mode,
+                                                 &local_package_usage,
+                                                 install_hook_mode).await?);
+            }
+            pkg_installs
+        };
+
+        if do_binlink {
+            let binlink_dir = PathBuf::from(&self.binlink_dir);
+            for pkg_install in &pkg_installs {
                 binlink::binlink_all_in_pkg(ui,
                                             pkg_install.ident(),
                                             &binlink_dir,
@@ -152,6 +186,20 @@ impl PkgInstallOptions {
             }
         }
 
+        if let Some(ref path) = self.write_lockfile {
+            install::lock(ui,
+                          &self.bldr_url.to_string(),
+                          &pkg_installs,
+                          PRODUCT,
+                          VERSION,
+                          fs_root_path,
+                          &artifact_cache_path,
+                          auth_token.as_deref(),
+                          &install_mode).await?
+                                        .write(path)?;
+            ui.status(Status::Created, path.display())?;
+        }
+
         Ok(())
     }
 }
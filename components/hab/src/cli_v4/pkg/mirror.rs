@@ -0,0 +1,112 @@
+// Implementation of `hab pkg mirror` command
+
+use clap_v4 as clap;
+
+use std::path::PathBuf;
+
+use clap::{ArgAction,
+           Parser,
+           Subcommand};
+
+use habitat_common::{cli::{PACKAGE_TARGET_ENVVAR,
+                           clap_validators::{HabPkgIdentValueParser,
+                                             TomlOrPkgIdentFileValueParser}},
+                     ui::UI};
+
+use habitat_core::{ChannelIdent,
+                   env::Config,
+                   package::{PackageIdent,
+                             PackageTarget}};
+
+use crate::{PRODUCT,
+            VERSION,
+            cli_v4::utils::{AuthToken,
+                            BldrUrl},
+            command::pkg::mirror,
+            error::Result as HabResult};
+
+use super::download::{default_target,
+                      package_sets};
+
+#[derive(Debug, Clone, Subcommand)]
+#[command(arg_required_else_help = true,
+          help_template = "{name} {version} {author-section} {about-section} \n{usage-heading} \
+                           {usage}\n\n{all-args}\n")]
+pub(crate) enum PkgMirrorCommand {
+    /// Downloads packages, their dependencies and keys into a local mirror of Builder
+    Sync(PkgMirrorSyncOptions),
+}
+
+impl PkgMirrorCommand {
+    pub(super) async fn do_mirror(&self, ui: &mut UI) -> HabResult<()> {
+        match self {
+            PkgMirrorCommand::Sync(opts) => opts.do_sync(ui).await,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Parser)]
+#[command(arg_required_else_help = true,
+          help_template = "{name} {version} {author-section} {about-section} \n{usage-heading} \
+                           {usage}\n\n{all-args}\n")]
+pub(crate) struct PkgMirrorSyncOptions {
+    #[command(flatten)]
+    auth_token: AuthToken,
+
+    #[command(flatten)]
+    bldr_url: BldrUrl,
+
+    /// Sync from the specified release channel. The synced packages are added to the mirror's
+    /// index of this channel. Overridden if channel is specified in toml file
+    #[arg(name = "CHANNEL",
+          short = 'c',
+          long = "channel",
+          env = habitat_core::ChannelIdent::ENVVAR)]
+    channel: Option<ChannelIdent>,
+
+    /// The path of the mirror. Use it with `--url file://<MIRROR_DIRECTORY>` to install from it
+    #[arg(name = "MIRROR_DIRECTORY", long = "mirror-directory", required = true)]
+    mirror_directory: PathBuf,
+
+    /// File with newline separated package identifiers, or TOML file (ending with .toml extension)
+    #[arg(name = "PKG_IDENT_FILE", long = "file", num_args = 1..=10, value_parser = TomlOrPkgIdentFileValueParser)]
+    pkg_ident_file: Vec<String>,
+
+    /// One or more Package Identifiers to sync (eg. core/redis)
+    #[arg(name = "PKG_IDENT", num_args = 1.., value_parser = HabPkgIdentValueParser::simple())]
+    pkg_ident: Vec<PackageIdent>,
+
+    /// A package target (ex: x86_64-windows) (default: system appropriate target)
+    #[arg(name = "PKG_TARGET", env = PACKAGE_TARGET_ENVVAR, short = 't', long = "target")]
+    pkg_target: Option<PackageTarget>,
+
+    /// Verify package integrity after download (Warning: this can be slow)
+    #[arg(name = "VERIFY", long = "verify", action = ArgAction::SetTrue)]
+    verify: bool,
+
+    /// Ignore packages specified that are not present on the target Builder
+    #[arg(name = "IGNORE_MISSING_SEEDS", long = "ignore-missing-seeds", action = ArgAction::SetTrue)]
+    ignore_missing_seed: bool,
+}
+
+impl PkgMirrorSyncOptions {
+    async fn do_sync(&self, ui: &mut UI) -> HabResult<()> {
+        let auth_token = self.auth_token.try_from_cli_or_config();
+
+        let target = default_target(self.pkg_target);
+        let package_sets = package_sets(self.channel.as_ref(),
+                                        &self.pkg_ident,
+                                        &self.pkg_ident_file,
+                                        target)?;
+
+        mirror::sync(ui,
+                     &self.bldr_url.to_string(),
+                     PRODUCT,
+                     VERSION,
+                     &package_sets,
+                     &self.mirror_directory,
+                     auth_token.as_deref(),
+                     self.verify,
+                     self.ignore_missing_seed).await
+    }
+}
@@ -4,10 +4,12 @@ use clap_v4 as clap;
 
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{ArgAction,
+           Parser};
 
 use habitat_core::{crypto,
-                   crypto::keys::KeyCache,
+                   crypto::{artifact::HartFormat,
+                            keys::KeyCache},
                    origin::Origin};
 
 use habitat_common::{cli::clap_validators::FileExistsValueParser,
@@ -36,6 +38,15 @@ pub(crate) struct PkgSignOptions {
     #[arg(name = "DEST")]
     dest: PathBuf,
 
+    /// The compression of the signed Habitat Artifact: xz (HART-1) or zstd (HART-2). A source
+    /// archive compressed differently is recompressed
+    #[arg(name = "FORMAT", long = "format", default_value = "xz", value_parser = clap::value_parser!(HartFormat))]
+    format: HartFormat,
+
+    /// Compress with a long window, which shrinks large packages (zstd only)
+    #[arg(name = "ZSTD_LONG", long = "zstd-long", action = ArgAction::SetTrue)]
+    zstd_long: bool,
+
     #[command(flatten)]
     cache_key_path: CacheKeyPath,
 }
@@ -50,6 +61,8 @@ impl PkgSignOptions {
         sign::start(ui,
                     &key,
                     &Into::<PathBuf>::into(self.source.clone()),
-                    &self.dest)
+                    &self.dest,
+                    self.format,
+                    self.zstd_long)
     }
 }
@@ -7,9 +7,12 @@ use std::path::PathBuf;
 use clap::Parser;
 
 use habitat_core::{crypto,
-                   crypto::keys::KeyCache};
+                   crypto::keys::KeyCache,
+                   fs::FS_ROOT_PATH,
+                   package::PackageIdent};
 
-use habitat_common::{cli::clap_validators::FileExistsValueParser,
+use habitat_common::{cli::clap_validators::{FileExistsValueParser,
+                                            HabPkgIdentValueParser},
                      ui::UI};
 
 use crate::{cli_v4::utils::CacheKeyPath,
@@ -22,8 +25,18 @@ use crate::{cli_v4::utils::CacheKeyPath,
                            {usage}\n\n{all-args}\n")]
 pub(crate) struct PkgVerifyOptions {
     /// A path to a Habitat Artifact (ex: /home/acme-redis-3.0.7-21120102031201-x86_64-linux.hart)
-    #[arg(name = "SOURCE", value_parser = FileExistsValueParser)]
-    source: PathBuf,
+    #[arg(name = "SOURCE",
+          value_parser = FileExistsValueParser,
+          required_unless_present = "INSTALLED")]
+    source: Option<PathBuf>,
+
+    /// Verify the files of an installed package against its signed FILES metafile instead,
+    /// reporting added, removed and modified files (ex: core/redis)
+    #[arg(name = "INSTALLED",
+          long = "installed",
+          conflicts_with = "SOURCE",
+          value_parser = HabPkgIdentValueParser::simple())]
+    installed: Option<PackageIdent>,
 
     #[command(flatten)]
     cache_key_path: CacheKeyPath,
@@ -34,6 +47,10 @@ impl PkgVerifyOptions {
         crypto::init()?;
         let key_cache = KeyCache::new::<PathBuf>((&self.cache_key_path).into());
 
-        verify::start(ui, &self.source, &key_cache)
+        match (&self.installed, &self.source) {
+            (Some(ident), _) => verify::start_installed(ui, ident, &FS_ROOT_PATH, &key_cache),
+            (None, Some(source)) => verify::start(ui, source, &key_cache),
+            (None, None) => unreachable!("clap requires SOURCE unless INSTALLED is present"),
+        }
     }
 }
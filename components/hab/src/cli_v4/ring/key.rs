@@ -6,10 +6,12 @@ use habitat_common::ui::UI;
 mod export;
 mod generate;
 mod import;
+mod rotate;
 
 use export::RingKeyExportOpts;
 use generate::RingKeyGenerateOpts;
 use import::RingKeyImportOpts;
+use rotate::RingKeyRotateCommand;
 
 #[derive(Debug, Clone, Subcommand)]
 #[command(rename_all = "kebab-case",
@@ -25,6 +27,10 @@ pub(crate) enum RingKeyCommand {
 
     /// Generates a Habitat ring key
     Generate(RingKeyGenerateOpts),
+
+    /// Rotates the ring key of running Supervisors without partitioning the ring
+    #[command(subcommand)]
+    Rotate(RingKeyRotateCommand),
 }
 
 impl RingKeyCommand {
@@ -33,6 +39,7 @@ impl RingKeyCommand {
             RingKeyCommand::Export(opts) => opts.do_export().await,
             RingKeyCommand::Generate(opts) => opts.do_generate(ui).await,
             RingKeyCommand::Import(opts) => opts.do_import(ui).await,
+            RingKeyCommand::Rotate(cmd) => cmd.do_command(ui).await,
         }
     }
 }
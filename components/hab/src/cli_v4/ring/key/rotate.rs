@@ -0,0 +1,145 @@
+// Implementation of `hab ring key rotate`
+
+use crate::{cli_v4::utils::RemoteSup,
+            error::Result as HabResult};
+use clap::{Parser,
+           Subcommand};
+use clap_v4 as clap;
+use futures::stream::StreamExt;
+use habitat_common::ui::{Status,
+                         UI,
+                         UIWriter};
+use habitat_sup_client::{SrvClient,
+                         SrvClientError};
+use habitat_sup_protocol::{self as sup_proto,
+                           codec::SrvMessage,
+                           ctl::SupRingKeys,
+                           net::NetErr};
+use std::{fmt,
+          io};
+
+/// Rotating a ring key without partitioning the ring takes three steps, each of which must be
+/// completed on every Supervisor in the ring before the next one is started:
+///
+/// 1. `stage` the new revision, after importing it into each Supervisor's key cache
+/// 2. `activate` the new revision
+/// 3. `retire` the old revision
+#[derive(Debug, Clone, Subcommand)]
+#[command(rename_all = "kebab-case",
+          arg_required_else_help = true,
+          help_template = "{name} {version} {author-section} \
+                           {about-section}\n{usage-heading}\n{usage}\n\n{all-args}\n")]
+pub(crate) enum RingKeyRotateCommand {
+    /// Accepts gossip encrypted with a ring key revision from a Supervisor's key cache
+    Stage(RingKeyRevisionOpts),
+
+    /// Encrypts gossip with a staged ring key revision
+    Activate(RingKeyRevisionOpts),
+
+    /// Stops accepting gossip encrypted with a ring key revision
+    Retire(RingKeyRevisionOpts),
+
+    /// Shows the ring key revisions a Supervisor is using
+    Status(RingKeyStatusOpts),
+}
+
+#[derive(Debug, Clone, Parser)]
+#[command(help_template = "{name} {version} {author-section} \
+                           {about-section}\n{usage-heading}\n{usage}\n\n{all-args}\n")]
+pub(crate) struct RingKeyRevisionOpts {
+    /// The ring key revision (ex: myring-20160504220722)
+    #[arg(name = "REVISION")]
+    revision: String,
+
+    #[command(flatten)]
+    remote_sup: RemoteSup,
+}
+
+#[derive(Debug, Clone, Parser)]
+#[command(help_template = "{name} {version} {author-section} \
+                           {about-section}\n{usage-heading}\n{usage}\n\n{all-args}\n")]
+pub(crate) struct RingKeyStatusOpts {
+    #[command(flatten)]
+    remote_sup: RemoteSup,
+}
+
+impl RingKeyRotateCommand {
+    pub(crate) async fn do_command(&self, ui: &mut UI) -> HabResult<()> {
+        match self {
+            RingKeyRotateCommand::Stage(opts) => {
+                let msg =
+                    sup_proto::ctl::SupRingKeyStage { revision: Some(opts.revision.clone()), };
+                ui.begin(format!("Staging ring key {}", opts.revision))?;
+                ui.status(Status::Applying,
+                          format!("via peer {}", opts.remote_sup.inner()))?;
+                let ring_keys = request_ring_keys(&opts.remote_sup, msg).await?;
+                ui.end("Ring key staged.")?;
+                print_ring_keys(&ring_keys);
+                Ok(())
+            }
+            RingKeyRotateCommand::Activate(opts) => {
+                let msg =
+                    sup_proto::ctl::SupRingKeyActivate { revision: Some(opts.revision.clone()), };
+                ui.begin(format!("Activating ring key {}", opts.revision))?;
+                ui.status(Status::Applying,
+                          format!("via peer {}", opts.remote_sup.inner()))?;
+                let ring_keys = request_ring_keys(&opts.remote_sup, msg).await?;
+                ui.end("Ring key activated.")?;
+                print_ring_keys(&ring_keys);
+                Ok(())
+            }
+            RingKeyRotateCommand::Retire(opts) => {
+                let msg =
+                    sup_proto::ctl::SupRingKeyRetire { revision: Some(opts.revision.clone()), };
+                ui.begin(format!("Retiring ring key {}", opts.revision))?;
+                ui.status(Status::Applying,
+                          format!("via peer {}", opts.remote_sup.inner()))?;
+                let ring_keys = request_ring_keys(&opts.remote_sup, msg).await?;
+                ui.end("Ring key retired.")?;
+                print_ring_keys(&ring_keys);
+                Ok(())
+            }
+            RingKeyRotateCommand::Status(opts) => {
+                let ring_keys =
+                    request_ring_keys(&opts.remote_sup,
+                                      sup_proto::ctl::SupRingKeyStatus::default()).await?;
+                print_ring_keys(&ring_keys);
+                Ok(())
+            }
+        }
+    }
+}
+
+async fn request_ring_keys(remote_sup: &RemoteSup,
+                           msg: impl Into<SrvMessage> + fmt::Debug)
+                           -> HabResult<SupRingKeys> {
+    let mut response = SrvClient::request(remote_sup.inner(), msg).await?;
+    let reply = match response.next().await {
+        Some(message_result) => message_result?,
+        None => {
+            return Err(SrvClientError::from(io::Error::from(io::ErrorKind::UnexpectedEof)).into());
+        }
+    };
+    match reply.message_id() {
+        "SupRingKeys" => {
+            Ok(reply.parse::<SupRingKeys>()
+                    .map_err(SrvClientError::Decode)?)
+        }
+        "NetErr" => {
+            let err = reply.parse::<NetErr>().map_err(SrvClientError::Decode)?;
+            Err(SrvClientError::from(err).into())
+        }
+        _ => Err(SrvClientError::from(io::Error::from(io::ErrorKind::UnexpectedEof)).into()),
+    }
+}
+
+fn print_ring_keys(ring_keys: &SupRingKeys) {
+    let accepted = if ring_keys.accepted.is_empty() {
+        "<none>".to_string()
+    } else {
+        ring_keys.accepted.join(", ")
+    };
+    println!("Primary ring key: {}",
+             ring_keys.primary.as_deref().unwrap_or("<none>"));
+    println!("Accepted ring keys: {}", accepted);
+}
@@ -77,6 +77,10 @@ impl From<EventStreamAddress> for NatsAddress {
     fn from(address: EventStreamAddress) -> Self { address.0 }
 }
 
+fn service_log_max_size_default() -> u64 { 10 }
+
+fn service_log_retain_default() -> usize { 5 }
+
 #[derive(GenConfig)]
 #[derive(Debug, Clone, Args, Serialize, Deserialize)]
 #[command(disable_version_flag = true,
@@ -100,9 +104,21 @@ pub struct SupRunOptions {
     #[serde(default)]
     pub peer_watch_file: Option<PathBuf>,
 
+    /// Sources to periodically discover peers from: `dns:NAME[@NAMESERVER[:PORT]]` (the SRV
+    /// records of NAME, or its addresses if it has none), `exec:PATH` (an executable printing one
+    /// peer per line), or an http(s) URL (returning a JSON list of peers).
+    #[arg(long = "peer-discovery", conflicts_with_all = &["peer", "peer_watch_file"])]
+    #[serde(default)]
+    pub peer_discovery: Vec<String>,
+
+    /// Time (seconds) between peer discovery refreshes.
+    #[arg(long = "peer-discovery-period", default_value = "60")]
+    #[serde(default = "DurationProxy::from_60")]
+    pub peer_discovery_period: DurationProxy,
+
     /// Start in local gossip mode.
     #[arg(long = "local-gossip-mode",
-        conflicts_with_all = &["listen_gossip", "peer", "peer_watch_file"])]
+        conflicts_with_all = &["listen_gossip", "peer", "peer_watch_file", "peer_discovery"])]
     #[serde(default)]
     pub local_gossip_mode: bool,
 
@@ -167,6 +183,31 @@ pub struct SupRunOptions {
             conflicts_with = "ring_key")]
     pub ring: Option<String>,
 
+    /// Authenticate gossip with per-member certificates, and set this Supervisor's certificate.
+    /// The certificate must be issued for the Supervisor's member ID.
+    #[arg(long = "gossip-certificate",
+            requires_all = &["gossip_key", "gossip_ca_certificate"])]
+    pub gossip_certificate: Option<CertificateChainCli>,
+
+    /// The private key of this Supervisor's gossip certificate.
+    #[arg(long = "gossip-key", requires = "gossip_certificate")]
+    pub gossip_key: Option<PrivateKeyCli>,
+
+    /// The certificate authority that issues the gossip certificates of the Supervisors in the
+    /// ring.
+    #[arg(long = "gossip-ca-certificate", requires = "gossip_certificate")]
+    pub gossip_ca_certificate: Option<RootCertificateStoreCli>,
+
+    /// The gossip segment, usually a region or a zone, this Supervisor belongs to (default:
+    /// default). Supervisors only probe the members of their own segment.
+    #[arg(long = "gossip-segment")]
+    pub gossip_segment: Option<String>,
+
+    /// Relay rumors between this Supervisor's gossip segment and the other segments
+    #[arg(long = "gossip-segment-gateway", requires = "gossip_segment")]
+    #[serde(default)]
+    pub gossip_segment_gateway: bool,
+
     /// Enable automatic updates for the Supervisor itself
     #[arg(long = "auto-update", short = 'A')]
     #[serde(default)]
@@ -200,6 +241,30 @@ pub struct SupRunOptions {
     #[serde(default = "DurationProxy::from_300")]
     pub service_restart_cooldown_period: DurationProxy,
 
+    /// The size in megabytes at which a service's run hook log files are rotated
+    ///
+    /// Set to '0' to disable size based rotation.
+    #[arg(long = "service-log-max-size", default_value = "10")]
+    #[serde(default = "service_log_max_size_default")]
+    pub service_log_max_size: u64,
+
+    /// The period of time in seconds after which a service's run hook log files are rotated
+    ///
+    /// Set to '0' to disable age based rotation.
+    #[arg(long = "service-log-max-age", default_value = "0")]
+    #[serde(default = "DurationProxy::from_0")]
+    pub service_log_max_age: DurationProxy,
+
+    /// Compress rotated service log files with gzip
+    #[arg(long = "service-log-compress")]
+    #[serde(default)]
+    pub service_log_compress: bool,
+
+    /// The number of rotated files to keep for each service log file
+    #[arg(long = "service-log-retain", default_value = "5")]
+    #[serde(default = "service_log_retain_default")]
+    pub service_log_retain: usize,
+
     /// The private key for HTTP Gateway TLS encryption
     ///
     /// Read the private key from KEY_FILE. This should be an RSA private key or PKCS8-encoded
@@ -309,6 +374,12 @@ pub struct SupRunOptions {
     #[arg(long = "keep-latest-packages", env = "HAB_KEEP_LATEST_PACKAGES")]
     pub keep_latest_packages: Option<usize>,
 
+    /// Only start services whose installed package files, and those of their dependencies,
+    /// match the signed FILES metafiles of the packages
+    #[arg(long = "verify-installed-files")]
+    #[serde(default)]
+    pub verify_installed_files: bool,
+
     /// Paths to config files to Read
     #[arg(long = "config-files", value_delimiter=' ', num_args = 1.., value_parser = FileExistsValueParser)]
     #[serde(skip)]
@@ -393,6 +464,12 @@ impl SupRunOptions {
         if self.peer_watch_file.is_none() {
             self.peer_watch_file = other.peer_watch_file;
         }
+        if self.peer_discovery.is_empty() {
+            self.peer_discovery = other.peer_discovery;
+        }
+        if self.peer_discovery_period == 60_u64.into() {
+            self.peer_discovery_period = other.peer_discovery_period;
+        }
 
         self.local_gossip_mode |= other.local_gossip_mode;
 
@@ -434,6 +511,23 @@ impl SupRunOptions {
             self.ring = other.ring;
         }
 
+        if self.gossip_certificate.is_none() {
+            self.gossip_certificate = other.gossip_certificate;
+        }
+
+        if self.gossip_key.is_none() {
+            self.gossip_key = other.gossip_key;
+        }
+
+        if self.gossip_ca_certificate.is_none() {
+            self.gossip_ca_certificate = other.gossip_ca_certificate;
+        }
+
+        if self.gossip_segment.is_none() {
+            self.gossip_segment = other.gossip_segment;
+        }
+        self.gossip_segment_gateway |= other.gossip_segment_gateway;
+
         self.auto_update |= other.auto_update;
 
         if self.auto_update_period == 60_u64.into() {
@@ -456,6 +550,20 @@ impl SupRunOptions {
             self.service_restart_cooldown_period = other.service_restart_cooldown_period;
         }
 
+        if self.service_log_max_size == service_log_max_size_default() {
+            self.service_log_max_size = other.service_log_max_size;
+        }
+
+        if self.service_log_max_age == 0_u64.into() {
+            self.service_log_max_age = other.service_log_max_age;
+        }
+
+        self.service_log_compress |= other.service_log_compress;
+
+        if self.service_log_retain == service_log_retain_default() {
+            self.service_log_retain = other.service_log_retain;
+        }
+
         if self.key_file.is_none() {
             self.key_file = other.key_file;
         }
@@ -517,6 +625,7 @@ impl SupRunOptions {
         if self.keep_latest_packages.is_none() {
             self.keep_latest_packages = other.keep_latest_packages;
         }
+        self.verify_installed_files |= other.verify_installed_files;
 
         self.config_files.clear();
 
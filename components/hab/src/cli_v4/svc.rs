@@ -12,9 +12,15 @@ use crate::error::Result as HabResult;
 mod key;
 use key::KeyCommand;
 
+mod leader;
+use leader::LeaderCommand;
+
 mod load;
 use load::LoadCommand;
 
+mod logs;
+use logs::LogsCommand;
+
 mod update;
 use update::UpdateCommand;
 
@@ -39,8 +45,13 @@ pub(crate) enum SvcCommand {
     #[clap(subcommand)]
     Key(KeyCommand),
 
+    #[clap(subcommand)]
+    Leader(LeaderCommand),
+
     Load(LoadCommand),
 
+    Logs(LogsCommand),
+
     Start(StartCommand),
 
     Status(StatusCommand),
@@ -58,7 +69,9 @@ impl SvcCommand {
                                    _feature_flags: FeatureFlag)
                                    -> HabResult<()> {
         match self {
+            Self::Leader(leader_cmd) => leader_cmd.do_command().await,
             Self::Load(load_cmd) => load_cmd.do_command().await,
+            Self::Logs(logs_cmd) => logs_cmd.do_command().await,
             Self::Unload(unload_cmd) => unload_cmd.clone().do_command().await,
             Self::Key(KeyCommand::Generate(key_generate_cmd)) => {
                 key_generate_cmd.do_command(ui).await
@@ -0,0 +1,90 @@
+use clap_v4 as clap;
+
+use clap::Parser;
+
+use habitat_core::service::ServiceGroup;
+
+use crate::{cli_v4::utils::RemoteSup,
+            error::Result as HabResult,
+            gateway_util};
+
+/// Commands relating to the leader of a service group running in the leader topology
+#[derive(Clone, Debug, Parser)]
+#[command(author = "\nThe Habitat Maintainers <humans@habitat.sh>",
+          help_template = "{name} {version} {author-section} {about-section} \n{usage-heading} \
+                           {usage}\n\n{all-args}\n")]
+pub(crate) enum LeaderCommand {
+    StepDown(LeaderStepDown),
+
+    Transfer(LeaderTransfer),
+}
+
+impl LeaderCommand {
+    pub(crate) async fn do_command(&self) -> HabResult<()> {
+        match self {
+            Self::StepDown(step_down_cmd) => step_down_cmd.do_command().await,
+            Self::Transfer(transfer_cmd) => transfer_cmd.do_command().await,
+        }
+    }
+}
+
+/// Have the leader of a service group step down
+///
+/// A new election is started in which the current leader cannot win. The suitability hook
+/// decides which of the other alive members becomes the leader.
+#[derive(Clone, Debug, Parser)]
+#[command(author = "\nThe Habitat Maintainers <humans@habitat.sh>",
+          help_template = "{name} {version} {author-section} {about-section} \n{usage-heading} \
+                           {usage}\n\n{all-args}\n")]
+pub(crate) struct LeaderStepDown {
+    /// Target service group service.group[@organization] (ex: redis.default or
+    /// foo.default@bazcorp)
+    #[arg(name = "SERVICE_GROUP")]
+    service_group: ServiceGroup,
+
+    #[command(flatten)]
+    remote_sup: RemoteSup,
+}
+
+impl LeaderStepDown {
+    pub(crate) async fn do_command(&self) -> HabResult<()> {
+        let msg = habitat_sup_protocol::ctl::SvcLeaderStepDown { service_group:
+                                                                     Some(self.service_group
+                                                                              .clone()
+                                                                              .into()), };
+        gateway_util::send(self.remote_sup.inner(), msg).await
+    }
+}
+
+/// Transfer the leadership of a service group to another member
+///
+/// A new election is started which the given member wins, as long as it stays alive.
+#[derive(Clone, Debug, Parser)]
+#[command(author = "\nThe Habitat Maintainers <humans@habitat.sh>",
+          help_template = "{name} {version} {author-section} {about-section} \n{usage-heading} \
+                           {usage}\n\n{all-args}\n")]
+pub(crate) struct LeaderTransfer {
+    /// Target service group service.group[@organization] (ex: redis.default or
+    /// foo.default@bazcorp)
+    #[arg(name = "SERVICE_GROUP")]
+    service_group: ServiceGroup,
+
+    /// The member ID of the Supervisor to transfer leadership to
+    #[arg(name = "MEMBER_ID")]
+    member_id: String,
+
+    #[command(flatten)]
+    remote_sup: RemoteSup,
+}
+
+impl LeaderTransfer {
+    pub(crate) async fn do_command(&self) -> HabResult<()> {
+        let msg = habitat_sup_protocol::ctl::SvcLeaderTransfer { service_group:
+                                                                     Some(self.service_group
+                                                                              .clone()
+                                                                              .into()),
+                                                                 member_id:
+                                                                     Some(self.member_id.clone()), };
+        gateway_util::send(self.remote_sup.inner(), msg).await
+    }
+}
@@ -1,14 +1,20 @@
 use clap_v4 as clap;
 
-use std::convert::TryFrom;
+use std::{convert::TryFrom,
+          fs,
+          path::PathBuf};
 
 use clap::{Parser,
            value_parser};
 
+use futures::stream::StreamExt;
 use serde::{Deserialize,
             Serialize};
 
 use hab_common_derive::GenConfig;
+use habitat_sup_client::{SrvClient,
+                         SrvClientError};
+use habitat_sup_protocol::ctl::SvcLockfile;
 
 use crate::{cli_v4::utils::{PkgIdent,
                             RemoteSup,
@@ -36,6 +42,15 @@ pub(crate) struct LoadCommand {
     #[arg(short = 'f', long = "force")]
     force: bool,
 
+    /// Install the service's package from a lockfile, as written by `hab pkg install
+    /// --write-lockfile`, instead of resolving it against its channel
+    #[arg(long = "lockfile", value_name = "LOCKFILE")]
+    lockfile: Option<PathBuf>,
+
+    /// Write a lockfile recording the service's package and all its dependencies
+    #[arg(long = "write-lockfile", value_name = "LOCKFILE")]
+    write_lockfile: Option<PathBuf>,
+
     #[arg(long = "generate-config", hide = true)]
     generate_config: bool,
 
@@ -50,9 +65,16 @@ impl TryFrom<LoadCommand> for habitat_sup_protocol::ctl::SvcLoad {
     type Error = HabError;
 
     fn try_from(cmd: LoadCommand) -> HabResult<Self> {
-        shared_load_cli_to_ctl(cmd.pkg_ident.unwrap().pkg_ident(),
-                               cmd.shared_load,
-                               cmd.force)
+        let lockfile = cmd.lockfile.map(fs::read_to_string).transpose()?;
+        let write_lockfile = cmd.write_lockfile.is_some();
+        let mut msg = shared_load_cli_to_ctl(cmd.pkg_ident.unwrap().pkg_ident(),
+                                             cmd.shared_load,
+                                             cmd.force)?;
+        msg.lockfile = lockfile;
+        if write_lockfile {
+            msg.write_lockfile = Some(true);
+        }
+        Ok(msg)
     }
 }
 
@@ -64,7 +86,21 @@ impl LoadCommand {
         } else {
             let remote_sup = self.remote_sup.clone();
             let msg = habitat_sup_protocol::ctl::SvcLoad::try_from(self.clone())?;
-            gateway_util::send(remote_sup.inner(), msg).await
+            let mut response = SrvClient::request(remote_sup.inner(), msg).await?;
+            while let Some(message_result) = response.next().await {
+                let reply = message_result?;
+                match reply.message_id() {
+                    "SvcLockfile" => {
+                        let lockfile = reply.parse::<SvcLockfile>()
+                                            .map_err(SrvClientError::Decode)?;
+                        if let Some(ref path) = self.write_lockfile {
+                            fs::write(path, lockfile.contents.unwrap_or_default())?;
+                        }
+                    }
+                    _ => gateway_util::handle_ctl_reply(&reply)?,
+                }
+            }
+            Ok(())
         }
     }
 }
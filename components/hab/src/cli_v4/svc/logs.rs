@@ -0,0 +1,115 @@
+use clap_v4 as clap;
+
+use chrono::{DateTime,
+             Duration,
+             Utc};
+use clap::Parser;
+
+use habitat_common::cli::clap_validators::HabPkgIdentValueParser;
+use habitat_core::package::PackageIdent;
+
+use crate::{cli_v4::utils::RemoteSup,
+            error::Result as HabResult,
+            gateway_util};
+
+/// Show the output of a Habitat service's run hook and lifecycle hooks
+#[derive(Clone, Debug, Parser)]
+#[command(author = "\nThe Habitat Maintainers <humans@habitat.sh>",
+          help_template = "{name} {version} {author-section} {about-section} \n{usage-heading} \
+                           {usage}\n\n{all-args}\n")]
+pub(crate) struct LogsCommand {
+    /// A package identifier (ex: core/redis, core/busybox-static/1.42.2)
+    #[arg(name = "PKG_IDENT", value_parser = HabPkgIdentValueParser::simple())]
+    pkg_ident: PackageIdent,
+
+    /// Keep streaming output as it is written
+    #[arg(name = "FOLLOW", short = 'f', long = "follow")]
+    follow: bool,
+
+    /// Only show output written since this time, either an RFC 3339 timestamp (ex:
+    /// 2024-01-01T12:00:00Z) or a duration relative to now (ex: 30s, 15m, 2h, 1d)
+    #[arg(name = "SINCE", long = "since", value_parser = parse_since)]
+    since: Option<DateTime<Utc>>,
+
+    /// Only show the last N lines of output
+    #[arg(name = "LINES", short = 'n', long = "lines")]
+    lines: Option<u32>,
+
+    #[command(flatten)]
+    remote_sup: RemoteSup,
+}
+
+impl LogsCommand {
+    pub(crate) async fn do_command(&self) -> HabResult<()> {
+        let remote_sup = self.remote_sup.clone();
+        let since = self.since.map(|since| since.timestamp().max(0) as u64);
+        let msg =
+            habitat_sup_protocol::ctl::SvcLogs { ident: Some(self.pkg_ident.clone().into()),
+                                                 since,
+                                                 lines: self.lines,
+                                                 follow: Some(self.follow) };
+        gateway_util::send(remote_sup.inner(), msg).await
+    }
+}
+
+fn parse_since(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+    let invalid = || {
+        format!("'{}' is neither an RFC 3339 timestamp nor a duration such as 30s, 15m, 2h or 1d",
+                value)
+    };
+    let unit = value.chars().last().ok_or_else(invalid)?;
+    let amount = value[..value.len() - unit.len_utf8()].parse::<i64>()
+                                                       .map_err(|_| invalid())?;
+    if amount <= 0 {
+        return Err(format!("'{}' is not a positive duration; durations count \
+                            back from now, such as 30s, 15m, 2h or 1d",
+                           value));
+    }
+    let duration = match unit {
+        's' => Duration::try_seconds(amount),
+        'm' => Duration::try_minutes(amount),
+        'h' => Duration::try_hours(amount),
+        'd' => Duration::try_days(amount),
+        _ => None,
+    };
+    duration.map(|duration| Utc::now() - duration)
+            .ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn since_accepts_timestamps() {
+        assert_eq!(parse_since("2024-01-01T12:00:00Z").unwrap(),
+                   DateTime::parse_from_rfc3339("2024-01-01T12:00:00Z").unwrap());
+    }
+
+    #[test]
+    fn since_accepts_relative_durations() {
+        let since = parse_since("15m").unwrap();
+        let expected = Utc::now() - Duration::minutes(15);
+        assert!((expected - since).num_seconds().abs() <= 1);
+
+        assert!(parse_since("2d").is_ok());
+    }
+
+    #[test]
+    fn since_rejects_garbage() {
+        assert!(parse_since("yesterday").is_err());
+        assert!(parse_since("15").is_err());
+        assert!(parse_since("m").is_err());
+        assert!(parse_since("").is_err());
+    }
+
+    #[test]
+    fn since_rejects_durations_which_are_not_positive() {
+        let err = parse_since("-5m").unwrap_err();
+        assert!(err.contains("not a positive duration"), "{}", err);
+        assert!(parse_since("0s").is_err());
+    }
+}
@@ -16,6 +16,7 @@ use habitat_sup_protocol::{codec::SrvMessage,
                            net::NetErr,
                            types::{DesiredState,
                                    ProcessState,
+                                   ResourceUsage,
                                    ServiceStatus}};
 
 use crate::{cli_v4::utils::RemoteSup,
@@ -29,7 +30,11 @@ lazy_static::lazy_static! {
              "state",
              "elapsed (s)",
              "pid",
-             "group",]
+             "group",
+             "cpu (s)",
+             "memory (MiB)",
+             "pids",
+             "scheduled exit",]
     };
 }
 
@@ -99,19 +104,40 @@ fn print_svc_status<T>(out: &mut T,
     };
     let svc_desired_state = status.desired_state
                                   .map_or_else(|| "<none>".to_string(), |s| s.to_string());
-    let (svc_state, svc_pid, svc_elapsed) = {
+    let (svc_state, svc_pid, svc_elapsed, svc_usage) = {
         match status.process {
             Some(process) => {
                 (process.state.to_string(),
                  process.pid
                         .map_or_else(|| "<none>".to_string(), |p| p.to_string()),
-                 process.elapsed.unwrap_or_default().to_string())
+                 process.elapsed.unwrap_or_default().to_string(),
+                 process.resource_usage.unwrap_or_default())
             }
             None => {
-                (ProcessState::default().to_string(), "<none>".to_string(), "<none>".to_string())
+                (ProcessState::default().to_string(),
+                 "<none>".to_string(),
+                 "<none>".to_string(),
+                 ResourceUsage::default())
             }
         }
     };
+    // Resource usage is only known for services with resource limits
+    let usage = |value: Option<u64>, scale: f64| {
+        value.map_or_else(|| "<none>".to_string(),
+                          |v| format!("{:.1}", v as f64 / scale))
+    };
+    let svc_cpu = usage(svc_usage.cpu_usage_usec, 1_000_000.0);
+    let svc_memory = usage(svc_usage.memory_current, 1024.0 * 1024.0);
+    let svc_pids = svc_usage.pids_current
+                            .map_or_else(|| "<none>".to_string(), |p| p.to_string());
+    // The exit code of the last run of the `scheduled` hook, if it has run
+    let svc_scheduled_exit = match status.last_scheduled_run {
+        Some(run) => {
+            run.exit_code
+               .map_or_else(|| "<unknown>".to_string(), |c| c.to_string())
+        }
+        None => "<none>".to_string(),
+    };
     if print_header {
         writeln!(out, "{}", STATUS_HEADER.join("\t")).unwrap();
     }
@@ -123,13 +149,19 @@ fn print_svc_status<T>(out: &mut T,
     //
     // TODO: Remove this when we have a stable machine-readable alternative
     // that scripts could depend on
+    //
+    // For the same reason, new columns are only ever appended.
     writeln!(out,
-             "{}\tstandalone\t{}\t{}\t{}\t{}\t{}",
+             "{}\tstandalone\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
              status.ident,
              DesiredState::from_str(&svc_desired_state)?,
              ProcessState::from_str(&svc_state)?,
              svc_elapsed,
              svc_pid,
-             status.service_group,)?;
+             status.service_group,
+             svc_cpu,
+             svc_memory,
+             svc_pids,
+             svc_scheduled_exit)?;
     Ok(())
 }
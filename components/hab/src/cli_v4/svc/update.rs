@@ -1,7 +1,8 @@
 use clap_v4 as clap;
 
 use std::{convert::TryFrom,
-          iter::FromIterator};
+          iter::FromIterator,
+          str::FromStr};
 
 use clap::Parser;
 
@@ -10,10 +11,13 @@ use habitat_common::cli::clap_validators::HabPkgIdentValueParser;
 use habitat_core::{ChannelIdent,
                    os::process::ShutdownTimeout,
                    package::PackageIdent,
-                   service::{HealthCheckInterval,
+                   service::{HealthCheck,
+                             HealthCheckInterval,
                              ServiceBind}};
 use habitat_sup_protocol::{ctl,
                            types::{BindingMode,
+                                   FencingPolicy,
+                                   RestartPolicy,
                                    Topology,
                                    UpdateCondition,
                                    UpdateStrategy}};
@@ -76,6 +80,82 @@ pub(crate) struct UpdateCommand {
     #[arg(long = "update-condition", default_value=UpdateCondition::Latest.as_str(), value_enum)]
     update_condition: UpdateCondition,
 
+    /// Whether the service is restarted after its run hook exits
+    ///
+    /// always: Restart the service whenever it exits.
+    ///
+    /// on-failure: Restart the service only if it exits with a non-zero exit code.
+    ///
+    /// never: Never restart the service.
+    #[arg(long = "restart-policy", value_enum)]
+    restart_policy: Option<RestartPolicy>,
+
+    /// The number of consecutive failed restarts after which the service is no longer restarted
+    /// and is reported as failed
+    #[arg(long = "max-restarts")]
+    max_restarts: Option<u32>,
+
+    /// The minimum duration in seconds to wait before restarting the service
+    #[arg(long = "min-backoff-period")]
+    min_backoff_period: Option<u64>,
+
+    /// The maximum duration in seconds to wait before restarting the service
+    #[arg(long = "max-backoff-period")]
+    max_backoff_period: Option<u64>,
+
+    /// One or more services on this Supervisor which, if they are loaded, must be healthy before
+    /// this service is started
+    #[arg(long = "after", num_args = 0.., value_parser = HabPkgIdentValueParser::simple())]
+    after: Option<Vec<PackageIdent>>,
+
+    /// One or more services on this Supervisor which must be loaded and healthy before this
+    /// service is started
+    #[arg(long = "requires", num_args = 0.., value_parser = HabPkgIdentValueParser::simple())]
+    requires: Option<Vec<PackageIdent>>,
+
+    /// The share of a single CPU the service may use, as a percentage
+    #[arg(long = "cpu-quota", value_parser = clap::value_parser!(u32).range(1..))]
+    cpu_quota: Option<u32>,
+
+    /// The most memory in bytes the service may use
+    #[arg(long = "memory-max")]
+    memory_max: Option<u64>,
+
+    /// The most processes and threads the service may run at once
+    #[arg(long = "pids-max")]
+    pids_max: Option<u64>,
+
+    /// The service's share of block IO relative to other services, from 1 to 10000
+    #[arg(long = "io-weight", value_parser = clap::value_parser!(u32).range(1..=10000))]
+    io_weight: Option<u32>,
+
+    /// Whether to mount a private, empty /tmp for the service
+    #[arg(long = "private-tmp", value_name = "BOOL")]
+    private_tmp: Option<bool>,
+
+    /// Whether to make the package's install directory read-only to the service
+    #[arg(long = "read-only-pkg", value_name = "BOOL")]
+    read_only_pkg: Option<bool>,
+
+    /// Whether to make the service directory read-only to the service, except for its data and
+    /// var directories
+    #[arg(long = "read-only-svc", value_name = "BOOL")]
+    read_only_svc: Option<bool>,
+
+    /// Zero or more capabilities the service may not have, such as CAP_NET_RAW, or ALL
+    #[arg(long = "cap-drop", num_args = 0..)]
+    cap_drop: Option<Vec<String>>,
+
+    /// Whether to keep the service from gaining privileges through setuid or file capability
+    /// binaries
+    #[arg(long = "no-new-privs", value_name = "BOOL")]
+    no_new_privs: Option<bool>,
+
+    /// A seccomp profile in seccompiler's JSON format, or an empty string to remove the
+    /// service's profile
+    #[arg(long = "seccomp-profile")]
+    seccomp_profile: Option<String>,
+
     /// One or more service groups to bind to a configuration
     #[arg(long = "bind", num_args = 0..)]
     bind: Option<Vec<ServiceBind>>,
@@ -99,12 +179,45 @@ pub(crate) struct UpdateCommand {
     #[arg(long = "shutdown-timeout")]
     shutdown_timeout: Option<ShutdownTimeout>,
 
+    /// A check to run in place of the package's health check hook, or an empty string to go back
+    /// to the package's health check
+    ///
+    /// See `hab svc load --help` for the forms a check can take.
+    #[arg(long = "health-check", value_parser = parse_health_check)]
+    health_check: Option<String>,
+
+    /// The number of consecutive critical health checks after which the service is restarted, or
+    /// 0 to stop restarting the service when its health checks fail
+    #[arg(long = "liveness-threshold")]
+    liveness_threshold: Option<u32>,
+
+    /// What to do with the service on the member that was the leader of its service group when the
+    /// group lost quorum
+    ///
+    /// none: Only report the member as fenced; the service keeps running.
+    ///
+    /// hook: Run the package's fence hook.
+    ///
+    /// stop: Stop the service until the service group regains quorum and elects a leader.
+    #[arg(long = "fencing", value_enum)]
+    fencing: Option<FencingPolicy>,
+
     #[cfg(target_os = "windows")]
     /// Password of the service user
     #[arg(long = "password")]
     password: Option<String>,
 }
 
+/// Accept either a valid health check or an empty string, which removes the service's check.
+fn parse_health_check(value: &str) -> std::result::Result<String, String> {
+    if value.is_empty() {
+        Ok(String::new())
+    } else {
+        HealthCheck::from_str(value).map(|check| check.to_string())
+                                    .map_err(|err| err.to_string())
+    }
+}
+
 impl TryFrom<UpdateCommand> for ctl::SvcUpdate {
     type Error = Error;
 
@@ -122,7 +235,26 @@ impl TryFrom<UpdateCommand> for ctl::SvcUpdate {
                                    topology: u.topology.map(|v| v as i32),
                                    update_strategy: u.strategy.map(|v| v as i32),
                                    update_condition: Some(u.update_condition as i32),
+                                   restart_policy: u.restart_policy.map(|v| v as i32),
+                                   max_restarts: u.max_restarts,
+                                   min_backoff_period: u.min_backoff_period,
+                                   max_backoff_period: u.max_backoff_period,
+                                   after: u.after.map(FromIterator::from_iter),
+                                   requires: u.requires.map(FromIterator::from_iter),
+                                   cpu_quota: u.cpu_quota,
+                                   memory_max: u.memory_max,
+                                   pids_max: u.pids_max,
+                                   io_weight: u.io_weight,
+                                   private_tmp: u.private_tmp,
+                                   read_only_pkg: u.read_only_pkg,
+                                   read_only_svc: u.read_only_svc,
+                                   cap_drop: u.cap_drop.map(FromIterator::from_iter),
+                                   no_new_privs: u.no_new_privs,
+                                   seccomp_profile: u.seccomp_profile,
                                    shutdown_timeout: u.shutdown_timeout.map(Into::into),
+                                   health_check: u.health_check,
+                                   liveness_threshold: u.liveness_threshold,
+                                   fencing: u.fencing.map(|v| v as i32),
                                    #[cfg(windows)]
                                    svc_encrypted_password: u.password,
                                    #[cfg(not(windows))]
@@ -142,7 +274,26 @@ impl TryFrom<UpdateCommand> for ctl::SvcUpdate {
                                 update_strategy: None,
                                 health_check_interval: None,
                                 shutdown_timeout: None,
-                                update_condition: None, } = &msg
+                                update_condition: None,
+                                restart_policy: None,
+                                max_restarts: None,
+                                min_backoff_period: None,
+                                max_backoff_period: None,
+                                after: None,
+                                requires: None,
+                                cpu_quota: None,
+                                memory_max: None,
+                                pids_max: None,
+                                io_weight: None,
+                                private_tmp: None,
+                                read_only_pkg: None,
+                                read_only_svc: None,
+                                cap_drop: None,
+                                no_new_privs: None,
+                                seccomp_profile: None,
+                                health_check: None,
+                                liveness_threshold: None,
+                                fencing: None, } = &msg
         {
             Err(Error::ArgumentError("No fields specified for update".to_string()))
         } else {
@@ -28,7 +28,8 @@ use habitat_core::{AUTH_TOKEN_ENVVAR,
                    origin::Origin as CoreOrigin,
                    os::process::ShutdownTimeout,
                    package::PackageIdent,
-                   service::ServiceBind,
+                   service::{HealthCheck,
+                             ServiceBind},
                    url::{BLDR_URL_ENVVAR,
                          DEFAULT_BLDR_URL,
                          bldr_url_from_env}};
@@ -41,7 +42,9 @@ use hab_common_derive::GenConfig;
 #[cfg(not(target_os = "macos"))]
 use habitat_sup_protocol::codec::SrvMessage;
 
-use habitat_sup_protocol::types::UpdateCondition;
+use habitat_sup_protocol::types::{FencingPolicy,
+                                  RestartPolicy,
+                                  UpdateCondition};
 
 use crate::error::{Error as HabError,
                    Result as HabResult};
@@ -424,6 +427,110 @@ pub struct SharedLoad {
     #[serde(default)]
     update_condition: UpdateCondition,
 
+    /// Whether the service is restarted after its run hook exits
+    ///
+    /// always: Restart the service whenever it exits.
+    ///
+    /// on-failure: Restart the service only if it exits with a non-zero exit code. A service
+    /// that exits cleanly stays down, which suits one-shot batch services.
+    ///
+    /// never: Never restart the service.
+    #[arg(long = "restart-policy",
+                default_value = RestartPolicy::Always.as_str(),
+            value_enum)]
+    #[serde(default)]
+    restart_policy: RestartPolicy,
+
+    /// The number of consecutive failed restarts after which the service is no longer restarted
+    /// and is reported as failed
+    ///
+    /// If this argument is not specified, the service is restarted indefinitely.
+    #[arg(long = "max-restarts")]
+    #[serde(default)]
+    max_restarts: Option<u32>,
+
+    /// The minimum duration in seconds to wait before restarting the service, overriding the
+    /// Supervisor's `--service-min-backoff-period`
+    #[arg(long = "min-backoff-period")]
+    #[serde(default)]
+    min_backoff_period: Option<u64>,
+
+    /// The maximum duration in seconds to wait before restarting the service, overriding the
+    /// Supervisor's `--service-max-backoff-period`
+    #[arg(long = "max-backoff-period")]
+    #[serde(default)]
+    max_backoff_period: Option<u64>,
+
+    /// One or more services on this Supervisor which, if they are loaded, must be healthy before
+    /// this service is started
+    #[arg(long = "after", num_args = 1.., value_delimiter = ' ')]
+    #[serde(default)]
+    after: Vec<PkgIdentStringySerde>,
+
+    /// One or more services on this Supervisor which must be loaded and healthy before this
+    /// service is started
+    #[arg(long = "requires", num_args = 1.., value_delimiter = ' ')]
+    #[serde(default)]
+    requires: Vec<PkgIdentStringySerde>,
+
+    /// The share of a single CPU the service may use, as a percentage
+    ///
+    /// For example, 150 allows the service one and a half CPUs. Resource limits are only enforced
+    /// on Linux, where the service runs in a cgroup v2 subtree of its own.
+    #[arg(long = "cpu-quota", value_parser = clap::value_parser!(u32).range(1..))]
+    #[serde(default)]
+    cpu_quota: Option<u32>,
+
+    /// The most memory in bytes the service may use
+    #[arg(long = "memory-max")]
+    #[serde(default)]
+    memory_max: Option<u64>,
+
+    /// The most processes and threads the service may run at once
+    #[arg(long = "pids-max")]
+    #[serde(default)]
+    pids_max: Option<u64>,
+
+    /// The service's share of block IO relative to other services, from 1 to 10000
+    #[arg(long = "io-weight", value_parser = clap::value_parser!(u32).range(1..=10000))]
+    #[serde(default)]
+    io_weight: Option<u32>,
+
+    /// Mount a private, empty /tmp for the service
+    ///
+    /// Sandboxing is only supported on Linux, and requires the Supervisor to run as root when any
+    /// of the filesystem options are used.
+    #[arg(long = "private-tmp")]
+    #[serde(default)]
+    private_tmp: bool,
+
+    /// Make the package's install directory read-only to the service
+    #[arg(long = "read-only-pkg")]
+    #[serde(default)]
+    read_only_pkg: bool,
+
+    /// Make the service directory read-only to the service, except for its data and var
+    /// directories
+    #[arg(long = "read-only-svc")]
+    #[serde(default)]
+    read_only_svc: bool,
+
+    /// One or more capabilities the service may not have, such as CAP_NET_RAW, or ALL
+    #[arg(long = "cap-drop", num_args = 1.., value_delimiter = ' ')]
+    #[serde(default)]
+    cap_drop: Vec<String>,
+
+    /// Keep the service from gaining privileges through setuid or file capability binaries
+    #[arg(long = "no-new-privs")]
+    #[serde(default)]
+    no_new_privs: bool,
+
+    /// A seccomp profile in seccompiler's JSON format, whose "main" filter is installed for the
+    /// service
+    #[arg(long = "seccomp-profile")]
+    #[serde(default)]
+    seccomp_profile: Option<PathBuf>,
+
     /// One or more service groups to bind to a configuration
     #[arg(long = "bind", num_args = 1.., value_delimiter = ' ')]
     #[serde(default)]
@@ -453,6 +560,38 @@ pub struct SharedLoad {
     #[serde(default)]
     shutdown_timeout: Option<ShutdownTimeout>,
 
+    /// A check to run in place of the package's health check hook
+    ///
+    /// One of `http(s)://<HOST>[:<PORT>]/<PATH> [status=<CODE>] [body=<REGEX>]`, which is healthy
+    /// when the response has the given status (any 2xx by default) and its body matches the
+    /// regular expression; `tcp://<HOST>:<PORT>`, which is healthy when a connection can be made;
+    /// or `exec:<COMMAND>`, whose exit code is interpreted like that of a health check hook. The
+    /// default value can be set in the package's plan file.
+    #[arg(long = "health-check")]
+    #[serde(default)]
+    health_check: Option<HealthCheck>,
+
+    /// The number of consecutive critical health checks after which the service is restarted
+    ///
+    /// If this argument is not specified, failing health checks do not restart the service.
+    #[arg(long = "liveness-threshold", value_parser = clap::value_parser!(u32).range(1..))]
+    #[serde(default)]
+    liveness_threshold: Option<u32>,
+
+    /// What to do with the service on the member that was the leader of its service group when the
+    /// group lost quorum
+    ///
+    /// none: Only report the member as fenced; the service keeps running.
+    ///
+    /// hook: Run the package's fence hook.
+    ///
+    /// stop: Stop the service until the service group regains quorum and elects a leader.
+    #[arg(long = "fencing",
+                default_value = FencingPolicy::None.as_str(),
+            value_enum)]
+    #[serde(default)]
+    fencing: FencingPolicy,
+
     #[cfg(target_os = "windows")]
     /// Password of the service user
     #[arg(long = "password")]
@@ -471,10 +610,29 @@ impl Default for SharedLoad {
                topology:                 None,
                strategy:                 habitat_sup_protocol::types::UpdateStrategy::None,
                update_condition:         UpdateCondition::Latest,
+               restart_policy:           RestartPolicy::Always,
+               max_restarts:             None,
+               min_backoff_period:       None,
+               max_backoff_period:       None,
+               after:                    vec![],
+               requires:                 vec![],
+               cpu_quota:                None,
+               memory_max:               None,
+               pids_max:                 None,
+               io_weight:                None,
+               private_tmp:              false,
+               read_only_pkg:            false,
+               read_only_svc:            false,
+               cap_drop:                 vec![],
+               no_new_privs:             false,
+               seccomp_profile:          None,
                bind:                     vec![],
                binding_mode:             habitat_sup_protocol::types::BindingMode::Strict,
                health_check_interval:    30,
                shutdown_timeout:         None,
+               health_check:             None,
+               liveness_threshold:       None,
+               fencing:                  FencingPolicy::None,
                #[cfg(windows)]
                password:                 None,
                config_from:              None, }
@@ -556,7 +714,7 @@ pub(crate) fn origin_param_or_env(opt: &Option<CoreOrigin>) -> Result<CoreOrigin
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(try_from = "String", into = "String")]
 struct PkgIdentStringySerde(PackageIdent);
 
@@ -606,7 +764,9 @@ pub fn shared_load_cli_to_ctl(ident: PackageIdent,
                               -> HabResult<habitat_sup_protocol::ctl::SvcLoad> {
     #[cfg(target_os = "windows")]
     use habitat_core::crypto::dpapi;
-    use habitat_sup_protocol::{ctl::{ServiceBindList,
+    use habitat_sup_protocol::{ctl::{CapabilityList,
+                                     PackageIdentList,
+                                     ServiceBindList,
                                      SvcLoad},
                                types::{HealthCheckInterval,
                                        ServiceBind}};
@@ -620,6 +780,22 @@ pub fn shared_load_cli_to_ctl(ident: PackageIdent,
                                                  .collect(), })
     };
 
+    let ident_list = |idents: Vec<PkgIdentStringySerde>| {
+        if idents.is_empty() {
+            None
+        } else {
+            Some(idents.into_iter()
+                       .map(|ident| ident.0)
+                       .collect::<PackageIdentList>())
+        }
+    };
+
+    let cap_drop = if shared_load.cap_drop.is_empty() {
+        None
+    } else {
+        Some(shared_load.cap_drop.into_iter().collect::<CapabilityList>())
+    };
+
     let config_from = if let Some(config_from) = shared_load.config_from {
         log::warn!("\nWARNING: Setting '--config-from' should only be used in development, not \
                     production!\n");
@@ -651,7 +827,29 @@ pub fn shared_load_cli_to_ctl(ident: PackageIdent,
                  health_check_interval:
                      Some(HealthCheckInterval { seconds: shared_load.health_check_interval, }),
                  shutdown_timeout: shared_load.shutdown_timeout.map(u32::from),
-                 update_condition: Some(shared_load.update_condition as i32) })
+                 health_check: shared_load.health_check.map(|check| check.to_string()),
+                 liveness_threshold: shared_load.liveness_threshold,
+                 fencing: Some(shared_load.fencing as i32),
+                 lockfile: None,
+                 write_lockfile: None,
+                 update_condition: Some(shared_load.update_condition as i32),
+                 restart_policy: Some(shared_load.restart_policy as i32),
+                 max_restarts: shared_load.max_restarts,
+                 min_backoff_period: shared_load.min_backoff_period,
+                 max_backoff_period: shared_load.max_backoff_period,
+                 after: ident_list(shared_load.after),
+                 requires: ident_list(shared_load.requires),
+                 cpu_quota: shared_load.cpu_quota,
+                 memory_max: shared_load.memory_max,
+                 pids_max: shared_load.pids_max,
+                 io_weight: shared_load.io_weight,
+                 private_tmp: Some(shared_load.private_tmp),
+                 read_only_pkg: Some(shared_load.read_only_pkg),
+                 read_only_svc: Some(shared_load.read_only_svc),
+                 cap_drop,
+                 no_new_privs: Some(shared_load.no_new_privs),
+                 seccomp_profile: shared_load.seccomp_profile
+                                             .map(|path| path.to_string_lossy().into_owned()) })
 }
 
 pub(crate) fn bldr_auth_token_from_args_env_or_load(opt: Option<String>) -> Result<String, Error> {
@@ -934,6 +1132,23 @@ mod tests {
         }
     }
 
+    mod shared_load {
+        use crate::cli_v4::utils::SharedLoad;
+
+        use clap_v4 as clap;
+
+        use clap::Parser;
+
+        #[test]
+        fn liveness_threshold_must_be_positive() {
+            let result = SharedLoad::try_parse_from(["load", "--liveness-threshold", "0"]);
+            assert!(result.is_err());
+
+            let result = SharedLoad::try_parse_from(["load", "--liveness-threshold", "3"]);
+            assert_eq!(result.unwrap().liveness_threshold, Some(3));
+        }
+    }
+
     mod refresh_channel_tests {
         use crate::cli_v4::utils::maybe_refresh_channel_from_args_env_or_config;
         use habitat_core::ChannelIdent;
@@ -1,10 +1,14 @@
-use crate::error::Result;
+use crate::error::{Error,
+                   Result};
+use chrono::{DateTime,
+             Utc};
 use futures::StreamExt;
 use habitat_common::{types::ResolvedListenCtlAddr,
                      ui::{Status,
                           UIWriter}};
 use habitat_core::{crypto::keys::{Key,
-                                  KeyCache},
+                                  KeyCache,
+                                  SignedBox},
                    fs::cache_key_path,
                    package::PackageIdent,
                    service::ServiceGroup};
@@ -12,17 +16,22 @@ use habitat_sup_client::{SrvClient,
                          SrvClientError};
 use habitat_sup_protocol::{self as sup_proto,
                            butterfly::MAX_SVC_CFG_SIZE,
+                           ctl::SvcCfgRevision,
                            net::ErrCode};
-use std::{convert::TryFrom,
+use std::{collections::{BTreeMap,
+                        BTreeSet},
+          convert::TryFrom,
           fs::File,
           io::{self,
-               Read},
+               Read,
+               Write},
           path::PathBuf,
           process};
+use tabwriter::TabWriter;
 
 pub(crate) async fn sub_svc_set<U>(ui: &mut U,
                                    grp: ServiceGroup,
-                                   cfg_path: &String,
+                                   cfg_path: &str,
                                    version: u64,
                                    user_opt: Option<String>,
                                    remote_sup: &ResolvedListenCtlAddr,
@@ -30,18 +39,7 @@ pub(crate) async fn sub_svc_set<U>(ui: &mut U,
                                    -> Result<()>
     where U: UIWriter
 {
-    let mut buf = Vec::with_capacity(MAX_SVC_CFG_SIZE);
-    let len = if cfg_path.as_str() == "-" {
-        io::stdin().read_to_end(&mut buf)?
-    } else {
-        let mut f = File::open(cfg_path)?;
-        f.read_to_end(&mut buf)?
-    };
-    if len > MAX_SVC_CFG_SIZE {
-        ui.fatal(format!("Configuration too large. Maximum allowed is {} bytes.",
-                         MAX_SVC_CFG_SIZE))?;
-        process::exit(1);
-    }
+    let buf = read_cfg(ui, cfg_path)?;
 
     let mut validate = sup_proto::ctl::SvcValidateCfg { service_group: Some(grp.clone().into()),
                                                         ..Default::default() };
@@ -115,6 +113,64 @@ pub(crate) async fn sub_svc_set<U>(ui: &mut U,
     Ok(())
 }
 
+/// Ask a Supervisor running a member of `grp` to render its configuration templates and hooks
+/// with the configuration at `cfg_path`, and print what applying it would change.
+pub(crate) async fn sub_svc_preview<U>(ui: &mut U,
+                                       grp: ServiceGroup,
+                                       cfg_path: &str,
+                                       version: u64,
+                                       remote_sup: &ResolvedListenCtlAddr)
+                                       -> Result<()>
+    where U: UIWriter
+{
+    let msg = sup_proto::ctl::SvcPreviewCfg { service_group: Some(grp.clone().into()),
+                                              cfg:           Some(read_cfg(ui, cfg_path)?),
+                                              version:       Some(version), };
+
+    ui.begin(format!("Previewing configuration version {} for {}", version, grp))?;
+    let mut resp = SrvClient::request(remote_sup, msg).await?;
+    let mut preview = None;
+    while let Some(msg) = resp.next().await {
+        let reply = msg?;
+        match reply.message_id() {
+            "SvcCfgPreview" => {
+                preview = Some(reply.parse::<sup_proto::ctl::SvcCfgPreview>()
+                                    .map_err(SrvClientError::Decode)?);
+            }
+            "NetErr" => {
+                let net_err = reply.parse::<sup_proto::net::NetErr>()
+                                   .map_err(SrvClientError::Decode)?;
+                return Err(SrvClientError::from(net_err).into());
+            }
+            _ => {
+                return Err(SrvClientError::from(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                                               "Unexpected reply")).into());
+            }
+        }
+    }
+    let preview =
+        preview.ok_or_else(|| SrvClientError::from(io::Error::from(io::ErrorKind::UnexpectedEof)))?;
+
+    for file_diff in &preview.file_diffs {
+        println!("{}", file_diff);
+    }
+    if preview.file_diffs.is_empty() {
+        ui.para("No configuration files would change.")?;
+    }
+    if !preview.changed_hooks.is_empty() {
+        ui.para(&format!("Hooks that would change: {}",
+                         preview.changed_hooks.join(", ")))?;
+    }
+    match (preview.restart_reason, preview.reconfigure.unwrap_or(false)) {
+        (Some(reason), _) => ui.para(&format!("The service would restart ({}).", reason))?,
+        (None, true) => ui.para("The reconfigure hook would run.")?,
+        (None, false) => ui.para("The service would neither restart nor reconfigure.")?,
+    }
+    ui.end("Configuration not applied (dry run)")?;
+
+    Ok(())
+}
+
 pub(crate) async fn sub_svc_config(ident: PackageIdent,
                                    remote_sup_addr: &ResolvedListenCtlAddr)
                                    -> Result<()> {
@@ -140,3 +196,261 @@ pub(crate) async fn sub_svc_config(ident: PackageIdent,
     }
     Ok(())
 }
+
+pub(crate) async fn sub_svc_history(grp: ServiceGroup,
+                                    remote_sup: &ResolvedListenCtlAddr)
+                                    -> Result<()> {
+    let revisions = request_cfg_history(&grp, remote_sup).await?;
+    if revisions.is_empty() {
+        println!("No configuration history for {}.", grp);
+        return Ok(());
+    }
+    let mut out = TabWriter::new(io::stdout());
+    writeln!(out, "version\tapplied\tfrom\tencrypted")?;
+    for revision in revisions {
+        let applied =
+            revision.applied_at
+                    .and_then(|secs| DateTime::<Utc>::from_timestamp(secs as i64, 0))
+                    .map_or_else(|| "<unknown>".to_string(),
+                                 |applied| applied.format("%Y-%m-%d %H:%M:%S UTC").to_string());
+        writeln!(out,
+                 "{}\t{}\t{}\t{}",
+                 revision.version.unwrap_or_default(),
+                 applied,
+                 revision.from_id.as_deref().unwrap_or("<unknown>"),
+                 revision.is_encrypted.unwrap_or(false))?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Print the changes between two versions of a service group's configuration. If no `to` version
+/// is given, the latest version is used.
+pub(crate) async fn sub_svc_history_diff(grp: ServiceGroup,
+                                         from: u64,
+                                         to: Option<u64>,
+                                         remote_sup: &ResolvedListenCtlAddr,
+                                         key_path: PathBuf)
+                                         -> Result<()> {
+    let revisions = request_cfg_history(&grp, remote_sup).await?;
+    let to = match to.or_else(|| revisions.last().and_then(|r| r.version)) {
+        Some(to) => to,
+        None => {
+            return Err(Error::ArgumentError(format!("No configuration history \
+                                                     for {}",
+                                                    grp)));
+        }
+    };
+    let find = |version: u64| {
+        revisions.iter()
+                 .find(|r| r.version == Some(version))
+                 .ok_or_else(|| {
+                     Error::ArgumentError(format!("Configuration version {} of {} is not in the \
+                                                   configuration history",
+                                                  version, grp))
+                 })
+    };
+    let (old, new) = (find(from)?, find(to)?);
+
+    let key_cache = KeyCache::new(cache_key_path(key_path));
+    let changes = diff_cfgs(&revision_cfg(old, &key_cache)?,
+                            &revision_cfg(new, &key_cache)?);
+    println!("--- {} version {}", grp, from);
+    println!("+++ {} version {}", grp, to);
+    if changes.is_empty() {
+        println!("No changes.");
+    }
+    for change in changes {
+        println!("{}", change);
+    }
+    Ok(())
+}
+
+pub(crate) async fn sub_svc_rollback<U>(ui: &mut U,
+                                        grp: ServiceGroup,
+                                        version: u64,
+                                        new_version: Option<u64>,
+                                        remote_sup: &ResolvedListenCtlAddr)
+                                        -> Result<()>
+    where U: UIWriter
+{
+    let msg = sup_proto::ctl::SvcRollbackCfg { service_group: Some(grp.clone().into()),
+                                               version: Some(version),
+                                               new_version };
+
+    ui.begin(format!("Rolling back configuration of {} to version {}",
+                     grp, version))?;
+    ui.status(Status::Applying, "applying...")?;
+    let mut resp = SrvClient::request(remote_sup, msg).await?;
+    let mut applied = None;
+    while let Some(msg) = resp.next().await {
+        let reply = msg?;
+        match reply.message_id() {
+            "SvcCfgRevision" => {
+                applied = reply.parse::<SvcCfgRevision>()
+                               .map_err(SrvClientError::Decode)?
+                               .version;
+            }
+            "NetErr" => {
+                let net_err = reply.parse::<sup_proto::net::NetErr>()
+                                   .map_err(SrvClientError::Decode)?;
+                return Err(SrvClientError::from(net_err).into());
+            }
+            _ => {
+                return Err(SrvClientError::from(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                                               "Unexpected reply")).into());
+            }
+        }
+    }
+
+    match applied {
+        Some(applied) => {
+            ui.end(format!("Applied configuration version {} as version {}",
+                           version, applied))?
+        }
+        None => ui.end(format!("Applied configuration version {}", version))?,
+    }
+    Ok(())
+}
+
+/// Read a configuration from `cfg_path`, or from stdin if it is `-`.
+fn read_cfg<U>(ui: &mut U, cfg_path: &str) -> Result<Vec<u8>>
+    where U: UIWriter
+{
+    let mut buf = Vec::with_capacity(MAX_SVC_CFG_SIZE);
+    let len = if cfg_path == "-" {
+        io::stdin().read_to_end(&mut buf)?
+    } else {
+        let mut f = File::open(cfg_path)?;
+        f.read_to_end(&mut buf)?
+    };
+    if len > MAX_SVC_CFG_SIZE {
+        ui.fatal(format!("Configuration too large. Maximum allowed is {} bytes.",
+                         MAX_SVC_CFG_SIZE))?;
+        process::exit(1);
+    }
+    Ok(buf)
+}
+
+async fn request_cfg_history(grp: &ServiceGroup,
+                             remote_sup: &ResolvedListenCtlAddr)
+                             -> Result<Vec<SvcCfgRevision>> {
+    let msg = sup_proto::ctl::SvcGetCfgHistory { service_group: Some(grp.clone().into()), };
+    let mut resp = SrvClient::request(remote_sup, msg).await?;
+    let mut revisions = vec![];
+    while let Some(msg) = resp.next().await {
+        let reply = msg?;
+        match reply.message_id() {
+            "SvcCfgRevision" => {
+                revisions.push(reply.parse::<SvcCfgRevision>()
+                                    .map_err(SrvClientError::Decode)?);
+            }
+            "NetOk" => {}
+            "NetErr" => {
+                let net_err = reply.parse::<sup_proto::net::NetErr>()
+                                   .map_err(SrvClientError::Decode)?;
+                return Err(SrvClientError::from(net_err).into());
+            }
+            _ => {
+                return Err(SrvClientError::from(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                                               "Unexpected reply")).into());
+            }
+        }
+    }
+    Ok(revisions)
+}
+
+/// Decode the configuration of `revision`, decrypting it with the keys in `key_cache` if needed.
+fn revision_cfg(revision: &SvcCfgRevision, key_cache: &KeyCache) -> Result<toml::value::Table> {
+    let cfg = revision.cfg.as_deref().unwrap_or_default();
+    let bytes = if revision.is_encrypted.unwrap_or(false) {
+        let secret = SignedBox::from_bytes(cfg)?;
+        let user_public_key = key_cache.user_public_encryption_key(secret.encryptor())?;
+        let service_secret_key = key_cache.service_secret_encryption_key(secret.decryptor())?;
+        service_secret_key.decrypt_user_message(&secret, &user_public_key)?
+    } else {
+        cfg.to_vec()
+    };
+    let cfg = String::from_utf8(bytes).map_err(|e| Error::Utf8Error(e.to_string()))?;
+    Ok(toml::from_str(&cfg)?)
+}
+
+/// Describe the changes from `old` to `new` as `-` and `+` lines, one for each value that was
+/// removed, added or changed. Nested tables are flattened to dotted keys, so a change deep in a
+/// table only shows the value that changed.
+fn diff_cfgs(old: &toml::value::Table, new: &toml::value::Table) -> Vec<String> {
+    let (old, new) = (flatten_cfg(old), flatten_cfg(new));
+    let keys = old.keys().chain(new.keys()).collect::<BTreeSet<_>>();
+    let mut changes = vec![];
+    for key in keys {
+        let (old_value, new_value) = (old.get(key), new.get(key));
+        if old_value == new_value {
+            continue;
+        }
+        if let Some(value) = old_value {
+            changes.push(format!("- {} = {}", key, value));
+        }
+        if let Some(value) = new_value {
+            changes.push(format!("+ {} = {}", key, value));
+        }
+    }
+    changes
+}
+
+fn flatten_cfg(cfg: &toml::value::Table) -> BTreeMap<String, toml::Value> {
+    fn flatten_into(prefix: &str,
+                    cfg: &toml::value::Table,
+                    flattened: &mut BTreeMap<String, toml::Value>) {
+        for (key, value) in cfg {
+            let key = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            match value {
+                toml::Value::Table(table) => flatten_into(&key, table, flattened),
+                value => {
+                    flattened.insert(key, value.clone());
+                }
+            }
+        }
+    }
+
+    let mut flattened = BTreeMap::new();
+    flatten_into("", cfg, &mut flattened);
+    flattened
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_cfgs_shows_removed_added_and_changed_values() {
+        let old = toml::from_str(
+                                 r#"
+port = 6379
+tcp-backlog = 128
+
+[cluster]
+enabled = false
+"#,
+        ).unwrap();
+        let new = toml::from_str(
+                                 r#"
+port = 6380
+tcp-backlog = 128
+save = ["900 1", "300 10"]
+
+[cluster]
+"#,
+        ).unwrap();
+
+        assert_eq!(diff_cfgs(&old, &new),
+                   vec!["- cluster.enabled = false",
+                        "- port = 6379",
+                        "+ port = 6380",
+                        "+ save = [\"900 1\", \"300 10\"]",]);
+        assert!(diff_cfgs(&old, &old).is_empty());
+    }
+}
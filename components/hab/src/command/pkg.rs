@@ -2,6 +2,7 @@ pub mod binlink;
 pub mod build;
 pub mod bulkupload;
 pub mod channels;
+pub mod convert;
 pub mod delete;
 pub mod demote;
 pub mod dependencies;
@@ -13,6 +14,7 @@ pub mod hash;
 pub mod header;
 pub mod info;
 pub mod list;
+pub mod mirror;
 pub mod path;
 pub mod promote;
 pub mod provides;
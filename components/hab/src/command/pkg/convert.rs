@@ -0,0 +1,34 @@
+use crate::{common::ui::{Status,
+                         UI,
+                         UIWriter},
+            error::Result};
+use habitat_core::crypto::{artifact::{self,
+                                      HartFormat},
+                           keys::KeyCache};
+use std::path::Path;
+
+/// Rewrites the artifact `src` as `format`. Its signature covers the compressed tarball, so the
+/// converted artifact is signed again, with the same origin key revision that signed `src`.
+pub fn start(ui: &mut UI,
+             src: &Path,
+             dst: &Path,
+             format: HartFormat,
+             long_window: bool,
+             key_cache: &KeyCache)
+             -> Result<()> {
+    ui.begin(format!("Converting artifact {} to {}",
+                     src.display(),
+                     format.version()))?;
+    let (name_with_rev, hash) = artifact::verify(src, key_cache)?;
+    ui.status(Status::Verified,
+              format!("checksum {} signed with {}", &hash, &name_with_rev))?;
+    let key = key_cache.secret_signing_key(&name_with_rev)?;
+    ui.status(Status::Signing,
+              format!("{} with {} to create {}",
+                      src.display(),
+                      name_with_rev,
+                      dst.display()))?;
+    artifact::convert(src, dst, &key, format, long_window)?;
+    ui.end(format!("Converted artifact {}.", dst.display()))?;
+    Ok(())
+}
@@ -123,6 +123,14 @@ pub struct PackageSet {
     pub idents:  Vec<PackageIdent>,
 }
 
+/// One of the identifiers of a `PackageSet`, resolved to the package it selected.
+#[derive(Debug, Clone)]
+pub struct ResolvedPackage {
+    pub target:  PackageTarget,
+    pub channel: ChannelIdent,
+    pub ident:   PackageIdent,
+}
+
 /// Download a Habitat package.
 ///
 /// If an `PackageIdent` is given, we retrieve the package from the specified Builder
@@ -136,6 +144,8 @@ pub struct PackageSet {
 /// dependencies will be downloaded on the system in the
 /// <download_path>/artifacts directory. Any signing keys will also be
 /// downloaded and put in the <download_path/keys> directory.
+///
+/// Returns the packages the given identifiers resolved to, without their dependencies.
 /// Also, in the future we may want to accept an alternate builder to 'filter' what we pull down by
 /// That would greatly optimize the 'sync' to on prem builder case, as we could point to that
 /// and only fetch what we don't already have.
@@ -149,7 +159,7 @@ pub async fn start<U>(ui: &mut U,
                       token: Option<&str>,
                       verify: bool,
                       ignore_missing_seeds: bool)
-                      -> Result<()>
+                      -> Result<Vec<ResolvedPackage>>
     where U: UIWriter
 {
     debug!(
@@ -193,11 +203,11 @@ pub async fn start<U>(ui: &mut U,
                               verify,
                               ignore_missing_seeds };
 
-    let download_count = task.execute(ui).await?;
+    let (resolved_packages, download_count) = task.execute(ui).await?;
 
     debug!("Expanded package count: {}", download_count);
 
-    Ok(())
+    Ok(resolved_packages)
 }
 
 struct DownloadTask<'a> {
@@ -211,7 +221,7 @@ struct DownloadTask<'a> {
 }
 
 impl DownloadTask<'_> {
-    async fn execute<T>(&self, ui: &mut T) -> Result<usize>
+    async fn execute<T>(&self, ui: &mut T) -> Result<(Vec<ResolvedPackage>, usize)>
         where T: UIWriter
     {
         // This was written intentionally with an eye towards data parallelism
@@ -221,20 +231,24 @@ impl DownloadTask<'_> {
         self.verify_and_prepare_download_directory(ui)?;
 
         // Phase 1: Expand to fully qualified deps and TDEPS
-        let expanded_idents = self.expand_sources(ui).await?;
+        let (resolved_packages, expanded_idents) = self.expand_sources(ui).await?;
 
         // Phase 2: Download artifacts
         let downloaded_artifacts = self.download_artifacts(ui, &expanded_idents).await?;
 
-        Ok(downloaded_artifacts.len())
+        Ok((resolved_packages, downloaded_artifacts.len()))
     }
 
     // For each source, use the builder/depot to expand it to a fully qualifed form
     // The same call gives us the TDEPS, add those as well.
-    async fn expand_sources<T>(&self, ui: &mut T) -> Result<HashSet<(PackageIdent, PackageTarget)>>
+    async fn expand_sources<T>(
+        &self,
+        ui: &mut T)
+        -> Result<(Vec<ResolvedPackage>, HashSet<(PackageIdent, PackageTarget)>)>
         where T: UIWriter
     {
         let mut expanded_packages = Vec::<(Package, PackageTarget)>::new();
+        let mut resolved_packages = Vec::<ResolvedPackage>::new();
         let mut expanded_idents = HashSet::<(PackageIdent, PackageTarget)>::new();
 
         // This loop should be easy to convert to a parallel map.
@@ -251,6 +265,9 @@ impl DownloadTask<'_> {
                                                                         ident)
                                            .await?
                 {
+                    resolved_packages.push(ResolvedPackage { target:  package_set.target,
+                                                             channel: package_set.channel.clone(),
+                                                             ident:   package.ident.clone(), });
                     expanded_packages.push((package, package_set.target));
                 }
             }
@@ -268,7 +285,7 @@ impl DownloadTask<'_> {
         ui.status(Status::Found,
                   format!("{} artifacts", expanded_idents.len()))?;
 
-        Ok(expanded_idents)
+        Ok((resolved_packages, expanded_idents))
     }
 
     async fn download_artifacts<T>(&self,
@@ -0,0 +1,113 @@
+//! Builds a local mirror of Builder that air-gapped sites can install from.
+//!
+//! # Examples
+//!
+//! ```bash
+//! $ hab pkg mirror sync --mirror-directory /srv/habitat-mirror core/redis
+//! ```
+//!
+//! Will download `core/redis` and all of its transitive dependencies, along with their signing
+//! keys, and add `core/redis` to the mirror's index of the `stable` channel. The mirror can then
+//! be used in place of Builder:
+//!
+//! ```bash
+//! $ hab pkg install --url file:///srv/habitat-mirror core/redis
+//! ```
+//!
+//! # Internals
+//!
+//! * Download the artifacts and keys into the mirror, as `hab pkg download` does
+//! * Record the metadata of every artifact in the mirror that does not have any yet
+//! * Add the packages that were asked for to the index of the channel they were resolved in
+
+use std::{collections::HashMap,
+          fs,
+          path::{Path,
+                 PathBuf}};
+
+use crate::{api_client::mirror::LocalMirror,
+            command::pkg::download::{self,
+                                     PackageSet},
+            hcore::{ChannelIdent,
+                    package::{PackageArchive,
+                              PackageIdent,
+                              PackageTarget}}};
+
+use habitat_common::ui::{Status,
+                         UIWriter};
+
+use crate::error::Result;
+
+/// Syncs the packages of `package_sets`, and their dependencies, into the mirror at
+/// `mirror_path`.
+///
+/// Syncing is additive: packages already in the mirror, and in its channel indexes, are kept.
+#[allow(clippy::too_many_arguments)]
+pub async fn sync<U>(ui: &mut U,
+                     url: &str,
+                     product: &str,
+                     version: &str,
+                     package_sets: &[PackageSet],
+                     mirror_path: &Path,
+                     token: Option<&str>,
+                     verify: bool,
+                     ignore_missing_seeds: bool)
+                     -> Result<()>
+    where U: UIWriter
+{
+    let mirror = LocalMirror::new(mirror_path);
+    let resolved_packages = download::start(ui,
+                                            url,
+                                            product,
+                                            version,
+                                            package_sets,
+                                            Some(&mirror.root().to_path_buf()),
+                                            token,
+                                            verify,
+                                            ignore_missing_seeds).await?;
+
+    let added = add_missing_metadata(&mirror)?;
+    ui.status(Status::Added, format!("metadata for {} artifacts", added))?;
+
+    let mut channels = HashMap::<(ChannelIdent, PackageTarget), Vec<PackageIdent>>::new();
+    for package in resolved_packages {
+        channels.entry((package.channel, package.target))
+                .or_default()
+                .push(package.ident);
+    }
+    for ((channel, target), idents) in channels {
+        mirror.add_to_channel(&channel, target, &idents)?;
+        ui.status(Status::Added,
+                  format!("{} packages to channel {} for {}",
+                          idents.len(),
+                          channel,
+                          target))?;
+    }
+
+    ui.end(format!("Mirror synced to {}", mirror.root().display()))?;
+    Ok(())
+}
+
+/// Records the metadata of the mirror's artifacts that were added since the last sync.
+fn add_missing_metadata(mirror: &LocalMirror) -> Result<usize> {
+    let mut added = 0;
+    for path in hart_files(&mirror.artifacts_path())? {
+        let artifact = PackageArchive::new(path)?;
+        if !mirror.has_package(&artifact.ident()?, artifact.target()?) {
+            mirror.add_artifact(&artifact)?;
+            added += 1;
+        }
+    }
+    Ok(added)
+}
+
+fn hart_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "hart") {
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
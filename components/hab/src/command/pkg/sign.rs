@@ -2,19 +2,27 @@ use crate::{common::ui::{Status,
                          UI,
                          UIWriter},
             error::Result};
-use habitat_core::crypto::{artifact,
+use habitat_core::crypto::{artifact::{self,
+                                      HartFormat},
                            keys::{Key,
                                   SecretOriginSigningKey}};
 use std::path::Path;
 
-pub fn start(ui: &mut UI, key: &SecretOriginSigningKey, src: &Path, dst: &Path) -> Result<()> {
+pub fn start(ui: &mut UI,
+             key: &SecretOriginSigningKey,
+             src: &Path,
+             dst: &Path,
+             format: HartFormat,
+             long_window: bool)
+             -> Result<()> {
     ui.begin(format!("Signing {}", src.display()))?;
     ui.status(Status::Signing,
-              format!("{} with {} to create {}",
+              format!("{} with {} to create {} ({})",
                       src.display(),
                       key.named_revision(),
-                      dst.display()))?;
-    artifact::sign(src, dst, key)?;
+                      dst.display(),
+                      format.version()))?;
+    artifact::compress_and_sign(src, dst, key, format, long_window)?;
     ui.end(format!("Signed artifact {}.", dst.display()))?;
     Ok(())
 }
@@ -1,9 +1,13 @@
-use crate::{common::ui::{Status,
+use crate::{common::ui::{Glyph,
+                         Status,
                          UI,
                          UIWriter},
             error::Result};
-use habitat_core::crypto::{artifact,
-                           keys::KeyCache};
+use habitat_core::{crypto::{artifact,
+                            keys::KeyCache},
+                   package::{PackageIdent,
+                             PackageInstall,
+                             files}};
 use std::path::Path;
 
 pub fn start(ui: &mut UI, src: &Path, key_cache: &KeyCache) -> Result<()> {
@@ -14,3 +18,32 @@ pub fn start(ui: &mut UI, src: &Path, key_cache: &KeyCache) -> Result<()> {
     ui.end(format!("Verified artifact {}.", &src.display()))?;
     Ok(())
 }
+
+/// Verifies the files of an installed package against its signed `FILES` metafile, reporting
+/// every file that was added, removed or modified since the package was built.
+pub fn start_installed(ui: &mut UI,
+                       ident: &PackageIdent,
+                       fs_root_path: &Path,
+                       key_cache: &KeyCache)
+                       -> Result<()> {
+    let package = PackageInstall::load(ident, Some(fs_root_path))?;
+    ui.begin(format!("Verifying installed package {}", package.ident()))?;
+    let report = files::verify(&package, key_cache)?;
+    ui.status(Status::Verified,
+              format!("FILES signed with {}", report.signer))?;
+    for path in &report.added {
+        ui.status(Status::Custom(Glyph::ErrorX, String::from("Added")),
+                  path.display())?;
+    }
+    for path in &report.removed {
+        ui.status(Status::Custom(Glyph::ErrorX, String::from("Removed")),
+                  path.display())?;
+    }
+    for path in &report.modified {
+        ui.status(Status::Custom(Glyph::ErrorX, String::from("Modified")),
+                  path.display())?;
+    }
+    report.into_result(&package)?;
+    ui.end(format!("Verified installed package {}.", package.ident()))?;
+    Ok(())
+}
@@ -37,7 +37,7 @@ pub async fn send(remote_sup_addr: &ResolvedListenCtlAddr,
 
 ////////////////////////////////////////////////////////////////////////
 
-fn handle_ctl_reply(reply: &SrvMessage) -> result::Result<(), SrvClientError> {
+pub(crate) fn handle_ctl_reply(reply: &SrvMessage) -> result::Result<(), SrvClientError> {
     let mut progress_bar = pbr::ProgressBar::<io::Stdout>::new(0);
     progress_bar.set_units(pbr::Units::Bytes);
     progress_bar.show_tick = true;
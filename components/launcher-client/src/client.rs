@@ -184,6 +184,18 @@ impl LauncherCli {
     /// `username` and `groupname` are string names, while `uid` and
     /// `gid` are numeric IDs. Newer versions of the Launcher can
     /// accept either, but prefer numeric IDs.
+    ///
+    /// If `logs` is given, the Launcher will additionally append the
+    /// service's standard output and standard error streams to the
+    /// respective files.
+    ///
+    /// `limits` are enforced by Launchers on Linux and ignored
+    /// elsewhere, as well as by Launchers which predate them.
+    ///
+    /// A `sandbox` is only applied by Launchers on Linux; other
+    /// Launchers refuse to spawn the service. Launchers which predate
+    /// sandboxing ignore it, so callers must check `version` first.
+    #[allow(clippy::too_many_arguments)]
     pub fn spawn(&self,
                  id: &str,
                  bin: &Path,
@@ -192,7 +204,10 @@ impl LauncherCli {
                             groupname,
                             gid, }: UserInfo,
                  password: Option<&str>,
-                 env: Env)
+                 env: Env,
+                 logs: Option<(&Path, &Path)>,
+                 limits: Option<protocol::ResourceLimits>,
+                 sandbox: Option<protocol::Sandbox>)
                  -> Result<Pid, IPCCommandError> {
         // On Windows, we only expect user to be Some.
         //
@@ -207,6 +222,14 @@ impl LauncherCli {
                                     svc_group_id: gid,
                                     svc_password: password.map(str::to_string),
                                     env,
+                                    stdout_log: logs.map(|(out, _)| {
+                                                        out.to_string_lossy().into_owned()
+                                                    }),
+                                    stderr_log: logs.map(|(_, err)| {
+                                                        err.to_string_lossy().into_owned()
+                                                    }),
+                                    resource_limits: limits,
+                                    sandbox,
                                     id: id.to_string() };
 
         Self::send(&self.tx, &msg).map_err(|err| IPCCommandError::Send("spawn", err))?;
@@ -237,6 +260,36 @@ impl LauncherCli {
         }
     }
 
+    /// Query the launcher for the exit code of the last process of the
+    /// named service. You'll only get `Ok(Some(i32))` if that process
+    /// exited on its own and the Launcher isn't running a newer one.
+    /// Launchers which predate exit code tracking always return
+    /// `Ok(None)`.
+    pub fn exit_code_of(&self, service_name: &str) -> Result<Option<i32>, TryIPCCommandError> {
+        let msg = protocol::PidOf { service_name: service_name.to_string(), };
+        Self::send(&self.tx, &msg).map_err(|err| TryIPCCommandError::Send("exit_code_of", err))?;
+        let reply = Self::recv_timeout::<protocol::PidIs>(&self.rx, self.timeout).map_err(|err| TryIPCCommandError::TryReceive("exit_code_of", err))?;
+        if reply.pid.is_some() {
+            Ok(None)
+        } else {
+            Ok(reply.exit_code)
+        }
+    }
+
+    /// Query the launcher for the resources currently used by the
+    /// named service. Usage is only known for services with resource
+    /// limits on Linux; otherwise every field of the returned
+    /// `ResourceUsage` is `None`.
+    pub fn resource_usage_of(&self,
+                             service_name: &str)
+                             -> Result<protocol::ResourceUsage, TryIPCCommandError> {
+        let msg = protocol::ResourceUsageOf { service_name: service_name.to_string(), };
+        Self::send(&self.tx, &msg).map_err(|err| {
+                                      TryIPCCommandError::Send("resource_usage_of", err)
+                                  })?;
+        Self::recv_timeout::<protocol::ResourceUsage>(&self.rx, self.timeout).map_err(|err| TryIPCCommandError::TryReceive("resource_usage_of", err))
+    }
+
     /// Query the launcher for its version. If the
     /// Launcher is aware of it, you'll get `Ok(u32)`
     pub fn version(&self) -> Result<u32, TryIPCCommandError> {
@@ -247,6 +300,30 @@ impl LauncherCli {
         Ok(reply.version)
     }
 
+    /// Query the launcher for whether it applies the sandbox of the
+    /// services it spawns. Launchers which predate sandboxing report
+    /// `Ok(false)`, or an error if they also predate the 'version'
+    /// command.
+    pub fn supports_sandbox(&self) -> Result<bool, TryIPCCommandError> {
+        let msg = protocol::Version {};
+        Self::send(&self.tx, &msg).map_err(|err| TryIPCCommandError::Send("version", err))?;
+
+        let reply = Self::recv_timeout::<protocol::VersionNumber>(&self.rx, self.timeout).map_err(|err| TryIPCCommandError::TryReceive("version", err))?;
+        Ok(reply.sandbox)
+    }
+
+    /// Query the launcher for whether it enforces the resource limits
+    /// of the services it spawns. Launchers which predate resource
+    /// limits, or which ignore them on their platform, report
+    /// `Ok(false)`.
+    pub fn supports_resource_limits(&self) -> Result<bool, TryIPCCommandError> {
+        let msg = protocol::Version {};
+        Self::send(&self.tx, &msg).map_err(|err| TryIPCCommandError::Send("version", err))?;
+
+        let reply = Self::recv_timeout::<protocol::VersionNumber>(&self.rx, self.timeout).map_err(|err| TryIPCCommandError::TryReceive("version", err))?;
+        Ok(reply.resource_limits)
+    }
+
     pub fn terminate(&self, pid: Pid) -> Result<i32, IPCCommandError> {
         let msg = protocol::Terminate { pid: pid.into() };
         Self::send(&self.tx, &msg).map_err(|err| IPCCommandError::Send("terminate", err))?;
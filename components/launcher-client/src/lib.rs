@@ -3,7 +3,10 @@ pub mod error;
 
 pub use habitat_launcher_protocol::{ERR_NO_RETRY_EXCODE,
                                     LAUNCHER_PID_ENV,
-                                    OK_NO_RETRY_EXCODE};
+                                    OK_NO_RETRY_EXCODE,
+                                    ResourceLimits,
+                                    ResourceUsage,
+                                    Sandbox};
 
 pub use crate::{client::{LauncherCli,
                          LauncherStatus},
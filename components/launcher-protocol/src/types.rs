@@ -2,6 +2,7 @@ use crate::{error::{Error,
                     Result},
             generated};
 use prost::Message;
+use serde::Serialize;
 use std::{collections::BTreeMap,
           convert::TryFrom,
           fmt};
@@ -126,14 +127,18 @@ impl From<Restart> for generated::Restart {
 
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Spawn {
-    pub id:           String,
-    pub binary:       String,
-    pub svc_user:     Option<String>,
-    pub svc_group:    Option<String>,
-    pub svc_password: Option<String>,
-    pub env:          BTreeMap<String, String>,
-    pub svc_user_id:  Option<u32>,
-    pub svc_group_id: Option<u32>,
+    pub id:              String,
+    pub binary:          String,
+    pub svc_user:        Option<String>,
+    pub svc_group:       Option<String>,
+    pub svc_password:    Option<String>,
+    pub env:             BTreeMap<String, String>,
+    pub svc_user_id:     Option<u32>,
+    pub svc_group_id:    Option<u32>,
+    pub stdout_log:      Option<String>,
+    pub stderr_log:      Option<String>,
+    pub resource_limits: Option<ResourceLimits>,
+    pub sandbox:         Option<Sandbox>,
 }
 
 impl LauncherMessage for Spawn {
@@ -142,27 +147,105 @@ impl LauncherMessage for Spawn {
     const MESSAGE_ID: &'static str = "Spawn";
 
     fn from_proto(proto: generated::Spawn) -> Result<Self> {
-        Ok(Spawn { id:           proto.id.ok_or(Error::ProtocolMismatch("id"))?,
-                   binary:       proto.binary.ok_or(Error::ProtocolMismatch("binary"))?,
-                   svc_user:     proto.svc_user,
-                   svc_group:    proto.svc_group,
-                   svc_password: proto.svc_password,
-                   env:          proto.env.into_iter().collect(),
-                   svc_user_id:  proto.svc_user_id,
-                   svc_group_id: proto.svc_group_id, })
+        Ok(Spawn { id:              proto.id.ok_or(Error::ProtocolMismatch("id"))?,
+                   binary:          proto.binary.ok_or(Error::ProtocolMismatch("binary"))?,
+                   svc_user:        proto.svc_user,
+                   svc_group:       proto.svc_group,
+                   svc_password:    proto.svc_password,
+                   env:             proto.env.into_iter().collect(),
+                   svc_user_id:     proto.svc_user_id,
+                   svc_group_id:    proto.svc_group_id,
+                   stdout_log:      proto.stdout_log,
+                   stderr_log:      proto.stderr_log,
+                   resource_limits: proto.resource_limits.map(ResourceLimits::from),
+                   sandbox:         proto.sandbox.map(Sandbox::from), })
     }
 }
 
 impl From<Spawn> for generated::Spawn {
     fn from(value: Spawn) -> Self {
-        generated::Spawn { id:           Some(value.id),
-                           binary:       Some(value.binary),
-                           svc_user:     value.svc_user,
-                           svc_group:    value.svc_group,
-                           svc_password: value.svc_password,
-                           env:          value.env.into_iter().collect(),
-                           svc_user_id:  value.svc_user_id,
-                           svc_group_id: value.svc_group_id, }
+        generated::Spawn { id:              Some(value.id),
+                           binary:          Some(value.binary),
+                           svc_user:        value.svc_user,
+                           svc_group:       value.svc_group,
+                           svc_password:    value.svc_password,
+                           env:             value.env.into_iter().collect(),
+                           svc_user_id:     value.svc_user_id,
+                           svc_group_id:    value.svc_group_id,
+                           stdout_log:      value.stdout_log,
+                           stderr_log:      value.stderr_log,
+                           resource_limits: value.resource_limits.map(Into::into),
+                           sandbox:         value.sandbox.map(Into::into), }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// The share of a single CPU the service may use, as a percentage
+    pub cpu_quota:  Option<u32>,
+    /// The most memory the service may use, in bytes
+    pub memory_max: Option<u64>,
+    pub pids_max:   Option<u64>,
+    /// The service's share of block IO relative to other services, from 1 to 10000
+    pub io_weight:  Option<u32>,
+}
+
+impl ResourceLimits {
+    /// Whether no limit at all is set.
+    pub fn is_empty(&self) -> bool { *self == Self::default() }
+}
+
+impl From<generated::ResourceLimits> for ResourceLimits {
+    fn from(proto: generated::ResourceLimits) -> Self {
+        ResourceLimits { cpu_quota:  proto.cpu_quota,
+                         memory_max: proto.memory_max,
+                         pids_max:   proto.pids_max,
+                         io_weight:  proto.io_weight, }
+    }
+}
+
+impl From<ResourceLimits> for generated::ResourceLimits {
+    fn from(value: ResourceLimits) -> Self {
+        generated::ResourceLimits { cpu_quota:  value.cpu_quota,
+                                    memory_max: value.memory_max,
+                                    pids_max:   value.pids_max,
+                                    io_weight:  value.io_weight, }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Sandbox {
+    /// Mount a private, empty `/tmp`
+    pub private_tmp:     bool,
+    pub read_only_paths: Vec<String>,
+    /// Paths beneath `read_only_paths` which stay writable
+    pub writable_paths:  Vec<String>,
+    /// Capabilities dropped from every capability set, e.g. `CAP_NET_RAW`, or `ALL`
+    pub cap_drop:        Vec<String>,
+    pub no_new_privs:    bool,
+    /// A seccomp profile in seccompiler's JSON format
+    pub seccomp_profile: Option<String>,
+}
+
+impl From<generated::Sandbox> for Sandbox {
+    fn from(proto: generated::Sandbox) -> Self {
+        Sandbox { private_tmp:     proto.private_tmp.unwrap_or(false),
+                  read_only_paths: proto.read_only_paths,
+                  writable_paths:  proto.writable_paths,
+                  cap_drop:        proto.cap_drop,
+                  no_new_privs:    proto.no_new_privs.unwrap_or(false),
+                  seccomp_profile: proto.seccomp_profile, }
+    }
+}
+
+impl From<Sandbox> for generated::Sandbox {
+    fn from(value: Sandbox) -> Self {
+        generated::Sandbox { private_tmp:     Some(value.private_tmp),
+                             read_only_paths: value.read_only_paths,
+                             writable_paths:  value.writable_paths,
+                             cap_drop:        value.cap_drop,
+                             no_new_privs:    Some(value.no_new_privs),
+                             seccomp_profile: value.seccomp_profile, }
     }
 }
 
@@ -299,7 +382,8 @@ impl From<PidOf> for generated::PidOf {
 
 #[derive(Clone, Debug)]
 pub struct PidIs {
-    pub pid: Option<u32>,
+    pub pid:       Option<u32>,
+    pub exit_code: Option<i32>,
 }
 
 impl LauncherMessage for PidIs {
@@ -309,7 +393,8 @@ impl LauncherMessage for PidIs {
 
     fn from_proto(proto: generated::PidIs) -> Result<Self> {
         // TODO (CM): ensure that the Pid is never Some(0)
-        Ok(PidIs { pid: proto.pid })
+        Ok(PidIs { pid:       proto.pid,
+                   exit_code: proto.exit_code, })
     }
 }
 
@@ -318,12 +403,70 @@ impl From<PidIs> for generated::PidIs {
     // a non-zero u32
     //
     // Perhaps we truly do need a NonZero Pid type here
-    fn from(value: PidIs) -> Self { generated::PidIs { pid: value.pid } }
+    fn from(value: PidIs) -> Self {
+        generated::PidIs { pid:       value.pid,
+                           exit_code: value.exit_code, }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ResourceUsageOf {
+    pub service_name: String,
+}
+
+impl LauncherMessage for ResourceUsageOf {
+    type Generated = generated::ResourceUsageOf;
+
+    const MESSAGE_ID: &'static str = "ResourceUsageOf";
+
+    fn from_proto(proto: generated::ResourceUsageOf) -> Result<Self> {
+        Ok(ResourceUsageOf { service_name: proto.service_name
+                                                .ok_or(Error::ProtocolMismatch("service_name"))?, })
+    }
+}
+
+impl From<ResourceUsageOf> for generated::ResourceUsageOf {
+    fn from(value: ResourceUsageOf) -> Self {
+        generated::ResourceUsageOf { service_name: Some(value.service_name), }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ResourceUsage {
+    /// The CPU time used by the service's processes, in microseconds
+    pub cpu_usage_usec: Option<u64>,
+    /// The memory currently used by the service's processes, in bytes
+    pub memory_current: Option<u64>,
+    pub pids_current:   Option<u64>,
+}
+
+impl LauncherMessage for ResourceUsage {
+    type Generated = generated::ResourceUsage;
+
+    const MESSAGE_ID: &'static str = "ResourceUsage";
+
+    fn from_proto(proto: generated::ResourceUsage) -> Result<Self> {
+        Ok(ResourceUsage { cpu_usage_usec: proto.cpu_usage_usec,
+                           memory_current: proto.memory_current,
+                           pids_current:   proto.pids_current, })
+    }
+}
+
+impl From<ResourceUsage> for generated::ResourceUsage {
+    fn from(value: ResourceUsage) -> Self {
+        generated::ResourceUsage { cpu_usage_usec: value.cpu_usage_usec,
+                                   memory_current: value.memory_current,
+                                   pids_current:   value.pids_current, }
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct VersionNumber {
-    pub version: u32,
+    pub version:         u32,
+    /// Whether the Launcher applies the sandbox of the services it spawns
+    pub sandbox:         bool,
+    /// Whether the Launcher enforces the resource limits of the services it spawns
+    pub resource_limits: bool,
 }
 
 impl LauncherMessage for VersionNumber {
@@ -332,13 +475,20 @@ impl LauncherMessage for VersionNumber {
     const MESSAGE_ID: &'static str = "VersionNumber";
 
     fn from_proto(proto: generated::VersionNumber) -> Result<Self> {
-        Ok(VersionNumber { version: proto.version.ok_or(Error::ProtocolMismatch("version"))?, })
+        Ok(VersionNumber { version:         proto.version
+                                                 .ok_or(Error::ProtocolMismatch("version"))?,
+                           // Launchers which predate sandboxing don't report it
+                           sandbox:         proto.sandbox.unwrap_or(false),
+                           // Nor do those which predate resource limits
+                           resource_limits: proto.resource_limits.unwrap_or(false), })
     }
 }
 
 impl From<VersionNumber> for generated::VersionNumber {
     fn from(value: VersionNumber) -> Self {
-        generated::VersionNumber { version: Some(value.version), }
+        generated::VersionNumber { version:         Some(value.version),
+                                   sandbox:         Some(value.sandbox),
+                                   resource_limits: Some(value.resource_limits), }
     }
 }
 
@@ -20,6 +20,15 @@ pub enum ServiceRunError {
     GroupNotFound(String),
     #[error("No UID for user '{0}' could be found")]
     UserNotFound(String),
+    #[cfg(target_os = "linux")]
+    #[error("Failed to set up the cgroup enforcing the service's resource limits")]
+    Cgroup(#[source] io::Error),
+    #[cfg(target_os = "linux")]
+    #[error("Failed to set up the service's sandbox")]
+    Sandbox(#[source] io::Error),
+    #[cfg(not(target_os = "linux"))]
+    #[error("Services can only be sandboxed on Linux")]
+    SandboxUnsupported,
 }
 
 impl From<ServiceRunError> for protocol::ErrCode {
@@ -335,6 +335,12 @@ impl Server {
                         debug!("Reaped supervisor process, PID {}", res);
                         // Note: from_raw is a Unix-only call
                         reaped_sup_status = Some(ExitStatus::from_raw(waitpid_status));
+                    } else if self.services.get(res as u32).is_some() {
+                        // A service process exited between `reap_services` and
+                        // here; record it just as `reap_services` would have.
+                        debug!("Reaped service process, PID {}", res);
+                        let status = ExitStatus::from_raw(waitpid_status);
+                        self.services.record_exit(res as u32, status.code());
                     } else {
                         debug!("Reaped a non-supervisor child process, PID {}", res);
                     }
@@ -361,16 +367,24 @@ impl Server {
 }
 
 #[derive(Debug, Default)]
-pub struct ServiceTable(HashMap<u32, Service>);
+pub struct ServiceTable {
+    services:   HashMap<u32, Service>,
+    /// The exit code of the last process of each service group that exited on its own, keyed
+    /// by service group name. Cleared when a new process is spawned for the service group.
+    exit_codes: HashMap<String, i32>,
+}
 
 impl ServiceTable {
-    pub fn get(&self, pid: u32) -> Option<&Service> { self.0.get(&pid) }
+    pub fn get(&self, pid: u32) -> Option<&Service> { self.services.get(&pid) }
 
-    pub fn get_mut(&mut self, pid: u32) -> Option<&mut Service> { self.0.get_mut(&pid) }
+    pub fn get_mut(&mut self, pid: u32) -> Option<&mut Service> { self.services.get_mut(&pid) }
 
-    pub fn insert(&mut self, service: Service) { self.0.insert(service.id(), service); }
+    pub fn insert(&mut self, service: Service) {
+        self.exit_codes.remove(service.name());
+        self.services.insert(service.id(), service);
+    }
 
-    pub fn remove(&mut self, pid: u32) -> Option<Service> { self.0.remove(&pid) }
+    pub fn remove(&mut self, pid: u32) -> Option<Service> { self.services.remove(&pid) }
 
     // Obviously this is not the most elegant implementation. However,
     // in practice we don't have a whole lot of processes per
@@ -394,17 +408,34 @@ impl ServiceTable {
     /// figure out if there are currently-running services to which it
     /// needs to re-attach itself.
     pub fn pid_of(&self, service_name: &str) -> Option<u32> {
-        self.0.iter().find_map(|(pid, service)| {
-                         if service_name == service.args().id {
-                             Some(*pid)
-                         } else {
-                             None
-                         }
-                     })
+        self.services.iter().find_map(|(pid, service)| {
+                                if service_name == service.args().id {
+                                    Some(*pid)
+                                } else {
+                                    None
+                                }
+                            })
+    }
+
+    /// Given the name of a service group, return the resources its running process currently
+    /// uses, if it has one.
+    pub fn resource_usage_of(&self, service_name: &str) -> Option<protocol::ResourceUsage> {
+        self.services
+            .values()
+            .find(|service| service_name == service.args().id)
+            .map(Service::resource_usage)
+    }
+
+    /// Given the name of a service group, return the exit code of its last process if that
+    /// process exited on its own and no new process has been spawned since.
+    ///
+    /// A process terminated by a signal has no exit code.
+    pub fn exit_code_of(&self, service_name: &str) -> Option<i32> {
+        self.exit_codes.get(service_name).copied()
     }
 
     fn kill_all(&mut self) {
-        for service in self.0.values_mut() {
+        for service in self.services.values_mut() {
             outputln!(preamble service.name(), "Stopping...");
             let shutdown_method = service.kill();
             outputln!(preamble service.name(), "Shutdown OK: {}", shutdown_method);
@@ -412,8 +443,8 @@ impl ServiceTable {
     }
 
     fn reap_services(&mut self) {
-        let mut dead: Vec<u32> = vec![];
-        for service in self.0.values_mut() {
+        let mut dead: Vec<(u32, Option<i32>)> = vec![];
+        for service in self.services.values_mut() {
             match service.try_wait() {
                 Ok(None) => (),
                 Ok(Some(code)) => {
@@ -421,16 +452,28 @@ impl ServiceTable {
                               service.name(),
                               service.id(),
                               code);
-                    dead.push(service.id());
+                    dead.push((service.id(), code.code()));
                 }
                 Err(err) => {
                     warn!("Error waiting for child, {}, {}", service.id(), err);
-                    dead.push(service.id());
+                    dead.push((service.id(), None));
                 }
             }
         }
-        for pid in dead {
-            self.0.remove(&pid);
+        for (pid, exit_code) in dead {
+            self.record_exit(pid, exit_code);
+        }
+    }
+
+    /// Forget the service process with the given PID, remembering its exit code (if any) so the
+    /// Supervisor can decide whether the service should be restarted.
+    fn record_exit(&mut self, pid: u32, exit_code: Option<i32>) {
+        if let Some(service) = self.services.remove(&pid) {
+            let name = service.name().to_string();
+            match exit_code {
+                Some(exit_code) => self.exit_codes.insert(name, exit_code),
+                None => self.exit_codes.remove(&name),
+            };
         }
     }
 }
@@ -500,6 +543,7 @@ fn dispatch(tx: &Sender, bytes: &[u8], services: &mut ServiceTable) {
         "Spawn" => handlers::SpawnHandler::run,
         "Terminate" => handlers::TerminateHandler::run,
         "PidOf" => handlers::PidHandler::run,
+        "ResourceUsageOf" => handlers::ResourceUsageHandler::run,
         "Version" => handlers::VersionHandler::run,
         unknown => {
             // This sucks a bit because it replicates some code from the
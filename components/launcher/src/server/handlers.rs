@@ -1,4 +1,5 @@
 mod pid;
+mod resource_usage;
 mod restart;
 mod spawn;
 mod terminate;
@@ -8,6 +9,7 @@ use log::{error,
           trace};
 
 pub use self::{pid::*,
+               resource_usage::*,
                restart::*,
                spawn::*,
                terminate::*,
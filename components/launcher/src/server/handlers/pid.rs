@@ -12,7 +12,12 @@ impl Handler for PidHandler {
     fn handle(msg: Self::Message, services: &mut ServiceTable) -> HandleResult<Self::Reply> {
         let service_name = msg.service_name;
         let pid = services.pid_of(&service_name);
-        let reply = protocol::PidIs { pid };
+        let exit_code = if pid.is_none() {
+            services.exit_code_of(&service_name)
+        } else {
+            None
+        };
+        let reply = protocol::PidIs { pid, exit_code };
         Ok(reply)
     }
 }
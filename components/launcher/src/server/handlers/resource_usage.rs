@@ -0,0 +1,17 @@
+use super::{HandleResult,
+            Handler};
+use crate::{protocol,
+            server::ServiceTable};
+
+pub struct ResourceUsageHandler;
+
+impl Handler for ResourceUsageHandler {
+    type Message = protocol::ResourceUsageOf;
+    type Reply = protocol::ResourceUsage;
+
+    fn handle(msg: Self::Message, services: &mut ServiceTable) -> HandleResult<Self::Reply> {
+        let reply = services.resource_usage_of(&msg.service_name)
+                            .unwrap_or_default();
+        Ok(reply)
+    }
+}
@@ -17,7 +17,9 @@ impl Handler for VersionHandler {
         let version = VERSION.unwrap_or(&max);
         match version.parse::<u32>() {
             Ok(v) => {
-                let reply = protocol::VersionNumber { version: v };
+                let reply = protocol::VersionNumber { version:         v,
+                                                      sandbox:         cfg!(target_os = "linux"),
+                                                      resource_limits: cfg!(target_os = "linux"), };
                 Ok(reply)
             }
             Err(err) => {
@@ -4,16 +4,22 @@ use crate::core::os::process::windows_child::{ChildStderr,
                                               ExitStatus};
 use crate::{core::util::BufReadLossy,
             protocol};
+use chrono::{SecondsFormat,
+             Utc};
 use habitat_common::output::{self,
                              StructuredOutput};
+use log::warn;
 #[cfg(unix)]
 use std::process::{ChildStderr,
                    ChildStdout,
                    ExitStatus};
 use std::{fmt,
+          fs::{File,
+               OpenOptions},
           io::{self,
                BufReader,
-               Read},
+               Read,
+               Write},
           thread};
 
 pub use crate::sys::service::*;
@@ -31,14 +37,16 @@ impl Service {
                -> Self {
         if let Some(stdout) = stdout {
             let id = spawn.id.to_string();
+            let log = spawn.stdout_log.as_deref().and_then(open_log);
             thread::Builder::new().name(format!("{}-out", spawn.id))
-                                  .spawn(move || pipe_stdout(stdout, &id))
+                                  .spawn(move || pipe_stdout(stdout, &id, log))
                                   .ok();
         }
         if let Some(stderr) = stderr {
             let id = spawn.id.to_string();
+            let log = spawn.stderr_log.as_deref().and_then(open_log);
             thread::Builder::new().name(format!("{}-err", spawn.id))
-                                  .spawn(move || pipe_stderr(stderr, &id))
+                                  .spawn(move || pipe_stderr(stderr, &id, log))
                                   .ok();
         }
         Service { args: spawn,
@@ -55,6 +63,9 @@ impl Service {
 
     pub fn name(&self) -> &str { &self.args.id }
 
+    /// The resources currently used by the service, as far as they are known.
+    pub fn resource_usage(&self) -> protocol::ResourceUsage { self.process.resource_usage() }
+
     pub fn take_args(self) -> protocol::Spawn { self.args }
 
     pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> { self.process.try_wait() }
@@ -69,12 +80,13 @@ impl fmt::Debug for Service {
 }
 
 /// Consume output from a child process until EOF, then finish
-fn pipe_stdout<T>(out: T, id: &str)
+fn pipe_stdout<T>(out: T, id: &str, mut log: Option<File>)
     where T: Read
 {
     for line in BufReader::new(out).lines_lossy() {
         match line {
             Ok(line) => {
+                append_log(&mut log, &line);
                 let so = StructuredOutput::succinct(id, "O", output::get_format(), &line);
                 if let Err(e) = so.println() {
                     println!("printing output: '{}' to stdout resulted in error: {}",
@@ -90,12 +102,13 @@ fn pipe_stdout<T>(out: T, id: &str)
 }
 
 /// Consume standard error from a child process until EOF, then finish
-fn pipe_stderr<T>(err: T, id: &str)
+fn pipe_stderr<T>(err: T, id: &str, mut log: Option<File>)
     where T: Read
 {
     for line in BufReader::new(err).lines_lossy() {
         match line {
             Ok(line) => {
+                append_log(&mut log, &line);
                 let so = StructuredOutput::succinct(id, "E", output::get_format(), &line);
                 if let Err(e) = so.eprintln() {
                     println!("printing output: '{}' to stderr resulted in error: {}",
@@ -109,3 +122,26 @@ fn pipe_stderr<T>(err: T, id: &str)
         }
     }
 }
+
+/// Open the file a service's output stream is persisted to, creating it if necessary.
+fn open_log(path: &str) -> Option<File> {
+    match OpenOptions::new().create(true).append(true).open(path) {
+        Ok(file) => Some(file),
+        Err(e) => {
+            warn!("Unable to open service log file {}, {}", path, e);
+            None
+        }
+    }
+}
+
+/// Append a line of service output to its log file, prefixed with the time it was read. A
+/// failed write stops any further writes to the file, but the output continues to be echoed.
+fn append_log(log: &mut Option<File>, line: &str) {
+    if let Some(file) = log {
+        let timestamp = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+        if let Err(e) = writeln!(file, "{} {}", timestamp, line) {
+            warn!("Unable to write to service log file, {}", e);
+            *log = None;
+        }
+    }
+}
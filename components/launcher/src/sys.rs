@@ -5,3 +5,11 @@ pub mod service;
 #[cfg(windows)]
 #[path = "sys/windows/service.rs"]
 pub mod service;
+
+#[cfg(target_os = "linux")]
+#[path = "sys/unix/cgroup.rs"]
+pub mod cgroup;
+
+#[cfg(target_os = "linux")]
+#[path = "sys/unix/sandbox.rs"]
+pub mod sandbox;
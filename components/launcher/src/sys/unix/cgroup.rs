@@ -0,0 +1,222 @@
+//! Resource limits for services, enforced by running each service in a cgroup v2 subtree of its
+//! own.
+//!
+//! Service cgroups are created beneath `/sys/fs/cgroup/hab`, or the directory named by
+//! `HAB_LAUNCH_CGROUP_ROOT`, which must be on a cgroup v2 hierarchy that makes the `cpu`, `io`,
+//! `memory` and `pids` controllers available to it.
+
+use crate::{core,
+            protocol::{ResourceLimits,
+                       ResourceUsage}};
+use log::debug;
+use std::{fs::{self,
+               OpenOptions},
+          io,
+          os::unix::{io::AsRawFd,
+                     process::CommandExt},
+          path::{Path,
+                 PathBuf},
+          process::Command};
+
+const CGROUP_ROOT_ENVVAR: &str = "HAB_LAUNCH_CGROUP_ROOT";
+const DEFAULT_CGROUP_ROOT: &str = "/sys/fs/cgroup/hab";
+/// The period over which `cpu.max` quotas are enforced, in microseconds.
+const CPU_PERIOD_USEC: u64 = 100_000;
+
+/// The directory beneath which service cgroups are created.
+pub fn root() -> PathBuf {
+    core::env::var(CGROUP_ROOT_ENVVAR).map_or_else(|_| PathBuf::from(DEFAULT_CGROUP_ROOT),
+                                                   PathBuf::from)
+}
+
+#[derive(Debug)]
+pub struct Cgroup {
+    path: PathBuf,
+}
+
+impl Cgroup {
+    /// Create the cgroup of the service named `id` beneath `root` and apply `limits` to it.
+    ///
+    /// A cgroup left behind by an earlier process of the same service is reused; any of its
+    /// limits which are not part of `limits` are lifted.
+    pub fn create(root: &Path, id: &str, limits: &ResourceLimits) -> io::Result<Self> {
+        // Usage is always accounted for, while the controllers that
+        // enforce limits are only enabled when they are needed.
+        let mut controllers = vec!["+memory", "+pids"];
+        if limits.cpu_quota.is_some() {
+            controllers.push("+cpu");
+        }
+        if limits.io_weight.is_some() {
+            controllers.push("+io");
+        }
+        fs::create_dir_all(root)?;
+        fs::write(root.join("cgroup.subtree_control"), controllers.join(" "))?;
+
+        let cgroup = Cgroup { path: root.join(id), };
+        fs::create_dir_all(&cgroup.path)?;
+        // The quota is a percentage of a single CPU, so truncating to whole microseconds is fine
+        #[allow(clippy::integer_division)]
+        let cpu_max = limits.cpu_quota
+                            .map(|quota| u64::from(quota) * CPU_PERIOD_USEC / 100)
+                            .map(|quota| format!("{} {}", quota, CPU_PERIOD_USEC));
+        cgroup.set("cpu.max", cpu_max, &format!("max {}", CPU_PERIOD_USEC))?;
+        cgroup.set("memory.max",
+                   limits.memory_max.map(|max| max.to_string()),
+                   "max")?;
+        cgroup.set("pids.max",
+                   limits.pids_max.map(|max| max.to_string()),
+                   "max")?;
+        cgroup.set("io.weight",
+                   limits.io_weight.map(|weight| format!("default {}", weight)),
+                   "default 100")?;
+        Ok(cgroup)
+    }
+
+    /// Arrange for the process spawned by `cmd` to move itself into this cgroup before it
+    /// executes, so that none of the processes it starts can escape the cgroup's limits.
+    ///
+    /// Joining from the child is best effort, as kernels older than 5.16 check the credentials
+    /// of the child rather than those of the Launcher. `add_process` must still be called with
+    /// the spawned process's PID.
+    pub fn join_on_exec(&self, cmd: &mut Command) -> io::Result<()> {
+        let procs = OpenOptions::new().write(true)
+                                      .open(self.path.join("cgroup.procs"))?;
+        unsafe {
+            cmd.pre_exec(move || {
+                   // Writing 0 moves the writing process itself.
+                   libc::write(procs.as_raw_fd(), b"0".as_ptr().cast(), 1);
+                   Ok(())
+               });
+        }
+        Ok(())
+    }
+
+    /// Move the process `pid` into this cgroup.
+    pub fn add_process(&self, pid: u32) -> io::Result<()> {
+        fs::write(self.path.join("cgroup.procs"), pid.to_string())
+    }
+
+    /// The resources currently used by the processes in this cgroup.
+    pub fn usage(&self) -> ResourceUsage {
+        let read = |file: &str| fs::read_to_string(self.path.join(file)).ok();
+        let parse = |value: &str| value.trim().parse::<u64>().ok();
+        let cpu_usage_usec =
+            read("cpu.stat").and_then(|stat| {
+                                stat.lines()
+                                    .find_map(|line| line.strip_prefix("usage_usec "))
+                                    .and_then(parse)
+                            });
+        let memory_current = read("memory.current").and_then(|value| parse(&value));
+        let pids_current = read("pids.current").and_then(|value| parse(&value));
+        ResourceUsage { cpu_usage_usec,
+                        memory_current,
+                        pids_current }
+    }
+
+    /// Write `value` to the interface file `file`, or `unlimited` if there is no value and the
+    /// file exists.
+    fn set(&self, file: &str, value: Option<String>, unlimited: &str) -> io::Result<()> {
+        let path = self.path.join(file);
+        match value {
+            Some(value) => fs::write(path, value),
+            None if path.exists() => fs::write(path, unlimited),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for Cgroup {
+    fn drop(&mut self) {
+        // This fails while the cgroup still holds processes, either
+        // ones which outlived the service's main process or those of
+        // the service's next process. The cgroup is in use then, so
+        // there is nothing to clean up.
+        if let Err(err) = fs::remove_dir(&self.path) {
+            debug!("Not removing cgroup {}: {}", self.path.display(), err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn read(cgroup: &Cgroup, file: &str) -> String {
+        fs::read_to_string(cgroup.path.join(file)).unwrap()
+    }
+
+    #[test]
+    fn create_applies_limits() {
+        let root = TempDir::new().unwrap();
+        let limits = ResourceLimits { cpu_quota:  Some(150),
+                                      memory_max: Some(512 * 1024 * 1024),
+                                      pids_max:   Some(64),
+                                      io_weight:  Some(500), };
+        let cgroup = Cgroup::create(root.path(), "redis.default", &limits).unwrap();
+
+        assert_eq!(cgroup.path, root.path().join("redis.default"));
+        assert_eq!(fs::read_to_string(root.path().join("cgroup.subtree_control")).unwrap(),
+                   "+memory +pids +cpu +io");
+        assert_eq!(read(&cgroup, "cpu.max"), "150000 100000");
+        assert_eq!(read(&cgroup, "memory.max"), "536870912");
+        assert_eq!(read(&cgroup, "pids.max"), "64");
+        assert_eq!(read(&cgroup, "io.weight"), "default 500");
+    }
+
+    #[test]
+    fn create_lifts_limits_of_a_reused_cgroup() {
+        let root = TempDir::new().unwrap();
+        let limits = ResourceLimits { cpu_quota: Some(50),
+                                      pids_max: Some(64),
+                                      ..Default::default() };
+        let first = Cgroup::create(root.path(), "redis.default", &limits).unwrap();
+        fs::write(first.path.join("cgroup.procs"), "").unwrap();
+
+        let limits = ResourceLimits { memory_max: Some(1024),
+                                      ..Default::default() };
+        let second = Cgroup::create(root.path(), "redis.default", &limits).unwrap();
+        // The first process's cgroup is still in use, so it isn't removed
+        drop(first);
+
+        assert_eq!(fs::read_to_string(root.path().join("cgroup.subtree_control")).unwrap(),
+                   "+memory +pids");
+        assert_eq!(read(&second, "cpu.max"), "max 100000");
+        assert_eq!(read(&second, "memory.max"), "1024");
+        assert_eq!(read(&second, "pids.max"), "max");
+        assert!(!second.path.join("io.weight").exists());
+    }
+
+    #[test]
+    fn processes_join_the_cgroup() {
+        let root = TempDir::new().unwrap();
+        let cgroup =
+            Cgroup::create(root.path(), "redis.default", &ResourceLimits::default()).unwrap();
+        fs::write(cgroup.path.join("cgroup.procs"), "").unwrap();
+
+        let mut cmd = Command::new("true");
+        cgroup.join_on_exec(&mut cmd).unwrap();
+        assert!(cmd.status().unwrap().success());
+        assert_eq!(read(&cgroup, "cgroup.procs"), "0");
+
+        cgroup.add_process(4242).unwrap();
+        assert_eq!(read(&cgroup, "cgroup.procs"), "4242");
+    }
+
+    #[test]
+    fn usage_is_read_from_the_cgroup() {
+        let root = TempDir::new().unwrap();
+        let cgroup =
+            Cgroup::create(root.path(), "redis.default", &ResourceLimits::default()).unwrap();
+        assert_eq!(cgroup.usage(), ResourceUsage::default());
+
+        fs::write(cgroup.path.join("cpu.stat"),
+                  "usage_usec 1234567\nuser_usec 1000000\nsystem_usec 234567\n").unwrap();
+        fs::write(cgroup.path.join("memory.current"), "8388608\n").unwrap();
+        fs::write(cgroup.path.join("pids.current"), "3\n").unwrap();
+        assert_eq!(cgroup.usage(),
+                   ResourceUsage { cpu_usage_usec: Some(1_234_567),
+                                   memory_current: Some(8_388_608),
+                                   pids_current:   Some(3), });
+    }
+}
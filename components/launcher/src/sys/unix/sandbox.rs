@@ -0,0 +1,279 @@
+//! Sandboxing of services, applied by their processes between being forked from the Launcher and
+//! executing their run hook.
+//!
+//! Paths are (re)mounted in a mount namespace of the service's own, which requires the Launcher to
+//! be running as root.
+
+use crate::protocol;
+use caps::{CapSet,
+           Capability};
+use habitat_core::os::process::exec;
+use nix::unistd::{Gid,
+                  Uid};
+use seccompiler::{BpfProgram,
+                  TargetArch};
+use std::{collections::BTreeMap,
+          env,
+          ffi::{CStr,
+                CString},
+          fs::File,
+          io,
+          os::unix::process::CommandExt,
+          process::Command,
+          ptr,
+          sync::Arc};
+
+/// The filter of a seccomp profile that is installed in a service's processes.
+const SECCOMP_FILTER: &str = "main";
+
+#[derive(Debug)]
+pub struct Sandbox {
+    private_tmp:     bool,
+    read_only_paths: Vec<CString>,
+    writable_paths:  Vec<CString>,
+    cap_drop:        Vec<Capability>,
+    no_new_privs:    bool,
+    seccomp_filter:  Option<BpfProgram>,
+}
+
+impl Sandbox {
+    /// Resolve everything `sandbox` refers to up front, so that as little as possible is left to
+    /// be done by the spawned process.
+    pub fn new(sandbox: &protocol::Sandbox) -> io::Result<Self> {
+        let cstrings = |paths: &[String]| {
+            paths.iter()
+                 .map(|path| CString::new(path.as_str()).map_err(io::Error::from))
+                 .collect::<io::Result<Vec<_>>>()
+        };
+        let mut cap_drop = Vec::new();
+        for name in &sandbox.cap_drop {
+            for cap in capabilities(name)? {
+                if !cap_drop.contains(&cap) {
+                    cap_drop.push(cap);
+                }
+            }
+        }
+        // Dropping capabilities from the bounding set requires
+        // CAP_SETPCAP, so it goes last.
+        cap_drop.sort_by_key(|cap| *cap == Capability::CAP_SETPCAP);
+        let seccomp_filter = sandbox.seccomp_profile
+                                    .as_deref()
+                                    .map(seccomp_filter)
+                                    .transpose()?;
+        Ok(Sandbox { private_tmp: sandbox.private_tmp,
+                     read_only_paths: cstrings(&sandbox.read_only_paths)?,
+                     writable_paths: cstrings(&sandbox.writable_paths)?,
+                     cap_drop,
+                     no_new_privs: sandbox.no_new_privs,
+                     seccomp_filter })
+    }
+
+    /// Prepare a `Command` to execute a run hook in this sandbox as the user and group in `ids`.
+    pub fn hook_command(self,
+                        executable: &str,
+                        env: &BTreeMap<String, String>,
+                        ids: (Uid, Gid))
+                        -> Command {
+        let sandbox = Arc::new(self);
+        let entered = Arc::clone(&sandbox);
+        let mut cmd = unsafe {
+            exec::unix::hook_command_with_setup(executable, env, Some(ids), move || entered.enter())
+        };
+        unsafe {
+            cmd.pre_exec(move || sandbox.restrict());
+        }
+        cmd
+    }
+
+    fn mounts(&self) -> bool {
+        self.private_tmp || !self.read_only_paths.is_empty() || !self.writable_paths.is_empty()
+    }
+
+    /// Set up the sandbox's mounts and capability bounding set. Runs while the process still has
+    /// the Launcher's privileges.
+    fn enter(&self) -> io::Result<()> {
+        if self.mounts() {
+            check(unsafe { libc::unshare(libc::CLONE_NEWNS) })?;
+            // Keep the mounts below from propagating back to the
+            // Launcher's mount namespace.
+            mount(None, c"/", None, libc::MS_REC | libc::MS_PRIVATE, None)?;
+        }
+        if self.private_tmp {
+            mount(Some(c"tmpfs"),
+                  c"/tmp",
+                  Some(c"tmpfs"),
+                  libc::MS_NOSUID | libc::MS_NODEV,
+                  Some(c"mode=1777"))?;
+        }
+        for path in &self.read_only_paths {
+            bind(path, true)?;
+        }
+        for path in &self.writable_paths {
+            bind(path, false)?;
+        }
+        for cap in &self.cap_drop {
+            caps::drop(None, CapSet::Bounding, *cap).map_err(io::Error::other)?;
+        }
+        Ok(())
+    }
+
+    /// Restrict the process further, once it is running as the service's user.
+    fn restrict(&self) -> io::Result<()> {
+        // Processes which aren't running as root have lost these
+        // capabilities by now anyway.
+        for cap in &self.cap_drop {
+            for set in [CapSet::Inheritable, CapSet::Effective, CapSet::Permitted] {
+                caps::drop(None, set, *cap).map_err(io::Error::other)?;
+            }
+        }
+        if self.no_new_privs {
+            check(unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) })?;
+        }
+        // This goes last, as the filter may well deny the calls made
+        // above.
+        if let Some(filter) = &self.seccomp_filter {
+            seccompiler::apply_filter(filter).map_err(io::Error::other)?;
+        }
+        Ok(())
+    }
+}
+
+/// The capabilities named by `name`, which is either a capability like `CAP_NET_RAW` or
+/// `net_raw`, or `ALL`.
+fn capabilities(name: &str) -> io::Result<Vec<Capability>> {
+    let name = name.to_uppercase();
+    if name == "ALL" {
+        let mut all = caps::runtime::thread_all_supported().into_iter()
+                                                           .collect::<Vec<_>>();
+        all.sort_by_key(Capability::index);
+        return Ok(all);
+    }
+    let name = if name.starts_with("CAP_") {
+        name
+    } else {
+        format!("CAP_{}", name)
+    };
+    name.parse::<Capability>()
+        .map(|cap| vec![cap])
+        .map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidInput,
+                           format!("Unknown capability '{}'", name))
+        })
+}
+
+/// Compile the seccomp filter of the profile at `path` for the architecture we're running on.
+fn seccomp_filter(path: &str) -> io::Result<BpfProgram> {
+    let arch = TargetArch::try_from(env::consts::ARCH).map_err(io::Error::other)?;
+    let mut filters =
+        seccompiler::compile_from_json(File::open(path)?, arch).map_err(io::Error::other)?;
+    filters.remove(SECCOMP_FILTER).ok_or_else(|| {
+                                      io::Error::new(io::ErrorKind::InvalidData,
+                                                     format!("Seccomp profile {} has no '{}' \
+                                                              filter",
+                                                             path, SECCOMP_FILTER))
+                                  })
+}
+
+/// Bind-mount `path` onto itself, so that it can be remounted read-only or writable independently
+/// of the mount it is on.
+fn bind(path: &CStr, read_only: bool) -> io::Result<()> {
+    mount(Some(path), path, None, libc::MS_BIND | libc::MS_REC, None)?;
+    let flags = if read_only { libc::MS_RDONLY } else { 0 };
+    mount(None,
+          path,
+          None,
+          libc::MS_BIND | libc::MS_REMOUNT | flags,
+          None)
+}
+
+fn mount(source: Option<&CStr>,
+         target: &CStr,
+         fstype: Option<&CStr>,
+         flags: libc::c_ulong,
+         data: Option<&CStr>)
+         -> io::Result<()> {
+    let as_ptr = |value: Option<&CStr>| value.map_or(ptr::null(), CStr::as_ptr);
+    check(unsafe {
+        libc::mount(as_ptr(source),
+                    target.as_ptr(),
+                    as_ptr(fstype),
+                    flags,
+                    as_ptr(data).cast())
+    })
+}
+
+fn check(rc: libc::c_int) -> io::Result<()> {
+    if rc == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn capabilities_are_named_with_or_without_prefix() {
+        assert_eq!(capabilities("CAP_NET_RAW").unwrap(),
+                   vec![Capability::CAP_NET_RAW]);
+        assert_eq!(capabilities("sys_admin").unwrap(),
+                   vec![Capability::CAP_SYS_ADMIN]);
+        assert!(!capabilities("all").unwrap().is_empty());
+        assert_eq!(capabilities("CAP_WIBBLE").unwrap_err().kind(),
+                   io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn setpcap_is_dropped_last() {
+        let sandbox = protocol::Sandbox { cap_drop: vec!["setpcap".to_string(),
+                                                         "net_raw".to_string(),
+                                                         "chown".to_string(),
+                                                         "CAP_NET_RAW".to_string()],
+                                          ..Default::default() };
+        let sandbox = Sandbox::new(&sandbox).unwrap();
+        assert_eq!(sandbox.cap_drop,
+                   vec![Capability::CAP_NET_RAW,
+                        Capability::CAP_CHOWN,
+                        Capability::CAP_SETPCAP]);
+        assert!(!sandbox.mounts());
+    }
+
+    #[test]
+    fn seccomp_profiles_are_compiled() {
+        let dir = TempDir::new().unwrap();
+        let profile = dir.path().join("profile.json");
+        fs::write(&profile,
+                  r#"{
+                       "main": {
+                         "mismatch_action": "allow",
+                         "match_action": {"errno": 1},
+                         "filter": [{"syscall": "ptrace"}]
+                       }
+                     }"#).unwrap();
+        assert!(!seccomp_filter(profile.to_str().unwrap()).unwrap()
+                                                          .is_empty());
+
+        fs::write(&profile,
+                  r#"{
+                       "other": {
+                         "mismatch_action": "allow",
+                         "match_action": "trap",
+                         "filter": []
+                       }
+                     }"#).unwrap();
+        assert_eq!(seccomp_filter(profile.to_str().unwrap()).unwrap_err()
+                                                            .kind(),
+                   io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn paths_must_not_contain_nul() {
+        let sandbox = protocol::Sandbox { read_only_paths: vec!["/hab/pkgs\0".to_string()],
+                                          ..Default::default() };
+        assert!(Sandbox::new(&sandbox).is_err());
+    }
+}
@@ -1,5 +1,10 @@
+#[cfg(target_os = "linux")]
+use crate::sys::{cgroup::{self,
+                          Cgroup},
+                 sandbox::Sandbox};
 use crate::{error::ServiceRunError,
             protocol::{self,
+                       ResourceUsage,
                        ShutdownMethod},
             service::Service};
 use anyhow::Result;
@@ -8,6 +13,8 @@ use habitat_core::os::{self,
                                  exec,
                                  signal}};
 use log::debug;
+#[cfg(not(target_os = "linux"))]
+use log::warn;
 use nix::unistd::{Gid,
                   Uid};
 use std::{io,
@@ -17,15 +24,20 @@ use std::{io,
           time::{Duration,
                  Instant}};
 
-pub struct Process(Child);
+pub struct Process {
+    child:  Child,
+    /// The cgroup enforcing the service's resource limits, if it has any
+    #[cfg(target_os = "linux")]
+    cgroup: Option<Cgroup>,
+}
 
 impl Process {
-    pub fn id(&self) -> u32 { self.0.id() }
+    pub fn id(&self) -> u32 { self.child.id() }
 
     /// Attempt to gracefully terminate a process and then forcefully kill it after
     /// 8 seconds if it has not terminated.
     pub fn kill(&mut self) -> ShutdownMethod {
-        let mut pid_to_kill = self.0.id() as i32;
+        let mut pid_to_kill = self.child.id() as i32;
         // check the group of the process being killed
         // if it is the root process of the process group
         // we send our signals to the entire process group
@@ -64,9 +76,19 @@ impl Process {
         }
     }
 
-    pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> { self.0.try_wait() }
+    /// The resources currently used by the process and its children. Only known for processes
+    /// with resource limits.
+    #[cfg(target_os = "linux")]
+    pub fn resource_usage(&self) -> ResourceUsage {
+        self.cgroup.as_ref().map(Cgroup::usage).unwrap_or_default()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn resource_usage(&self) -> ResourceUsage { ResourceUsage::default() }
 
-    pub fn wait(&mut self) -> io::Result<ExitStatus> { self.0.wait() }
+    pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> { self.child.try_wait() }
+
+    pub fn wait(&mut self) -> io::Result<ExitStatus> { self.child.wait() }
 }
 
 pub fn run(msg: protocol::Spawn) -> Result<Service, ServiceRunError> {
@@ -101,12 +123,58 @@ pub fn run(msg: protocol::Spawn) -> Result<Service, ServiceRunError> {
     };
     let gid = Gid::from_raw(group_id);
 
+    #[cfg(target_os = "linux")]
+    let sandbox = msg.sandbox
+                     .as_ref()
+                     .map(Sandbox::new)
+                     .transpose()
+                     .map_err(ServiceRunError::Sandbox)?;
+    #[cfg(not(target_os = "linux"))]
+    if msg.sandbox.is_some() {
+        return Err(ServiceRunError::SandboxUnsupported);
+    }
+
+    #[cfg(target_os = "linux")]
+    let mut cmd = match sandbox {
+        Some(sandbox) => sandbox.hook_command(&msg.binary, &msg.env, (uid, gid)),
+        None => exec::unix::hook_command(&msg.binary, &msg.env, Some((uid, gid))),
+    };
+    #[cfg(not(target_os = "linux"))]
     let mut cmd = exec::unix::hook_command(&msg.binary, &msg.env, Some((uid, gid)));
 
+    #[cfg(target_os = "linux")]
+    let cgroup = match msg.resource_limits.filter(|limits| !limits.is_empty()) {
+        Some(limits) => {
+            let cgroup =
+                Cgroup::create(&cgroup::root(), &msg.id, &limits).map_err(ServiceRunError::Cgroup)?;
+            cgroup.join_on_exec(&mut cmd)
+                  .map_err(ServiceRunError::Cgroup)?;
+            Some(cgroup)
+        }
+        None => None,
+    };
+    #[cfg(not(target_os = "linux"))]
+    if msg.resource_limits.is_some_and(|limits| !limits.is_empty()) {
+        warn!("Resource limits are only supported on Linux; ignoring them for {}",
+              msg.id);
+    }
+
     let mut child = cmd.spawn().map_err(ServiceRunError::Spawn)?;
     let stdout = child.stdout.take();
     let stderr = child.stderr.take();
-    let process = Process(child);
+    #[cfg(target_os = "linux")]
+    if let Some(cgroup) = &cgroup
+       && let Err(err) = cgroup.add_process(child.id())
+    {
+        // The child has already started, so it's killed rather than
+        // left running without its limits.
+        child.kill().ok();
+        child.wait().ok();
+        return Err(ServiceRunError::Cgroup(err));
+    }
+    let process = Process { child,
+                            #[cfg(target_os = "linux")]
+                            cgroup };
     debug!(target: "pidfile_tracing", "Launcher spawned {} with PID = {}", msg.binary, process.id());
     Ok(Service::new(msg, process, stdout, stderr))
 }
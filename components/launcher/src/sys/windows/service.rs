@@ -4,11 +4,13 @@ use crate::{core::{os::process::{handle_from_pid,
                    util},
             error::ServiceRunError,
             protocol::{self,
+                       ResourceUsage,
                        ShutdownMethod},
             service::Service};
 use anyhow::Result;
 use log::{debug,
-          error};
+          error,
+          warn};
 use std::{collections::HashMap,
           io,
           mem,
@@ -74,6 +76,9 @@ impl Process {
         }
     }
 
+    /// Resource limits are not supported on Windows, so usage is never known.
+    pub fn resource_usage(&self) -> ResourceUsage { ResourceUsage::default() }
+
     pub fn wait(&mut self) -> io::Result<ExitStatus> {
         unsafe {
             let res = synchapi::WaitForSingleObject(self.handle.raw(), INFINITE);
@@ -118,6 +123,13 @@ pub fn run(msg: protocol::Spawn) -> Result<Service, ServiceRunError> {
     debug!("launcher is spawning {}", msg.binary);
     let ps_cmd = format!("iex $(gc {} | out-string)", &msg.binary);
     let password = msg.svc_password.clone();
+    if msg.resource_limits.is_some_and(|limits| !limits.is_empty()) {
+        warn!("Resource limits are only supported on Linux; ignoring them for {}",
+              msg.id);
+    }
+    if msg.sandbox.is_some() {
+        return Err(ServiceRunError::SandboxUnsupported);
+    }
 
     let user = match msg.svc_user.as_ref() {
         Some(u) => u.to_string(),
@@ -1459,6 +1459,14 @@ function Write-Metadata {
         "$pkg_shutdown_timeout_sec" |
             Out-File "$pkg_prefix\SHUTDOWN_TIMEOUT" -Encoding ascii
     }
+    if (-Not ([string]::IsNullOrEmpty($pkg_health_check))) {
+        "$pkg_health_check" |
+            Out-File "$pkg_prefix\HEALTH_CHECK" -Encoding ascii
+    }
+    if (-Not ([string]::IsNullOrEmpty($pkg_schedule))) {
+        "$pkg_schedule" |
+            Out-File "$pkg_prefix\SCHEDULE" -Encoding ascii
+    }
 
     # Generate the blake2b hashes of all the files in the package. This
     # is not in the resulting MANIFEST because MANIFEST is included!
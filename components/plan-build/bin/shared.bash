@@ -267,6 +267,22 @@ _render_metadata_SHUTDOWN_SIGNAL() {
   fi
 }
 
+_render_metadata_HEALTH_CHECK() {
+  if [[ -n "${pkg_health_check:-}" ]]; then
+    debug "Rendering HEALTH_CHECK metadata file"
+    # shellcheck disable=2154
+    echo "$pkg_health_check" > "$pkg_prefix"/HEALTH_CHECK
+  fi
+}
+
+_render_metadata_SCHEDULE() {
+  if [[ -n "${pkg_schedule:-}" ]]; then
+    debug "Rendering SCHEDULE metadata file"
+    # shellcheck disable=2154
+    echo "$pkg_schedule" > "$pkg_prefix"/SCHEDULE
+  fi
+}
+
 _render_metadata_SHUTDOWN_TIMEOUT() {
   if [[ -n "${pkg_shutdown_timeout_sec:-}" ]]; then
     debug "Rendering SHUTDOWN_TIMEOUT metadata file"
@@ -20,6 +20,14 @@ impl message::MessageStatic for ServiceBindList {
     const MESSAGE_ID: &'static str = "ServiceBindList";
 }
 
+impl message::MessageStatic for PackageIdentList {
+    const MESSAGE_ID: &'static str = "PackageIdentList";
+}
+
+impl message::MessageStatic for CapabilityList {
+    const MESSAGE_ID: &'static str = "CapabilityList";
+}
+
 impl message::MessageStatic for SupDepart {
     const MESSAGE_ID: &'static str = "SupDepart";
 }
@@ -28,6 +36,26 @@ impl message::MessageStatic for SupRestart {
     const MESSAGE_ID: &'static str = "SupRestart";
 }
 
+impl message::MessageStatic for SupRingKeyStage {
+    const MESSAGE_ID: &'static str = "SupRingKeyStage";
+}
+
+impl message::MessageStatic for SupRingKeyActivate {
+    const MESSAGE_ID: &'static str = "SupRingKeyActivate";
+}
+
+impl message::MessageStatic for SupRingKeyRetire {
+    const MESSAGE_ID: &'static str = "SupRingKeyRetire";
+}
+
+impl message::MessageStatic for SupRingKeyStatus {
+    const MESSAGE_ID: &'static str = "SupRingKeyStatus";
+}
+
+impl message::MessageStatic for SupRingKeys {
+    const MESSAGE_ID: &'static str = "SupRingKeys";
+}
+
 impl message::MessageStatic for SvcFilePut {
     const MESSAGE_ID: &'static str = "SvcFilePut";
 }
@@ -44,10 +72,42 @@ impl message::MessageStatic for SvcSetCfg {
     const MESSAGE_ID: &'static str = "SvcSetCfg";
 }
 
+impl message::MessageStatic for SvcPreviewCfg {
+    const MESSAGE_ID: &'static str = "SvcPreviewCfg";
+}
+
+impl message::MessageStatic for SvcCfgPreview {
+    const MESSAGE_ID: &'static str = "SvcCfgPreview";
+}
+
+impl message::MessageStatic for SvcGetCfgHistory {
+    const MESSAGE_ID: &'static str = "SvcGetCfgHistory";
+}
+
+impl message::MessageStatic for SvcCfgRevision {
+    const MESSAGE_ID: &'static str = "SvcCfgRevision";
+}
+
+impl message::MessageStatic for SvcRollbackCfg {
+    const MESSAGE_ID: &'static str = "SvcRollbackCfg";
+}
+
+impl message::MessageStatic for SvcLeaderStepDown {
+    const MESSAGE_ID: &'static str = "SvcLeaderStepDown";
+}
+
+impl message::MessageStatic for SvcLeaderTransfer {
+    const MESSAGE_ID: &'static str = "SvcLeaderTransfer";
+}
+
 impl message::MessageStatic for SvcLoad {
     const MESSAGE_ID: &'static str = "SvcLoad";
 }
 
+impl message::MessageStatic for SvcLockfile {
+    const MESSAGE_ID: &'static str = "SvcLockfile";
+}
+
 impl message::MessageStatic for SvcUpdate {
     const MESSAGE_ID: &'static str = "SvcUpdate";
 }
@@ -68,6 +128,10 @@ impl message::MessageStatic for SvcStatus {
     const MESSAGE_ID: &'static str = "SvcStatus";
 }
 
+impl message::MessageStatic for SvcLogs {
+    const MESSAGE_ID: &'static str = "SvcLogs";
+}
+
 impl message::MessageStatic for ConsoleLine {
     const MESSAGE_ID: &'static str = "ConsoleLine";
 }
@@ -90,3 +154,31 @@ impl Into<Vec<habitat_core::service::ServiceBind>> for ServiceBindList {
         self.binds.into_iter().map(Into::into).collect()
     }
 }
+
+impl std::iter::FromIterator<habitat_core::package::PackageIdent> for PackageIdentList {
+    fn from_iter<T>(iter: T) -> Self
+        where T: IntoIterator<Item = habitat_core::package::PackageIdent>
+    {
+        PackageIdentList { idents: iter.into_iter().map(Into::into).collect(), }
+    }
+}
+
+#[allow(clippy::from_over_into)]
+impl Into<Vec<habitat_core::package::PackageIdent>> for PackageIdentList {
+    fn into(self) -> Vec<habitat_core::package::PackageIdent> {
+        self.idents.into_iter().map(Into::into).collect()
+    }
+}
+
+impl std::iter::FromIterator<String> for CapabilityList {
+    fn from_iter<T>(iter: T) -> Self
+        where T: IntoIterator<Item = String>
+    {
+        CapabilityList { capabilities: iter.into_iter().collect(), }
+    }
+}
+
+#[allow(clippy::from_over_into)]
+impl Into<Vec<String>> for CapabilityList {
+    fn into(self) -> Vec<String> { self.capabilities }
+}
@@ -26,6 +26,9 @@ impl message::MessageStatic for PackageIdent {
 impl message::MessageStatic for ProcessStatus {
     const MESSAGE_ID: &'static str = "ProcessStatus";
 }
+impl message::MessageStatic for ResourceUsage {
+    const MESSAGE_ID: &'static str = "ResourceUsage";
+}
 impl message::MessageStatic for ServiceBind {
     const MESSAGE_ID: &'static str = "ServiceBind";
 }
@@ -87,6 +90,7 @@ impl fmt::Display for ProcessState {
         let state = match *self {
             ProcessState::Down => "down",
             ProcessState::Up => "up",
+            ProcessState::Failed => "failed",
         };
         write!(f, "{}", state)
     }
@@ -127,10 +131,11 @@ impl FromStr for ProcessState {
         match value.to_lowercase().as_ref() {
             "0" => Ok(ProcessState::Down),
             "1" => Ok(ProcessState::Up),
+            "2" => Ok(ProcessState::Failed),
             _ => {
                 Err(net::err(ErrCode::InvalidPayload,
                              format!("Invalid process state \"{:?}\", must \
-                                      be `up` or `down`.",
+                                      be `up`, `down` or `failed`.",
                                      value)))
             }
         }
@@ -401,6 +406,88 @@ impl clap_v4::ValueEnum for UpdateCondition {
     }
 }
 
+impl RestartPolicy {
+    pub const VARIANTS: &'static [&'static str] = &["always", "on-failure", "never"];
+
+    pub fn as_str(&self) -> &str {
+        match *self {
+            RestartPolicy::Always => "always",
+            RestartPolicy::OnFailure => "on-failure",
+            RestartPolicy::Never => "never",
+        }
+    }
+}
+
+impl FromStr for RestartPolicy {
+    type Err = NetErr;
+
+    fn from_str(policy: &str) -> Result<Self, Self::Err> {
+        match policy {
+            "always" => Ok(RestartPolicy::Always),
+            "on-failure" => Ok(RestartPolicy::OnFailure),
+            "never" => Ok(RestartPolicy::Never),
+            _ => Err(net::err(ErrCode::InvalidPayload, "Invalid restart policy.")),
+        }
+    }
+}
+
+impl fmt::Display for RestartPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self.as_str()) }
+}
+
+impl clap_v4::ValueEnum for RestartPolicy {
+    fn value_variants<'a>() -> &'a [Self] { &[Self::Always, Self::OnFailure, Self::Never] }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+                 Self::Always => PossibleValue::new("always"),
+                 Self::OnFailure => PossibleValue::new("on-failure"),
+                 Self::Never => PossibleValue::new("never"),
+             })
+    }
+}
+
+impl FencingPolicy {
+    pub const VARIANTS: &'static [&'static str] = &["none", "hook", "stop"];
+
+    pub fn as_str(&self) -> &str {
+        match *self {
+            FencingPolicy::None => "none",
+            FencingPolicy::Hook => "hook",
+            FencingPolicy::Stop => "stop",
+        }
+    }
+}
+
+impl FromStr for FencingPolicy {
+    type Err = NetErr;
+
+    fn from_str(policy: &str) -> Result<Self, Self::Err> {
+        match policy {
+            "none" => Ok(FencingPolicy::None),
+            "hook" => Ok(FencingPolicy::Hook),
+            "stop" => Ok(FencingPolicy::Stop),
+            _ => Err(net::err(ErrCode::InvalidPayload, "Invalid fencing policy.")),
+        }
+    }
+}
+
+impl fmt::Display for FencingPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self.as_str()) }
+}
+
+impl clap_v4::ValueEnum for FencingPolicy {
+    fn value_variants<'a>() -> &'a [Self] { &[Self::None, Self::Hook, Self::Stop] }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+                 Self::None => PossibleValue::new("none"),
+                 Self::Hook => PossibleValue::new("hook"),
+                 Self::Stop => PossibleValue::new("stop"),
+             })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde::{Deserialize,
@@ -516,4 +603,68 @@ mod tests {
 
         assert!(toml.starts_with(r#"key = "at-once""#));
     }
+
+    #[test]
+    fn restart_policy_default() {
+        // Restarting a service whenever it exits is the legacy behavior, if this default gets
+        // changed, we have a failing test to confirm we changed our minds
+        assert_eq!(RestartPolicy::default(), RestartPolicy::Always);
+    }
+
+    #[test]
+    fn restart_policy_from_str() {
+        let policy = RestartPolicy::from_str("on-failure").unwrap();
+
+        assert_eq!(policy, RestartPolicy::OnFailure);
+    }
+
+    #[test]
+    fn restart_policy_from_str_invalid() {
+        assert!(RestartPolicy::from_str("sometimes").is_err());
+    }
+
+    #[test]
+    fn restart_policy_to_string() {
+        let policy = RestartPolicy::OnFailure;
+
+        assert_eq!("on-failure", policy.to_string())
+    }
+
+    #[test]
+    fn restart_policy_toml_serialize() {
+        #[derive(Serialize)]
+        struct Data {
+            key: RestartPolicy,
+        }
+        let data = Data { key: RestartPolicy::OnFailure, };
+        let toml = toml::to_string(&data).unwrap();
+
+        assert!(toml.starts_with(r#"key = "on-failure""#));
+    }
+
+    #[test]
+    fn fencing_policy_default() {
+        // Leaving the service running is the legacy behavior, if this default gets changed, we
+        // have a failing test to confirm we changed our minds
+        assert_eq!(FencingPolicy::default(), FencingPolicy::None);
+    }
+
+    #[test]
+    fn fencing_policy_from_str() {
+        assert_eq!(FencingPolicy::from_str("stop").unwrap(),
+                   FencingPolicy::Stop);
+        assert!(FencingPolicy::from_str("shoot-the-other-node").is_err());
+    }
+
+    #[test]
+    fn fencing_policy_toml_serialize() {
+        #[derive(Serialize)]
+        struct Data {
+            key: FencingPolicy,
+        }
+        let data = Data { key: FencingPolicy::Hook, };
+        let toml = toml::to_string(&data).unwrap();
+
+        assert!(toml.starts_with(r#"key = "hook""#));
+    }
 }
@@ -360,12 +360,12 @@ impl CensusGroup {
 
     /// Same as `members`, but only returns members that are either
     /// alive or suspect, i.e., nothing that is confirmed dead or
-    /// departed. These are the members that we'll reasonably be
-    /// interacting with at runtime.
+    /// departed, and that haven't unloaded the service. These are the
+    /// members that we'll reasonably be interacting with at runtime.
     pub fn active_members(&self) -> impl Iterator<Item = &CensusMember> {
         self.population
             .values()
-            .filter(|cm| cm.alive() || cm.suspect())
+            .filter(|cm| !cm.unloaded() && (cm.alive() || cm.suspect()))
     }
 
     /// Return references to all a `CensusGroup`'s `ServiceFiles`.
@@ -589,11 +589,18 @@ pub struct CensusMember {
     pub update_election_is_running: bool,
     pub update_election_is_no_quorum: bool,
     pub update_election_is_finished: bool,
+    /// Whether the member was the leader when its service group lost quorum. It stays fenced
+    /// until the service group finishes electing a leader again.
+    pub fenced: bool,
     pub sys: SysInfo,
     pub alive: bool,
     pub suspect: bool,
     pub confirmed: bool,
     pub departed: bool,
+    /// Whether the member's service passes its readiness check
+    pub ready: bool,
+    /// Whether the member unloaded the service. It stays in the census group, but isn't active.
+    pub unloaded: bool,
     pub cfg: toml::value::Table,
 }
 
@@ -611,6 +618,8 @@ impl CensusMember {
         };
         self.pkg_incarnation = rumor.pkg_incarnation;
         self.sys = rumor.sys.clone();
+        self.ready = rumor.ready;
+        self.unloaded = rumor.unloaded;
         self.cfg =
             toml::from_str(str::from_utf8(&rumor.cfg).unwrap_or_default()).unwrap_or_default();
     }
@@ -619,7 +628,11 @@ impl CensusMember {
         self.election_is_running = election.status == ElectionStatusRumor::Running;
         self.election_is_no_quorum = election.status == ElectionStatusRumor::NoQuorum;
         self.election_is_finished = election.status == ElectionStatusRumor::Finished;
+        if self.election_is_no_quorum && self.leader {
+            self.fenced = true;
+        }
         if self.election_is_finished {
+            self.fenced = false;
             if self.member_id == election.member_id {
                 self.leader = true;
                 self.follower = false;
@@ -674,6 +687,10 @@ impl CensusMember {
     pub fn confirmed(&self) -> bool { self.confirmed }
 
     pub fn departed(&self) -> bool { self.departed }
+
+    pub fn ready(&self) -> bool { self.ready }
+
+    pub fn unloaded(&self) -> bool { self.unloaded }
 }
 
 /// This data structure just wraps the CensusMember and allows us to tweak the serialization logic.
@@ -700,7 +717,7 @@ impl Serialize for CensusMemberProxy<'_> {
     fn serialize<S>(&self, serializer: S) -> result::Result<S::Ok, S::Error>
         where S: Serializer
     {
-        let mut strukt = serializer.serialize_struct("census_member", 24)?;
+        let mut strukt = serializer.serialize_struct("census_member", 26)?;
         strukt.serialize_field("member_id", &self.member_id)?;
         strukt.serialize_field("pkg", &self.pkg)?;
         strukt.serialize_field("pkg_incarnation", &self.pkg_incarnation)?;
@@ -723,11 +740,16 @@ impl Serialize for CensusMemberProxy<'_> {
                                &self.update_election_is_no_quorum)?;
         strukt.serialize_field("update_election_is_finished",
                                &self.update_election_is_finished)?;
+        strukt.serialize_field("fenced", &self.fenced)?;
         strukt.serialize_field("sys", &self.sys)?;
-        strukt.serialize_field("alive", &self.alive)?;
+        // Members which aren't ready, or which unloaded the service,
+        // are kept from the services which bind to them, and from
+        // `eachAlive`, by not advertising them as alive.
+        strukt.serialize_field("alive", &(self.alive && self.ready && !self.unloaded))?;
         strukt.serialize_field("suspect", &self.suspect)?;
         strukt.serialize_field("confirmed", &self.confirmed)?;
         strukt.serialize_field("departed", &self.departed)?;
+        strukt.serialize_field("ready", &self.ready)?;
         strukt.serialize_field("cfg", &self.cfg)?;
         strukt.end()
     }
@@ -881,20 +903,81 @@ mod tests {
                        update_election_is_running: false,
                        update_election_is_no_quorum: false,
                        update_election_is_finished: false,
+                       fenced: false,
                        sys: SysInfo::default(),
                        alive: health == Health::Alive,
                        suspect: health == Health::Suspect,
                        confirmed: health == Health::Confirmed,
                        departed: health == Health::Departed,
+                       ready: true,
+                       unloaded: false,
                        cfg: toml::value::Table::new() }
     }
 
+    #[test]
+    fn members_which_are_not_ready_are_not_advertised_as_alive() {
+        let mut member = test_census_member("unready-one", Health::Alive);
+        member.ready = false;
+        let json = serde_json::to_value(CensusMemberProxy::new(&member)).unwrap();
+        assert_eq!(json["alive"], false);
+        assert_eq!(json["ready"], false);
+        assert!(member.alive());
+
+        member.ready = true;
+        let json = serde_json::to_value(CensusMemberProxy::new(&member)).unwrap();
+        assert_eq!(json["alive"], true);
+        assert_eq!(json["ready"], true);
+    }
+
+    #[test]
+    fn members_which_unloaded_the_service_are_not_advertised_as_alive() {
+        let mut member = test_census_member("unloaded-one", Health::Alive);
+        member.unloaded = true;
+        let json = serde_json::to_value(CensusMemberProxy::new(&member)).unwrap();
+        assert_eq!(json["alive"], false);
+        assert!(member.alive());
+    }
+
+    #[test]
+    fn leader_is_fenced_until_a_leader_is_elected_again() {
+        let sg = ServiceGroup::new("shield", "one", None).unwrap();
+        let mut leader = test_census_member("member-a", Health::Alive);
+        let mut follower = test_census_member("member-b", Health::Alive);
+
+        let mut election = ElectionRumor::new("member-a", &sg, 1, 10, true /* has_quorum */);
+        election.finish();
+        assert!(leader.update_from_election_rumor(&election));
+        assert!(!follower.update_from_election_rumor(&election));
+        assert!(!leader.fenced);
+
+        election.no_quorum();
+        leader.update_from_election_rumor(&election);
+        follower.update_from_election_rumor(&election);
+        assert!(leader.fenced);
+        assert!(!follower.fenced);
+        let json = serde_json::to_value(CensusMemberProxy::new(&leader)).unwrap();
+        assert_eq!(json["fenced"], true);
+
+        election.running();
+        leader.update_from_election_rumor(&election);
+        assert!(leader.fenced);
+
+        let mut election = ElectionRumor::new("member-b", &sg, 2, 10, true /* has_quorum */);
+        election.finish();
+        assert!(!leader.update_from_election_rumor(&election));
+        assert!(follower.update_from_election_rumor(&election));
+        assert!(!leader.fenced);
+        assert!(!follower.fenced);
+    }
+
     #[test]
     fn active_members_leaves_only_active_members() {
         let population = [test_census_member("live-one", Health::Alive),
                           test_census_member("suspect-one", Health::Suspect),
                           test_census_member("confirmed-one", Health::Confirmed),
-                          test_census_member("departed-one", Health::Departed)];
+                          test_census_member("departed-one", Health::Departed),
+                          CensusMember { unloaded: true,
+                                         ..test_census_member("unloaded-one", Health::Alive) }];
 
         let sg: ServiceGroup =
             "test-service.default".parse()
@@ -34,6 +34,7 @@ mod manager_config {
               io::Write,
               net::{SocketAddr,
                     ToSocketAddrs},
+              path::PathBuf,
               str::FromStr,
               time::Duration};
 
@@ -47,15 +48,21 @@ mod manager_config {
                        package::PackageIdent};
     use habitat_sup::{event::EventStreamConfig,
                       manager::{ManagerConfig,
+                                PeerDiscoverySource,
+                                ServiceLogConfig,
                                 ServiceRestartConfig,
                                 TLSConfig}};
 
-    use habitat_sup_protocol::{ctl::ServiceBindList,
+    use habitat_sup_protocol::{ctl::{CapabilityList,
+                                     PackageIdentList,
+                                     ServiceBindList},
                                types::{BindingMode,
                                        ServiceBind}};
 
     use habitat_common::command::package::install::InstallSource;
-    use habitat_sup::manager::service::{Topology,
+    use habitat_sup::manager::service::{FencingPolicy,
+                                        RestartPolicy,
+                                        Topology,
                                         UpdateCondition,
                                         UpdateStrategy};
 
@@ -255,6 +262,22 @@ mod manager_config {
         assert_eq!(config.watch_peer_file, None);
     }
 
+    #[test]
+    fn peer_discovery_should_be_set() {
+        let config = config_from_cmd_str("hab-sup run --peer-discovery dns:hab.service.consul \
+                                          --peer-discovery exec:/bin/peers \
+                                          --peer-discovery-period 30");
+        assert_eq!(config.peer_discovery,
+                   vec![PeerDiscoverySource::Dns { name:       String::from("hab.service.consul"),
+                                                   nameserver: None, },
+                        PeerDiscoverySource::Exec(PathBuf::from("/bin/peers"))]);
+        assert_eq!(config.peer_discovery_period, Duration::from_secs(30));
+
+        let config = config_from_cmd_str("hab-sup run");
+        assert!(config.peer_discovery.is_empty());
+        assert_eq!(config.peer_discovery_period, Duration::from_secs(60));
+    }
+
     #[test]
     fn ring_key_is_set_properly_by_name() {
         let temp_dir = TempDir::new().expect("Could not create tempdir");
@@ -333,6 +356,7 @@ gpoVMSncu2jMIDZX63IkQII=
                                    auto_update_period:         Duration::from_secs(60),
                                    service_update_period:      Duration::from_secs(60),
                                    service_restart_config:     ServiceRestartConfig::default(),
+                                   service_log_config:         ServiceLogConfig::default(),
                                    custom_state_path:          None,
                                    key_cache:                  KeyCache::new(&*CACHE_KEY_PATH),
                                    update_url:
@@ -347,13 +371,21 @@ gpoVMSncu2jMIDZX63IkQII=
                                    http_disable:               false,
                                    gossip_peers:               vec![],
                                    gossip_permanent:           false,
+                                   gossip_certificates:        None,
+                                   gossip_key:                 None,
+                                   gossip_ca_certificates:     None,
+                                   gossip_segment:             None,
+                                   gossip_segment_gateway:     false,
                                    ring_key:                   None,
                                    organization:               None,
                                    watch_peer_file:            None,
+                                   peer_discovery:             vec![],
+                                   peer_discovery_period:      Duration::from_secs(60),
                                    tls_config:                 None,
                                    feature_flags:              FeatureFlag::empty(),
                                    event_stream_config:        None,
                                    keep_latest_packages:       None,
+                                   verify_installed_files:     false,
                                    sys_ip:
                                        habitat_core::util::sys::ip().unwrap(), },
                    config);
@@ -406,6 +438,7 @@ gpoVMSncu2jMIDZX63IkQII=
                                    auto_update_period: Duration::from_secs(90),
                                    service_update_period: Duration::from_secs(30),
                                    service_restart_config: ServiceRestartConfig::default(),
+                                   service_log_config: ServiceLogConfig::default(),
                                    custom_state_path: None,
                                    key_cache: KeyCache::new(temp_dir_str),
                                    update_url: String::from("https://bldr.habitat.sh"),
@@ -421,9 +454,16 @@ gpoVMSncu2jMIDZX63IkQII=
                                    http_disable: true,
                                    gossip_peers,
                                    gossip_permanent: true,
+                                   gossip_certificates: None,
+                                   gossip_key: None,
+                                   gossip_ca_certificates: None,
+                                   gossip_segment: None,
+                                   gossip_segment_gateway: false,
                                    ring_key: Some(ring_key),
                                    organization: Some(String::from("MY_ORG")),
                                    watch_peer_file: None,
+                                   peer_discovery: vec![],
+                                   peer_discovery_period: Duration::from_secs(60),
                                    tls_config: Some(TLSConfig { cert_path,
                                                                 key_path,
                                                                 ca_cert_path:
@@ -431,6 +471,7 @@ gpoVMSncu2jMIDZX63IkQII=
                                    feature_flags: FeatureFlag::empty(),
                                    event_stream_config: None,
                                    keep_latest_packages: Some(5),
+                                   verify_installed_files: false,
                                    sys_ip: "7.8.9.0".parse().unwrap() },
                    config);
     }
@@ -447,6 +488,7 @@ gpoVMSncu2jMIDZX63IkQII=
                                    auto_update_period:         Duration::from_secs(60),
                                    service_update_period:      Duration::from_secs(60),
                                    service_restart_config:     ServiceRestartConfig::default(),
+                                   service_log_config:         ServiceLogConfig::default(),
                                    custom_state_path:          None,
                                    key_cache:                  KeyCache::new(&*CACHE_KEY_PATH),
                                    update_url:
@@ -462,13 +504,21 @@ gpoVMSncu2jMIDZX63IkQII=
                                    http_disable:               false,
                                    gossip_peers:               vec![],
                                    gossip_permanent:           false,
+                                   gossip_certificates:        None,
+                                   gossip_key:                 None,
+                                   gossip_ca_certificates:     None,
+                                   gossip_segment:             None,
+                                   gossip_segment_gateway:     false,
                                    ring_key:                   None,
                                    organization:               None,
                                    watch_peer_file:            None,
+                                   peer_discovery:             vec![],
+                                   peer_discovery_period:      Duration::from_secs(60),
                                    tls_config:                 None,
                                    feature_flags:              FeatureFlag::empty(),
                                    event_stream_config:        None,
                                    keep_latest_packages:       None,
+                                   verify_installed_files:     false,
                                    sys_ip:
                                        habitat_core::util::sys::ip().unwrap(), },
                    config);
@@ -486,6 +536,7 @@ gpoVMSncu2jMIDZX63IkQII=
                                    auto_update_period:         Duration::from_secs(60),
                                    service_update_period:      Duration::from_secs(60),
                                    service_restart_config:     ServiceRestartConfig::default(),
+                                   service_log_config:         ServiceLogConfig::default(),
                                    custom_state_path:          None,
                                    key_cache:                  KeyCache::new(&*CACHE_KEY_PATH),
                                    update_url:
@@ -500,13 +551,21 @@ gpoVMSncu2jMIDZX63IkQII=
                                    http_disable:               false,
                                    gossip_peers:               vec![],
                                    gossip_permanent:           false,
+                                   gossip_certificates:        None,
+                                   gossip_key:                 None,
+                                   gossip_ca_certificates:     None,
+                                   gossip_segment:             None,
+                                   gossip_segment_gateway:     false,
                                    ring_key:                   None,
                                    organization:               None,
                                    watch_peer_file:            Some(String::from("/some/path")),
+                                   peer_discovery:             vec![],
+                                   peer_discovery_period:      Duration::from_secs(60),
                                    tls_config:                 None,
                                    feature_flags:              FeatureFlag::empty(),
                                    event_stream_config:        None,
                                    keep_latest_packages:       None,
+                                   verify_installed_files:     false,
                                    sys_ip:
                                        habitat_core::util::sys::ip().unwrap(), },
                    config);
@@ -558,6 +617,7 @@ gpoVMSncu2jMIDZX63IkQII=
                     auto_update_period: Duration::from_secs(60),
                     service_update_period: Duration::from_secs(60),
                     service_restart_config: ServiceRestartConfig::default(),
+                    service_log_config: ServiceLogConfig::default(),
                     custom_state_path: None,
                     key_cache: KeyCache::new(&*CACHE_KEY_PATH),
                     update_url: String::from("https://bldr.habitat.sh"),
@@ -571,9 +631,16 @@ gpoVMSncu2jMIDZX63IkQII=
                     http_disable: false,
                     gossip_peers: vec![],
                     gossip_permanent: false,
+                    gossip_certificates: None,
+                    gossip_key: None,
+                    gossip_ca_certificates: None,
+                    gossip_segment: None,
+                    gossip_segment_gateway: false,
                     ring_key: None,
                     organization: None,
                     watch_peer_file: None,
+                    peer_discovery: vec![],
+                    peer_discovery_period: Duration::from_secs(60),
                     tls_config: None,
                     feature_flags: FeatureFlag::empty(),
                     event_stream_config: Some(EventStreamConfig {
@@ -587,6 +654,7 @@ gpoVMSncu2jMIDZX63IkQII=
                         server_certificate: Some(certificate_path_str.parse().unwrap()),
                     }),
                     keep_latest_packages: None,
+                    verify_installed_files: false,
                     sys_ip: habitat_core::util::sys::ip().unwrap(),
                 },
                 config,
@@ -602,7 +670,13 @@ gpoVMSncu2jMIDZX63IkQII=
                                 two:service2.default --binding-mode relaxed --url http://my_url.com \
                                 --config-from={} --group MyGroup --topology leader \
                                 --strategy rolling --update-condition track-channel --health-check-interval 17 \
-                                --shutdown-timeout=12 core/redis",
+                                --after core/nginx --requires core/postgresql myorg/migrations \
+                                --shutdown-timeout=12 --restart-policy on-failure --max-restarts 4 \
+                                --min-backoff-period 5 --max-backoff-period 60 \
+                                --cpu-quota 150 --memory-max 536870912 --private-tmp \
+                                --read-only-pkg --no-new-privs \
+                                --health-check tcp://localhost:6379 --liveness-threshold 3 \
+                                --fencing stop core/redis",
                                temp_dir_str);
 
         let mut binds = ServiceBindList::default();
@@ -611,6 +685,13 @@ gpoVMSncu2jMIDZX63IkQII=
         binds.binds
              .push(ServiceBind::from_str("two:service2.default").unwrap());
         let health_check_interval = sup_proto::types::HealthCheckInterval { seconds: 17 };
+        let idents = |idents: &[&str]| {
+            idents.iter()
+                  .map(|ident| ident.parse::<PackageIdent>().unwrap())
+                  .collect::<PackageIdentList>()
+        };
+        let after = idents(&["core/nginx"]);
+        let requires = idents(&["core/postgresql", "myorg/migrations"]);
 
         let service_load = service_load_from_cmd_str(&args);
         assert_eq!(sup_proto::ctl::SvcLoad { ident:
@@ -634,7 +715,31 @@ gpoVMSncu2jMIDZX63IkQII=
                                              health_check_interval:  Some(health_check_interval),
                                              shutdown_timeout:       Some(12),
                                              update_condition:
-                                                 Some(UpdateCondition::TrackChannel.into()), },
+                                                 Some(UpdateCondition::TrackChannel.into()),
+                                             restart_policy:
+                                                 Some(RestartPolicy::OnFailure.into()),
+                                             max_restarts:           Some(4),
+                                             min_backoff_period:     Some(5),
+                                             max_backoff_period:     Some(60),
+                                             after:                  Some(after),
+                                             requires:               Some(requires),
+                                             cpu_quota:              Some(150),
+                                             memory_max:             Some(536_870_912),
+                                             pids_max:               None,
+                                             io_weight:              None,
+                                             private_tmp:            Some(true),
+                                             read_only_pkg:          Some(true),
+                                             read_only_svc:          Some(false),
+                                             cap_drop:               None,
+                                             no_new_privs:           Some(true),
+                                             seccomp_profile:        None,
+                                             health_check:
+                                                 Some(String::from("tcp://localhost:6379")),
+                                             liveness_threshold:     Some(3),
+                                             fencing:
+                                                 Some(FencingPolicy::Stop.into()),
+                                             lockfile:               None,
+                                             write_lockfile:         None, },
                    service_load);
     }
 
@@ -736,6 +841,7 @@ sys_ip_address = "7.8.9.0"
                                    auto_update_period: Duration::from_secs(3600),
                                    service_update_period: Duration::from_secs(1_000),
                                    service_restart_config: ServiceRestartConfig::default(),
+                                   service_log_config: ServiceLogConfig::default(),
                                    custom_state_path: None,
                                    key_cache: KeyCache::new(temp_dir_str),
                                    update_url: String::from("https://bldr.habitat.sh"),
@@ -751,9 +857,16 @@ sys_ip_address = "7.8.9.0"
                                    http_disable: true,
                                    gossip_peers,
                                    gossip_permanent: true,
+                                   gossip_certificates: None,
+                                   gossip_key: None,
+                                   gossip_ca_certificates: None,
+                                   gossip_segment: None,
+                                   gossip_segment_gateway: false,
                                    ring_key: Some(ring_key),
                                    organization: Some(String::from("MY_ORG")),
                                    watch_peer_file: None,
+                                   peer_discovery: vec![],
+                                   peer_discovery_period: Duration::from_secs(60),
                                    tls_config: Some(TLSConfig { cert_path,
                                                                 key_path,
                                                                 ca_cert_path:
@@ -761,6 +874,7 @@ sys_ip_address = "7.8.9.0"
                                    feature_flags: FeatureFlag::empty(),
                                    event_stream_config: None,
                                    keep_latest_packages: Some(5),
+                                   verify_installed_files: false,
                                    sys_ip: "7.8.9.0".parse().unwrap() },
                    config);
     }
@@ -786,6 +900,7 @@ sys_ip_address = "7.8.9.0"
                                    auto_update_period:         Duration::from_secs(60),
                                    service_update_period:      Duration::from_secs(60),
                                    service_restart_config:     ServiceRestartConfig::default(),
+                                   service_log_config:         ServiceLogConfig::default(),
                                    custom_state_path:          None,
                                    key_cache:                  KeyCache::new(&*CACHE_KEY_PATH),
                                    update_url:
@@ -801,13 +916,21 @@ sys_ip_address = "7.8.9.0"
                                    http_disable:               false,
                                    gossip_peers:               vec![],
                                    gossip_permanent:           false,
+                                   gossip_certificates:        None,
+                                   gossip_key:                 None,
+                                   gossip_ca_certificates:     None,
+                                   gossip_segment:             None,
+                                   gossip_segment_gateway:     false,
                                    ring_key:                   None,
                                    organization:               None,
                                    watch_peer_file:            None,
+                                   peer_discovery:             vec![],
+                                   peer_discovery_period:      Duration::from_secs(60),
                                    tls_config:                 None,
                                    feature_flags:              FeatureFlag::empty(),
                                    event_stream_config:        None,
                                    keep_latest_packages:       None,
+                                   verify_installed_files:     false,
                                    sys_ip:
                                        habitat_core::util::sys::ip().unwrap(), },
                    config);
@@ -834,6 +957,7 @@ sys_ip_address = "7.8.9.0"
                                    auto_update_period:         Duration::from_secs(60),
                                    service_update_period:      Duration::from_secs(60),
                                    service_restart_config:     ServiceRestartConfig::default(),
+                                   service_log_config:         ServiceLogConfig::default(),
                                    custom_state_path:          None,
                                    key_cache:                  KeyCache::new(&*CACHE_KEY_PATH),
                                    update_url:
@@ -848,13 +972,21 @@ sys_ip_address = "7.8.9.0"
                                    http_disable:               false,
                                    gossip_peers:               vec![],
                                    gossip_permanent:           false,
+                                   gossip_certificates:        None,
+                                   gossip_key:                 None,
+                                   gossip_ca_certificates:     None,
+                                   gossip_segment:             None,
+                                   gossip_segment_gateway:     false,
                                    ring_key:                   None,
                                    organization:               None,
                                    watch_peer_file:            Some(String::from("/some/path")),
+                                   peer_discovery:             vec![],
+                                   peer_discovery_period:      Duration::from_secs(60),
                                    tls_config:                 None,
                                    feature_flags:              FeatureFlag::empty(),
                                    event_stream_config:        None,
                                    keep_latest_packages:       None,
+                                   verify_installed_files:     false,
                                    sys_ip:
                                        habitat_core::util::sys::ip().unwrap(), },
                    config);
@@ -887,6 +1019,7 @@ sys_ip_address = "7.8.9.0"
                                    auto_update_period: Duration::from_secs(60),
                                    service_update_period: Duration::from_secs(60),
                                    service_restart_config: ServiceRestartConfig::default(),
+                                   service_log_config: ServiceLogConfig::default(),
                                    custom_state_path: None,
                                    key_cache: KeyCache::new(&*CACHE_KEY_PATH),
                                    update_url: String::from("https://bldr.habitat.sh"),
@@ -901,13 +1034,21 @@ sys_ip_address = "7.8.9.0"
                                    http_disable: false,
                                    gossip_peers,
                                    gossip_permanent: false,
+                                   gossip_certificates: None,
+                                   gossip_key: None,
+                                   gossip_ca_certificates: None,
+                                   gossip_segment: None,
+                                   gossip_segment_gateway: false,
                                    ring_key: None,
                                    organization: None,
                                    watch_peer_file: None,
+                                   peer_discovery: vec![],
+                                   peer_discovery_period: Duration::from_secs(60),
                                    tls_config: None,
                                    feature_flags: FeatureFlag::empty(),
                                    event_stream_config: None,
                                    keep_latest_packages: None,
+                                   verify_installed_files: false,
                                    sys_ip: habitat_core::util::sys::ip().unwrap() },
                    config);
     }
@@ -996,6 +1137,7 @@ event_stream_server_certificate = "{}"
                     auto_update_period: Duration::from_secs(60),
                     service_update_period: Duration::from_secs(60),
                     service_restart_config: ServiceRestartConfig::default(),
+                    service_log_config: ServiceLogConfig::default(),
                     custom_state_path: None,
                     key_cache: KeyCache::new(&*CACHE_KEY_PATH),
                     update_url: String::from("https://bldr.habitat.sh"),
@@ -1009,9 +1151,16 @@ event_stream_server_certificate = "{}"
                     http_disable: false,
                     gossip_peers: vec![],
                     gossip_permanent: false,
+                    gossip_certificates: None,
+                    gossip_key: None,
+                    gossip_ca_certificates: None,
+                    gossip_segment: None,
+                    gossip_segment_gateway: false,
                     ring_key: None,
                     organization: None,
                     watch_peer_file: None,
+                    peer_discovery: vec![],
+                    peer_discovery_period: Duration::from_secs(60),
                     tls_config: None,
                     feature_flags: FeatureFlag::empty(),
                     event_stream_config: Some(EventStreamConfig {
@@ -1025,6 +1174,7 @@ event_stream_server_certificate = "{}"
                         server_certificate: Some(certificate_path_str.parse().unwrap()),
                     }),
                     keep_latest_packages: None,
+                    verify_installed_files: false,
                     sys_ip: habitat_core::util::sys::ip().unwrap(),
                 },
                 config,
@@ -1048,6 +1198,15 @@ group = "MyGroup"
 topology = "standalone"
 strategy = "at-once"
 update_condition = "track-channel"
+restart_policy = "never"
+fencing = "hook"
+requires = ["core/postgresql"]
+pids_max = 64
+io_weight = 500
+read_only_svc = true
+cap_drop = ["CAP_NET_RAW", "CAP_SYS_PTRACE"]
+seccomp_profile = "/etc/seccomp/redis.json"
+health_check = "http://localhost:8080/health status=200"
 health_check_interval = 17
 shutdown_timeout = 12
 pkg_ident_or_artifact = "core/redis"
@@ -1067,6 +1226,11 @@ pkg_ident_or_artifact = "core/redis"
         binds.binds
              .push(ServiceBind::from_str("two:service2.default").unwrap());
         let health_check_interval = sup_proto::types::HealthCheckInterval { seconds: 17 };
+        let requires = std::iter::once("core/postgresql".parse::<PackageIdent>().unwrap())
+            .collect::<PackageIdentList>();
+        let cap_drop = ["CAP_NET_RAW", "CAP_SYS_PTRACE"].iter()
+                                                        .map(ToString::to_string)
+                                                        .collect::<CapabilityList>();
 
         let service_load = service_load_from_cmd_str(&args);
         assert_eq!(sup_proto::ctl::SvcLoad { ident:
@@ -1091,7 +1255,33 @@ pkg_ident_or_artifact = "core/redis"
                                              health_check_interval:  Some(health_check_interval),
                                              shutdown_timeout:       Some(12),
                                              update_condition:
-                                                 Some(UpdateCondition::TrackChannel.into()), },
+                                                 Some(UpdateCondition::TrackChannel.into()),
+                                             restart_policy:
+                                                 Some(RestartPolicy::Never.into()),
+                                             max_restarts:           None,
+                                             min_backoff_period:     None,
+                                             max_backoff_period:     None,
+                                             after:                  None,
+                                             requires:               Some(requires),
+                                             cpu_quota:              None,
+                                             memory_max:             None,
+                                             pids_max:               Some(64),
+                                             io_weight:              Some(500),
+                                             private_tmp:            Some(false),
+                                             read_only_pkg:          Some(false),
+                                             read_only_svc:          Some(true),
+                                             cap_drop:               Some(cap_drop),
+                                             no_new_privs:           Some(false),
+                                             seccomp_profile:
+                                                 Some(String::from("/etc/seccomp/redis.json")),
+                                             health_check:
+                                                 Some(String::from("http://localhost:8080/health \
+                                                                    status=200")),
+                                             liveness_threshold:     None,
+                                             fencing:
+                                                 Some(FencingPolicy::Hook.into()),
+                                             lockfile:               None,
+                                             write_lockfile:         None, },
                    service_load);
     }
 
@@ -1183,6 +1373,7 @@ organization = "MY_ORG_FROM_SECOND_CONFG"
                                    auto_update_period:         Duration::from_secs(60),
                                    service_update_period:      Duration::from_secs(60),
                                    service_restart_config:     ServiceRestartConfig::default(),
+                                   service_log_config:         ServiceLogConfig::default(),
                                    custom_state_path:          None,
                                    key_cache:                  KeyCache::new(&*CACHE_KEY_PATH),
                                    update_url:
@@ -1200,14 +1391,22 @@ organization = "MY_ORG_FROM_SECOND_CONFG"
                                    http_disable:               false,
                                    gossip_peers:               vec![],
                                    gossip_permanent:           false,
+                                   gossip_certificates:        None,
+                                   gossip_key:                 None,
+                                   gossip_ca_certificates:     None,
+                                   gossip_segment:             None,
+                                   gossip_segment_gateway:     false,
                                    ring_key:                   None,
                                    organization:
                                        Some(String::from("MY_ORG_FROM_SECOND_CONFG")),
                                    watch_peer_file:            None,
+                                   peer_discovery:             vec![],
+                                   peer_discovery_period:      Duration::from_secs(60),
                                    tls_config:                 None,
                                    feature_flags:              FeatureFlag::empty(),
                                    event_stream_config:        None,
                                    keep_latest_packages:       None,
+                                   verify_installed_files:     false,
                                    sys_ip:
                                        habitat_core::util::sys::ip().unwrap(), },
                    config);
@@ -1241,5 +1440,23 @@ organization = "MY_ORG_FROM_SECOND_CONFG"
         let svc_load = service_load_from_cmd_str(args);
         assert_eq!(i32::from(UpdateStrategy::Rolling),
                    svc_load.update_strategy.unwrap());
+
+        let args = "hab-sup run core/redis";
+        let svc_load = service_load_from_cmd_str(args);
+        assert_eq!(i32::from(RestartPolicy::Always),
+                   svc_load.restart_policy.unwrap());
+
+        let args = "hab-sup run --restart-policy never core/redis";
+        let svc_load = service_load_from_cmd_str(args);
+        assert_eq!(i32::from(RestartPolicy::Never),
+                   svc_load.restart_policy.unwrap());
+
+        let args = "hab-sup run core/redis";
+        let svc_load = service_load_from_cmd_str(args);
+        assert_eq!(i32::from(FencingPolicy::None), svc_load.fencing.unwrap());
+
+        let args = "hab-sup run --fencing hook core/redis";
+        let svc_load = service_load_from_cmd_str(args);
+        assert_eq!(i32::from(FencingPolicy::Hook), svc_load.fencing.unwrap());
     }
 }
@@ -6,7 +6,8 @@ use std::{io,
           io::Write,
           net::{IpAddr,
                 Ipv4Addr},
-          process};
+          process,
+          time::Duration};
 
 use log::{info,
           warn};
@@ -39,6 +40,7 @@ use habitat_sup::{error::{Error,
                   event::EventStreamConfig,
                   manager::{Manager,
                             ManagerConfig,
+                            ServiceLogConfig,
                             ServiceRestartConfig,
                             TLSConfig},
                   util};
@@ -231,6 +233,20 @@ pub(crate) async fn split_apart_sup_run(
         ChannelIdent::default()
     };
 
+    // A value of zero disables the corresponding kind of rotation
+    let service_log_max_size = sup_run.service_log_max_size * 1024 * 1024;
+    let service_log_max_age = Duration::from(sup_run.service_log_max_age);
+    let service_log_config =
+        ServiceLogConfig::new((service_log_max_size > 0).then_some(service_log_max_size),
+                              (!service_log_max_age.is_zero()).then_some(service_log_max_age),
+                              sup_run.service_log_compress,
+                              sup_run.service_log_retain);
+
+    let peer_discovery = sup_run.peer_discovery
+                                .iter()
+                                .map(|source| source.parse())
+                                .collect::<Result<Vec<_>>>()?;
+
     let cfg =
         ManagerConfig { auto_update: sup_run.auto_update,
                         auto_update_period: sup_run.auto_update_period.into(),
@@ -240,6 +256,7 @@ pub(crate) async fn split_apart_sup_run(
                                                       sup_run.service_max_backoff_period.into(),
                                                       sup_run.service_restart_cooldown_period
                                                              .into()),
+                        service_log_config,
                         custom_state_path: None, // remove entirely?
                         key_cache,
                         update_url: bldr_url.clone(),
@@ -247,10 +264,19 @@ pub(crate) async fn split_apart_sup_run(
                         http_disable: sup_run.http_disable,
                         organization: sup_run.organization,
                         gossip_permanent: sup_run.permanent_peer,
+                        gossip_certificates: sup_run.gossip_certificate
+                                                    .map(CertificateChainCli::into_inner),
+                        gossip_key: sup_run.gossip_key.map(|key| key.into_inner().into()),
+                        gossip_ca_certificates: sup_run.gossip_ca_certificate
+                                                       .map(RootCertificateStoreCli::into_inner),
+                        gossip_segment: sup_run.gossip_segment,
+                        gossip_segment_gateway: sup_run.gossip_segment_gateway,
                         ring_key,
                         gossip_peers: sup_run.peer.iter().map(Into::into).collect(),
                         watch_peer_file: sup_run.peer_watch_file
                                                 .map(|p| p.to_string_lossy().to_string()),
+                        peer_discovery,
+                        peer_discovery_period: sup_run.peer_discovery_period.into(),
                         gossip_listen: if sup_run.local_gossip_mode {
                             GossipListenAddr::local_only()
                         } else {
@@ -269,6 +295,7 @@ pub(crate) async fn split_apart_sup_run(
                         feature_flags,
                         event_stream_config,
                         keep_latest_packages: sup_run.keep_latest_packages,
+                        verify_installed_files: sup_run.verify_installed_files,
                         sys_ip: sup_run.sys_ip_address
                                        .or_else(|| {
                                            let result_ip = habitat_core::util::sys::ip();
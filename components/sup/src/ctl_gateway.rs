@@ -99,6 +99,9 @@ impl CtlRequest {
     /// Returns true if the request is transactional and false if not.
     pub fn transactional(&self) -> bool { self.transaction.is_some() && self.tx.is_some() }
 
+    /// Returns true if replies can no longer be delivered, i.e. the client has gone away.
+    pub fn is_closed(&self) -> bool { self.tx.as_ref().is_none_or(server::CtlSender::is_closed) }
+
     fn send_msg<T>(&mut self, msg: T, complete: bool)
         where T: Into<habitat_sup_protocol::codec::SrvMessage> + fmt::Debug
     {
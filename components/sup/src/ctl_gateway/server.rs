@@ -316,6 +316,17 @@ impl SrvHandler {
             "SvcGetDefaultCfg" => util::to_command(msg, ctl_sender, commands::service_cfg_msr),
             "SvcFilePut" => util::to_command(msg, ctl_sender, commands::service_file_put),
             "SvcSetCfg" => util::to_command(msg, ctl_sender, commands::service_cfg_set),
+            "SvcPreviewCfg" => util::to_command(msg, ctl_sender, commands::service_cfg_preview_msr),
+            "SvcGetCfgHistory" => util::to_command(msg, ctl_sender, commands::service_cfg_history),
+            "SvcRollbackCfg" => {
+                util::to_command(msg, ctl_sender, commands::service_cfg_rollback_rsr)
+            }
+            "SvcLeaderStepDown" => {
+                util::to_command(msg, ctl_sender, commands::service_leader_step_down_msr)
+            }
+            "SvcLeaderTransfer" => {
+                util::to_command(msg, ctl_sender, commands::service_leader_transfer_msr)
+            }
             "SvcValidateCfg" => util::to_command(msg, ctl_sender, commands::service_cfg_validate),
             "SvcLoad" => {
                 // This arm doesn't use a `util` module helper because
@@ -343,8 +354,21 @@ impl SrvHandler {
             "SvcStart" => util::to_command(msg, ctl_sender, commands::service_start),
             "SvcStop" => util::to_supervisor_command(msg, ctl_sender, commands::service_stop),
             "SvcStatus" => util::to_command(msg, ctl_sender, commands::service_status_gsr),
+            "SvcLogs" => util::to_command(msg, ctl_sender, commands::service_logs_gsr),
             "SupDepart" => util::to_command(msg, ctl_sender, commands::supervisor_depart),
             "SupRestart" => util::to_command(msg, ctl_sender, commands::supervisor_restart),
+            "SupRingKeyStage" => {
+                util::to_command(msg, ctl_sender, commands::supervisor_ring_key_stage)
+            }
+            "SupRingKeyActivate" => {
+                util::to_command(msg, ctl_sender, commands::supervisor_ring_key_activate)
+            }
+            "SupRingKeyRetire" => {
+                util::to_command(msg, ctl_sender, commands::supervisor_ring_key_retire)
+            }
+            "SupRingKeyStatus" => {
+                util::to_command(msg, ctl_sender, commands::supervisor_ring_key_status)
+            }
             _ => {
                 warn!("Unhandled message, {}", msg.message_id());
                 Err(HandlerError::from(io::Error::from(io::ErrorKind::InvalidData)))
@@ -53,6 +53,7 @@ pub enum Error {
     InvalidHealthCheckResult(i32),
     InvalidKeyFile(PathBuf),
     InvalidKeyParameter(String),
+    InvalidPeerDiscoverySource(String, String),
     InvalidPidFile,
     InvalidTopology(String),
     InvalidUpdateStrategy(String),
@@ -60,6 +61,8 @@ pub enum Error {
     TaskJoin(JoinError),
     LauncherIPCCommand(habitat_launcher_client::IPCCommandError),
     LauncherTryIPCCommand(habitat_launcher_client::TryIPCCommandError),
+    LauncherIgnoresResourceLimits,
+    LauncherPredatesSandbox,
     LockFileError(crate::lock_file::Error),
     MissingRequiredBind(Vec<String>),
     MissingRequiredIdent,
@@ -75,6 +78,7 @@ pub enum Error {
     OneshotCanceled(oneshot::Canceled),
     PackageNotFound(package::PackageIdent),
     PackageNotRunnable(package::PackageIdent),
+    PeerDiscovery(String, String),
     Permissions(String),
     RecvError(mpsc::RecvError),
     RecvTimeoutError(mpsc::RecvTimeoutError),
@@ -182,6 +186,9 @@ impl fmt::Display for Error {
             Error::InvalidKeyParameter(e) => {
                 format!("Invalid parameter for key generation: {:?}", e)
             }
+            Error::InvalidPeerDiscoverySource(source, reason) => {
+                format!("Invalid peer discovery source {}: {}", source, reason)
+            }
             Error::InvalidPidFile => "Invalid child process PID file".to_string(),
             Error::InvalidTopology(t) => format!("Invalid topology: {}", t),
             Error::InvalidUpdateStrategy(s) => format!("Invalid update strategy: {}", s),
@@ -207,6 +214,16 @@ impl fmt::Display for Error {
                 format!("Supervisor failed to try executing launcher command via IPC: {}",
                         chain.join(", "))
             }
+            Error::LauncherIgnoresResourceLimits => {
+                "The launcher does not enforce the resource limits of the service; upgrade the \
+                 launcher or remove the resource limits from the service"
+                                                                         .to_string()
+            }
+            Error::LauncherPredatesSandbox => {
+                "The launcher does not apply the sandbox of the service; upgrade the launcher or \
+                 remove the sandbox from the service"
+                                                     .to_string()
+            }
             Error::MissingRequiredBind(e) => {
                 format!("Missing required bind(s), {}", e.join(", "))
             }
@@ -233,6 +250,9 @@ impl fmt::Display for Error {
                 }
             }
             Error::PackageNotRunnable(pkg) => format!("Package is not runnable: {}", pkg),
+            Error::PeerDiscovery(source, reason) => {
+                format!("Peer discovery from {} failed: {}", source, reason)
+            }
             Error::RecvError(err) => err.to_string(),
             Error::RecvTimeoutError(err) => err.to_string(),
             Error::ServiceDeserializationError(e) => {
@@ -19,6 +19,7 @@ pub(crate) use self::types::ServiceMetadata;
 use self::types::{EventMessage,
                   EventMetadata,
                   HealthCheckEvent,
+                  ScheduledHookEvent,
                   ServiceStartedEvent,
                   ServiceStoppedEvent,
                   ServiceUpdateStartedEvent};
@@ -35,7 +36,8 @@ use habitat_common::types::{EventStreamConnectMethod,
                             EventStreamServerCertificate,
                             EventStreamToken};
 use habitat_core::{package::ident::PackageIdent,
-                   service::HealthCheckInterval};
+                   service::{HealthCheckInterval,
+                             Schedule}};
 use lazy_static::lazy_static;
 use log::debug;
 use nats_message_stream::{NatsMessage,
@@ -61,6 +63,8 @@ lazy_static! {
         "habitat.event.service_update_started".parse().expect("valid NATS subject");
     static ref HEALTHCHECK_SUBJECT: Subject =
         "habitat.event.healthcheck".parse().expect("valid NATS subject");
+    static ref SCHEDULED_HOOK_SUBJECT: Subject =
+        "habitat.event.scheduled_hook".parse().expect("valid NATS subject");
 
     /// Reference to the event stream.
     static ref NATS_MESSAGE_STREAM: InitCell<NatsMessageStream> = InitCell::new();
@@ -140,12 +144,9 @@ pub fn health_check(metadata: ServiceMetadata,
     if initialized() {
         let health_check_result: types::HealthCheckResult = health_check_result.into();
         let maybe_duration = health_check_hook_status.maybe_duration();
-        let maybe_process_output = health_check_hook_status.maybe_process_output();
-        let exit_status = maybe_process_output.as_ref()
-                                              .and_then(|o| o.exit_status().code());
-        let StandardStreams { stdout, stderr } =
-            maybe_process_output.map(ProcessOutput::standard_streams)
-                                .unwrap_or_default();
+        let exit_status = health_check_hook_status.maybe_exit_status();
+        let StandardStreams { stdout, stderr } = health_check_hook_status.maybe_standard_streams()
+                                                                         .unwrap_or_default();
 
         let prost_interval =
             ProstDuration::try_from(Duration::from(health_check_interval)).unwrap_or_default();
@@ -164,6 +165,30 @@ pub fn health_check(metadata: ServiceMetadata,
     }
 }
 
+/// Send an event for a run of a service's `scheduled` hook. `output` is `None` if the hook could
+/// not be run.
+pub fn scheduled_hook(metadata: ServiceMetadata,
+                      schedule: &Schedule,
+                      output: Option<ProcessOutput>,
+                      execution: Option<Duration>) {
+    if initialized() {
+        let exit_status = output.as_ref().and_then(|o| o.exit_status().code());
+        let StandardStreams { stdout, stderr } = output.map(ProcessOutput::standard_streams)
+                                                       .unwrap_or_default();
+
+        publish(&SCHEDULED_HOOK_SUBJECT,
+                ScheduledHookEvent { service_metadata: Some(metadata),
+                                     event_metadata: None,
+                                     schedule: schedule.to_string(),
+                                     execution: execution.map(|x| {
+                                                    ProstDuration::try_from(x).unwrap_or_default()
+                                                }),
+                                     exit_status,
+                                     stdout,
+                                     stderr });
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////
 
 /// A collection of data that will be present in all events. Rather
@@ -240,6 +265,7 @@ fn publish(subject: &'static Subject, mut event: impl EventMessage) {
 mod tests {
     use super::{nats_message_stream::NatsMessageStream,
                 *};
+    use crate::manager::service::ProcessOutput;
     use futures::{channel::mpsc as futures_mpsc,
                   stream::StreamExt};
     #[cfg(windows)]
@@ -294,7 +320,15 @@ mod tests {
                      HealthCheckResult::Unknown,
                      HealthCheckHookStatus::Ran(process_output, Duration::from_secs(15)),
                      HealthCheckInterval::default());
-        let events = rx.take(4).collect::<Vec<_>>().await;
+        health_check(ServiceMetadata::default(),
+                     HealthCheckResult::Critical,
+                     HealthCheckHookStatus::Probed(HealthCheckResult::Critical,
+                                                   StandardStreams { stdout: None,
+                                                                     stderr:
+                                                                         Some(String::from("refused")), },
+                                                   Duration::from_secs(2)),
+                     HealthCheckInterval::default());
+        let events = rx.take(5).collect::<Vec<_>>().await;
 
         let event = HealthCheckEvent::decode(events[0].payload()).unwrap();
         assert_eq!(event.result, 0);
@@ -338,5 +372,12 @@ mod tests {
         assert_eq!(event.exit_status, None);
         assert_eq!(event.stdout, None);
         assert_eq!(event.stderr, Some(String::from("stderr")));
+
+        let event = HealthCheckEvent::decode(events[4].payload()).unwrap();
+        assert_eq!(event.result, 2);
+        assert_eq!(event.execution.unwrap().seconds, 2);
+        assert_eq!(event.exit_status, None);
+        assert_eq!(event.stdout, None);
+        assert_eq!(event.stderr, Some(String::from("refused")));
     }
 }
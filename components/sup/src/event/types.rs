@@ -106,3 +106,4 @@ event_msg_impl!(ServiceStartedEvent);
 event_msg_impl!(ServiceStoppedEvent);
 event_msg_impl!(ServiceUpdateStartedEvent);
 event_msg_impl!(HealthCheckEvent);
+event_msg_impl!(ScheduledHookEvent);
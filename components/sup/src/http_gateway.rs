@@ -499,6 +499,12 @@ fn service_gsr(svc: String, group: String, org: Option<&str>, state: &AppState)
 }
 
 async fn metrics() -> HttpResponse {
+    HttpResponse::Ok().content_type(TextEncoder::new().format_type())
+                      .body(encode_metrics())
+}
+
+/// The metrics served on `/metrics`, in the Prometheus text format.
+pub(crate) fn encode_metrics() -> String {
     let encoder = TextEncoder::new();
     let metric_families = prometheus::gather();
     let mut buffer = vec![];
@@ -507,16 +513,13 @@ async fn metrics() -> HttpResponse {
         error!("Error encoding metrics: {:?}", e);
     }
 
-    let resp = match String::from_utf8(buffer) {
+    match String::from_utf8(buffer) {
         Ok(s) => s,
         Err(e) => {
             error!("Error constructing string from metrics buffer: {:?}", e);
             String::from("")
         }
-    };
-
-    HttpResponse::Ok().content_type(encoder.format_type())
-                      .body(resp)
+    }
 }
 
 async fn doc() -> HttpResponse { HttpResponse::Ok().content_type("text/html").body(APIDOCS) }
@@ -617,6 +620,7 @@ mod tests {
                         None,
                         None,
                         None,
+                        None,
                         std::sync::Arc::new(ZeroSuitability)).unwrap()
         }
 
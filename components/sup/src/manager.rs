@@ -1,9 +1,12 @@
 pub(crate) mod action;
 pub mod commands;
 mod file_watcher;
+mod log_rotator;
+mod peer_discovery;
 mod peer_watcher;
 mod self_updater;
 pub mod service;
+mod service_order;
 mod service_updater;
 mod spec_dir;
 mod spec_watcher;
@@ -11,14 +14,19 @@ mod sup_watcher;
 pub(crate) mod sys;
 mod user_config_watcher;
 
+pub use self::peer_discovery::PeerDiscoverySource;
 use self::{action::{ShutdownInput,
                     SupervisorAction},
+           log_rotator::LogRotator,
+           peer_discovery::PeerDiscovery,
            peer_watcher::PeerWatcher,
            self_updater::{SUP_PKG_IDENT,
                           SelfUpdater},
            service::{ConfigRendering,
                      DesiredState,
+                     HealthCheckResult,
                      PersistentServiceWrapper,
+                     RestartPolicy,
                      Service,
                      ServiceQueryModel,
                      ServiceRunState,
@@ -26,6 +34,7 @@ use self::{action::{ShutdownInput,
                      Topology,
                      spec::{RefreshOperation,
                             ServiceOperation}},
+           service_order::ServiceOrder,
            service_updater::ServiceUpdater,
            spec_dir::SpecDir,
            spec_watcher::SpecWatcher,
@@ -52,6 +61,7 @@ use futures::{channel::{mpsc as fut_mpsc,
               prelude::*,
               stream::FuturesUnordered};
 use habitat_butterfly::{member::Member,
+                        ring_keys::RingKeys,
                         server::{ServerProxy,
                                  Suitability,
                                  timing::Timing}};
@@ -66,7 +76,9 @@ use habitat_core::os::{process::{ShutdownSignal,
                                  Signal},
                        signals};
 use habitat_core::{ChannelIdent,
-                   crypto::keys::{KeyCache,
+                   crypto::keys::{Key,
+                                  KeyCache,
+                                  NamedRevision,
                                   RingKey},
                    env,
                    env::Config,
@@ -77,8 +89,9 @@ use habitat_core::{ChannelIdent,
                              PackageIdent,
                              PackageInstall},
                    service::ServiceGroup,
-                   tls::rustls_wrapper::{certificates_from_file,
-                                         private_key_from_file},
+                   tls::{gossip::MemberAuthenticator,
+                         rustls_wrapper::{certificates_from_file,
+                                          private_key_from_file}},
                    util::ToI64};
 use habitat_launcher_client::{LauncherCli,
                               LauncherStatus};
@@ -110,7 +123,7 @@ use std::{collections::{HashMap,
                File},
           io::{Read,
                Write},
-          iter::{FromIterator,
+          iter::{self,
                  IntoIterator},
           net::{IpAddr,
                 SocketAddr},
@@ -132,6 +145,9 @@ use winapi::{shared::minwindef::PDWORD,
              um::processthreadsapi};
 
 const MEMBER_ID_FILE: &str = "MEMBER_ID";
+/// Records the revisions of the ring key in use after a ring key rotation, one per line with the
+/// primary revision first.
+const RING_KEYS_FILE: &str = "RING_KEYS";
 pub const PROC_LOCK_FILE: &str = "LOCK";
 
 static LOGKEY: &str = "MR";
@@ -237,6 +253,13 @@ pub struct ServiceRestartConfig {
     /// know if a service started successfully other than waiting for some time and checking
     /// that it does not go down.
     pub cooldown_period:    Duration,
+    /// Whether a service is restarted after its run hook exits on its own
+    #[serde(default)]
+    pub policy:             RestartPolicy,
+    /// The number of restarts in a row, without the service staying up for the cooldown period,
+    /// after which a failing service is no longer restarted. `None` means there is no limit.
+    #[serde(default)]
+    pub max_restarts:       Option<u32>,
 }
 
 impl ServiceRestartConfig {
@@ -246,7 +269,23 @@ impl ServiceRestartConfig {
                -> ServiceRestartConfig {
         ServiceRestartConfig { min_backoff_period,
                                max_backoff_period,
-                               cooldown_period: restart_cooldown_period }
+                               cooldown_period: restart_cooldown_period,
+                               policy: RestartPolicy::default(),
+                               max_restarts: None }
+    }
+
+    /// The restart configuration of a particular service: the Supervisor-wide settings with any
+    /// overrides from the service's spec applied.
+    pub fn for_spec(&self, spec: &ServiceSpec) -> ServiceRestartConfig {
+        let min_backoff_period = spec.min_backoff_period
+                                     .map_or(self.min_backoff_period, Duration::from_secs);
+        let max_backoff_period = spec.max_backoff_period
+                                     .map_or(self.max_backoff_period, Duration::from_secs);
+        ServiceRestartConfig { min_backoff_period,
+                               max_backoff_period,
+                               cooldown_period: self.cooldown_period,
+                               policy: spec.restart_policy,
+                               max_restarts: spec.max_restarts }
     }
 }
 
@@ -254,7 +293,48 @@ impl Default for ServiceRestartConfig {
     fn default() -> Self {
         Self { min_backoff_period: Default::default(),
                max_backoff_period: Default::default(),
-               cooldown_period:    Duration::from_secs(300), }
+               cooldown_period:    Duration::from_secs(300),
+               policy:             RestartPolicy::default(),
+               max_restarts:       None, }
+    }
+}
+
+/// Configuration parameters that control the rotation and retention of the log files capturing
+/// the output of each service's run hook
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceLogConfig {
+    /// Rotate a log file once it grows larger than this many bytes
+    pub max_size: Option<u64>,
+    /// Rotate a log file once this much time has passed since it was last rotated
+    pub max_age:  Option<Duration>,
+    /// Compress rotated log files with gzip
+    pub compress: bool,
+    /// The number of rotated files to keep for each log file; older ones are deleted
+    pub retain:   usize,
+}
+
+impl ServiceLogConfig {
+    pub fn new(max_size: Option<u64>,
+               max_age: Option<Duration>,
+               compress: bool,
+               retain: usize)
+               -> ServiceLogConfig {
+        ServiceLogConfig { max_size,
+                           max_age,
+                           compress,
+                           retain }
+    }
+
+    /// Returns `true` if log files will ever be rotated.
+    pub fn rotation_enabled(&self) -> bool { self.max_size.is_some() || self.max_age.is_some() }
+}
+
+impl Default for ServiceLogConfig {
+    fn default() -> Self {
+        Self { max_size: Some(10 * 1024 * 1024),
+               max_age:  None,
+               compress: false,
+               retain:   5, }
     }
 }
 
@@ -275,6 +355,7 @@ pub struct ManagerConfig {
     pub auto_update_period:         Duration,
     pub service_update_period:      Duration,
     pub service_restart_config:     ServiceRestartConfig,
+    pub service_log_config:         ServiceLogConfig,
     pub custom_state_path:          Option<PathBuf>,
     pub key_cache:                  KeyCache,
     pub update_url:                 String,
@@ -288,9 +369,20 @@ pub struct ManagerConfig {
     pub http_disable:               bool,
     pub gossip_peers:               Vec<SocketAddr>,
     pub gossip_permanent:           bool,
+    pub gossip_certificates:        Option<Vec<CertificateDer<'static>>>,
+    pub gossip_key:                 Option<CloneablePkcs8PrivKey>,
+    pub gossip_ca_certificates:     Option<RootCertStore>,
+    /// The gossip segment, usually a region or a zone, the Supervisor probes the members of. If
+    /// this field is `None`, the Supervisor is in the default segment.
+    pub gossip_segment:             Option<String>,
+    /// Whether the Supervisor relays rumors between its gossip segment and the other segments.
+    pub gossip_segment_gateway:     bool,
     pub ring_key:                   Option<RingKey>,
     pub organization:               Option<String>,
     pub watch_peer_file:            Option<String>,
+    /// Sources to periodically discover peers from, replacing the initial members of the ring.
+    pub peer_discovery:             Vec<PeerDiscoverySource>,
+    pub peer_discovery_period:      Duration,
     pub tls_config:                 Option<TLSConfig>,
     pub feature_flags:              FeatureFlag,
     pub event_stream_config:        Option<EventStreamConfig>,
@@ -298,6 +390,9 @@ pub struct ManagerConfig {
     /// others during service start. If this field is `None`, automatic package cleanup is
     /// disabled.
     pub keep_latest_packages:       Option<usize>,
+    /// Whether services only start once the installed files of their packages match the
+    /// packages' signed `FILES` metafiles.
+    pub verify_installed_files:     bool,
     pub sys_ip:                     IpAddr,
 }
 
@@ -319,6 +414,75 @@ impl ManagerConfig {
             .join(ServiceSpec::ident_file(ident))
     }
 
+    fn ring_keys_file(&self) -> PathBuf { self.sup_root().join(RING_KEYS_FILE) }
+
+    /// The authenticator gossip is signed and authenticated with, if gossip members are
+    /// authenticated with certificates.
+    fn gossip_member_authenticator(&self) -> Result<Option<MemberAuthenticator>> {
+        match (&self.gossip_certificates, &self.gossip_key, &self.gossip_ca_certificates) {
+            (Some(certificates), Some(key), Some(ca_certificates)) => {
+                let authenticator = MemberAuthenticator::new(certificates.clone(),
+                                                             key.0.clone_key(),
+                                                             ca_certificates.clone())
+                                    .map_err(habitat_butterfly::error::Error::from)?;
+                Ok(Some(authenticator))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Record the ring keys in use, so that the Supervisor uses them again after a restart.
+    pub fn save_ring_keys(&self, ring_keys: &RingKeys) -> Result<()> {
+        let path = self.ring_keys_file();
+        let content =
+            iter::once(ring_keys.primary()).chain(ring_keys.accepted())
+                                           .map(|key| format!("{}\n", key.named_revision()))
+                                           .collect::<String>();
+        fs::write(&path, content).map_err(|e| Error::BadDataFile(path, e))
+    }
+
+    /// The ring keys recorded by the last ring key rotation, as long as they are revisions of
+    /// `ring_key`. Otherwise, only `ring_key` is used.
+    fn load_ring_keys(&self, ring_key: RingKey) -> RingKeys {
+        let path = self.ring_keys_file();
+        let Ok(content) = fs::read_to_string(&path) else {
+            return RingKeys::new(ring_key);
+        };
+        let recorded =
+            content.lines()
+                   .map(str::trim)
+                   .filter(|line| !line.is_empty())
+                   .map(|line| {
+                       line.parse::<NamedRevision>()
+                           .and_then(|named_revision| self.key_cache.ring_key(&named_revision))
+                   })
+                   .collect::<std::result::Result<Vec<_>, _>>();
+        let mut recorded = match recorded {
+            Ok(recorded) => recorded.into_iter(),
+            Err(err) => {
+                warn!("Unable to load the ring keys recorded in {}, using {}: {}",
+                      path.display(),
+                      ring_key.named_revision(),
+                      err);
+                return RingKeys::new(ring_key);
+            }
+        };
+        match recorded.next() {
+            Some(primary)
+                if primary.named_revision().name() == ring_key.named_revision().name() =>
+            {
+                let mut ring_keys = RingKeys::new(primary);
+                for key in recorded {
+                    if let Err(err) = ring_keys.stage(key) {
+                        warn!("Not accepting recorded ring key: {}", err);
+                    }
+                }
+                ring_keys
+            }
+            _ => RingKeys::new(ring_key),
+        }
+    }
+
     pub fn save_spec_for(&self, spec: &ServiceSpec) -> Result<()> {
         spec.to_file(self.spec_path_for(&spec.ident))
     }
@@ -339,6 +503,7 @@ impl PartialEq for ManagerConfig {
             && self.auto_update_period == other.auto_update_period
             && self.service_update_period == other.service_update_period
             && self.service_restart_config == other.service_restart_config
+            && self.service_log_config == other.service_log_config
             && self.custom_state_path == other.custom_state_path
             && self.key_cache == other.key_cache
             && self.update_url == other.update_url
@@ -352,13 +517,21 @@ impl PartialEq for ManagerConfig {
             && self.http_disable == other.http_disable
             && self.gossip_peers == other.gossip_peers
             && self.gossip_permanent == other.gossip_permanent
+            && self.gossip_certificates == other.gossip_certificates
+            && self.gossip_key == other.gossip_key
+            // Explicitly excluding gossip_ca_certificates from comparison
+            && self.gossip_segment == other.gossip_segment
+            && self.gossip_segment_gateway == other.gossip_segment_gateway
             && self.ring_key == other.ring_key
             && self.organization == other.organization
             && self.watch_peer_file == other.watch_peer_file
+            && self.peer_discovery == other.peer_discovery
+            && self.peer_discovery_period == other.peer_discovery_period
             && self.tls_config == other.tls_config
             && self.feature_flags == other.feature_flags
             && self.event_stream_config == other.event_stream_config
             && self.keep_latest_packages == other.keep_latest_packages
+            && self.verify_installed_files == other.verify_installed_files
             && self.sys_ip == other.sys_ip
     }
 }
@@ -437,6 +610,10 @@ pub struct ManagerState {
     services:       Arc<sync::ManagerServices>,
     gateway_state:  Arc<sync::GatewayState>,
     should_restart: AtomicBool,
+    /// The Supervisor's gossip server, whose ring keys can be rotated while it is running
+    butterfly:      habitat_butterfly::Server,
+    /// The census ring services are rendered against, for previewing configuration changes
+    census_ring:    Arc<RwLock<CensusRing>>,
 }
 
 pub(crate) mod sync {
@@ -606,6 +783,15 @@ pub(crate) mod sync {
     }
 }
 
+/// A service that was not started because the installed files of its package, or of its
+/// dependencies, do not match their signed `FILES` metafiles.
+struct UnverifiedService {
+    /// When the service's spec file was last written. The service is only tried again once its
+    /// spec file is written again, e.g. by `hab svc load --force`.
+    spec_modified: Option<SystemTime>,
+    failed_at:     SystemTime,
+}
+
 pub struct Manager {
     pub state:           Arc<ManagerState>,
     butterfly:           habitat_butterfly::Server,
@@ -614,6 +800,7 @@ pub struct Manager {
     launcher:            LauncherCli,
     service_updater:     Arc<Mutex<ServiceUpdater>>,
     peer_watcher:        Option<PeerWatcher>,
+    peer_discovery:      Option<PeerDiscovery>,
     spec_watcher:        SpecWatcher,
     // This Arc<RwLock<>> business is a potentially temporary
     // change. Right now, in order to asynchronously shut down
@@ -656,6 +843,13 @@ pub struct Manager {
     busy_services: Arc<Mutex<HashSet<PackageIdent>>>,
     updated_service_pkg_incarnations: Arc<Mutex<HashMap<ServiceGroup, u64>>>,
     services_need_reconciliation:     ReconciliationFlag,
+    /// The service that each service held back by its start ordering
+    /// is currently waiting on, so that each wait is only reported
+    /// once.
+    services_awaiting_start:          HashMap<PackageIdent, PackageIdent>,
+    /// The services that were not started because the installed
+    /// files of their packages failed verification.
+    unverified_services:              HashMap<PackageIdent, UnverifiedService>,
 
     feature_flags: FeatureFlag,
 
@@ -727,17 +921,25 @@ impl Manager {
                                cfg.ctl_listen,
                                cfg.http_listen,
                                cfg.sys_ip);
-        let member = Self::load_member(&mut sys, &fs_cfg)?;
+        let mut member = Self::load_member(&mut sys, &fs_cfg)?;
+        if let Some(segment) = cfg.gossip_segment {
+            member.segment = segment;
+        }
+        member.gateway = cfg.gossip_segment_gateway;
         let services = Arc::default();
         let suitability_lookup = Arc::clone(&services) as Arc<dyn Suitability>;
 
-        let server = habitat_butterfly::Server::new(sys.gossip_listen(),
-                                                    sys.gossip_listen(),
-                                                    member,
-                                                    cfg.ring_key,
-                                                    None,
-                                                    Some(&fs_cfg.data_path),
-                                                    suitability_lookup)?;
+        let server =
+            habitat_butterfly::Server::new(sys.gossip_listen(),
+                                           sys.gossip_listen(),
+                                           member,
+                                           cfg.ring_key.map(|ring_key| {
+                                                           cfg_static.load_ring_keys(ring_key)
+                                                       }),
+                                           cfg_static.gossip_member_authenticator()?,
+                                           None,
+                                           Some(&fs_cfg.data_path),
+                                           suitability_lookup)?;
         outputln!("Supervisor Member-ID {}", sys.member_id);
         for peer_addr in &cfg.gossip_peers {
             let peer = Member { address: format!("{}", peer_addr.ip()),
@@ -752,6 +954,9 @@ impl Manager {
         } else {
             None
         };
+        let peer_discovery = (!cfg.peer_discovery.is_empty()).then(|| {
+                                 PeerDiscovery::run(cfg.peer_discovery, cfg.peer_discovery_period)
+                             });
 
         let spec_dir = SpecDir::new(&fs_cfg.specs_path)?;
         spec_dir.migrate_specs();
@@ -771,7 +976,9 @@ impl Manager {
         Ok(Manager { state: Arc::new(ManagerState { cfg: cfg_static,
                                                     services,
                                                     gateway_state: Arc::default(),
-                                                    should_restart: AtomicBool::default() }),
+                                                    should_restart: AtomicBool::default(),
+                                                    butterfly: server.clone(),
+                                                    census_ring: Arc::clone(&census_ring) }),
                      self_updater,
                      service_updater:
                          Arc::new(Mutex::new(ServiceUpdater::new(server.clone(),
@@ -781,6 +988,7 @@ impl Manager {
                      butterfly: server,
                      launcher,
                      peer_watcher,
+                     peer_discovery,
                      spec_watcher,
                      user_config_watcher: UserConfigWatcher::new(),
                      spec_dir,
@@ -792,6 +1000,8 @@ impl Manager {
                      busy_services: Arc::default(),
                      updated_service_pkg_incarnations: Arc::default(),
                      services_need_reconciliation: ReconciliationFlag::new(false),
+                     services_awaiting_start: HashMap::new(),
+                     unverified_services: HashMap::new(),
                      feature_flags: cfg.feature_flags,
                      _lock_file: lock_file })
     }
@@ -895,6 +1105,7 @@ impl Manager {
     /// * `ManagerServices::inner` (read)
     async fn add_service_rsw_mlw_rhw_msr(&mut self, spec: ServiceSpec) {
         let ident = spec.ident.clone();
+        self.unverified_services.remove(&ident);
         let mut service = match Service::new(self.sys.clone(),
                                              spec,
                                              self.fs_cfg.clone(),
@@ -915,6 +1126,20 @@ impl Manager {
             }
         };
 
+        if self.state.cfg.verify_installed_files
+           && let Err(err) = pkg::verify_installed_files(service.pkg.ident.as_ref().clone(),
+                                                         self.state.cfg.key_cache.clone()).await
+        {
+            // The service stays loaded, and is reported as failed, until it is loaded again
+            // after its package was repaired.
+            outputln!("Unable to start {}, {}", ident, err);
+            let spec_modified = self.spec_modified(&ident);
+            self.unverified_services.insert(ident,
+                                            UnverifiedService { spec_modified,
+                                                                failed_at: SystemTime::now() });
+            return;
+        }
+
         // Resolve an auth token for install hooks, if available.
         let auth_token = pkg::get_auth_token();
 
@@ -970,11 +1195,15 @@ impl Manager {
 
         event::service_started(&service);
 
+        let restart_config = self.state
+                                 .cfg
+                                 .service_restart_config
+                                 .for_spec(&service.spec());
         self.state
             .services
             .lock_msw()
             .insert(service.spec_ident(),
-                    PersistentServiceWrapper::new(service, &self.state.cfg.service_restart_config))
+                    PersistentServiceWrapper::new(service, &restart_config))
     }
 
     // If we ever need to modify this function, it would be an excellent opportunity to
@@ -1079,6 +1308,9 @@ impl Manager {
         tokio::spawn(ctl_gateway_server.run());
         debug!("ctl-gateway started");
 
+        let log_rotator = LogRotator::new(self.state.cfg.service_log_config.clone());
+        log_rotator.run_msr(Arc::clone(&self.state.services))?;
+
         if self.http_disable {
             info!("http-gateway disabled");
         } else {
@@ -1241,6 +1473,7 @@ impl Manager {
                     UnloadService { service_spec,
                                     shutdown_input, } => {
                         self.remove_spec_file(&service_spec.ident).ok();
+                        self.gossip_service_unloaded_rsw_rhw_msr(&service_spec.ident);
                         self.stop_service_gsw_msw(&service_spec.ident, &shutdown_input);
                     }
                     UpdateService { service_spec } => {
@@ -1281,7 +1514,7 @@ impl Manager {
                     Vec::new()
                 };
 
-            self.update_peers_from_watch_file_mlr_imlw()?;
+            self.update_peers_mlr_imlw()?;
             self.update_running_services_from_user_config_watcher_msw();
 
             // Restart all services that need it
@@ -1375,19 +1608,7 @@ impl Manager {
             ShutdownMode::Normal | ShutdownMode::Departed => {
                 outputln!("Gracefully departing from butterfly network.");
                 self.butterfly.set_departed_mlw_smw_rhw();
-
-                #[allow(clippy::from_iter_instead_of_collect)]
-                let service_stop_futures =
-                    FuturesUnordered::from_iter(self.state
-                                                    .services
-                                                    .lock_msw()
-                                                    .drain_services()
-                                                    .map(|service| {
-                                                        self.stop_service_future_gsw(service, None,
-                                                                                     None)
-                                                    }));
-                // Wait while all services are stopped
-                service_stop_futures.collect::<Vec<_>>().await;
+                self.stop_all_services_gsw_msw().await;
             }
         }
 
@@ -1399,6 +1620,50 @@ impl Manager {
         }
     }
 
+    /// Stop every running service. Services are stopped in batches, so
+    /// that each service keeps running until all of the services
+    /// which start after it have stopped.
+    ///
+    /// # Locking (see locking.md)
+    /// * `GatewayState::inner` (write)
+    /// * `ManagerServices::inner` (write)
+    async fn stop_all_services_gsw_msw(&self) {
+        let mut services = self.state
+                               .services
+                               .lock_msw()
+                               .drain_services()
+                               .map(|service| (service.spec_ident(), service))
+                               .collect::<HashMap<_, _>>();
+        let specs = services.values().map(Service::spec).collect::<Vec<_>>();
+        let order = match ServiceOrder::new(&specs).stop_batches() {
+            Ok(order) => order,
+            Err(in_cycle) => {
+                outputln!("Stopping all services at once; {} is part of a start ordering cycle",
+                          in_cycle);
+                Vec::new()
+            }
+        };
+        let mut batches = order.iter()
+                               .map(|batch| {
+                                   batch.iter()
+                                        .filter_map(|ident| services.remove(ident))
+                                        .collect::<Vec<_>>()
+                               })
+                               .collect::<Vec<_>>();
+        // Anything left over wasn't ordered, so it can be stopped last
+        batches.push(services.into_values().collect());
+
+        let stop = |service| self.stop_service_future_gsw(service, None, None);
+        for batch in batches {
+            // Wait while all services in the batch are stopped
+            batch.into_iter()
+                 .map(stop)
+                 .collect::<FuturesUnordered<_>>()
+                 .collect::<Vec<_>>()
+                 .await;
+        }
+    }
+
     async fn check_for_updated_supervisor(&mut self) -> Option<PackageInstall> {
         if let Some(ref mut self_updater) = self.self_updater {
             return self_updater.updated().await;
@@ -1488,20 +1753,29 @@ impl Manager {
     // Creates a rumor for the specified service.
     /// # Locking (see locking.md)
     /// * `RumorStore::list` (write)
+    /// * `RumorStore::tombstones` (read)
     /// * `MemberList::entries` (write)
     /// * `RumorHeat::inner` (write)
     fn gossip_latest_service_rumor_rsw_mlw_rhw(&self,
                                                service: &Service,
                                                updated_pkg_incarnation: Option<u64>) {
-        let (incarnation, last_pkg_incarnation) = self.butterfly
-                                                      .service_store
-                                                      .lock_rsr()
-                                                      .service_group(&service.service_group)
-                                                      .map_rumor(&self.sys.member_id, |rumor| {
-                                                          (rumor.incarnation + 1,
-                                                           rumor.pkg_incarnation)
-                                                      })
-                                                      .unwrap_or((1, 0));
+        let service_store = &self.butterfly.service_store;
+        let last = service_store.lock_rsr()
+                                .service_group(&service.service_group)
+                                .map_rumor(&self.sys.member_id, |rumor| {
+                                    (rumor.incarnation, rumor.pkg_incarnation)
+                                });
+        // If the service was unloaded and its rumor purged since, members which haven't purged it
+        // yet only accept a rumor with a higher incarnation than the purged one.
+        let (incarnation, last_pkg_incarnation) =
+            last.or_else(|| {
+                    service_store.purged_incarnation_rsr(service.service_group.as_ref(),
+                                                         &self.sys.member_id)
+                                 .map(|incarnation| (incarnation, 0))
+                })
+                .map_or((1, 0), |(incarnation, pkg_incarnation)| {
+                    (incarnation + 1, pkg_incarnation)
+                });
         // The package incarnation is either the updated package incarnation if it is
         // larger than the last package incarnation or the last known package incarnation
         // from the rumour store.
@@ -1511,6 +1785,25 @@ impl Manager {
             .insert_service_rsw_mlw_rhw(service.to_rumor(incarnation, pkg_incarnation));
     }
 
+    /// Gossip that the service `ident` was unloaded, so that its rumor is eventually purged by
+    /// every member of the ring.
+    ///
+    /// # Locking (see locking.md)
+    /// * `RumorStore::list` (write)
+    /// * `RumorHeat::inner` (write)
+    /// * `ManagerServices::inner` (read)
+    fn gossip_service_unloaded_rsw_rhw_msr(&self, ident: &PackageIdent) {
+        let service_group = self.state
+                                .services
+                                .lock_msr()
+                                .get(ident)
+                                .and_then(PersistentServiceWrapper::service)
+                                .map(|service| service.service_group.to_string());
+        if let Some(service_group) = service_group {
+            self.butterfly.unload_service_rsw_rhw(&service_group);
+        }
+    }
+
     fn check_for_departure(&self) -> bool { self.butterfly.is_departed() }
 
     fn check_for_restart(&self) -> bool {
@@ -1546,7 +1839,10 @@ impl Manager {
             //
             // TODO (CM): why do we bother tracking loaded but not
             // running services at all?
-            service_states.insert(loaded.ident.clone(), SystemTime::UNIX_EPOCH);
+            let failed_at = self.unverified_services
+                                .get(&loaded.ident)
+                                .map_or(SystemTime::UNIX_EPOCH, |unverified| unverified.failed_at);
+            service_states.insert(loaded.ident.clone(), failed_at);
         }
 
         if service_states != self.service_states {
@@ -1634,21 +1930,29 @@ impl Manager {
                 _ => {
                     // If there is no wrapper for the service, we create one
                     match Service::new(self.sys.clone(),
-                                   spec,
-                                   self.fs_cfg.clone(),
-                                   self.organization.as_deref(),
-                                   self.census_ring.clone(),
-                                   self.state.gateway_state.clone(),
-                                   self.feature_flags).await
-                {
-                    Ok(service) => {
-                        watched_services.push((service,
-                                               ServiceRunState::new(&self.state
-                                                                         .cfg
-                                                                         .service_restart_config)))
-                    }
-                    Err(err) => warn!("Failed to create service '{}' from spec: {:?}", ident, err),
-                };
+                                       spec,
+                                       self.fs_cfg.clone(),
+                                       self.organization.as_deref(),
+                                       self.census_ring.clone(),
+                                       self.state.gateway_state.clone(),
+                                       self.feature_flags).await
+                    {
+                        Ok(service) => {
+                            let restart_config = self.state
+                                                     .cfg
+                                                     .service_restart_config
+                                                     .for_spec(&service.spec());
+                            let mut run_state = ServiceRunState::new(&restart_config);
+                            if let Some(unverified) = self.unverified_services.get(&ident) {
+                                run_state.mark_integrity_check_failed(unverified.failed_at);
+                                service.mark_failed();
+                            }
+                            watched_services.push((service, run_state))
+                        }
+                        Err(err) => {
+                            warn!("Failed to create service '{}' from spec: {:?}", ident, err)
+                        }
+                    };
                 }
             }
         }
@@ -1728,11 +2032,17 @@ impl Manager {
         // cluster
         // TODO (CM): But only if we're not going down for a restart.
         let ident = service.spec_ident();
+        let spec_file = self.state.cfg.spec_path_for(&ident);
         let stop_it = async move {
             service.stop_gsw(shutdown_config).await;
             event::service_stopped(&service);
             user_config_watcher.remove(&service);
             service_updater.lock().remove(&service.service_group);
+            // Without its spec file, the service was unloaded rather than stopped or restarted
+            if !spec_file.exists() {
+                service.remove_metrics();
+                service_updater::remove_metrics(&service.service_group);
+            }
             // At this point the service process is stopped but the package is still loaded by the
             // Supervisor.
             if let Some(latest_desired_ident) = latest_desired_on_restart {
@@ -1768,6 +2078,12 @@ impl Manager {
         }
     }
 
+    /// When the spec file of `ident` was last written, if it exists.
+    fn spec_modified(&self, ident: &PackageIdent) -> Option<SystemTime> {
+        fs::metadata(self.state.cfg.spec_path_for(ident)).and_then(|metadata| metadata.modified())
+                                                         .ok()
+    }
+
     fn remove_spec_file(&self, ident: &PackageIdent) -> std::io::Result<()> {
         let file = self.state.cfg.spec_path_for(ident);
         let result = fs::remove_file(&file);
@@ -1873,6 +2189,10 @@ impl Manager {
                     // future; then we could just chain that future
                     // onto the end of the stop one for a *real*
                     // restart future.
+                    if self.spec_modified(&spec.ident).is_none() {
+                        // The spec file was removed, so the service was unloaded
+                        self.gossip_service_unloaded_rsw_rhw_msr(&spec.ident);
+                    }
                     match self.remove_service_from_state_msw(&spec.ident)
                               .and_then(|mut service_state| service_state.shutdown(false))
                     {
@@ -1888,6 +2208,14 @@ impl Manager {
                     }
                 }
                 ServiceOperation::Start(spec) => {
+                    if self.unverified_services
+                           .get(&spec.ident)
+                           .is_some_and(|unverified| {
+                               unverified.spec_modified == self.spec_modified(&spec.ident)
+                           })
+                    {
+                        continue;
+                    }
                     // We need to check if the service is already known, if yes, then is it ready to
                     // be restarted yet
                     if self.state
@@ -1896,27 +2224,49 @@ impl Manager {
                            .get(&spec.ident)
                            .is_none_or(PersistentServiceWrapper::is_ready_for_restart)
                     {
-                        self.add_service_rsw_mlw_rhw_msr(spec.clone()).await;
-                        services_started.push(spec.ident.clone());
+                        if self.start_dependencies_healthy_msr(&spec) {
+                            self.add_service_rsw_mlw_rhw_msr(spec.clone()).await;
+                            services_started.push(spec.ident.clone());
+                        } else {
+                            // Nothing else will prompt us to look at
+                            // this service again once its
+                            // dependencies are healthy, so keep
+                            // checking on every pass through the main
+                            // loop.
+                            self.services_need_reconciliation.set();
+                        }
                     }
                 }
                 ServiceOperation::Update(spec, ops) => {
                     trace!("ServiceOperation::Update! {:?}", spec);
+                    let restart_config = self.state.cfg.service_restart_config.for_spec(&spec);
                     let mut services = self.state.services.lock_msw();
                     // Relies on spec.ident not having changed, which
                     // ServiceSpec#reconcile must guarantee.
-                    if let Some(service) = services.get_mut(&spec.ident)
-                                                   .and_then(PersistentServiceWrapper::service_mut)
+                    if let Some(service_state) = services.get_mut(&spec.ident)
+                       && let Some(service) = service_state.service_mut()
                     {
                         service.set_spec(spec);
                         self.gossip_latest_service_rumor_rsw_mlw_rhw(service, None);
+                        let mut restart_config_changed = false;
                         for op in ops {
                             match op {
                                 RefreshOperation::RestartUpdater => {
                                     self.service_updater.lock().register(service);
                                 }
+                                RefreshOperation::UpdateRestartConfig => {
+                                    restart_config_changed = true;
+                                }
+                                RefreshOperation::UpdateStartOrder
+                                | RefreshOperation::UpdateFencing => {
+                                    // Swapping in the new spec above is
+                                    // all that's needed
+                                }
                             }
                         }
+                        if restart_config_changed {
+                            service_state.set_restart_config(restart_config);
+                        }
                     } else {
                         // We really don't expect this to
                         // happen... this would likely mean that a
@@ -1932,6 +2282,61 @@ impl Manager {
         services_started
     }
 
+    /// Returns `true` if every service that `spec` must be started
+    /// after, through its `after` and `requires` settings, is running
+    /// and healthy.
+    ///
+    /// # Locking (see locking.md)
+    /// * `ManagerServices::inner` (read)
+    fn start_dependencies_healthy_msr(&mut self, spec: &ServiceSpec) -> bool {
+        if spec.after.is_empty() && spec.requires.is_empty() {
+            return true;
+        }
+        let loaded_specs = self.spec_dir
+                               .specs()
+                               .into_iter()
+                               .filter(|s| s.desired_state == DesiredState::Up)
+                               .collect::<Vec<_>>();
+        self.services_awaiting_start
+            .retain(|ident, _| loaded_specs.iter().any(|s| &s.ident == ident));
+
+        let order = ServiceOrder::new(&loaded_specs);
+        if let Some(in_cycle) = order.cycle() {
+            outputln!("Ignoring the start ordering of {}; {} is part of a start ordering cycle",
+                      spec.ident,
+                      in_cycle);
+            return true;
+        }
+        let waiting_on = {
+            let services = self.state.services.lock_msr();
+            order.dependencies(&spec.ident)
+                 .into_iter()
+                 .find(|dep| {
+                     services.get(dep)
+                             .and_then(PersistentServiceWrapper::service)
+                             .is_none_or(|service| {
+                                 service.health_check_result() != HealthCheckResult::Ok
+                             })
+                 })
+                 .cloned()
+        };
+        match waiting_on {
+            Some(dep) => {
+                if self.services_awaiting_start.get(&spec.ident) != Some(&dep) {
+                    outputln!("Waiting for {} to be healthy before starting {}",
+                              dep,
+                              spec.ident);
+                    self.services_awaiting_start.insert(spec.ident.clone(), dep);
+                }
+                false
+            }
+            None => {
+                self.services_awaiting_start.remove(&spec.ident);
+                true
+            }
+        }
+    }
+
     /// Determine what services we need to start, stop, or restart in
     /// order to be running what our on-disk spec files tell us we
     /// should be running.
@@ -1997,20 +2402,22 @@ impl Manager {
     /// # Locking (see locking.md)
     /// * `MemberList::entries` (read)
     /// * `MemberList::initial_members` (write)
-    fn update_peers_from_watch_file_mlr_imlw(&mut self) -> Result<()> {
+    fn update_peers_mlr_imlw(&mut self) -> Result<()> {
         if !self.butterfly.need_peer_seeding_mlr() {
             return Ok(());
         }
-        match self.peer_watcher {
-            None => Ok(()),
-            Some(ref watcher) => {
-                if watcher.has_fs_events() {
-                    let members = watcher.get_members()?;
-                    self.butterfly.member_list.set_initial_members_imlw(members);
-                }
-                Ok(())
-            }
+        if let Some(ref watcher) = self.peer_watcher
+           && watcher.has_fs_events()
+        {
+            let members = watcher.get_members()?;
+            self.butterfly.member_list.set_initial_members_imlw(members);
+        }
+        if let Some(ref mut discovery) = self.peer_discovery
+           && let Some(members) = discovery.updated_members()
+        {
+            self.butterfly.member_list.set_initial_members_imlw(members);
         }
+        Ok(())
     }
 
     /// # Locking (see locking.md)
@@ -2125,6 +2532,7 @@ mod tests {
                             auto_update_period:         Duration::from_secs(60),
                             service_update_period:      Duration::from_secs(60),
                             service_restart_config:     ServiceRestartConfig::default(),
+                            service_log_config:         ServiceLogConfig::default(),
                             custom_state_path:          None,
                             key_cache:                  KeyCache::new(&*CACHE_KEY_PATH),
                             update_url:                 "".to_string(),
@@ -2138,13 +2546,21 @@ mod tests {
                             http_disable:               false,
                             gossip_peers:               vec![],
                             gossip_permanent:           false,
+                            gossip_certificates:        None,
+                            gossip_key:                 None,
+                            gossip_ca_certificates:     None,
+                            gossip_segment:             None,
+                            gossip_segment_gateway:     false,
                             ring_key:                   None,
                             organization:               None,
                             watch_peer_file:            None,
+                            peer_discovery:             vec![],
+                            peer_discovery_period:      Duration::from_secs(60),
                             tls_config:                 None,
                             feature_flags:              FeatureFlag::empty(),
                             event_stream_config:        None,
                             keep_latest_packages:       None,
+                            verify_installed_files:     false,
                             sys_ip:                     IpAddr::V4(Ipv4Addr::LOCALHOST), }
         }
     }
@@ -2176,4 +2592,33 @@ mod tests {
 
         assert_eq!(PathBuf::from("/tmp/partay"), path);
     }
+
+    #[test]
+    fn recorded_ring_keys_are_used_after_a_restart() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let cfg = ManagerConfig { custom_state_path: Some(tmpdir.path().join("sup")),
+                                  key_cache: KeyCache::new(tmpdir.path().join("keys")),
+                                  ..Default::default() };
+        fs::create_dir_all(cfg.sup_root()).unwrap();
+        cfg.key_cache.setup().unwrap();
+        let old_key = cfg.key_cache.new_ring_key("ring").unwrap();
+        // Ring key revisions are timestamped to the second
+        thread::sleep(Duration::from_secs(1));
+        let new_key = cfg.key_cache.new_ring_key("ring").unwrap();
+
+        // Without a recorded rotation the latest key is used on its own
+        assert_eq!(cfg.load_ring_keys(new_key.clone()),
+                   RingKeys::new(new_key.clone()));
+
+        // The new key has been staged, but not activated
+        let mut ring_keys = RingKeys::new(old_key.clone());
+        ring_keys.stage(new_key.clone()).unwrap();
+        cfg.save_ring_keys(&ring_keys).unwrap();
+        assert_eq!(cfg.load_ring_keys(new_key.clone()), ring_keys);
+
+        // Keys recorded for another ring are ignored
+        let other_key = RingKey::new("other-ring");
+        assert_eq!(cfg.load_ring_keys(other_key.clone()),
+                   RingKeys::new(other_key));
+    }
 }
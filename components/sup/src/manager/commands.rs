@@ -6,13 +6,24 @@ use crate::{ctl_gateway::CtlRequest,
                       action::{ActionSender,
                                SupervisorAction},
                       service::{DesiredState,
+                                logs::{self,
+                                       LogLine,
+                                       LogReader},
                                 spec::ServiceSpec}},
             util};
-use habitat_butterfly as butterfly;
-use habitat_common::{command::package::install::InstallSource,
+use chrono::{DateTime,
+             Utc};
+use habitat_butterfly::{self as butterfly,
+                        ring_keys::RingKeys};
+use habitat_common::{command::package::{install::InstallSource,
+                                        lockfile::Lockfile},
                      outputln,
                      ui::UIWriter};
-use habitat_core::{package::{Identifiable,
+use habitat_core::{crypto::keys::{Key,
+                                  NamedRevision,
+                                  RingKey},
+                   fs,
+                   package::{Identifiable,
                              PackageIdent,
                              PackageTarget},
                    service::ServiceGroup};
@@ -22,10 +33,20 @@ use habitat_sup_protocol::{self as protocol,
                                  NetResult}};
 use std::{convert::TryFrom,
           str,
-          sync::atomic::Ordering};
+          sync::atomic::Ordering,
+          thread,
+          time::{Duration,
+                 Instant,
+                 UNIX_EPOCH}};
 
 static LOGKEY: &str = "CMD";
 
+/// How often a followed `SvcLogs` request checks for new output.
+const LOG_FOLLOW_INTERVAL: Duration = Duration::from_secs(1);
+/// The longest a followed `SvcLogs` request goes without replying. Replying is the only way we
+/// find out that the client has gone away, so quiet services still get an empty reply this often.
+const LOG_FOLLOW_KEEPALIVE: Duration = Duration::from_secs(10);
+
 /// # Locking (see locking.md)
 /// * `ManagerServices::inner` (read)
 pub fn service_cfg_msr(mgr: &ManagerState,
@@ -126,7 +147,8 @@ pub fn service_cfg_set(mgr: &ManagerState,
               service_group,);
     let mut client =
         match butterfly::client::Client::new(&mgr.cfg.gossip_listen.local_addr().to_string(),
-                                             mgr.cfg.ring_key.clone())
+                                             primary_ring_key(mgr),
+                                             mgr.butterfly.member_authenticator().cloned())
         {
             Ok(client) => client,
             Err(err) => {
@@ -141,6 +163,182 @@ pub fn service_cfg_set(mgr: &ManagerState,
           })
 }
 
+/// # Locking (see locking.md)
+/// * `ManagerServices::inner` (read)
+pub fn service_cfg_preview_msr(mgr: &ManagerState,
+                               req: &mut CtlRequest,
+                               opts: protocol::ctl::SvcPreviewCfg)
+                               -> NetResult<()> {
+    let cfg = opts.cfg.ok_or_else(err_update_client)?;
+    let version = opts.version.ok_or_else(err_update_client)?;
+    let service_group: ServiceGroup = opts.service_group.ok_or_else(err_update_client)?.into();
+    if cfg.len() > protocol::butterfly::MAX_SVC_CFG_SIZE {
+        return Err(net::err(ErrCode::EntityTooLarge, "Configuration too large."));
+    }
+    let cfg_str =
+        str::from_utf8(&cfg).map_err(|e| {
+                                net::err(ErrCode::BadPayload,
+                                         format!("Unable to decode configuration to string, {}", e))
+                            })?;
+    let gossip: toml::value::Table = toml::from_str(cfg_str).map_err(|e| {
+                                                                net::err(ErrCode::BadPayload,
+                                                  format!("Unable to decode configuration as \
+                                                           toml, {}",
+                                                          e))
+                                                            })?;
+    let scratch = tempfile::tempdir().map_err(|e| {
+                                         net::err(ErrCode::Io,
+                                                  format!("Unable to create scratch directory, {}",
+                                                          e))
+                                     })?;
+
+    let services = mgr.services.lock_msr();
+    let service = services.running_services()
+                          .find(|service| service.service_group == service_group)
+                          .ok_or_else(|| {
+                              net::err(ErrCode::NotFound,
+                                       format!("Service group not loaded, {}", service_group))
+                          })?;
+    if version <= service.cfg.gossip_incarnation {
+        return Err(net::err(ErrCode::InvalidPayload,
+                            format!("Configuration version {} of {} must be \
+                                     greater than the current version, {}",
+                                    version,
+                                    service_group,
+                                    service.cfg.gossip_incarnation)));
+    }
+    let preview =
+        service.preview_gossip_config(&mgr.census_ring.read(), version, gossip, scratch.path())
+               .map_err(|e| {
+                   net::err(ErrCode::InvalidPayload,
+                            format!("Unable to render configuration of {}, {}", service_group, e))
+               })?;
+    req.reply_complete(protocol::ctl::SvcCfgPreview { file_diffs:     preview.file_diffs,
+                                                      changed_hooks:  preview.changed_hooks
+                                                                             .into_iter()
+                                                                             .map(String::from)
+                                                                             .collect(),
+                                                      restart_reason:
+                                                          preview.restart
+                                                                 .map(|reason| reason.to_string()),
+                                                      reconfigure:    Some(preview.reconfigure), });
+    Ok(())
+}
+
+pub fn service_cfg_history(mgr: &ManagerState,
+                           req: &mut CtlRequest,
+                           opts: protocol::ctl::SvcGetCfgHistory)
+                           -> NetResult<()> {
+    let service_group: ServiceGroup = opts.service_group.ok_or_else(err_update_client)?.into();
+    let revisions: Vec<_> = mgr.butterfly
+                               .service_config_history(&service_group)
+                               .into_iter()
+                               .map(cfg_revision)
+                               .collect();
+    if revisions.is_empty() {
+        req.reply_complete(net::ok());
+    } else {
+        let mut list = revisions.into_iter().peekable();
+        while let Some(revision) = list.next() {
+            if list.peek().is_some() {
+                req.reply_partial(revision);
+            } else {
+                req.reply_complete(revision);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// # Locking (see locking.md)
+/// * `RumorStore::list` (read)
+pub fn service_cfg_rollback_rsr(mgr: &ManagerState,
+                                req: &mut CtlRequest,
+                                opts: protocol::ctl::SvcRollbackCfg)
+                                -> NetResult<()> {
+    let service_group: ServiceGroup = opts.service_group.ok_or_else(err_update_client)?.into();
+    let version = opts.version.ok_or_else(err_update_client)?;
+    let history = mgr.butterfly.service_config_history(&service_group);
+    let entry = history.iter()
+                       .find(|entry| entry.incarnation == version)
+                       .cloned()
+                       .ok_or_else(|| {
+                           net::err(ErrCode::NotFound,
+                                    format!("Configuration version {} of {} is not in the \
+                                             configuration history",
+                                            version, service_group))
+                       })?;
+    // The history may have been trimmed, or lag behind the rumor, so the rollback must also
+    // supersede the incarnation that is currently gossiped.
+    let latest = mgr.butterfly
+                    .service_config_incarnation_rsr(&service_group)
+                    .into_iter()
+                    .chain(history.last().map(|entry| entry.incarnation))
+                    .max()
+                    .unwrap_or(version);
+    let new_version = opts.new_version.unwrap_or(latest + 1);
+    if new_version <= latest {
+        return Err(net::err(ErrCode::InvalidPayload,
+                            format!("Configuration version {} of {} must be \
+                                     greater than the latest version, {}",
+                                    new_version, service_group, latest)));
+    }
+    outputln!("Rolling back configuration of {} to version {} as version {}",
+              service_group,
+              version,
+              new_version);
+    let mut client =
+        match butterfly::client::Client::new(&mgr.cfg.gossip_listen.local_addr().to_string(),
+                                             primary_ring_key(mgr),
+                                             mgr.butterfly.member_authenticator().cloned())
+        {
+            Ok(client) => client,
+            Err(err) => {
+                outputln!("Failed to connect to own gossip server, {}", err);
+                return Err(net::err(ErrCode::Internal, err.to_string()));
+            }
+        };
+    client.send_service_config(service_group,
+                               new_version,
+                               entry.config.as_bytes(),
+                               entry.encrypted)
+          .map_err(|e| net::err(ErrCode::Internal, e.to_string()))?;
+    let mut revision = cfg_revision(entry);
+    revision.version = Some(new_version);
+    revision.from_id = Some(mgr.butterfly.member_id().to_string());
+    revision.applied_at = Some(Utc::now().timestamp() as u64);
+    req.reply_complete(revision);
+    Ok(())
+}
+
+/// # Locking (see locking.md)
+/// * `ManagerServices::inner` (read)
+pub fn service_leader_step_down_msr(mgr: &ManagerState,
+                                    req: &mut CtlRequest,
+                                    opts: protocol::ctl::SvcLeaderStepDown)
+                                    -> NetResult<()> {
+    let service_group: ServiceGroup = opts.service_group.ok_or_else(err_update_client)?.into();
+    let leader = hand_off_leadership_msr(mgr, &service_group, None)?;
+    req.info(format!("Leader {} of {} is stepping down", leader, service_group))?;
+    req.reply_complete(net::ok());
+    Ok(())
+}
+
+/// # Locking (see locking.md)
+/// * `ManagerServices::inner` (read)
+pub fn service_leader_transfer_msr(mgr: &ManagerState,
+                                   req: &mut CtlRequest,
+                                   opts: protocol::ctl::SvcLeaderTransfer)
+                                   -> NetResult<()> {
+    let service_group: ServiceGroup = opts.service_group.ok_or_else(err_update_client)?.into();
+    let member_id = opts.member_id.ok_or_else(err_update_client)?;
+    let leader = hand_off_leadership_msr(mgr, &service_group, Some(&member_id))?;
+    req.info(format!("Transferring leadership of {} from {} to {}",
+                     service_group, leader, member_id))?;
+    req.reply_complete(net::ok());
+    Ok(())
+}
+
 pub fn service_file_put(mgr: &ManagerState,
                         req: &mut CtlRequest,
                         opts: protocol::ctl::SvcFilePut)
@@ -159,7 +357,8 @@ pub fn service_file_put(mgr: &ManagerState,
               service_group,);
     let mut client =
         match butterfly::client::Client::new(&mgr.cfg.gossip_listen.local_addr().to_string(),
-                                             mgr.cfg.ring_key.clone())
+                                             primary_ring_key(mgr),
+                                             mgr.butterfly.member_authenticator().cloned())
         {
             Ok(client) => client,
             Err(err) => {
@@ -176,8 +375,28 @@ pub fn service_file_put(mgr: &ManagerState,
 
 pub async fn service_load(mgr: &ManagerState,
                           req: &mut CtlRequest,
-                          opts: protocol::ctl::SvcLoad)
+                          mut opts: protocol::ctl::SvcLoad)
                           -> NetResult<()> {
+    let lockfile = opts.lockfile
+                       .take()
+                       .map(|lockfile| lockfile.parse::<Lockfile>())
+                       .transpose()
+                       .map_err(Error::from)?;
+    if let Some(ref lockfile) = lockfile {
+        // Pin the service to the package the lockfile was written for
+        let ident: PackageIdent = opts.ident.clone().ok_or_else(err_update_client)?.into();
+        let root = lockfile.roots
+                           .iter()
+                           .find(|root| root.satisfies(&ident))
+                           .ok_or_else(|| {
+                               net::err(ErrCode::BadPayload,
+                                        format!("The lockfile does not lock a package satisfying \
+                                                 {}",
+                                                ident))
+                           })?;
+        opts.ident = Some(PackageIdent::from(root.clone()).into());
+    }
+    let write_lockfile = opts.write_lockfile.unwrap_or(false);
     let ident: PackageIdent = opts.ident.clone().ok_or_else(err_update_client)?.into();
     let source = InstallSource::Ident(ident.clone(), PackageTarget::active_target());
     let spec = if let Some(spec) = mgr.cfg.spec_for_ident(source.as_ref()) {
@@ -196,10 +415,21 @@ pub async fn service_load(mgr: &ManagerState,
         ServiceSpec::try_from(opts)?
     };
 
-    let package = util::pkg::satisfy_or_install(req, &source, &spec.bldr_url, &spec.channel).await?;
+    let package = match lockfile {
+        Some(ref lockfile) => {
+            util::pkg::install_from_lockfile(req, &spec.bldr_url, lockfile, &ident).await?
+        }
+        None => util::pkg::satisfy_or_install(req, &source, &spec.bldr_url, &spec.channel).await?,
+    };
     spec.validate(&package)?;
     mgr.cfg.save_spec_for(&spec)?;
 
+    if write_lockfile {
+        let lockfile = util::pkg::lock(req, &spec.bldr_url, &package).await?;
+        let contents = lockfile.to_toml_string().map_err(Error::from)?;
+        req.reply_partial(protocol::ctl::SvcLockfile { contents: Some(contents), });
+    }
+
     req.info(format!("The {} service was successfully loaded", spec.ident))?;
     req.reply_complete(net::ok());
     Ok(())
@@ -309,7 +539,8 @@ pub fn supervisor_depart(mgr: &ManagerState,
     let member_id = opts.member_id.ok_or_else(err_update_client)?;
     let mut client =
         match butterfly::client::Client::new(&mgr.cfg.gossip_listen.local_addr().to_string(),
-                                             mgr.cfg.ring_key.clone())
+                                             primary_ring_key(mgr),
+                                             mgr.butterfly.member_authenticator().cloned())
         {
             Ok(client) => client,
             Err(err) => {
@@ -327,6 +558,47 @@ pub fn supervisor_depart(mgr: &ManagerState,
     }
 }
 
+pub fn supervisor_ring_key_stage(mgr: &ManagerState,
+                                 req: &mut CtlRequest,
+                                 opts: protocol::ctl::SupRingKeyStage)
+                                 -> NetResult<()> {
+    let named_revision = ring_key_revision(opts.revision)?;
+    let key = mgr.cfg
+                 .key_cache
+                 .ring_key(&named_revision)
+                 .map_err(|e| net::err(ErrCode::NotFound, e.to_string()))?;
+    outputln!("Staging ring key {}", named_revision);
+    reply_ring_keys(mgr, req, mgr.butterfly.stage_ring_key(key))
+}
+
+pub fn supervisor_ring_key_activate(mgr: &ManagerState,
+                                    req: &mut CtlRequest,
+                                    opts: protocol::ctl::SupRingKeyActivate)
+                                    -> NetResult<()> {
+    let named_revision = ring_key_revision(opts.revision)?;
+    outputln!("Activating ring key {}", named_revision);
+    reply_ring_keys(mgr, req, mgr.butterfly.activate_ring_key(&named_revision))
+}
+
+pub fn supervisor_ring_key_retire(mgr: &ManagerState,
+                                  req: &mut CtlRequest,
+                                  opts: protocol::ctl::SupRingKeyRetire)
+                                  -> NetResult<()> {
+    let named_revision = ring_key_revision(opts.revision)?;
+    outputln!("Retiring ring key {}", named_revision);
+    reply_ring_keys(mgr, req, mgr.butterfly.retire_ring_key(&named_revision))
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn supervisor_ring_key_status(mgr: &ManagerState,
+                                  req: &mut CtlRequest,
+                                  _opts: protocol::ctl::SupRingKeyStatus)
+                                  -> NetResult<()> {
+    let ring_keys = mgr.butterfly.ring_keys().ok_or_else(err_not_encrypted)?;
+    req.reply_complete(ring_keys_msg(&ring_keys));
+    Ok(())
+}
+
 #[allow(clippy::needless_pass_by_value)]
 pub fn supervisor_restart(mgr: &ManagerState,
                           _req: &mut CtlRequest,
@@ -387,10 +659,145 @@ pub fn service_status_gsr(mgr: &ManagerState,
     }
 }
 
+/// # Locking (see locking.md)
+/// * `GatewayState::inner` (read)
+pub fn service_logs_gsr(mgr: &ManagerState,
+                        req: &mut CtlRequest,
+                        opts: protocol::ctl::SvcLogs)
+                        -> NetResult<()> {
+    let ident: PackageIdent = opts.ident.ok_or_else(err_update_client)?.into();
+    let pkg_name =
+        mgr.gateway_state
+           .lock_gsr()
+           .services_data()
+           .iter()
+           .find(|service| service.pkg.ident.satisfies(&ident))
+           .map(|service| service.pkg.name.clone())
+           .ok_or_else(|| net::err(ErrCode::NotFound, format!("Service not loaded, {}", ident)))?;
+    let since = opts.since
+                    .map(|secs| DateTime::<Utc>::from(UNIX_EPOCH + Duration::from_secs(secs)));
+    let mut reader = LogReader::new(fs::svc_logs_path(&pkg_name));
+    let lines = reader.read().map_err(|e| {
+                                  net::err(ErrCode::Internal,
+                                           format!("Unable to read logs for {}, {}", ident, e))
+                              })?;
+    for line in logs::select(lines, since, opts.lines.map(|n| n as usize)) {
+        req.reply_partial(console_line(&line));
+    }
+    if !opts.follow.unwrap_or(false) {
+        req.reply_complete(net::ok());
+        return Ok(());
+    }
+    // Following never completes on its own, so it can't be allowed to
+    // tie up the main loop; the request stays open for as long as the
+    // spawned thread holds on to it.
+    let follow_req = req.clone();
+    thread::Builder::new().name(format!("svc-logs-{}", pkg_name))
+                          .spawn(move || follow_logs(follow_req, reader))
+                          .map_err(|e| {
+                              net::err(ErrCode::Internal,
+                                       format!("Unable to follow logs for {}, {}", ident, e))
+                          })?;
+    Ok(())
+}
+
 ////////////////////////////////////////////////////////////////////////
 // Private helper functions
+fn cfg_revision(entry: butterfly::server::config_history::ConfigHistoryEntry)
+                -> protocol::ctl::SvcCfgRevision {
+    protocol::ctl::SvcCfgRevision { version:      Some(entry.incarnation),
+                                    from_id:      Some(entry.from_id),
+                                    cfg:          Some(entry.config.into_bytes()),
+                                    is_encrypted: Some(entry.encrypted),
+                                    applied_at:   Some(entry.recorded_at), }
+}
+
+/// # Locking (see locking.md)
+/// * `ManagerServices::inner` (read)
+fn hand_off_leadership_msr(mgr: &ManagerState,
+                           service_group: &ServiceGroup,
+                           preferred_member_id: Option<&str>)
+                           -> NetResult<String> {
+    mgr.butterfly
+       .hand_off_leadership_rsw_mlr_rhw_msr(service_group, preferred_member_id)
+       .map_err(|e| net::err(ErrCode::InvalidPayload, e.to_string()))
+}
+
 fn err_update_client() -> net::NetErr { net::err(ErrCode::UpdateClient, "client out of date") }
 
+fn err_not_encrypted() -> net::NetErr {
+    net::err(ErrCode::NotSupported,
+             "This Supervisor's gossip is not encrypted with a ring key")
+}
+
+/// The ring key to send rumors to our own gossip server with. It changes as the ring key is
+/// rotated, so it can't be taken from the Supervisor's configuration.
+fn primary_ring_key(mgr: &ManagerState) -> Option<RingKey> {
+    mgr.butterfly
+       .ring_keys()
+       .map(|ring_keys| ring_keys.primary().clone())
+}
+
+fn ring_key_revision(revision: Option<String>) -> NetResult<NamedRevision> {
+    revision.ok_or_else(err_update_client)?
+            .parse()
+            .map_err(|e: habitat_core::Error| net::err(ErrCode::InvalidPayload, e.to_string()))
+}
+
+/// Record the result of rotating the ring key and reply with the ring keys now in use.
+fn reply_ring_keys(mgr: &ManagerState,
+                   req: &mut CtlRequest,
+                   result: butterfly::error::Result<RingKeys>)
+                   -> NetResult<()> {
+    let ring_keys = result.map_err(|e| net::err(ErrCode::InvalidPayload, e.to_string()))?;
+    mgr.cfg
+       .save_ring_keys(&ring_keys)
+       .map_err(|e| net::err(ErrCode::Internal, e.to_string()))?;
+    req.reply_complete(ring_keys_msg(&ring_keys));
+    Ok(())
+}
+
+fn ring_keys_msg(ring_keys: &RingKeys) -> protocol::ctl::SupRingKeys {
+    protocol::ctl::SupRingKeys { primary:  Some(ring_keys.primary().named_revision().to_string()),
+                                 accepted: ring_keys.accepted()
+                                                    .iter()
+                                                    .map(|key| key.named_revision().to_string())
+                                                    .collect(), }
+}
+
+fn console_line(line: &LogLine) -> protocol::ctl::ConsoleLine {
+    protocol::ctl::ConsoleLine { line:  format!("{}\n", line),
+                                 color: line.is_stderr().then(|| "red".to_string()),
+                                 bold:  false, }
+}
+
+/// Stream newly written service output back to the client until it disconnects.
+fn follow_logs(mut req: CtlRequest, mut reader: LogReader) {
+    let mut last_reply = Instant::now();
+    while !req.is_closed() {
+        thread::sleep(LOG_FOLLOW_INTERVAL);
+        match reader.read() {
+            Ok(lines) if !lines.is_empty() => {
+                for line in lines {
+                    req.reply_partial(console_line(&line));
+                }
+                last_reply = Instant::now();
+            }
+            Ok(_) => {
+                if last_reply.elapsed() >= LOG_FOLLOW_KEEPALIVE {
+                    req.reply_partial(net::ok());
+                    last_reply = Instant::now();
+                }
+            }
+            Err(e) => {
+                req.reply_complete(net::err(ErrCode::Internal,
+                                            format!("Unable to read logs, {}", e)));
+                return;
+            }
+        }
+    }
+}
+
 /// Helper function to ensure that all errors in sending are handled identically.
 fn send_action(action: SupervisorAction, sender: &ActionSender) -> NetResult<()> {
     if sender.send(action).is_err() {
@@ -0,0 +1,284 @@
+//! Rotation of the log files capturing the output of each service's run hook.
+//!
+//! The Launcher holds these files open in append mode for as long as the service is running, so
+//! rather than renaming a file out from under it, rotation copies the contents into an archive
+//! next to the original and then truncates the original in place.
+
+use super::{ServiceLogConfig,
+            service::RunHook,
+            sync::ManagerServices};
+use chrono::{DateTime,
+             Utc};
+use flate2::{Compression,
+             write::GzEncoder};
+use habitat_common::{liveliness_checker,
+                     outputln,
+                     templating::hooks};
+use log::debug;
+use std::{collections::HashMap,
+          fs::{self,
+               File,
+               OpenOptions},
+          io,
+          path::{Path,
+                 PathBuf},
+          sync::Arc,
+          thread::{self,
+                   Builder as ThreadBuilder},
+          time::{Duration,
+                 SystemTime}};
+
+static LOGKEY: &str = "LR";
+
+/// How often the log files of loaded services are checked for rotation.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+pub struct LogRotator {
+    config:       ServiceLogConfig,
+    /// When each log file was last rotated (or first seen, if it hasn't been rotated yet).
+    last_rotated: HashMap<PathBuf, SystemTime>,
+}
+
+impl LogRotator {
+    pub fn new(config: ServiceLogConfig) -> Self {
+        LogRotator { config,
+                     last_rotated: HashMap::new() }
+    }
+
+    /// Start a thread which periodically rotates the run hook log files of every loaded
+    /// service. Does nothing if rotation is disabled.
+    ///
+    /// # Locking (see locking.md)
+    /// * `ManagerServices::inner` (read)
+    pub fn run_msr(mut self, services: Arc<ManagerServices>) -> io::Result<()> {
+        if !self.config.rotation_enabled() {
+            debug!("Service log rotation disabled");
+            return Ok(());
+        }
+        ThreadBuilder::new().name("log-rotator".to_string())
+                            .spawn(move || {
+                                loop {
+                                    liveliness_checker::mark_thread_alive().and_divergent();
+                                    let pkg_names = services.lock_msr()
+                                                            .running_services()
+                                                            .map(|service| service.pkg.name.clone())
+                                                            .collect::<Vec<_>>();
+                                    for pkg_name in pkg_names {
+                                        self.rotate_service_logs(&pkg_name);
+                                    }
+                                    thread::sleep(CHECK_INTERVAL);
+                                }
+                            })?;
+        Ok(())
+    }
+
+    fn rotate_service_logs(&mut self, pkg_name: &str) {
+        for path in [hooks::stdout_log_path::<RunHook>(pkg_name),
+                     hooks::stderr_log_path::<RunHook>(pkg_name)]
+        {
+            if let Err(e) = self.rotate_if_needed(&path, SystemTime::now()) {
+                outputln!(preamble pkg_name, "Failed to rotate log file {}, {}", path.display(), e);
+            }
+        }
+    }
+
+    /// Rotate the log file at `path` if it has outgrown the configured size or age, returning
+    /// `true` if it was rotated.
+    fn rotate_if_needed(&mut self, path: &Path, now: SystemTime) -> io::Result<bool> {
+        let metadata = match fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        let last_rotated = *self.last_rotated
+                                .entry(path.to_path_buf())
+                                .or_insert_with(|| newest_archive_time(path).unwrap_or(now));
+        let too_big = self.config
+                          .max_size
+                          .is_some_and(|max_size| metadata.len() > max_size);
+        let too_old = self.config.max_age.is_some_and(|max_age| {
+                                             now.duration_since(last_rotated)
+                                                .is_ok_and(|age| age >= max_age)
+                                         });
+        if metadata.len() == 0 || !(too_big || too_old) {
+            return Ok(false);
+        }
+        self.rotate(path, now)?;
+        self.last_rotated.insert(path.to_path_buf(), now);
+        prune_archives(path, self.config.retain)?;
+        Ok(true)
+    }
+
+    fn rotate(&self, path: &Path, now: SystemTime) -> io::Result<()> {
+        let mut archive_name = path.file_name()
+                                   .expect("log file paths always have a file name")
+                                   .to_os_string();
+        archive_name.push(format!(".{}",
+                                  DateTime::<Utc>::from(now).format("%Y%m%dT%H%M%S%.3fZ")));
+        if self.config.compress {
+            archive_name.push(".gz");
+        }
+        let archive_path = path.with_file_name(archive_name);
+
+        let mut source = File::open(path)?;
+        let archive = File::create(&archive_path)?;
+        if self.config.compress {
+            let mut encoder = GzEncoder::new(archive, Compression::default());
+            io::copy(&mut source, &mut encoder)?;
+            encoder.finish()?;
+        } else {
+            let mut archive = archive;
+            io::copy(&mut source, &mut archive)?;
+        }
+        // Any output written between the copy and the truncation is
+        // lost; this is the same trade-off `logrotate`'s
+        // `copytruncate` makes.
+        OpenOptions::new().write(true).open(path)?.set_len(0)?;
+        debug!("Rotated {} to {}", path.display(), archive_path.display());
+        Ok(())
+    }
+}
+
+/// Returns the archives of the log file at `path`, oldest first.
+///
+/// Archive names embed the time of rotation in a sortable format, so ordering by name is
+/// ordering by age.
+fn archives(path: &Path) -> io::Result<Vec<PathBuf>> {
+    let (Some(dir), Some(file_name)) = (path.parent(), path.file_name().and_then(|n| n.to_str()))
+    else {
+        return Ok(Vec::new());
+    };
+    let prefix = format!("{}.", file_name);
+    let mut archives = fs::read_dir(dir)?.filter_map(|entry| entry.ok().map(|e| e.path()))
+                                         .filter(|archive| {
+                                             archive.file_name()
+                                                    .and_then(|n| n.to_str())
+                                                    .is_some_and(|n| n.starts_with(&prefix))
+                                         })
+                                         .collect::<Vec<_>>();
+    archives.sort();
+    Ok(archives)
+}
+
+fn newest_archive_time(path: &Path) -> Option<SystemTime> {
+    archives(path).ok()?
+                  .last()
+                  .and_then(|archive| fs::metadata(archive).ok())
+                  .and_then(|metadata| metadata.modified().ok())
+}
+
+/// Delete all but the newest `retain` archives of the log file at `path`.
+fn prune_archives(path: &Path, retain: usize) -> io::Result<()> {
+    let archives = archives(path)?;
+    let excess = archives.len().saturating_sub(retain);
+    for archive in &archives[..excess] {
+        fs::remove_file(archive)?;
+        debug!("Removed {}", archive.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+    use tempfile::TempDir;
+
+    fn config(max_size: Option<u64>,
+              max_age: Option<Duration>,
+              compress: bool,
+              retain: usize)
+              -> ServiceLogConfig {
+        ServiceLogConfig::new(max_size, max_age, compress, retain)
+    }
+
+    #[test]
+    fn rotates_files_larger_than_max_size() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("run.stdout.log");
+        let mut rotator = LogRotator::new(config(Some(10), None, false, 5));
+        let now = SystemTime::now();
+
+        fs::write(&path, "short\n").unwrap();
+        assert!(!rotator.rotate_if_needed(&path, now).unwrap());
+
+        fs::write(&path, "this is more than ten bytes\n").unwrap();
+        assert!(rotator.rotate_if_needed(&path, now).unwrap());
+        assert_eq!(fs::metadata(&path).unwrap().len(), 0);
+
+        let archives = archives(&path).unwrap();
+        assert_eq!(archives.len(), 1);
+        assert_eq!(fs::read_to_string(&archives[0]).unwrap(),
+                   "this is more than ten bytes\n");
+    }
+
+    #[test]
+    fn rotates_files_older_than_max_age() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("run.stderr.log");
+        let mut rotator = LogRotator::new(config(None, Some(Duration::from_secs(60)), false, 5));
+        let now = SystemTime::now();
+
+        fs::write(&path, "oops\n").unwrap();
+        assert!(!rotator.rotate_if_needed(&path, now).unwrap());
+        assert!(!rotator.rotate_if_needed(&path, now + Duration::from_secs(30))
+                        .unwrap());
+        assert!(rotator.rotate_if_needed(&path, now + Duration::from_secs(60))
+                       .unwrap());
+
+        // Age is measured from the last rotation, and empty files are
+        // never rotated
+        assert!(!rotator.rotate_if_needed(&path, now + Duration::from_secs(150))
+                        .unwrap());
+        fs::write(&path, "oops again\n").unwrap();
+        assert!(rotator.rotate_if_needed(&path, now + Duration::from_secs(150))
+                       .unwrap());
+    }
+
+    #[test]
+    fn compresses_rotated_files() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("run.stdout.log");
+        let mut rotator = LogRotator::new(config(Some(1), None, true, 5));
+
+        fs::write(&path, "compress me\n").unwrap();
+        assert!(rotator.rotate_if_needed(&path, SystemTime::now()).unwrap());
+
+        let archives = archives(&path).unwrap();
+        assert_eq!(archives.len(), 1);
+        assert_eq!(archives[0].extension().unwrap(), "gz");
+        let mut contents = String::new();
+        GzDecoder::new(File::open(&archives[0]).unwrap()).read_to_string(&mut contents)
+                                                         .unwrap();
+        assert_eq!(contents, "compress me\n");
+    }
+
+    #[test]
+    fn keeps_only_the_newest_archives() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("run.stdout.log");
+        let mut rotator = LogRotator::new(config(Some(1), None, false, 2));
+        let now = SystemTime::now();
+
+        for i in 0..4 {
+            fs::write(&path, format!("rotation {}\n", i)).unwrap();
+            assert!(rotator.rotate_if_needed(&path, now + Duration::from_secs(i))
+                           .unwrap());
+        }
+
+        let contents = archives(&path).unwrap()
+                                      .iter()
+                                      .map(|archive| fs::read_to_string(archive).unwrap())
+                                      .collect::<Vec<_>>();
+        assert_eq!(contents, vec!["rotation 2\n", "rotation 3\n"]);
+    }
+
+    #[test]
+    fn ignores_missing_files() {
+        let dir = TempDir::new().unwrap();
+        let mut rotator = LogRotator::new(config(Some(1), None, false, 5));
+        assert!(!rotator.rotate_if_needed(&dir.path().join("run.stdout.log"), SystemTime::now())
+                        .unwrap());
+    }
+}
@@ -0,0 +1,540 @@
+//! Discovers peers from sources outside the ring, so that Supervisors that come and go (for
+//! instance in an autoscaling group) can find each other without something keeping a
+//! `--peer-watch-file` up to date.
+//!
+//! Each source is asked for peers every refresh period, and the peers found by all of them
+//! replace the initial members of the ring, just like the contents of a peer watch file do.
+//! Sources are asked at the same time, and a source that doesn't answer within
+//! `HAB_PEER_DISCOVERY_TIMEOUT_SECS` is skipped.
+
+use crate::{error::{Error,
+                    Result},
+            manager::peer_watcher};
+use futures::future;
+use habitat_butterfly::member::Member;
+use habitat_common::types::GossipListenAddr;
+use hickory_resolver::{Resolver,
+                       TokioResolver,
+                       config::{NameServerConfigGroup,
+                                ResolverConfig},
+                       name_server::TokioConnectionProvider};
+use log::{debug,
+          warn};
+use std::{fmt,
+          net::SocketAddr,
+          path::PathBuf,
+          str::FromStr,
+          time::Duration};
+use tokio::{process::Command,
+            sync::watch,
+            time as tokiotime};
+use url::Url;
+
+/// The port of a name server given without one.
+const DNS_PORT: u16 = 53;
+
+habitat_core::env_config_duration!(
+    /// How long a source may take to discover peers before it is skipped.
+    PeerDiscoveryTimeout,
+    HAB_PEER_DISCOVERY_TIMEOUT_SECS => from_secs,
+    Duration::from_secs(30));
+
+/// Where to discover peers from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PeerDiscoverySource {
+    /// The targets of the SRV records for `name`, or the addresses of its A and AAAA records if it
+    /// has no SRV records. Queries go to `nameserver` if given, or to the system's name servers.
+    Dns {
+        name:       String,
+        nameserver: Option<SocketAddr>,
+    },
+    /// An executable that prints one `HOST[:PORT]` peer per line.
+    Exec(PathBuf),
+    /// An HTTP endpoint returning a JSON list of `HOST[:PORT]` peers.
+    Http(Url),
+}
+
+impl FromStr for PeerDiscoverySource {
+    type Err = Error;
+
+    /// Sources are given as `dns:NAME[@NAMESERVER[:PORT]]`, `exec:PATH`, or an `http://` or
+    /// `https://` URL.
+    fn from_str(value: &str) -> Result<Self> {
+        let invalid =
+            |reason: &str| Error::InvalidPeerDiscoverySource(value.to_string(), reason.to_string());
+        if let Some(dns) = value.strip_prefix("dns:") {
+            let (name, nameserver) = match dns.split_once('@') {
+                Some((name, nameserver)) => {
+                    let nameserver =
+                        nameserver.parse::<SocketAddr>()
+                                  .or_else(|_| {
+                                      nameserver.parse().map(|ip| SocketAddr::new(ip, DNS_PORT))
+                                  })
+                                  .map_err(|_| invalid("invalid name server address"))?;
+                    (name, Some(nameserver))
+                }
+                None => (dns, None),
+            };
+            if name.is_empty() {
+                return Err(invalid("missing name"));
+            }
+            Ok(PeerDiscoverySource::Dns { name: name.to_string(),
+                                          nameserver })
+        } else if let Some(path) = value.strip_prefix("exec:") {
+            if path.is_empty() {
+                return Err(invalid("missing path"));
+            }
+            Ok(PeerDiscoverySource::Exec(PathBuf::from(path)))
+        } else if value.starts_with("http://") || value.starts_with("https://") {
+            Url::parse(value).map(PeerDiscoverySource::Http)
+                             .map_err(|e| invalid(&e.to_string()))
+        } else {
+            Err(invalid("expected dns:NAME, exec:PATH, or an http(s) URL"))
+        }
+    }
+}
+
+impl fmt::Display for PeerDiscoverySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PeerDiscoverySource::Dns { name,
+                                       nameserver: Some(nameserver), } => {
+                write!(f, "dns:{}@{}", name, nameserver)
+            }
+            PeerDiscoverySource::Dns { name,
+                                       nameserver: None, } => write!(f, "dns:{}", name),
+            PeerDiscoverySource::Exec(path) => write!(f, "exec:{}", path.display()),
+            PeerDiscoverySource::Http(url) => write!(f, "{}", url),
+        }
+    }
+}
+
+impl PeerDiscoverySource {
+    /// The addresses of the peers this source currently knows of, if it answers within `timeout`.
+    /// An executable that doesn't is killed.
+    async fn discover(&self, timeout: Duration) -> Result<Vec<SocketAddr>> {
+        match tokiotime::timeout(timeout, self.discover_unbounded(timeout)).await {
+            Ok(result) => result,
+            Err(_) => Err(self.error(format!("timed out after {:?}", timeout))),
+        }
+    }
+
+    async fn discover_unbounded(&self, timeout: Duration) -> Result<Vec<SocketAddr>> {
+        match self {
+            PeerDiscoverySource::Dns { name, nameserver } => {
+                self.discover_dns(name, *nameserver).await
+            }
+            PeerDiscoverySource::Exec(path) => {
+                let output = Command::new(path).kill_on_drop(true)
+                                               .output()
+                                               .await
+                                               .map_err(|e| {
+                                                   self.error(format!("could not run {}: {}",
+                                                                      path.display(),
+                                                                      e))
+                                               })?;
+                if !output.status.success() {
+                    return Err(self.error(format!("{} exited with {}",
+                                                  path.display(),
+                                                  output.status)));
+                }
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                self.resolve_peers(stdout.lines()).await
+            }
+            PeerDiscoverySource::Http(url) => {
+                let client = reqwest::Client::builder().timeout(timeout)
+                                                       .build()
+                                                       .map_err(|e| self.error(e.to_string()))?;
+                let response = client.get(url.clone())
+                                     .send()
+                                     .await
+                                     .and_then(reqwest::Response::error_for_status)
+                                     .map_err(|e| self.error(e.to_string()))?;
+                let peers = response.json::<Vec<String>>()
+                                    .await
+                                    .map_err(|e| self.error(e.to_string()))?;
+                self.resolve_peers(peers.iter().map(String::as_str)).await
+            }
+        }
+    }
+
+    async fn discover_dns(&self,
+                          name: &str,
+                          nameserver: Option<SocketAddr>)
+                          -> Result<Vec<SocketAddr>> {
+        let resolver = match nameserver {
+            Some(nameserver) => {
+                let name_servers = NameServerConfigGroup::from_ips_clear(&[nameserver.ip()],
+                                                                         nameserver.port(),
+                                                                         true);
+                let config = ResolverConfig::from_parts(None, vec![], name_servers);
+                Resolver::builder_with_config(config, TokioConnectionProvider::default()).build()
+            }
+            None => {
+                TokioResolver::builder_tokio().map_err(|e| self.error(e.to_string()))?
+                                              .build()
+            }
+        };
+
+        let mut addrs = Vec::new();
+        match resolver.srv_lookup(name).await {
+            Ok(srv_lookup) => {
+                for srv in srv_lookup.iter() {
+                    match resolver.lookup_ip(srv.target().clone()).await {
+                        Ok(ips) => {
+                            addrs.extend(ips.iter().map(|ip| SocketAddr::new(ip, srv.port())))
+                        }
+                        Err(e) => {
+                            warn!("Skipping peer {} from {}: {}", srv.target(), self, e);
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                debug!("No SRV records for {} ({}), looking up its addresses",
+                       name, e);
+                let ips = resolver.lookup_ip(name)
+                                  .await
+                                  .map_err(|e| self.error(e.to_string()))?;
+                addrs.extend(ips.iter()
+                                .map(|ip| SocketAddr::new(ip, GossipListenAddr::DEFAULT_PORT)));
+            }
+        }
+        Ok(addrs)
+    }
+
+    async fn resolve_peers(&self, peers: impl Iterator<Item = &str>) -> Result<Vec<SocketAddr>> {
+        let mut addrs = Vec::new();
+        for peer in peers.map(str::trim).filter(|peer| !peer.is_empty()) {
+            let peer_addr = peer_watcher::peer_with_port(peer);
+            match tokio::net::lookup_host(&peer_addr).await {
+                Ok(resolved) => addrs.extend(resolved.take(1)),
+                Err(e) => warn!("Skipping peer {} from {}: {}", peer_addr, self, e),
+            }
+        }
+        Ok(addrs)
+    }
+
+    fn error(&self, reason: String) -> Error { Error::PeerDiscovery(self.to_string(), reason) }
+}
+
+/// Periodically discovers peers in the background.
+pub struct PeerDiscovery {
+    rx: watch::Receiver<Vec<SocketAddr>>,
+}
+
+impl PeerDiscovery {
+    /// Spawn a task asking every source for peers every `period`.
+    pub fn run(sources: Vec<PeerDiscoverySource>, period: Duration) -> Self {
+        let (tx, rx) = watch::channel(Vec::new());
+        let timeout = PeerDiscoveryTimeout::configured_value().into();
+        tokio::spawn(Self::discover_periodically(sources, period, timeout, tx));
+        PeerDiscovery { rx }
+    }
+
+    async fn discover_periodically(sources: Vec<PeerDiscoverySource>,
+                                   period: Duration,
+                                   timeout: Duration,
+                                   tx: watch::Sender<Vec<SocketAddr>>) {
+        while !tx.is_closed() {
+            if let Some(mut addrs) = discover_all(&sources, timeout).await {
+                addrs.sort();
+                addrs.dedup();
+                tx.send_if_modified(|current| {
+                      if *current == addrs {
+                          false
+                      } else {
+                          *current = addrs;
+                          true
+                      }
+                  });
+            }
+            tokiotime::sleep(period).await;
+        }
+    }
+
+    /// The discovered peers, if they changed since the last time this was called.
+    pub fn updated_members(&mut self) -> Option<Vec<Member>> {
+        match self.rx.has_changed() {
+            Ok(true) => {
+                Some(self.rx
+                         .borrow_and_update()
+                         .iter()
+                         .copied()
+                         .map(peer_watcher::member_from_addr)
+                         .collect())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Ask every source for peers at once. Sources that fail, or take longer than `timeout`, are
+/// skipped; if they all fail, we don't know any better than before and return `None`.
+async fn discover_all(sources: &[PeerDiscoverySource],
+                      timeout: Duration)
+                      -> Option<Vec<SocketAddr>> {
+    let results = future::join_all(sources.iter().map(|source| source.discover(timeout))).await;
+    let mut discovered = None;
+    for (source, result) in sources.iter().zip(results) {
+        match result {
+            Ok(addrs) => {
+                debug!("Discovered {} peers from {}", addrs.len(), source);
+                discovered.get_or_insert_with(Vec::new).extend(addrs);
+            }
+            Err(e) => warn!("{}", e),
+        }
+    }
+    discovered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{io::{Read,
+                   Write},
+              net::{Ipv4Addr,
+                    TcpListener,
+                    UdpSocket},
+              thread};
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    #[test]
+    fn parse_sources() {
+        assert_eq!("dns:hab.service.consul".parse::<PeerDiscoverySource>()
+                                           .unwrap(),
+                   PeerDiscoverySource::Dns { name:       String::from("hab.service.consul"),
+                                              nameserver: None, });
+        assert_eq!("dns:hab.service.consul@127.0.0.1:8600".parse::<PeerDiscoverySource>()
+                                                          .unwrap(),
+                   PeerDiscoverySource::Dns { name:       String::from("hab.service.consul"),
+                                              nameserver: Some("127.0.0.1:8600".parse().unwrap()), });
+        assert_eq!("dns:hab.example.com@10.0.0.2".parse::<PeerDiscoverySource>()
+                                                 .unwrap(),
+                   PeerDiscoverySource::Dns { name:       String::from("hab.example.com"),
+                                              nameserver: Some("10.0.0.2:53".parse().unwrap()), });
+        assert_eq!("exec:/bin/peers".parse::<PeerDiscoverySource>().unwrap(),
+                   PeerDiscoverySource::Exec(PathBuf::from("/bin/peers")));
+        assert_eq!("https://peers.example.com/ring".parse::<PeerDiscoverySource>()
+                                                   .unwrap(),
+                   PeerDiscoverySource::Http(Url::parse("https://peers.example.com/ring").unwrap()));
+
+        for invalid in ["dns:",
+                        "dns:name@nowhere",
+                        "exec:",
+                        "ftp://peers",
+                        "peers.example.com"]
+        {
+            assert!(invalid.parse::<PeerDiscoverySource>().is_err(),
+                    "{} should not parse",
+                    invalid);
+        }
+    }
+
+    /// Answer DNS queries for `_hab._udp.test` with an SRV record pointing at port 9700 of
+    /// `peer.test`, for `_mixed._udp.test` with SRV records pointing at port 9700 of
+    /// `_missing.test` and port 9701 of `peer.test`, and A queries for any other name not starting
+    /// with an underscore with 127.0.0.2.
+    fn dns_stub() -> SocketAddr {
+        let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = socket.local_addr().unwrap();
+        thread::spawn(move || {
+            let mut query = [0; 512];
+            while let Ok((len, from)) = socket.recv_from(&mut query) {
+                let query = &query[..len];
+                let mut labels = Vec::new();
+                let mut pos = 12;
+                while query[pos] != 0 {
+                    let len = query[pos] as usize;
+                    labels.push(String::from_utf8_lossy(&query[pos + 1..=pos + len]).to_string());
+                    pos += len + 1;
+                }
+                let question_end = pos + 5;
+                let qtype = u16::from_be_bytes([query[pos + 1], query[pos + 2]]);
+
+                let srv = |port: u16, target: &[u8]| {
+                    let mut rdata = vec![0, 10, 0, 10];
+                    rdata.extend_from_slice(&port.to_be_bytes());
+                    rdata.extend_from_slice(target);
+                    (33_u16, rdata)
+                };
+                let answers = match (qtype, labels.join(".").as_str()) {
+                    (33, "_hab._udp.test") => vec![srv(9700, b"\x04peer\x04test\x00")],
+                    (33, "_mixed._udp.test") => {
+                        vec![srv(9700, b"\x08_missing\x04test\x00"),
+                             srv(9701, b"\x04peer\x04test\x00")]
+                    }
+                    (1, name) if !name.starts_with('_') => vec![(1, vec![127, 0, 0, 2])],
+                    _ => vec![],
+                };
+
+                let mut response = Vec::from(&query[..2]);
+                response.extend_from_slice(&[0x81, 0x80, 0, 1, 0, answers.len() as u8, 0, 0, 0, 0]);
+                response.extend_from_slice(&query[12..question_end]);
+                for (rtype, rdata) in answers {
+                    response.extend_from_slice(&[0xc0, 0x0c]);
+                    response.extend_from_slice(&rtype.to_be_bytes());
+                    response.extend_from_slice(&[0, 1, 0, 0, 0, 60]);
+                    response.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+                    response.extend_from_slice(&rdata);
+                }
+                socket.send_to(&response, from).unwrap();
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn discover_from_dns_srv_records() {
+        let source = PeerDiscoverySource::Dns { name:       String::from("_hab._udp.test"),
+                                                nameserver: Some(dns_stub()), };
+        assert_eq!(source.discover(TIMEOUT).await.unwrap(),
+                   vec!["127.0.0.2:9700".parse::<SocketAddr>().unwrap()]);
+    }
+
+    #[tokio::test]
+    async fn dns_srv_targets_that_do_not_resolve_are_skipped() {
+        let source = PeerDiscoverySource::Dns { name:       String::from("_mixed._udp.test"),
+                                                nameserver: Some(dns_stub()), };
+        assert_eq!(source.discover(TIMEOUT).await.unwrap(),
+                   vec!["127.0.0.2:9701".parse::<SocketAddr>().unwrap()]);
+    }
+
+    #[tokio::test]
+    async fn discover_from_dns_address_records() {
+        let source = PeerDiscoverySource::Dns { name:       String::from("ring.test"),
+                                                nameserver: Some(dns_stub()), };
+        assert_eq!(source.discover(TIMEOUT).await.unwrap(),
+                   vec![SocketAddr::new([127, 0, 0, 2].into(), GossipListenAddr::DEFAULT_PORT)]);
+    }
+
+    #[tokio::test]
+    async fn discover_from_http() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            let body = r#"["127.0.0.3:9700", "127.0.0.4"]"#;
+            write!(stream,
+                   "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: \
+                    {}\r\nConnection: close\r\n\r\n{}",
+                   body.len(),
+                   body).unwrap();
+        });
+
+        let source = format!("http://{}/peers", addr).parse::<PeerDiscoverySource>()
+                                                     .unwrap();
+        assert_eq!(source.discover(TIMEOUT).await.unwrap(),
+                   vec!["127.0.0.3:9700".parse::<SocketAddr>().unwrap(),
+                        SocketAddr::new([127, 0, 0, 4].into(), GossipListenAddr::DEFAULT_PORT)]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn discover_from_exec() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmpdir = tempfile::TempDir::new().unwrap();
+        let path = tmpdir.path().join("peers");
+        std::fs::write(&path,
+                       "#!/bin/sh\necho 127.0.0.5:9700\necho\necho 127.0.0.6\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let source = PeerDiscoverySource::Exec(path);
+        assert_eq!(source.discover(TIMEOUT).await.unwrap(),
+                   vec!["127.0.0.5:9700".parse::<SocketAddr>().unwrap(),
+                        SocketAddr::new([127, 0, 0, 6].into(), GossipListenAddr::DEFAULT_PORT)]);
+
+        let malformed = exec_source(tmpdir.path(),
+                                    "malformed",
+                                    "echo 127.0.0.5:9700\necho 127.0.0.7:notaport\necho 127.0.0.6");
+        assert_eq!(malformed.discover(TIMEOUT).await.unwrap(),
+                   vec!["127.0.0.5:9700".parse::<SocketAddr>().unwrap(),
+                        SocketAddr::new([127, 0, 0, 6].into(), GossipListenAddr::DEFAULT_PORT)]);
+
+        let failing = PeerDiscoverySource::Exec(PathBuf::from("/bin/false"));
+        assert!(failing.discover(TIMEOUT).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn failing_sources_are_skipped() {
+        let tmpdir = tempfile::TempDir::new().unwrap();
+        let missing = PeerDiscoverySource::Exec(tmpdir.path().join("missing"));
+        assert_eq!(discover_all(std::slice::from_ref(&missing), TIMEOUT).await,
+                   None);
+
+        let dns = PeerDiscoverySource::Dns { name:       String::from("ring.test"),
+                                             nameserver: Some(dns_stub()), };
+        assert_eq!(discover_all(&[missing, dns], TIMEOUT).await,
+                   Some(vec![SocketAddr::new([127, 0, 0, 2].into(),
+                                             GossipListenAddr::DEFAULT_PORT)]));
+    }
+
+    /// Write an executable to `dir` that runs `script`.
+    #[cfg(unix)]
+    fn exec_source(dir: &std::path::Path, name: &str, script: &str) -> PeerDiscoverySource {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        PeerDiscoverySource::Exec(path)
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn sources_are_asked_at_once() {
+        let tmpdir = tempfile::TempDir::new().unwrap();
+        let sources = [exec_source(tmpdir.path(), "one", "sleep 1\necho 127.0.0.5"),
+                       exec_source(tmpdir.path(), "two", "sleep 1\necho 127.0.0.6")];
+
+        let start = std::time::Instant::now();
+        let discovered = discover_all(&sources, TIMEOUT).await;
+        assert!(start.elapsed() < Duration::from_millis(1900),
+                "sources were asked one after the other");
+        assert_eq!(discovered,
+                   Some(vec![SocketAddr::new([127, 0, 0, 5].into(),
+                                             GossipListenAddr::DEFAULT_PORT),
+                             SocketAddr::new([127, 0, 0, 6].into(),
+                                             GossipListenAddr::DEFAULT_PORT)]));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn sources_that_time_out_are_skipped_and_killed() {
+        let tmpdir = tempfile::TempDir::new().unwrap();
+        let finished = tmpdir.path().join("finished");
+        let slow = exec_source(tmpdir.path(),
+                               "slow",
+                               &format!("sleep 1\ntouch {}\necho 127.0.0.5", finished.display()));
+        let fast = exec_source(tmpdir.path(), "fast", "echo 127.0.0.6");
+
+        let timeout = Duration::from_millis(200);
+        assert!(slow.discover(timeout).await.is_err());
+        assert_eq!(discover_all(&[slow, fast], timeout).await,
+                   Some(vec![SocketAddr::new([127, 0, 0, 6].into(),
+                                             GossipListenAddr::DEFAULT_PORT)]));
+
+        tokiotime::sleep(Duration::from_secs(2)).await;
+        assert!(!finished.exists(),
+                "the slow source should have been killed");
+    }
+
+    #[tokio::test]
+    async fn http_sources_that_time_out_are_skipped() {
+        // Accept the connection, but never answer
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let _stream = listener.accept().unwrap();
+            thread::sleep(Duration::from_secs(5));
+        });
+
+        let source = format!("http://{}/peers", addr).parse::<PeerDiscoverySource>()
+                                                     .unwrap();
+        assert!(source.discover(Duration::from_millis(200)).await.is_err());
+    }
+}
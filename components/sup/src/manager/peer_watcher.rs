@@ -112,11 +112,7 @@ impl PeerWatcher {
 
         for line_result in reader.lines() {
             let line = line_result.map_err(Error::Io)?;
-            let peer_addr = if line.find(':').is_some() {
-                line
-            } else {
-                format!("{}:{}", line, GossipListenAddr::DEFAULT_PORT)
-            };
+            let peer_addr = peer_with_port(&line);
             let addrs: Vec<SocketAddr> = match peer_addr.to_socket_addrs() {
                 Ok(addrs) => addrs.collect(),
                 Err(e) => {
@@ -124,12 +120,7 @@ impl PeerWatcher {
                     return Err(Error::NameLookup(e));
                 }
             };
-            let addr: SocketAddr = addrs[0];
-            let member = Member { address: format!("{}", addr.ip()),
-                                  swim_port: addr.port(),
-                                  gossip_port: addr.port(),
-                                  ..Default::default() };
-            members.push(member);
+            members.push(member_from_addr(addrs[0]));
         }
 
         self.have_events.store(false, Ordering::Relaxed);
@@ -137,6 +128,22 @@ impl PeerWatcher {
     }
 }
 
+/// A peer given as `HOST[:PORT]`, with the default gossip port if it has none.
+pub fn peer_with_port(peer: &str) -> String {
+    if peer.find(':').is_some() {
+        peer.to_string()
+    } else {
+        format!("{}:{}", peer, GossipListenAddr::DEFAULT_PORT)
+    }
+}
+
+pub fn member_from_addr(addr: SocketAddr) -> Member {
+    Member { address: format!("{}", addr.ip()),
+             swim_port: addr.port(),
+             gossip_port: addr.port(),
+             ..Default::default() }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
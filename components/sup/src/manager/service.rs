@@ -12,8 +12,10 @@ mod context;
 mod health;
 mod hook_runner;
 mod hooks;
+pub mod logs;
 #[cfg(windows)]
 mod pipe_hook_client;
+mod scheduled;
 pub mod spec;
 mod supervisor;
 mod terminator;
@@ -23,6 +25,7 @@ use self::{context::RenderContext,
            hooks::{HookCompileTable,
                    HookTable,
                    HookTableQueryModel},
+           scheduled::ScheduledHookStatus,
            supervisor::{PidUpdate,
                         SupervisedProcessQueryModel,
                         Supervisor}};
@@ -31,6 +34,7 @@ pub use self::{health::{HealthCheckBundle,
                         HealthCheckResult},
                hooks::{HealthCheckHook,
                        ProcessOutput,
+                       RunHook,
                        StandardStreams},
                spec::{DesiredState,
                       ServiceSpec}};
@@ -74,11 +78,14 @@ use habitat_core::{ChannelIdent,
                              PackageInstall,
                              metadata::Bind},
                    service::{HealthCheckInterval,
+                             Schedule,
                              ServiceBind,
                              ServiceGroup}};
 use habitat_launcher_client::LauncherCli;
 use habitat_sup_protocol::types::BindingMode;
-pub use habitat_sup_protocol::types::{ProcessState,
+pub use habitat_sup_protocol::types::{FencingPolicy,
+                                      ProcessState,
+                                      RestartPolicy,
                                       Topology,
                                       UpdateCondition,
                                       UpdateStrategy};
@@ -87,12 +94,17 @@ use log::{debug,
 use parking_lot::RwLock;
 use prometheus::{HistogramTimer,
                  HistogramVec,
-                 register_histogram_vec};
+                 IntCounterVec,
+                 IntGaugeVec,
+                 register_histogram_vec,
+                 register_int_counter_vec,
+                 register_int_gauge_vec};
 use serde::{Deserialize,
             Serialize,
             Serializer,
             ser::{Error as _,
                   SerializeStruct}};
+use similar::TextDiff;
 use std::{self,
           collections::HashSet,
           convert::TryFrom,
@@ -102,8 +114,11 @@ use std::{self,
           path::{Path,
                  PathBuf},
           result,
+          str::FromStr,
           sync::{Arc,
-                 Mutex},
+                 Mutex,
+                 atomic::{AtomicBool,
+                          Ordering}},
           time::{Duration,
                  SystemTime}};
 
@@ -115,11 +130,40 @@ static LOGKEY: &str = "SR";
 #[cfg(not(windows))]
 pub const GOSSIP_FILE_PERMISSIONS: u32 = 0o640;
 
+/// The hooks whose durations are tracked per service by `hab_sup_service_hook_duration_seconds`.
+const TIMED_HOOKS: [&str; 13] = ["fence",
+                                 "file-updated",
+                                 "health-check",
+                                 "init",
+                                 "install",
+                                 "post-run",
+                                 "post-stop",
+                                 "readiness-check",
+                                 "reconfigure",
+                                 "run",
+                                 "scheduled",
+                                 "suitability",
+                                 "uninstall"];
+
 lazy_static! {
     static ref HOOK_DURATION: HistogramVec =
         register_histogram_vec!("hab_sup_hook_duration_seconds",
                                 "The time it takes for a hook to run",
                                 &["hook"]).unwrap();
+    static ref SERVICE_HOOK_DURATION: HistogramVec =
+        register_histogram_vec!("hab_sup_service_hook_duration_seconds",
+                                "The time it takes for a hook of the service to run",
+                                &["service_group", "hook"]).unwrap();
+    static ref HEALTH_CHECK_RESULT: IntGaugeVec =
+        register_int_gauge_vec!("hab_sup_service_health_check_result",
+                                "The result of the service's most recent health check, using the \
+                                 health-check hook exit codes (0 = OK, 1 = WARNING, 2 = \
+                                 CRITICAL, 3 = UNKNOWN)",
+                                &["service_group"]).unwrap();
+    static ref HEALTH_CHECKS: IntCounterVec =
+        register_int_counter_vec!("hab_sup_service_health_checks_total",
+                                  "Total number of health checks run for the service, by result",
+                                  &["service_group", "result"]).unwrap();
 }
 
 /// When evaluating whether a particular service group can satisfy a
@@ -213,6 +257,8 @@ pub enum ProcessTerminationReason {
     InitHookFailed,
     #[serde(rename = "run_hook_failed")]
     RunHookFailed,
+    #[serde(rename = "run_hook_exited")]
+    RunHookExited,
     #[serde(rename = "app_config_updated")]
     AppConfigUpdated,
     #[serde(rename = "init_hook_updated")]
@@ -221,6 +267,55 @@ pub enum ProcessTerminationReason {
     RunHookUpdated,
     #[serde(rename = "post_run_hook_updated")]
     PostRunHookUpdated,
+    #[serde(rename = "liveness_check_failed")]
+    LivenessCheckFailed,
+    #[serde(rename = "fenced")]
+    Fenced,
+    #[serde(rename = "integrity_check_failed")]
+    IntegrityCheckFailed,
+}
+
+impl fmt::Display for ProcessTerminationReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            ProcessTerminationReason::PackageUpdated => "package updated",
+            ProcessTerminationReason::InitHookFailed => "init hook failed",
+            ProcessTerminationReason::RunHookFailed => "run hook failed",
+            ProcessTerminationReason::RunHookExited => "run hook exited",
+            ProcessTerminationReason::AppConfigUpdated => "app config updated",
+            ProcessTerminationReason::InitHookUpdated => "init hook updated",
+            ProcessTerminationReason::RunHookUpdated => "run hook updated",
+            ProcessTerminationReason::PostRunHookUpdated => "post-run hook updated",
+            ProcessTerminationReason::LivenessCheckFailed => "liveness check failed",
+            ProcessTerminationReason::Fenced => "fenced after losing quorum",
+            ProcessTerminationReason::IntegrityCheckFailed => {
+                "installed files do not match the signed FILES"
+            }
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+/// What applying a new gossip configuration to a service would do.
+#[derive(Debug)]
+pub struct ConfigPreview {
+    /// A unified diff of each rendered configuration file that would change.
+    pub file_diffs:    Vec<String>,
+    /// The file names of the hooks whose rendered content would change.
+    pub changed_hooks: Vec<&'static str>,
+    /// Why the service would be restarted, if it would be.
+    pub restart:       Option<ProcessTerminationReason>,
+    /// If the service's `reconfigure` hook would run.
+    pub reconfigure:   bool,
+}
+
+/// Why a service whose process went down is not being restarted.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ParkedState {
+    /// The run hook exited cleanly and the restart policy does not restart clean exits.
+    Exited,
+    /// The service failed and the restart policy does not allow restarting it again.
+    Failed,
 }
 
 #[derive(Debug, Clone)]
@@ -260,20 +355,89 @@ pub struct ServiceRunState {
     current_pid:            Option<Pid>,
     restart_state:          RestartState,
     restart_backoff:        Backoff,
+    /// The number of restarts since the service last stayed up for the cooldown period. This is
+    /// what `max_restarts` is checked against.
+    consecutive_restarts:   u32,
+    /// Set when the restart policy decided not to restart the service. A parked service stays
+    /// down until it is restarted intentionally.
+    parked:                 Option<ParkedState>,
     last_updated_at:        SystemTime,
 }
 
 impl ServiceRunState {
     pub fn new(restart_config: &ServiceRestartConfig) -> ServiceRunState {
-        ServiceRunState { restart_count:      0,
-                          restart_config:     restart_config.clone(),
-                          last_process_state: None,
-                          current_pid:        None,
-                          restart_state:      RestartState::None,
-                          restart_backoff:    Backoff::new(restart_config.min_backoff_period,
-                                                           restart_config.max_backoff_period,
-                                                           3f64),
-                          last_updated_at:    SystemTime::now(), }
+        ServiceRunState { restart_count:        0,
+                          restart_config:       restart_config.clone(),
+                          last_process_state:   None,
+                          current_pid:          None,
+                          restart_state:        RestartState::None,
+                          restart_backoff:      Self::backoff(restart_config),
+                          consecutive_restarts: 0,
+                          parked:               None,
+                          last_updated_at:      SystemTime::now(), }
+    }
+
+    fn backoff(restart_config: &ServiceRestartConfig) -> Backoff {
+        Backoff::new(restart_config.min_backoff_period,
+                     restart_config.max_backoff_period,
+                     3f64)
+    }
+
+    /// Apply a new restart configuration. Any backoff accumulated so far is discarded.
+    pub fn set_restart_config(&mut self, restart_config: ServiceRestartConfig) {
+        self.restart_backoff = Self::backoff(&restart_config);
+        self.restart_config = restart_config;
+        self.last_updated_at = SystemTime::now();
+    }
+
+    pub fn parked(&self) -> Option<ParkedState> { self.parked }
+
+    /// Decide, according to the restart policy, whether a service whose process went down for
+    /// `reason` should be restarted. The service is either marked for restart or parked; the
+    /// returned value is `Some` if it was parked.
+    pub fn mark_for_restart_or_park(&mut self,
+                                    old_pid: Option<Pid>,
+                                    reason: ProcessTerminationReason,
+                                    timestamp: SystemTime)
+                                    -> Option<ParkedState> {
+        let exited_cleanly = reason == ProcessTerminationReason::RunHookExited;
+        let restart_limit_reached = self.restart_config
+                                        .max_restarts
+                                        .is_some_and(|max| self.consecutive_restarts >= max);
+        let parked = match self.restart_config.policy {
+            RestartPolicy::Always if !restart_limit_reached => None,
+            RestartPolicy::OnFailure if !restart_limit_reached && !exited_cleanly => None,
+            RestartPolicy::Always | RestartPolicy::OnFailure | RestartPolicy::Never => {
+                if exited_cleanly {
+                    Some(ParkedState::Exited)
+                } else {
+                    Some(ParkedState::Failed)
+                }
+            }
+        };
+        match parked {
+            Some(parked) => {
+                self.parked = Some(parked);
+                self.last_process_state = Some(LastProcessState { pid:                old_pid,
+                                                                  terminated_at:      timestamp,
+                                                                  termination_reason: reason, });
+                self.last_updated_at = timestamp;
+            }
+            None => self.mark_for_restart(old_pid, reason, timestamp),
+        }
+        parked
+    }
+
+    /// Park a service that was not started because the installed files of its package, or of its
+    /// dependencies, do not match their signed `FILES` metafiles.
+    pub fn mark_integrity_check_failed(&mut self, timestamp: SystemTime) {
+        self.parked = Some(ParkedState::Failed);
+        self.last_process_state =
+            Some(LastProcessState { pid:                None,
+                                    terminated_at:      timestamp,
+                                    termination_reason:
+                                        ProcessTerminationReason::IntegrityCheckFailed, });
+        self.last_updated_at = timestamp;
     }
 
     pub fn mark_for_restart(&mut self,
@@ -297,12 +461,15 @@ impl ServiceRunState {
                                                           termination_reason: reason, });
         // Immediate restarts wipe out the restart out
         self.restart_count = 0;
+        self.consecutive_restarts = 0;
+        self.parked = None;
         self.restart_backoff.reset();
         self.last_updated_at = timestamp;
     }
 
     pub fn reset_backoff(&mut self) {
         self.restart_backoff.reset();
+        self.consecutive_restarts = 0;
         self.last_updated_at = SystemTime::now();
     }
 }
@@ -331,6 +498,11 @@ impl PersistentServiceWrapper {
 
     pub fn service_run_state_mut(&mut self) -> &ServiceRunState { &mut self.run_state }
 
+    /// Apply a new restart configuration, e.g. after the service's spec changed
+    pub fn set_restart_config(&mut self, restart_config: ServiceRestartConfig) {
+        self.run_state.set_restart_config(restart_config);
+    }
+
     /// Mark this service for an immediate restart
     pub fn mark_for_restart_due_to_update(&mut self, timestamp: SystemTime) {
         self.run_state
@@ -391,6 +563,7 @@ impl PersistentServiceWrapper {
                                                    .record_attempt_start()
                                                    .unwrap_or_default();
                         self.run_state.restart_count += 1;
+                        self.run_state.consecutive_restarts += 1;
                         if restart_duration == Duration::from_secs(0) {
                             outputln!(preamble service.service_group, "Stopping service, will restart immediately");
                         } else {
@@ -476,7 +649,18 @@ pub struct Service {
     // hook, we need to wrap some Arc<Mutex<_>> protection around it
     // :(
     health_check_result:  Arc<Mutex<HealthCheckResult>>,
+    /// Whether the service passes its readiness check, as last reported by the health check
+    /// future. Services without a `readiness-check` hook are always ready.
+    ready:                Arc<Mutex<bool>>,
+    /// The readiness last gossiped in this service's rumor.
+    advertised_ready:     bool,
+    /// Set by the health check future once the service has failed `liveness_threshold`
+    /// consecutive health checks, and consumed when the service is marked for restart.
+    liveness_failed:      Arc<AtomicBool>,
     last_election_status: ElectionStatus,
+    /// The fencing policy that was applied when this member lost quorum as the leader of its
+    /// service group, until a leader is elected again.
+    fenced:               Option<FencingPolicy>,
     /// The binds that the current service package declares, both
     /// required and optional. We don't differentiate because this is
     /// used to validate the user-specified bindings against the
@@ -504,9 +688,17 @@ pub struct Service {
     /// A "handle" to the never-ending future that periodically runs
     /// health checks on this service. This is the means by which we
     /// can stop that future.
-    health_check_handle: Option<AbortHandle>,
-    post_run_handle:     Option<AbortHandle>,
-    initialize_handle:   Option<AbortHandle>,
+    health_check_handle:     Option<AbortHandle>,
+    post_run_handle:         Option<AbortHandle>,
+    initialize_handle:       Option<AbortHandle>,
+    /// A "handle" to the never-ending future that runs the `scheduled` hook on the service's
+    /// schedule.
+    scheduled_hook_handle:   Option<AbortHandle>,
+    /// The schedule that the future of `scheduled_hook_handle` runs the `scheduled` hook on.
+    scheduled_hook_schedule: Option<Schedule>,
+    /// The process of the run of the `scheduled` hook that is in progress, if any.
+    scheduled_hook_pid:      Arc<Mutex<Option<u32>>>,
+    scheduled_hook_status:   Arc<Mutex<ScheduledHookStatus>>,
 }
 
 impl Service {
@@ -531,6 +723,14 @@ impl Service {
         self.spec = spec
     }
 
+    pub(crate) fn health_check_result(&self) -> HealthCheckResult {
+        *self.health_check_result
+             .lock()
+             .expect("Couldn't lock health check result")
+    }
+
+    pub(crate) fn ready(&self) -> bool { *self.ready.lock().expect("Couldn't lock readiness") }
+
     #[allow(clippy::too_many_arguments)]
     async fn with_package(sys: Arc<Sys>,
                           package: &PackageInstall,
@@ -547,22 +747,32 @@ impl Service {
         if let Some(timeout) = spec.shutdown_timeout {
             pkg.shutdown_timeout = timeout;
         }
+        if let Some(health_check) = &spec.health_check {
+            pkg.health_check = Some(health_check.clone());
+        }
         let spec_file = manager_fs_cfg.specs_path.join(spec.file());
         let service_group = ServiceGroup::new(&pkg.name, &spec.group, organization)?;
         let config_root = Self::config_root(&pkg, spec.config_from.as_ref());
         let hooks_root = Self::hooks_root(&pkg, spec.config_from.as_ref());
         let cfg = Cfg::new(&pkg, spec.config_from.as_ref())?;
+        let hooks = HookTable::load(&pkg.name,
+                                    hooks_root,
+                                    svc_hooks_path(service_group.service()),
+                                    feature_flags);
+        // A service with a readiness check is not ready until the check first passes
+        let ready = hooks.readiness_check.is_none();
         let mut service =
             Service { spec,
                       sys,
                       cfg,
                       config_renderer: CfgRenderer::new(config_root)?,
                       health_check_result: Arc::new(Mutex::new(HealthCheckResult::Unknown)),
-                      hooks: HookTable::load(&pkg.name,
-                                             hooks_root,
-                                             svc_hooks_path(service_group.service()),
-                                             feature_flags),
+                      ready: Arc::new(Mutex::new(ready)),
+                      advertised_ready: ready,
+                      liveness_failed: Arc::new(AtomicBool::new(false)),
+                      hooks,
                       last_election_status: ElectionStatus::None,
+                      fenced: None,
                       user_config_updated: false,
                       initialization_state:
                           Arc::new(RwLock::new(InitializationState::Uninitialized)),
@@ -576,7 +786,11 @@ impl Service {
                       gateway_state,
                       health_check_handle: None,
                       post_run_handle: None,
-                      initialize_handle: None };
+                      initialize_handle: None,
+                      scheduled_hook_handle: None,
+                      scheduled_hook_schedule: None,
+                      scheduled_hook_pid: Arc::default(),
+                      scheduled_hook_status: Arc::default() };
 
         // Update the service gossip from census data.
         // We do this to ensure that the data rendered out via the HTTP API through the ServiceProxy
@@ -668,10 +882,13 @@ impl Service {
                          .start(&self.pkg,
                                 &self.service_group,
                                 launcher,
-                                self.spec.svc_encrypted_password.as_deref());
+                                self.spec.svc_encrypted_password.as_deref(),
+                                self.spec.resource_limits(),
+                                self.spec.sandbox(&self.pkg));
         match result {
             Ok(_) => {
                 self.start_health_checks();
+                self.start_scheduled_hook();
             }
             Err(e) => {
                 outputln!(preamble self.service_group, "Service start failed: {}", e);
@@ -688,11 +905,15 @@ impl Service {
     ///
     /// * Cache the health check result for this service
     /// * Set the health check result for this service in the gateway state
+    /// * Record the health check result in the service's Prometheus metrics
     /// * Send a `HealthCheckEvent` over the event stream
+    /// * Cache whether the service is ready, so that it can be gossiped
+    /// * Flag the service for restart once it fails its liveness threshold
     fn start_health_checks(&mut self) {
         debug!("Starting health checks for {}", self.pkg.ident);
         let mut rx = health::check_repeatedly(Arc::clone(&self.supervisor),
                                               self.hooks.health_check.clone(),
+                                              self.hooks.readiness_check.clone(),
                                               self.spec.health_check_interval,
                                               self.service_group.clone(),
                                               self.pkg.clone(),
@@ -701,16 +922,43 @@ impl Service {
         let service_group = self.service_group.clone();
         let service_event_metadata = self.to_service_metadata();
         let service_health_result = Arc::clone(&self.health_check_result);
+        let service_ready = Arc::clone(&self.ready);
+        let liveness_failed = Arc::clone(&self.liveness_failed);
+        let liveness_threshold = self.spec.liveness_threshold;
         let gateway_state = Arc::clone(&self.gateway_state);
         let f = async move {
+            let mut consecutive_failures = 0;
             while let Some(HealthCheckBundle { status,
                                                result,
-                                               interval, }) = rx.recv().await
+                                               interval,
+                                               ready, }) = rx.recv().await
             {
                 debug!("Caching HealthCheckResult = '{}' for '{}'",
                        result, service_group);
+                let service_group_label = service_group.to_string();
+                HEALTH_CHECK_RESULT.with_label_values(&[&service_group_label])
+                                   .set(i64::from(result));
+                HEALTH_CHECKS.with_label_values(&[&service_group_label, &result.to_string()])
+                             .inc();
                 *service_health_result.lock()
                                       .expect("Could not unlock service_health_result") = result;
+                *service_ready.lock()
+                              .expect("Could not unlock service_ready") = ready;
+
+                if result == HealthCheckResult::Critical {
+                    consecutive_failures += 1;
+                } else {
+                    consecutive_failures = 0;
+                }
+                if let Some(threshold) = liveness_threshold
+                   && consecutive_failures >= threshold
+                {
+                    outputln!(preamble service_group,
+                              "Failed {} consecutive health checks, liveness threshold reached",
+                              consecutive_failures);
+                    liveness_failed.store(true, Ordering::Relaxed);
+                    consecutive_failures = 0;
+                }
 
                 gateway_state.lock_gsw()
                              .get_services_data_mut()
@@ -749,6 +997,79 @@ impl Service {
         self.start_health_checks();
     }
 
+    /// When the `scheduled` hook runs: the `schedule` of the `[scheduled_hook]` table in the
+    /// service's `user.toml`, or the package's schedule.
+    fn schedule(&self) -> Option<Schedule> {
+        let user_schedule = self.cfg
+                                .user
+                                .as_ref()
+                                .and_then(|user| user.get("scheduled_hook"))
+                                .and_then(|table| table.get("schedule"))
+                                .and_then(toml::Value::as_str);
+        match user_schedule.map(Schedule::from_str) {
+            Some(Ok(schedule)) => Some(schedule),
+            Some(Err(e)) => {
+                outputln!(preamble self.service_group,
+                          "Ignoring the schedule in user.toml: {}", e);
+                self.pkg.schedule.clone()
+            }
+            None => self.pkg.schedule.clone(),
+        }
+    }
+
+    /// Initiate an endless task that runs the `scheduled` hook on the service's schedule, if the
+    /// service has both.
+    fn start_scheduled_hook(&mut self) {
+        let Some(hook) = self.hooks.scheduled.clone() else {
+            return;
+        };
+        let Some(schedule) = self.schedule() else {
+            outputln!(preamble self.service_group,
+                      "Not running the scheduled hook; no schedule is set in the plan or \
+                       user.toml");
+            return;
+        };
+        debug!("Running the scheduled hook for {} on schedule '{}'",
+               self.pkg.ident, schedule);
+        let f = scheduled::run_on_schedule(hook,
+                                           schedule.clone(),
+                                           self.service_group.clone(),
+                                           self.pkg.clone(),
+                                           self.spec.svc_encrypted_password.clone(),
+                                           self.to_service_metadata(),
+                                           Arc::clone(&self.scheduled_hook_pid),
+                                           Arc::clone(&self.scheduled_hook_status));
+        let (f, handle) = future::abortable(f);
+        self.scheduled_hook_handle = Some(handle);
+        self.scheduled_hook_schedule = Some(schedule);
+        tokio::spawn(f);
+    }
+
+    /// Stop the endless future that runs the `scheduled` hook. A run which is in progress is
+    /// abandoned, and its process terminated.
+    fn stop_scheduled_hook(&mut self) {
+        self.scheduled_hook_schedule = None;
+        if let Some(h) = self.scheduled_hook_handle.take() {
+            debug!("Stopping the scheduled hook for {}", self.pkg.ident);
+            h.abort();
+            self.scheduled_hook_status
+                .lock()
+                .expect("Couldn't lock scheduled hook status")
+                .next_run_at = None;
+            let pid = self.scheduled_hook_pid
+                          .lock()
+                          .expect("Couldn't lock scheduled hook pid")
+                          .take();
+            if let Some(pid) = pid {
+                outputln!(preamble self.service_group,
+                          "Terminating the scheduled hook run in progress (PID: {})", pid);
+                tokio::spawn(terminator::terminate_service(pid as Pid,
+                                                           self.service_group.clone(),
+                                                           ShutdownConfig::new(None, self)));
+            }
+        }
+    }
+
     /// Called when the Supervisor reattaches itself to an already
     /// running service. Use this to re-initiate any associated
     /// processes, futures, etc.
@@ -758,6 +1079,8 @@ impl Service {
         outputln!("Reattaching to {}", self.service_group);
         *self.initialization_state.write() = InitializationState::Initialized;
         self.restart_health_checks();
+        self.stop_scheduled_hook();
+        self.start_scheduled_hook();
         // We intentionally do not restart the `post_run` retry future. Currently, there is not
         // a way to track if `post_run` ran successfully following a Supervisor restart.
         // See https://github.com/habitat-sh/habitat/issues/6739
@@ -779,6 +1102,7 @@ impl Service {
         self.stop_initialize();
         self.stop_post_run();
         self.stop_health_checks();
+        self.stop_scheduled_hook();
     }
 
     /// Return a future that will shut down a service, performing any
@@ -809,12 +1133,41 @@ impl Service {
         self.supervisor
             .lock()
             .expect("Couldn't lock supervisor")
-            .state_entered()
+            .last_change()
+    }
+
+    /// Stop exporting the metrics of the service, once it is unloaded.
+    pub fn remove_metrics(&self) {
+        let service_group = self.service_group.to_string();
+        for hook in TIMED_HOOKS {
+            SERVICE_HOOK_DURATION.remove_label_values(&[service_group.as_str(), hook])
+                                 .ok();
+        }
+        HEALTH_CHECK_RESULT.remove_label_values(&[&service_group])
+                           .ok();
+        for result in [HealthCheckResult::Ok,
+                       HealthCheckResult::Warning,
+                       HealthCheckResult::Critical,
+                       HealthCheckResult::Unknown]
+        {
+            HEALTH_CHECKS.remove_label_values(&[&service_group, &result.to_string()])
+                         .ok();
+        }
+        supervisor::remove_metrics(&self.service_group);
+    }
+
+    /// Report the service's process as failed, for a service that the Supervisor refused to start.
+    pub fn mark_failed(&self) {
+        self.supervisor
+            .lock()
+            .expect("Couldn't lock supervisor")
+            .mark_failed();
     }
 
     /// Performs updates and executes hooks.
     ///
-    /// Returns `true` if the service was marked to be restarted or reconfigured.
+    /// Returns `true` if the service was marked to be restarted or reconfigured, or its readiness
+    /// changed and needs to be gossiped.
     fn tick(&mut self,
             run_state: &mut ServiceRunState,
             census_ring: &CensusRing,
@@ -879,6 +1232,9 @@ impl Service {
 
                             self.last_election_status = census_group.election_status;
                         }
+                        if self.fenced.is_none() && census_group.me().is_some_and(|me| me.fenced) {
+                            self.fence();
+                        }
                     }
                     ElectionStatus::ElectionFinished => {
                         let leader_id = census_group.leader_id
@@ -890,12 +1246,35 @@ impl Service {
                                       leader_id);
                             self.last_election_status = census_group.election_status;
                         }
-                        self.execute_hooks(run_state, launcher, &template_update)
+                        let fenced = self.fenced.take();
+                        if fenced.is_some() {
+                            outputln!(preamble self.service_group,
+                                      "No longer fenced; {} is the leader",
+                                      leader_id);
+                        }
+                        if fenced == Some(FencingPolicy::Stop) {
+                            // Bring the stopped process back like any other restart, rather than
+                            // letting it look like it went down on its own
+                            run_state.mark_for_immediate_restart(None,
+                                                                 ProcessTerminationReason::Fenced,
+                                                                 SystemTime::now());
+                        } else {
+                            self.execute_hooks(run_state, launcher, &template_update)
+                        }
                     }
                 }
             }
         };
-        template_data_changed
+
+        let ready = self.ready();
+        let readiness_changed = ready != self.advertised_ready;
+        if readiness_changed {
+            outputln!(preamble self.service_group,
+                      "Service is {}",
+                      if ready { "ready" } else { "not ready" });
+            self.advertised_ready = ready;
+        }
+        template_data_changed || readiness_changed
     }
 
     /// Iterate through all the service binds, marking any that are
@@ -1061,6 +1440,22 @@ impl Service {
         }
     }
 
+    /// Reloads the user-config and reschedules the `scheduled` hook if its schedule was set,
+    /// changed or removed in it. The hook is only scheduled once the service is initialized;
+    /// until then, starting the service schedules it.
+    fn reload_user_config(&mut self) {
+        if let Err(e) = self.cfg.reload_user() {
+            outputln!(preamble self.service_group, "Reloading user-config failed: {}", e);
+        }
+        if self.hooks.scheduled.is_some()
+           && self.initialized()
+           && self.schedule() != self.scheduled_hook_schedule
+        {
+            self.stop_scheduled_hook();
+            self.start_scheduled_hook();
+        }
+    }
+
     /// Compares the current state of the service to the current state of the census ring and the
     /// user-config, and re-renders all templatable content to disk.
     fn update_templates(&mut self, census_ring: &CensusRing) -> (bool, TemplateUpdate) {
@@ -1071,10 +1466,7 @@ impl Service {
         let template_data_changed = cfg_updated_from_rumors || self.user_config_updated;
 
         if self.user_config_updated {
-            if let Err(e) = self.cfg.reload_user() {
-                outputln!(preamble self.service_group, "Reloading user-config failed: {}", e);
-            }
-
+            self.reload_user_config();
             self.user_config_updated = false;
         }
 
@@ -1089,6 +1481,52 @@ impl Service {
         (template_data_changed, template_update)
     }
 
+    /// Render the configuration templates and hooks of this service as they would be if `gossip`
+    /// were applied to its service group as `incarnation`, without changing the service.
+    ///
+    /// The configuration files are compiled into `scratch_path` and compared to the ones the
+    /// service is currently using.
+    pub fn preview_gossip_config(&self,
+                                 census_ring: &CensusRing,
+                                 incarnation: u64,
+                                 gossip: toml::value::Table,
+                                 scratch_path: &Path)
+                                 -> Result<ConfigPreview> {
+        let mut cfg = self.cfg.clone();
+        cfg.set_gossip(incarnation, gossip);
+        let ctx = self.render_context_for_cfg(&cfg, census_ring);
+
+        self.config_renderer
+            .compile(&ctx.service_group_name(), &self.pkg, scratch_path, &ctx)?;
+        let mut file_diffs = Vec::new();
+        for file in self.config_renderer.rendered_files() {
+            let current =
+                fs::read_to_string(self.pkg.svc_config_path.join(file)).unwrap_or_default();
+            let proposed = fs::read_to_string(scratch_path.join(file))?;
+            if current != proposed {
+                let diff = TextDiff::from_lines(&current, &proposed);
+                file_diffs.push(diff.unified_diff()
+                                    .header(&format!("{} (current)", file),
+                                            &format!("{} (proposed)", file))
+                                    .to_string());
+            }
+        }
+
+        let hooks = self.hooks.preview(&ctx)?;
+        let changed_hooks = hooks.changed_hooks();
+        let template_update = TemplateUpdate::new(hooks,
+                                                  !file_diffs.is_empty(),
+                                                  self.hooks.reconfigure.is_some());
+        let restart = template_update.needs_restart();
+        let reconfigure = restart.is_none()
+                          && template_update.needs_reconfigure()
+                          && self.hooks.reconfigure.is_some();
+        Ok(ConfigPreview { file_diffs,
+                           changed_hooks,
+                           restart,
+                           reconfigure })
+    }
+
     pub fn to_rumor(&self, incarnation: u64, pkg_incarnation: u64) -> ServiceRumor {
         let exported = match self.cfg.to_exported(&self.pkg) {
             Ok(exported) => Some(exported),
@@ -1106,6 +1544,7 @@ impl Service {
                                           exported);
         rumor.incarnation = incarnation;
         rumor.pkg_incarnation = pkg_incarnation;
+        rumor.ready = self.ready();
         rumor
     }
 
@@ -1154,7 +1593,7 @@ impl Service {
 
     /// Run reconfigure hook if present.
     fn reconfigure(&mut self) {
-        let _timer = hook_timer("reconfigure");
+        let _timer = hook_timer("reconfigure", &self.service_group);
 
         if let Some(ref hook) = self.hooks.reconfigure {
             hook.run(&self.service_group,
@@ -1199,7 +1638,7 @@ impl Service {
     }
 
     pub fn suitability(&self) -> Option<u64> {
-        let _timer = hook_timer("suitability");
+        let _timer = hook_timer("suitability", &self.service_group);
 
         if !self.initialized() {
             return None;
@@ -1308,6 +1747,17 @@ impl Service {
         // having to lock the supervisor for this information.
         run_state.current_pid = pid_update.new_pid;
 
+        // A parked service stays down until it is restarted intentionally, which includes an
+        // update to its run hook or configuration.
+        if run_state.parked().is_some() {
+            if let Some(termination_reason) = template_update.needs_restart() {
+                run_state.mark_for_immediate_restart(pid_update.new_pid,
+                                                     termination_reason,
+                                                     SystemTime::now());
+            }
+            return;
+        }
+
         // It is ok that we do not hold this lock while we are performing the match. If we
         // transistion states while we are matching, we will catch the new state on the next tick.
         let initialization_state = self.initialization_state.read().clone();
@@ -1323,9 +1773,10 @@ impl Service {
                 }
             }
             InitializationState::InitializerFailed(failed_at) => {
-                run_state.mark_for_restart(None,
-                                           ProcessTerminationReason::InitHookFailed,
-                                           failed_at);
+                self.mark_for_restart_or_park(run_state,
+                                              None,
+                                              ProcessTerminationReason::InitHookFailed,
+                                              failed_at);
             }
             InitializationState::Initializing => {
                 // Wait until the initializer finishes running
@@ -1344,16 +1795,36 @@ impl Service {
                              })
                              .unwrap_or(false);
                 // If the service is initialized and the process is not running, the process
-                // unexpectedly died and needs to be restarted.
+                // exited and may need to be restarted.
                 if !pid_update.is_running() {
-                    run_state.mark_for_restart(pid_update.old_pid,
-                                               ProcessTerminationReason::RunHookFailed,
-                                               pid_update.timestamp.expect("Process update time \
-                                                                            should be present"));
+                    let reason = if pid_update.exit_code == Some(0) {
+                        ProcessTerminationReason::RunHookExited
+                    } else {
+                        ProcessTerminationReason::RunHookFailed
+                    };
+                    let terminated_at = pid_update.timestamp
+                                                  .expect("Process update time should be present");
+                    self.mark_for_restart_or_park(run_state,
+                                                  pid_update.old_pid,
+                                                  reason,
+                                                  terminated_at);
                 } else if let Some(termination_reason) = template_update.needs_restart() {
                     run_state.mark_for_immediate_restart(pid_update.new_pid,
                                                          termination_reason,
                                                          SystemTime::now());
+                } else if self.liveness_failed.swap(false, Ordering::Relaxed) {
+                    self.mark_for_restart_or_park(run_state,
+                                                  pid_update.new_pid,
+                                                  ProcessTerminationReason::LivenessCheckFailed,
+                                                  SystemTime::now());
+                    // A service that will not be restarted is stopped rather than left running
+                    // while it fails its health checks
+                    if run_state.parked().is_some() {
+                        self.supervisor
+                            .lock()
+                            .expect("Couldn't lock supervisor")
+                            .stop(ShutdownConfig::new(None, self));
+                    }
                 } else if template_update.needs_reconfigure() {
                     // Only reconfigure if we did NOT restart the service
                     self.reconfigure();
@@ -1369,9 +1840,72 @@ impl Service {
         };
     }
 
+    /// Mark the service for restart, unless its restart policy says it should stay down.
+    fn mark_for_restart_or_park(&self,
+                                run_state: &mut ServiceRunState,
+                                old_pid: Option<Pid>,
+                                reason: ProcessTerminationReason,
+                                timestamp: SystemTime) {
+        match run_state.mark_for_restart_or_park(old_pid, reason, timestamp) {
+            Some(ParkedState::Exited) => {
+                outputln!(preamble self.service_group,
+                          "Run hook exited successfully, not restarting (restart policy: {})",
+                          run_state.restart_config.policy);
+            }
+            Some(ParkedState::Failed) => {
+                outputln!(preamble self.service_group,
+                          "Service failed, not restarting (restart policy: {}, consecutive \
+                           restarts: {})",
+                          run_state.restart_config.policy,
+                          run_state.consecutive_restarts);
+                self.supervisor
+                    .lock()
+                    .expect("Couldn't lock supervisor")
+                    .mark_failed();
+            }
+            None => {}
+        }
+    }
+
+    /// Apply the service's fencing policy after this member lost quorum as the leader of its
+    /// service group, so that it stops acting as the leader while another one may be elected.
+    fn fence(&mut self) {
+        let policy = self.spec.fencing;
+        match policy {
+            FencingPolicy::None => {
+                outputln!(preamble self.service_group,
+                          "Fenced after losing quorum as the leader; leaving the service running");
+            }
+            FencingPolicy::Hook => {
+                outputln!(preamble self.service_group,
+                          "Fenced after losing quorum as the leader; running the fence hook");
+                let _timer = hook_timer("fence", &self.service_group);
+                if let Some(ref hook) = self.hooks.fence {
+                    hook.run(&self.service_group,
+                             &self.pkg,
+                             self.spec.svc_encrypted_password.as_ref())
+                        .ok();
+                } else {
+                    outputln!(preamble self.service_group,
+                              "No fence hook is present; leaving the service running");
+                }
+            }
+            FencingPolicy::Stop => {
+                outputln!(preamble self.service_group,
+                          "Fenced after losing quorum as the leader; stopping the service until \
+                           a leader is elected");
+                self.supervisor
+                    .lock()
+                    .expect("Couldn't lock supervisor")
+                    .stop(ShutdownConfig::new(None, self));
+            }
+        }
+        self.fenced = Some(policy);
+    }
+
     /// Run file-updated hook if present.
     fn file_updated(&self) -> bool {
-        let _timer = hook_timer("file-updated");
+        let _timer = hook_timer("file-updated", &self.service_group);
 
         if self.initialized()
            && let Some(ref hook) = self.hooks.file_updated
@@ -1442,13 +1976,21 @@ impl Service {
 
     /// Helper for constructing a new render context for the service.
     fn render_context<'a>(&'a self, census: &'a CensusRing) -> RenderContext<'a> {
+        self.render_context_for_cfg(&self.cfg, census)
+    }
+
+    /// Helper for constructing a new render context for the service with the given configuration.
+    fn render_context_for_cfg<'a>(&'a self,
+                                  cfg: &'a Cfg,
+                                  census: &'a CensusRing)
+                                  -> RenderContext<'a> {
         // Unsatisfied binds are filtered out; you only get bind
         // information in the render context if they actually satisfy
         // the contract!
         RenderContext::new(&self.service_group,
                            &self.sys,
                            &self.pkg,
-                           &self.cfg,
+                           cfg,
                            census,
                            self.spec
                                .binds
@@ -1538,10 +2080,13 @@ impl fmt::Display for Service {
     }
 }
 
-// This returns a HistogramTimer that we can use to track how long hooks take to execute. Note that
-// times will get tracked automatically when the HistogramTimer goes out of scope.
-fn hook_timer(name: &str) -> HistogramTimer {
-    HOOK_DURATION.with_label_values(&[name]).start_timer()
+// This returns HistogramTimers that we can use to track how long hooks take to execute, across all
+// services and for the service itself. Note that times will get tracked automatically when the
+// HistogramTimers go out of scope.
+fn hook_timer(name: &str, service_group: &ServiceGroup) -> (HistogramTimer, HistogramTimer) {
+    (HOOK_DURATION.with_label_values(&[name]).start_timer(),
+     SERVICE_HOOK_DURATION.with_label_values(&[service_group.to_string().as_str(), name])
+                          .start_timer())
 }
 
 /// This enum represents whether or not we want to render config information when we serialize this
@@ -1596,6 +2141,8 @@ pub struct ServiceQueryModel {
     pub next_restart_at:        Option<UnixTimestamp>,
     pub restart_count:          u64,
     pub restart_config:         ServiceRestartConfig,
+    /// Only present for services with a `scheduled` hook
+    pub scheduled_hook:         Option<ScheduledHookStatus>,
     pub service_group:          ServiceGroup,
     pub spec_file:              PathBuf,
     pub spec_ident:             PackageIdent,
@@ -1654,6 +2201,16 @@ impl ServiceQueryModel {
                                                  }),
                             restart_count:          service_run_state.restart_count,
                             restart_config:         service_run_state.restart_config.clone(),
+                            scheduled_hook:
+                                service.hooks.scheduled.as_ref().map(|_| {
+                                                                    service.scheduled_hook_status
+                                                                           .lock()
+                                                                           .expect("Couldn't lock \
+                                                                                    scheduled hook \
+                                                                                    status for \
+                                                                                    serialization")
+                                                                           .clone()
+                                                                }),
                             service_group:          service.service_group.clone(),
                             spec_file:              service.spec_file.clone(),
                             spec_ident:             service.spec.ident.clone(),
@@ -1670,10 +2227,14 @@ impl ServiceQueryModel {
 
 impl From<&ServiceQueryModel> for habitat_sup_protocol::types::ServiceStatus {
     fn from(service: &ServiceQueryModel) -> Self {
-        Self { ident:         (*service.pkg.ident.as_ref()).clone().into(),
-               process:       Some((&service.process).into()),
-               service_group: service.service_group.clone().into(),
-               desired_state: Some(service.desired_state.into()), }
+        Self { ident:              (*service.pkg.ident.as_ref()).clone().into(),
+               process:            Some((&service.process).into()),
+               service_group:      service.service_group.clone().into(),
+               desired_state:      Some(service.desired_state.into()),
+               last_scheduled_run: service.scheduled_hook
+                                          .as_ref()
+                                          .and_then(|status| status.last_run.as_ref())
+                                          .map(Into::into), }
     }
 }
 
@@ -1762,4 +2323,139 @@ mod tests {
                                                                    JSON but failed");
         assert_valid(&json_without_config, "http_gateway_services_schema.json");
     }
+
+    fn run_state_with(policy: RestartPolicy, max_restarts: Option<u32>) -> ServiceRunState {
+        let restart_config = ServiceRestartConfig { policy,
+                                                    max_restarts,
+                                                    ..Default::default() };
+        ServiceRunState::new(&restart_config)
+    }
+
+    #[test]
+    fn always_restart_policy_restarts_until_max_restarts() {
+        let mut run_state = run_state_with(RestartPolicy::Always, Some(2));
+        for reason in [ProcessTerminationReason::RunHookExited,
+                       ProcessTerminationReason::RunHookFailed]
+        {
+            assert_eq!(run_state.mark_for_restart_or_park(None, reason, SystemTime::now()),
+                       None);
+            assert_eq!(run_state.restart_state, RestartState::NeedsRestart);
+            run_state.restart_state = RestartState::None;
+            run_state.consecutive_restarts += 1;
+        }
+
+        assert_eq!(run_state.mark_for_restart_or_park(None,
+                                                      ProcessTerminationReason::RunHookFailed,
+                                                      SystemTime::now()),
+                   Some(ParkedState::Failed));
+        assert_eq!(run_state.restart_state, RestartState::None);
+        assert_eq!(run_state.parked(), Some(ParkedState::Failed));
+    }
+
+    #[test]
+    fn on_failure_restart_policy_only_restarts_failures() {
+        let mut run_state = run_state_with(RestartPolicy::OnFailure, None);
+        assert_eq!(run_state.mark_for_restart_or_park(None,
+                                                      ProcessTerminationReason::InitHookFailed,
+                                                      SystemTime::now()),
+                   None);
+        assert_eq!(run_state.restart_state, RestartState::NeedsRestart);
+
+        let mut run_state = run_state_with(RestartPolicy::OnFailure, None);
+        assert_eq!(run_state.mark_for_restart_or_park(Some(1234),
+                                                      ProcessTerminationReason::RunHookExited,
+                                                      SystemTime::now()),
+                   Some(ParkedState::Exited));
+        assert_eq!(run_state.restart_state, RestartState::None);
+        assert_eq!(run_state.last_process_state.unwrap().termination_reason,
+                   ProcessTerminationReason::RunHookExited);
+    }
+
+    #[test]
+    fn never_restart_policy_parks_the_service() {
+        let mut run_state = run_state_with(RestartPolicy::Never, None);
+        assert_eq!(run_state.mark_for_restart_or_park(None,
+                                                      ProcessTerminationReason::RunHookFailed,
+                                                      SystemTime::now()),
+                   Some(ParkedState::Failed));
+
+        // Intentional restarts bring a parked service back
+        run_state.mark_for_immediate_restart(None,
+                                             ProcessTerminationReason::PackageUpdated,
+                                             SystemTime::now());
+        assert_eq!(run_state.parked(), None);
+        assert_eq!(run_state.restart_state, RestartState::NeedsImmediateRestart);
+    }
+
+    #[tokio::test]
+    async fn schedule_added_to_user_toml_schedules_the_scheduled_hook() {
+        let mut service_wrapper = initialize_test_service().await;
+        let service = service_wrapper.service_mut().unwrap();
+        let templates = tempfile::TempDir::new().unwrap();
+        let hooks = tempfile::TempDir::new().unwrap();
+        let user_config = tempfile::TempDir::new().unwrap();
+        std::fs::write(templates.path().join("scheduled"), "echo scheduled").unwrap();
+        service.hooks =
+            HookTable::load("tree", templates.path(), hooks.path(), FeatureFlag::empty());
+        service.cfg.user_config_path = UserConfigPath::new(user_config.path().to_path_buf());
+        *service.initialization_state.write() = InitializationState::Initialized;
+
+        // Neither the plan nor user.toml set a schedule when the service starts
+        service.start_scheduled_hook();
+        assert!(service.scheduled_hook_handle.is_none());
+
+        std::fs::write(user_config.path().join("user.toml"),
+                       "[scheduled_hook]\nschedule = \"@daily\"\n").unwrap();
+        service.reload_user_config();
+        assert!(service.scheduled_hook_handle.is_some());
+
+        // Changes to user.toml that leave the schedule alone don't reschedule the hook
+        let handle = service.scheduled_hook_handle.clone().unwrap();
+        std::fs::write(user_config.path().join("user.toml"),
+                       "port = 8080\n[scheduled_hook]\nschedule = \"@daily\"\n").unwrap();
+        service.reload_user_config();
+        assert!(!handle.is_aborted());
+
+        std::fs::remove_file(user_config.path().join("user.toml")).unwrap();
+        service.reload_user_config();
+        assert!(service.scheduled_hook_handle.is_none());
+    }
+
+    /// The number of series of `metric` served on `/metrics` for `service_group`.
+    fn exported_series(metric: &str, service_group: &ServiceGroup) -> usize {
+        let label = format!("service_group=\"{}\"", service_group);
+        crate::http_gateway::encode_metrics().lines()
+                                             .filter(|line| {
+                                                 line.starts_with(metric) && line.contains(&label)
+                                             })
+                                             .count()
+    }
+
+    #[tokio::test]
+    async fn service_metrics_are_removed_when_the_service_is_unloaded() {
+        let mut service_wrapper = initialize_test_service().await;
+        let service = service_wrapper.service_mut().unwrap();
+        // A service group of its own, so that the services of other tests don't export the same
+        // series
+        service.service_group = ServiceGroup::new("tree", "metrics", None).unwrap();
+        service.supervisor = Arc::new(Mutex::new(Supervisor::new(&service.service_group)));
+        drop(hook_timer("init", &service.service_group));
+
+        assert!(exported_series("hab_sup_service_hook_duration_seconds",
+                                &service.service_group)
+                > 0);
+        assert_eq!(exported_series("hab_sup_service_up", &service.service_group),
+                   1);
+        let hook_durations = "hab_sup_hook_duration_seconds_count{hook=\"init\"}";
+        assert!(crate::http_gateway::encode_metrics().lines()
+                                                     .any(|line| line.starts_with(hook_durations)),
+                "hook durations across services should keep their labels");
+
+        service.remove_metrics();
+        assert_eq!(exported_series("hab_sup_service_hook_duration_seconds",
+                                   &service.service_group),
+                   0);
+        assert_eq!(exported_series("hab_sup_service_up", &service.service_group),
+                   0);
+    }
 }
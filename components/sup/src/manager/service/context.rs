@@ -467,11 +467,14 @@ two = 2
                                            update_election_is_running: false,
                                            update_election_is_no_quorum: false,
                                            update_election_is_finished: false,
+                                           fenced: false,
                                            sys: SysInfo::default(),
                                            alive: true,
                                            suspect: false,
                                            confirmed: false,
                                            departed: false,
+                                           ready: true,
+                                           unloaded: false,
                                            cfg: toml::value::Table::new(), };
         SvcMember::new_owned(census_member)
     }
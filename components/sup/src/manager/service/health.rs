@@ -1,29 +1,42 @@
+use super::hook_timer;
 use crate::{error::Error,
             manager::service::{ProcessOutput,
                                ProcessState,
+                               StandardStreams,
                                hook_runner,
-                               hooks::HealthCheckHook,
+                               hooks::{HealthCheckHook,
+                                       ReadinessCheckHook},
                                supervisor::Supervisor}};
 use habitat_common::{outputln,
-                     templating::package::Pkg};
-use habitat_core::service::{HealthCheckInterval,
+                     templating::{hooks::{self,
+                                          Hook,
+                                          HookOutput},
+                                  package::Pkg}};
+use habitat_core::service::{HealthCheck,
+                            HealthCheckInterval,
                             ServiceGroup};
 use log::{debug,
           error,
           trace};
 use rand::RngExt;
+use regex::Regex;
 use serde::Serialize;
 use std::{cmp,
           convert::TryFrom,
           fmt,
           sync::{Arc,
                  Mutex},
-          time::Duration};
-use tokio::{sync::mpsc::{self,
+          time::{Duration,
+                 Instant}};
+use tokio::{net::TcpStream,
+            sync::mpsc::{self,
                          UnboundedReceiver},
+            task,
             time};
 
 static LOGKEY: &str = "HK";
+/// How long an HTTP or TCP health check may take before the service is considered critical.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// The possible service health result from the status of running the health check.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
@@ -49,6 +62,18 @@ impl TryFrom<i32> for HealthCheckResult {
     }
 }
 
+/// Convert `HealthCheckResult` back into the equivalent health check hook exit code.
+impl From<HealthCheckResult> for i64 {
+    fn from(value: HealthCheckResult) -> Self {
+        match value {
+            HealthCheckResult::Ok => 0,
+            HealthCheckResult::Warning => 1,
+            HealthCheckResult::Critical => 2,
+            HealthCheckResult::Unknown => 3,
+        }
+    }
+}
+
 impl fmt::Display for HealthCheckResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let msg = match *self {
@@ -64,6 +89,8 @@ impl fmt::Display for HealthCheckResult {
 /// The possible statuses from running a health check hook.
 pub enum HealthCheckHookStatus {
     Ran(ProcessOutput, Duration),
+    /// An HTTP or TCP health check was made in place of the hook
+    Probed(HealthCheckResult, StandardStreams, Duration),
     FailedToRun(Duration),
     FailedToStart,
     NoHook,
@@ -71,20 +98,30 @@ pub enum HealthCheckHookStatus {
 
 impl HealthCheckHookStatus {
     pub fn maybe_duration(&self) -> Option<Duration> {
-        if let Self::Ran(_, duration) | Self::FailedToRun(duration) = self {
+        if let Self::Ran(_, duration) | Self::Probed(_, _, duration) | Self::FailedToRun(duration) =
+            self
+        {
             Some(*duration)
         } else {
             None
         }
     }
 
-    pub fn maybe_process_output(self) -> Option<ProcessOutput> {
+    pub fn maybe_exit_status(&self) -> Option<i32> {
         if let Self::Ran(output, _) = self {
-            Some(output)
+            output.exit_status().code()
         } else {
             None
         }
     }
+
+    pub fn maybe_standard_streams(self) -> Option<StandardStreams> {
+        match self {
+            Self::Ran(output, _) => Some(output.standard_streams()),
+            Self::Probed(_, streams, _) => Some(streams),
+            _ => None,
+        }
+    }
 }
 
 /// The complete set of information from running a health check
@@ -93,10 +130,12 @@ impl HealthCheckHookStatus {
 /// `result` is a computed value from `status` and other conditions (eg supervisor status if there
 /// is not a health check hook)
 /// `interval` the computed interval to wait until running the next health check
+/// `ready` is whether the readiness check hook passed, or `true` if there is no hook
 pub struct HealthCheckBundle {
     pub status:   HealthCheckHookStatus,
     pub result:   HealthCheckResult,
     pub interval: HealthCheckInterval,
+    pub ready:    bool,
 }
 
 /// Run the health check hook and get the hook status and result.
@@ -106,7 +145,9 @@ async fn check(supervisor: Arc<Mutex<Supervisor>>,
                package: Pkg,
                password: Option<String>)
                -> (HealthCheckHookStatus, HealthCheckResult) {
-    let status = if let Some(hook) = hook {
+    let status = if let Some(health_check) = package.health_check.clone() {
+        probe(health_check, &service_group, &package, password).await
+    } else if let Some(hook) = hook {
         let result = hook_runner::HookRunner::new(hook,
                                                   service_group.clone(),
                                                   package.clone(),
@@ -146,6 +187,7 @@ async fn check(supervisor: Arc<Mutex<Supervisor>>,
                   })
                   .unwrap_or(HealthCheckResult::Unknown)
         }
+        HealthCheckHookStatus::Probed(result, ..) => *result,
         HealthCheckHookStatus::FailedToRun(_) | HealthCheckHookStatus::FailedToStart => {
             // There was a hook but it did not successfully run. The health check result is
             // unknown.
@@ -158,7 +200,7 @@ async fn check(supervisor: Arc<Mutex<Supervisor>>,
                             .status()
             {
                 ProcessState::Up => HealthCheckResult::Ok,
-                ProcessState::Down => HealthCheckResult::Critical,
+                ProcessState::Down | ProcessState::Failed => HealthCheckResult::Critical,
             }
         }
     };
@@ -166,12 +208,144 @@ async fn check(supervisor: Arc<Mutex<Supervisor>>,
     (status, result)
 }
 
+/// Run the readiness check hook, if any, to determine whether the service should be advertised as
+/// alive to its peers.
+async fn check_readiness(hook: Option<Arc<ReadinessCheckHook>>,
+                         service_group: &ServiceGroup,
+                         package: &Pkg,
+                         password: Option<String>)
+                         -> bool {
+    let Some(hook) = hook else {
+        return true;
+    };
+    let result = hook_runner::HookRunner::new(hook,
+                                              service_group.clone(),
+                                              package.clone(),
+                                              password).into_future()
+                                                       .await;
+    match result {
+        Ok((ready, _)) => ready,
+        Err(e) => {
+            error!("Error running readiness check hook for {}: {:?}",
+                   service_group, e);
+            false
+        }
+    }
+}
+
+/// Run a health check given in the package or the service's spec in place of the health check
+/// hook. The exit code of an `exec` check is interpreted just like that of the hook.
+async fn probe(health_check: HealthCheck,
+               service_group: &ServiceGroup,
+               package: &Pkg,
+               password: Option<String>)
+               -> HealthCheckHookStatus {
+    let start = Instant::now();
+    let probed = match health_check {
+        HealthCheck::Exec { command } => {
+            return probe_exec(command, service_group, package, password).await;
+        }
+        HealthCheck::Http { url, status, body } => probe_http(&url, status, body.as_deref()).await,
+        HealthCheck::Tcp { address } => {
+            match time::timeout(PROBE_TIMEOUT, TcpStream::connect(&address)).await {
+                Ok(Ok(_)) => Ok(format!("Connected to {}", address)),
+                Ok(Err(e)) => Err(format!("Unable to connect to {}, {}", address, e)),
+                Err(_) => Err(format!("Timed out connecting to {}", address)),
+            }
+        }
+    };
+    let duration = start.elapsed();
+    match probed {
+        Ok(message) => {
+            debug!("Health check for {} succeeded: {}", service_group, message);
+            HealthCheckHookStatus::Probed(HealthCheckResult::Ok,
+                                          StandardStreams { stdout: Some(message),
+                                                            stderr: None, },
+                                          duration)
+        }
+        Err(message) => {
+            outputln!(preamble service_group, "Health check failed, {}", message);
+            HealthCheckHookStatus::Probed(HealthCheckResult::Critical,
+                                          StandardStreams { stdout: None,
+                                                            stderr: Some(message), },
+                                          duration)
+        }
+    }
+}
+
+/// Run `command` as the service's user, just like the health check hook would be run.
+async fn probe_exec(command: String,
+                    service_group: &ServiceGroup,
+                    package: &Pkg,
+                    password: Option<String>)
+                    -> HealthCheckHookStatus {
+    let start = Instant::now();
+    let group = service_group.clone();
+    let package = package.clone();
+    let result = task::spawn_blocking(move || {
+                     let _timer = hook_timer(HealthCheckHook::FILE_NAME, &group);
+                     let exit_status = hooks::run_command::<HealthCheckHook, _>(&command,
+                                                                                &group,
+                                                                                &package,
+                                                                                password.as_ref())?;
+                     let stdout_log_path = hooks::stdout_log_path::<HealthCheckHook>(&package.name);
+                     let stderr_log_path = hooks::stderr_log_path::<HealthCheckHook>(&package.name);
+                     let output = HookOutput::new(&stdout_log_path, &stderr_log_path);
+                     let streams = StandardStreams { stdout: output.stdout_str().ok(),
+                                                     stderr: output.stderr_str().ok(), };
+                     Ok::<_, Error>(ProcessOutput::from_raw(streams, exit_status))
+                 }).await;
+    match result {
+        Ok(Ok(output)) => HealthCheckHookStatus::Ran(output, start.elapsed()),
+        Ok(Err(e)) => {
+            error!("Error running health check for {}: {:?}", service_group, e);
+            HealthCheckHookStatus::FailedToRun(start.elapsed())
+        }
+        Err(e) => {
+            error!("Error starting health check for {}: {:?}", service_group, e);
+            HealthCheckHookStatus::FailedToStart
+        }
+    }
+}
+
+/// Request `url`, which is healthy if it responds with `status`, or any successful status if
+/// there is none, and with a body that matches `body`.
+async fn probe_http(url: &str,
+                    status: Option<u16>,
+                    body: Option<&str>)
+                    -> std::result::Result<String, String> {
+    let client = reqwest::Client::builder().timeout(PROBE_TIMEOUT)
+                                           .build()
+                                           .map_err(|e| e.to_string())?;
+    let response = client.get(url)
+                         .send()
+                         .await
+                         .map_err(|e| format!("Request to {} failed, {}", url, e))?;
+    let code = response.status();
+    let expected = status.map_or_else(|| code.is_success(), |status| code.as_u16() == status);
+    if !expected {
+        return Err(format!("{} responded with status {}", url, code));
+    }
+    if let Some(body) = body {
+        // The pattern was validated when the check was parsed
+        let regex = Regex::new(body).map_err(|e| e.to_string())?;
+        let text = response.text()
+                           .await
+                           .map_err(|e| format!("Unable to read response from {}, {}", url, e))?;
+        if !regex.is_match(&text) {
+            return Err(format!("Response from {} does not match '{}'", url, body));
+        }
+    }
+    Ok(format!("{} responded with status {}", url, code))
+}
+
 /// Start a task to repeatedly check the service health, followed by an appropriate delay, forever.
 /// The function returns the receiving end of a channel that acts as a stream of
 /// `HealthCheckBundle`s. When this receiving end is dropped or closed health checking will be
 /// stopped.
 pub fn check_repeatedly(supervisor: Arc<Mutex<Supervisor>>,
                         hook: Option<Arc<HealthCheckHook>>,
+                        readiness_hook: Option<Arc<ReadinessCheckHook>>,
                         nominal_interval: HealthCheckInterval,
                         service_group: ServiceGroup,
                         package: Pkg,
@@ -193,6 +367,10 @@ pub fn check_repeatedly(supervisor: Arc<Mutex<Supervisor>>,
                                          service_group.clone(),
                                          package.clone(),
                                          password.clone()).await;
+            let ready = check_readiness(readiness_hook.as_ref().map(Arc::clone),
+                                        &service_group,
+                                        &package,
+                                        password.clone()).await;
 
             let interval = if result == HealthCheckResult::Ok {
                 if !first_ok_health_check_recorded {
@@ -223,14 +401,15 @@ pub fn check_repeatedly(supervisor: Arc<Mutex<Supervisor>>,
             // executing health checks.
             if tx.send(HealthCheckBundle { status,
                                            result,
-                                           interval })
+                                           interval,
+                                           ready })
                  .is_err()
             {
                 break;
             }
 
-            trace!("`{}` health-check was `{}` next check in {}",
-                   service_group, result, interval);
+            trace!("`{}` health-check was `{}` (ready: {}) next check in {}",
+                   service_group, result, ready, interval);
             time::sleep(interval.into()).await;
         }
         outputln!(preamble service_group_clone, "Health checking has been stopped");
@@ -17,7 +17,8 @@ use habitat_core::service::ServiceGroup;
 use log::{debug,
           error};
 use std::{clone::Clone,
-          sync::Arc,
+          sync::{Arc,
+                 Mutex},
           time::{Duration,
                  Instant}};
 use tokio::task;
@@ -69,16 +70,46 @@ impl<H> HookRunner<H> where H: Hook + Sync + 'static
     }
 
     pub async fn into_future(self) -> Result<(H::ExitValue, Duration)> {
+        self.into_future_impl(None).await
+    }
+
+    /// Like `into_future`, but `pid` holds the ID of the hook's process while it runs. Dropping
+    /// the future doesn't stop the hook, so this lets the caller terminate it.
+    pub async fn into_future_recording_pid(self,
+                                           pid: Arc<Mutex<Option<u32>>>)
+                                           -> Result<(H::ExitValue, Duration)> {
+        self.into_future_impl(Some(pid)).await
+    }
+
+    async fn into_future_impl(self,
+                              pid: Option<Arc<Mutex<Option<u32>>>>)
+                              -> Result<(H::ExitValue, Duration)> {
         // TODO (CM): May want to consider adding a configurable
         // timeout to how long this hook is allowed to run.
         task::spawn_blocking(move || {
             // _timer is for Prometheus metrics, but we also want
             // the runtime for other purposes. Unfortunately,
             // we're not able to use the same timer for both :(
-            let _timer = hook_timer(H::FILE_NAME);
+            let _timer = hook_timer(H::FILE_NAME, &self.service_group);
             let start = Instant::now();
-            let result = self.hook
-                             .run(&self.service_group, &self.pkg, self.passwd.as_ref());
+            let result = match &pid {
+                Some(pid) => {
+                    let result = self.hook.run_reporting_pid(&self.service_group,
+                                                             &self.pkg,
+                                                             self.passwd.as_ref(),
+                                                             |id| {
+                                                                 *pid.lock()
+                                                                     .expect("Couldn't lock \
+                                                                              hook pid") = Some(id);
+                                                             });
+                    *pid.lock().expect("Couldn't lock hook pid") = None;
+                    result
+                }
+                None => {
+                    self.hook
+                        .run(&self.service_group, &self.pkg, self.passwd.as_ref())
+                }
+            };
             let run_time = start.elapsed();
             let exit_value = result.map_err(|e| Error::from(e).with_duration(run_time))?;
             Ok((exit_value, run_time))
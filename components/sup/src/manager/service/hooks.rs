@@ -307,6 +307,48 @@ impl Hook for PostRunHook {
     fn stderr_log_path(&self) -> &Path { &self.stderr_log_path }
 }
 
+#[derive(Debug, Serialize)]
+pub struct ReadinessCheckHook {
+    render_pair:     RenderPair,
+    stdout_log_path: PathBuf,
+    stderr_log_path: PathBuf,
+}
+
+impl Hook for ReadinessCheckHook {
+    type ExitValue = bool;
+
+    const FILE_NAME: &'static str = "readiness-check";
+
+    fn new(package_name: &str, pair: RenderPair, _feature_flags: FeatureFlag) -> Self {
+        ReadinessCheckHook { render_pair:     pair,
+                             stdout_log_path: hooks::stdout_log_path::<Self>(package_name),
+                             stderr_log_path: hooks::stderr_log_path::<Self>(package_name), }
+    }
+
+    fn handle_exit(&self, pkg: &Pkg, _: &HookOutput, status: ExitStatus) -> Self::ExitValue {
+        match status.code() {
+            Some(0) => true,
+            Some(code) => {
+                debug!("{}, Readiness check exited with status code {}",
+                       pkg.name, code);
+                false
+            }
+            None => {
+                Self::output_termination_message(&pkg.name, status);
+                false
+            }
+        }
+    }
+
+    fn path(&self) -> &Path { &self.render_pair.path }
+
+    fn renderer(&self) -> &TemplateRenderer { &self.render_pair.renderer }
+
+    fn stdout_log_path(&self) -> &Path { &self.stdout_log_path }
+
+    fn stderr_log_path(&self) -> &Path { &self.stderr_log_path }
+}
+
 #[derive(Debug, Serialize)]
 pub struct ReconfigureHook {
     render_pair:     RenderPair,
@@ -344,6 +386,49 @@ impl Hook for ReconfigureHook {
     fn stderr_log_path(&self) -> &Path { &self.stderr_log_path }
 }
 
+#[derive(Debug, Serialize)]
+pub struct ScheduledHook {
+    render_pair:     RenderPair,
+    stdout_log_path: PathBuf,
+    stderr_log_path: PathBuf,
+}
+
+impl Hook for ScheduledHook {
+    type ExitValue = ProcessOutput;
+
+    const FILE_NAME: &'static str = "scheduled";
+
+    fn new(package_name: &str, pair: RenderPair, _feature_flags: FeatureFlag) -> Self {
+        ScheduledHook { render_pair:     pair,
+                        stdout_log_path: hooks::stdout_log_path::<Self>(package_name),
+                        stderr_log_path: hooks::stderr_log_path::<Self>(package_name), }
+    }
+
+    fn handle_exit(&self,
+                   pkg: &Pkg,
+                   hook_output: &HookOutput,
+                   status: ExitStatus)
+                   -> Self::ExitValue {
+        match status.code() {
+            Some(0) => {}
+            Some(code) => {
+                outputln!(preamble pkg.name, "Scheduled hook failed! '{}' exited with \
+                    status code {}", Self::FILE_NAME, code);
+            }
+            None => Self::output_termination_message(&pkg.name, status),
+        }
+        ProcessOutput::new(hook_output, status)
+    }
+
+    fn path(&self) -> &Path { &self.render_pair.path }
+
+    fn renderer(&self) -> &TemplateRenderer { &self.render_pair.renderer }
+
+    fn stdout_log_path(&self) -> &Path { &self.stdout_log_path }
+
+    fn stderr_log_path(&self) -> &Path { &self.stderr_log_path }
+}
+
 #[derive(Debug, Serialize)]
 pub struct SuitabilityHook {
     render_pair:     RenderPair,
@@ -470,17 +555,63 @@ impl Hook for PostStopHook {
     fn stderr_log_path(&self) -> &Path { &self.stderr_log_path }
 }
 
+#[derive(Debug, Serialize)]
+pub struct FenceHook {
+    render_pair:     RenderPair,
+    stdout_log_path: PathBuf,
+    stderr_log_path: PathBuf,
+}
+
+impl Hook for FenceHook {
+    type ExitValue = bool;
+
+    const FILE_NAME: &'static str = "fence";
+
+    fn new(package_name: &str, pair: RenderPair, _feature_flags: FeatureFlag) -> Self {
+        FenceHook { render_pair:     pair,
+                    stdout_log_path: hooks::stdout_log_path::<Self>(package_name),
+                    stderr_log_path: hooks::stderr_log_path::<Self>(package_name), }
+    }
+
+    fn handle_exit(&self, pkg: &Pkg, _: &HookOutput, status: ExitStatus) -> Self::ExitValue {
+        let pkg_name = &pkg.name;
+        match status.code() {
+            Some(0) => true,
+            Some(code) => {
+                outputln!(preamble pkg_name, "Fencing failed! '{}' exited with \
+                    status code {}", Self::FILE_NAME, code);
+                false
+            }
+            None => {
+                Self::output_termination_message(pkg_name, status);
+                false
+            }
+        }
+    }
+
+    fn path(&self) -> &Path { &self.render_pair.path }
+
+    fn renderer(&self) -> &TemplateRenderer { &self.render_pair.renderer }
+
+    fn stdout_log_path(&self) -> &Path { &self.stdout_log_path }
+
+    fn stderr_log_path(&self) -> &Path { &self.stderr_log_path }
+}
+
 /// A lookup of hooks that have changed after compilation.
 #[derive(Default)]
 pub struct HookCompileTable {
-    health_check: bool,
-    init:         bool,
-    file_updated: bool,
-    reconfigure:  bool,
-    suitability:  bool,
-    run:          bool,
-    post_run:     bool,
-    post_stop:    bool,
+    health_check:    bool,
+    init:            bool,
+    file_updated:    bool,
+    reconfigure:     bool,
+    suitability:     bool,
+    run:             bool,
+    post_run:        bool,
+    post_stop:       bool,
+    readiness_check: bool,
+    scheduled:       bool,
+    fence:           bool,
 }
 
 impl HookCompileTable {
@@ -494,6 +625,25 @@ impl HookCompileTable {
 
     pub fn post_run_changed(&self) -> bool { self.post_run }
 
+    /// The file names of the hooks that have changed.
+    pub fn changed_hooks(&self) -> Vec<&'static str> {
+        [(self.health_check, HealthCheckHook::FILE_NAME),
+         (self.init, InitHook::FILE_NAME),
+         (self.file_updated, FileUpdatedHook::FILE_NAME),
+         (self.reconfigure, ReconfigureHook::FILE_NAME),
+         (self.suitability, SuitabilityHook::FILE_NAME),
+         (self.run, RunHook::FILE_NAME),
+         (self.post_run, PostRunHook::FILE_NAME),
+         (self.post_stop, PostStopHook::FILE_NAME),
+         (self.readiness_check, ReadinessCheckHook::FILE_NAME),
+         (self.scheduled, ScheduledHook::FILE_NAME),
+         (self.fence, FenceHook::FILE_NAME)].into_iter()
+                                            .filter_map(|(changed, file_name)| {
+                                                changed.then_some(file_name)
+                                            })
+                                            .collect()
+    }
+
     pub fn changed(&self) -> bool {
         let Self { health_check,
                    init,
@@ -502,7 +652,10 @@ impl HookCompileTable {
                    suitability,
                    run,
                    post_run,
-                   post_stop, } = self;
+                   post_stop,
+                   readiness_check,
+                   scheduled,
+                   fence, } = self;
         *health_check
         || *init
         || *file_updated
@@ -511,6 +664,9 @@ impl HookCompileTable {
         || *run
         || *post_run
         || *post_stop
+        || *readiness_check
+        || *scheduled
+        || *fence
     }
 }
 
@@ -525,14 +681,17 @@ pub struct HookQueryModel {
 // Queryable representation of all hooks of a service
 #[derive(Debug, Clone, Serialize)]
 pub struct HookTableQueryModel {
-    pub health_check: Option<HookQueryModel>,
-    pub init:         Option<HookQueryModel>,
-    pub file_updated: Option<HookQueryModel>,
-    pub reconfigure:  Option<HookQueryModel>,
-    pub suitability:  Option<HookQueryModel>,
-    pub run:          Option<HookQueryModel>,
-    pub post_run:     Option<HookQueryModel>,
-    pub post_stop:    Option<HookQueryModel>,
+    pub health_check:    Option<HookQueryModel>,
+    pub init:            Option<HookQueryModel>,
+    pub file_updated:    Option<HookQueryModel>,
+    pub reconfigure:     Option<HookQueryModel>,
+    pub suitability:     Option<HookQueryModel>,
+    pub run:             Option<HookQueryModel>,
+    pub post_run:        Option<HookQueryModel>,
+    pub post_stop:       Option<HookQueryModel>,
+    pub readiness_check: Option<HookQueryModel>,
+    pub scheduled:       Option<HookQueryModel>,
+    pub fence:           Option<HookQueryModel>,
 }
 
 impl HookTableQueryModel {
@@ -545,7 +704,10 @@ impl HookTableQueryModel {
             suitability: hook_table.suitability.as_ref().map(|hook| HookQueryModel { render_pair: hook.render_pair.path.clone(), stdout_log_path: hook.stdout_log_path.clone(), stderr_log_path: hook.stderr_log_path.clone() }),
             run: hook_table.run.as_ref().map(|hook| HookQueryModel { render_pair: hook.render_pair.path.clone(), stdout_log_path: hook.stdout_log_path.clone(), stderr_log_path: hook.stderr_log_path.clone() }),
             post_run: hook_table.post_run.as_ref().map(|hook| HookQueryModel { render_pair: hook.render_pair.path.clone(), stdout_log_path: hook.stdout_log_path.clone(), stderr_log_path: hook.stderr_log_path.clone() }),
-            post_stop: hook_table.post_stop.as_ref().map(|hook| HookQueryModel { render_pair: hook.render_pair.path.clone(), stdout_log_path: hook.stdout_log_path.clone(), stderr_log_path: hook.stderr_log_path.clone() })
+            post_stop: hook_table.post_stop.as_ref().map(|hook| HookQueryModel { render_pair: hook.render_pair.path.clone(), stdout_log_path: hook.stdout_log_path.clone(), stderr_log_path: hook.stderr_log_path.clone() }),
+            readiness_check: hook_table.readiness_check.as_ref().map(|hook| HookQueryModel { render_pair: hook.render_pair.path.clone(), stdout_log_path: hook.stdout_log_path.clone(), stderr_log_path: hook.stderr_log_path.clone() }),
+            scheduled: hook_table.scheduled.as_ref().map(|hook| HookQueryModel { render_pair: hook.render_pair.path.clone(), stdout_log_path: hook.stdout_log_path.clone(), stderr_log_path: hook.stderr_log_path.clone() }),
+            fence: hook_table.fence.as_ref().map(|hook| HookQueryModel { render_pair: hook.render_pair.path.clone(), stdout_log_path: hook.stdout_log_path.clone(), stderr_log_path: hook.stderr_log_path.clone() })
         }
     }
 }
@@ -554,14 +716,17 @@ impl HookTableQueryModel {
 // refactor hooks to be able to run asynchronously.
 #[derive(Debug, Default, Serialize)]
 pub struct HookTable {
-    pub health_check: Option<Arc<HealthCheckHook>>,
-    pub init:         Option<Arc<InitHook>>,
-    pub file_updated: Option<FileUpdatedHook>,
-    pub reconfigure:  Option<ReconfigureHook>,
-    pub suitability:  Option<SuitabilityHook>,
-    pub run:          Option<RunHook>,
-    pub post_run:     Option<Arc<PostRunHook>>,
-    pub post_stop:    Option<Arc<PostStopHook>>,
+    pub health_check:    Option<Arc<HealthCheckHook>>,
+    pub init:            Option<Arc<InitHook>>,
+    pub file_updated:    Option<FileUpdatedHook>,
+    pub reconfigure:     Option<ReconfigureHook>,
+    pub suitability:     Option<SuitabilityHook>,
+    pub run:             Option<RunHook>,
+    pub post_run:        Option<Arc<PostRunHook>>,
+    pub post_stop:       Option<Arc<PostStopHook>>,
+    pub readiness_check: Option<Arc<ReadinessCheckHook>>,
+    pub scheduled:       Option<Arc<ScheduledHook>>,
+    pub fence:           Option<FenceHook>,
 }
 
 impl HookTable {
@@ -599,6 +764,15 @@ impl HookTable {
                                                  &hooks_path,
                                                  &templates,
                                                  feature_flags).map(Arc::new);
+            table.readiness_check = ReadinessCheckHook::load(package_name,
+                                                             &hooks_path,
+                                                             &templates,
+                                                             feature_flags).map(Arc::new);
+            table.scheduled = ScheduledHook::load(package_name,
+                                                  &hooks_path,
+                                                  &templates,
+                                                  feature_flags).map(Arc::new);
+            table.fence = FenceHook::load(package_name, &hooks_path, &templates, feature_flags);
         }
         debug!("{}, Hooks loaded, destination={}, templates={}",
                package_name,
@@ -637,9 +811,61 @@ impl HookTable {
         if let Some(ref hook) = self.post_stop {
             changed.post_stop = self.compile_one(hook.as_ref(), service_group, ctx);
         }
+        if let Some(ref hook) = self.readiness_check {
+            changed.readiness_check = self.compile_one(hook.as_ref(), service_group, ctx);
+        }
+        if let Some(ref hook) = self.scheduled {
+            changed.scheduled = self.compile_one(hook.as_ref(), service_group, ctx);
+        }
+        if let Some(ref hook) = self.fence {
+            changed.fence = self.compile_one(hook, service_group, ctx);
+        }
         changed
     }
 
+    /// Render all loaded hooks from the table without writing them, to find out which of them
+    /// compiling would change.
+    #[allow(clippy::result_large_err)]
+    pub fn preview<T>(&self, ctx: &T) -> Result<HookCompileTable>
+        where T: Serialize
+    {
+        let mut changed = HookCompileTable::new();
+        if let Some(ref hook) = self.file_updated {
+            changed.file_updated = hook.would_change(ctx)?;
+        }
+        if let Some(ref hook) = self.health_check {
+            changed.health_check = hook.would_change(ctx)?;
+        }
+        if let Some(ref hook) = self.init {
+            changed.init = hook.would_change(ctx)?;
+        }
+        if let Some(ref hook) = self.reconfigure {
+            changed.reconfigure = hook.would_change(ctx)?;
+        }
+        if let Some(ref hook) = self.suitability {
+            changed.suitability = hook.would_change(ctx)?;
+        }
+        if let Some(ref hook) = self.run {
+            changed.run = hook.would_change(ctx)?;
+        }
+        if let Some(ref hook) = self.post_run {
+            changed.post_run = hook.would_change(ctx)?;
+        }
+        if let Some(ref hook) = self.post_stop {
+            changed.post_stop = hook.would_change(ctx)?;
+        }
+        if let Some(ref hook) = self.readiness_check {
+            changed.readiness_check = hook.would_change(ctx)?;
+        }
+        if let Some(ref hook) = self.scheduled {
+            changed.scheduled = hook.would_change(ctx)?;
+        }
+        if let Some(ref hook) = self.fence {
+            changed.fence = hook.would_change(ctx)?;
+        }
+        Ok(changed)
+    }
+
     fn compile_one<H, T>(&self, hook: &H, service_group: &str, ctx: &T) -> bool
         where H: Hook,
               T: Serialize
@@ -716,7 +942,10 @@ mod tests {
                       ReconfigureHook
                       RunHook
                       SuitabilityHook
-                      PostStopHook);
+                      PostStopHook
+                      ReadinessCheckHook
+                      ScheduledHook
+                      FenceHook);
 
     fn hook_templates_path() -> PathBuf {
         PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests")
@@ -868,6 +1097,45 @@ mod tests {
         assert_eq!(run_hook_content_normalized, expected_run_hook);
     }
 
+    #[tokio::test]
+    async fn preview_hook_table_does_not_write_hooks() {
+        let tmp_root = rendered_hooks_path();
+        let hooks_path = tmp_root.path().join("hooks");
+        fs::create_dir_all(&hooks_path).unwrap();
+        let service_group = service_group();
+        let template_path = hook_templates_path();
+
+        let cfg_path = &hooks_path.as_path().join("default.toml");
+        create_with_content(cfg_path, "message = \"Hello\"");
+
+        let pkg = pkg(&service_group).await;
+        let sys = Sys::new(true,
+                           GossipListenAddr::default(),
+                           ListenCtlAddr::default(),
+                           HttpListenAddr::default(),
+                           IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let cfg =
+            Cfg::new(&pkg, Some(&hooks_path.as_path().to_path_buf())).expect("Could not create \
+                                                                              config");
+        let mut ring = CensusRing::new("member-a");
+        let ctx = ctx(&service_group, &pkg, &sys, &cfg, &mut ring);
+
+        let hook_table = HookTable::load(&service_group,
+                                         &template_path,
+                                         &hooks_path,
+                                         FeatureFlag::empty());
+        let preview = hook_table.preview(&ctx).expect("Could not preview hooks");
+        assert_eq!(preview.changed_hooks(),
+                   vec![HealthCheckHook::FILE_NAME,
+                        InitHook::FILE_NAME,
+                        RunHook::FILE_NAME]);
+        assert!(!hooks_path.join(InitHook::FILE_NAME).exists());
+
+        hook_table.compile(&service_group, &ctx);
+        let preview = hook_table.preview(&ctx).expect("Could not preview hooks");
+        assert!(!preview.changed());
+    }
+
     #[test]
     fn parse_suitability() {
         #[allow(clippy::string_lit_as_bytes)]
@@ -0,0 +1,263 @@
+//! Reading back the output of a service's run hook and lifecycle hooks.
+//!
+//! Every hook persists its standard output and standard error streams under the service's `logs`
+//! directory as `<hook>.stdout.log` and `<hook>.stderr.log`. The Launcher prefixes each line of
+//! the run hook's output with an RFC 3339 timestamp as it is written. The lifecycle hooks'
+//! files are rewritten on every run and carry no timestamps, so their lines are attributed to
+//! the time the file was last modified.
+
+use chrono::{DateTime,
+             SecondsFormat,
+             Utc};
+use log::debug;
+use std::{collections::HashMap,
+          fmt,
+          fs::{self,
+               File},
+          io::{self,
+               Read,
+               Seek,
+               SeekFrom},
+          path::{Path,
+                 PathBuf}};
+
+const STDOUT_SUFFIX: &str = ".stdout.log";
+const STDERR_SUFFIX: &str = ".stderr.log";
+
+/// A single line of output from one of a service's hooks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogLine {
+    pub timestamp: DateTime<Utc>,
+    /// The file the line was read from, minus its `.log` extension, e.g. `run.stdout`.
+    pub source:    String,
+    pub text:      String,
+}
+
+impl LogLine {
+    /// Split a line on a leading RFC 3339 timestamp, falling back to `default` if there isn't
+    /// one.
+    fn parse(source: &str, line: &str, default: DateTime<Utc>) -> Self {
+        let (timestamp, text) = match line.split_once(' ') {
+            Some((prefix, rest)) => {
+                match DateTime::parse_from_rfc3339(prefix) {
+                    Ok(timestamp) => (timestamp.with_timezone(&Utc), rest),
+                    Err(_) => (default, line),
+                }
+            }
+            None => (default, line),
+        };
+        LogLine { timestamp,
+                  source: source.to_string(),
+                  text: text.to_string() }
+    }
+
+    pub fn is_stderr(&self) -> bool { self.source.ends_with(".stderr") }
+}
+
+impl fmt::Display for LogLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,
+               "{} {}: {}",
+               self.timestamp.to_rfc3339_opts(SecondsFormat::Secs, true),
+               self.source,
+               self.text)
+    }
+}
+
+/// Incrementally reads the hook log files in a service's `logs` directory.
+///
+/// The first call to `read` returns everything currently on disk; subsequent calls only return
+/// lines written since the previous call. A file that shrinks is assumed to have been truncated
+/// (which is what happens when a lifecycle hook runs again) and is read from the beginning.
+#[derive(Debug)]
+pub struct LogReader {
+    dir:     PathBuf,
+    offsets: HashMap<PathBuf, u64>,
+}
+
+impl LogReader {
+    pub fn new<T: Into<PathBuf>>(dir: T) -> Self {
+        LogReader { dir:     dir.into(),
+                    offsets: HashMap::new(), }
+    }
+
+    /// Returns all complete lines written since the last call, ordered by time.
+    pub fn read(&mut self) -> io::Result<Vec<LogLine>> {
+        let mut lines = Vec::new();
+        if !self.dir.is_dir() {
+            return Ok(lines);
+        }
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if let Some(source) = log_source(&path) {
+                // Files can disappear out from under us (e.g. log
+                // rotation); skip them rather than failing the whole read.
+                if let Err(e) = self.read_file(&path, &source, &mut lines) {
+                    debug!("Unable to read log file {}, {}", path.display(), e);
+                }
+            }
+        }
+        // The sort is stable, so lines from the same file keep their order.
+        lines.sort_by_key(|line| line.timestamp);
+        Ok(lines)
+    }
+
+    fn read_file(&mut self, path: &Path, source: &str, lines: &mut Vec<LogLine>) -> io::Result<()> {
+        let mut file = File::open(path)?;
+        let metadata = file.metadata()?;
+        let modified = DateTime::<Utc>::from(metadata.modified()?);
+        let mut offset = self.offsets.get(path).copied().unwrap_or(0);
+        if metadata.len() < offset {
+            offset = 0;
+        }
+        file.seek(SeekFrom::Start(offset))?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
+        // Only consume complete lines; a partially written line will
+        // be picked up in full on a later read.
+        let consumed = match buf.iter().rposition(|b| *b == b'\n') {
+            Some(index) => index + 1,
+            None => 0,
+        };
+        lines.extend(String::from_utf8_lossy(&buf[..consumed]).lines()
+                                                              .map(|line| {
+                                                                  LogLine::parse(source, line,
+                                                                                 modified)
+                                                              }));
+        self.offsets
+            .insert(path.to_path_buf(), offset + consumed as u64);
+        Ok(())
+    }
+}
+
+/// Restrict `lines` to those written at or after `since`, keeping only the last `count`.
+pub fn select(mut lines: Vec<LogLine>,
+              since: Option<DateTime<Utc>>,
+              count: Option<usize>)
+              -> Vec<LogLine> {
+    if let Some(since) = since {
+        lines.retain(|line| line.timestamp >= since);
+    }
+    if let Some(count) = count {
+        let skip = lines.len().saturating_sub(count);
+        lines.drain(..skip);
+    }
+    lines
+}
+
+/// Returns the name lines from `path` should be attributed to, if it is a hook log file.
+fn log_source(path: &Path) -> Option<String> {
+    let file_name = path.file_name()?.to_str()?;
+    if file_name.ends_with(STDOUT_SUFFIX) || file_name.ends_with(STDERR_SUFFIX) {
+        file_name.strip_suffix(".log").map(str::to_string)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn timestamp(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn line(ts: &str, source: &str, text: &str) -> LogLine {
+        LogLine { timestamp: timestamp(ts),
+                  source:    source.to_string(),
+                  text:      text.to_string(), }
+    }
+
+    #[test]
+    fn parse_line_with_timestamp() {
+        let default = timestamp("2020-01-01T00:00:00Z");
+        let parsed = LogLine::parse("run.stdout",
+                                    "2021-02-03T04:05:06.789Z hello world",
+                                    default);
+        assert_eq!(parsed.timestamp, timestamp("2021-02-03T04:05:06.789Z"));
+        assert_eq!(parsed.text, "hello world");
+        assert_eq!(parsed.source, "run.stdout");
+    }
+
+    #[test]
+    fn parse_line_without_timestamp() {
+        let default = timestamp("2020-01-01T00:00:00Z");
+        let parsed = LogLine::parse("init.stderr", "something went wrong", default);
+        assert_eq!(parsed.timestamp, default);
+        assert_eq!(parsed.text, "something went wrong");
+        assert!(parsed.is_stderr());
+    }
+
+    #[test]
+    fn select_filters_by_time_and_count() {
+        let lines = vec![line("2020-01-01T00:00:00Z", "run.stdout", "one"),
+                         line("2020-01-01T00:00:01Z", "run.stdout", "two"),
+                         line("2020-01-01T00:00:02Z", "run.stdout", "three"),
+                         line("2020-01-01T00:00:03Z", "run.stdout", "four"),];
+
+        let selected = select(lines.clone(), Some(timestamp("2020-01-01T00:00:01Z")), None);
+        assert_eq!(selected, lines[1..].to_vec());
+
+        let selected = select(lines.clone(), None, Some(2));
+        assert_eq!(selected, lines[2..].to_vec());
+
+        let selected = select(lines.clone(), None, Some(10));
+        assert_eq!(selected, lines);
+    }
+
+    #[test]
+    fn reader_only_returns_hook_logs() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("run.stdout.log"),
+                  "2020-01-01T00:00:01Z started\n").unwrap();
+        fs::write(dir.path().join("health-check.stderr.log"),
+                  "2020-01-01T00:00:00Z unhealthy\n").unwrap();
+        fs::write(dir.path().join("notes.txt"), "ignored\n").unwrap();
+
+        let lines = LogReader::new(dir.path()).read().unwrap();
+        assert_eq!(lines,
+                   vec![line("2020-01-01T00:00:00Z", "health-check.stderr", "unhealthy"),
+                        line("2020-01-01T00:00:01Z", "run.stdout", "started"),]);
+    }
+
+    #[test]
+    fn reader_returns_only_new_complete_lines() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("run.stdout.log");
+        let mut file = File::create(&path).unwrap();
+        write!(file, "2020-01-01T00:00:00Z one\n2020-01-01T00:00:01Z tw").unwrap();
+
+        let mut reader = LogReader::new(dir.path());
+        assert_eq!(reader.read().unwrap(),
+                   vec![line("2020-01-01T00:00:00Z", "run.stdout", "one")]);
+
+        writeln!(file, "o").unwrap();
+        assert_eq!(reader.read().unwrap(),
+                   vec![line("2020-01-01T00:00:01Z", "run.stdout", "two")]);
+        assert!(reader.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn reader_rereads_truncated_files() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("init.stdout.log");
+        fs::write(&path, "2020-01-01T00:00:00Z first run, which was long\n").unwrap();
+
+        let mut reader = LogReader::new(dir.path());
+        assert_eq!(reader.read().unwrap().len(), 1);
+
+        fs::write(&path, "2020-01-01T00:01:00Z rerun\n").unwrap();
+        assert_eq!(reader.read().unwrap(),
+                   vec![line("2020-01-01T00:01:00Z", "init.stdout", "rerun")]);
+    }
+
+    #[test]
+    fn reader_handles_missing_directory() {
+        let dir = TempDir::new().unwrap();
+        let mut reader = LogReader::new(dir.path().join("logs"));
+        assert!(reader.read().unwrap().is_empty());
+    }
+}
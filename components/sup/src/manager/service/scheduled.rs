@@ -0,0 +1,102 @@
+use super::{UnixTimestamp,
+            hook_runner::HookRunner,
+            hooks::ScheduledHook};
+use crate::{error::Error,
+            event::{self,
+                    ServiceMetadata}};
+use chrono::Local;
+use habitat_common::{outputln,
+                     templating::package::Pkg};
+use habitat_core::service::{Schedule,
+                            ServiceGroup};
+use log::{debug,
+          error};
+use serde::Serialize;
+use std::{convert::TryFrom,
+          sync::{Arc,
+                 Mutex},
+          time::SystemTime};
+use tokio::time;
+
+static LOGKEY: &str = "SH";
+
+/// The outcome of a run of a service's `scheduled` hook.
+#[derive(Clone, Debug, Serialize)]
+pub struct ScheduledRun {
+    pub started_at: UnixTimestamp,
+    /// The hook's exit code, or `None` if it could not be run or was terminated by a signal
+    pub exit_code:  Option<i32>,
+}
+
+impl From<&ScheduledRun> for habitat_sup_protocol::types::ScheduledRun {
+    fn from(run: &ScheduledRun) -> Self {
+        Self { started_at: run.started_at.0,
+               exit_code:  run.exit_code, }
+    }
+}
+
+/// What is known about the scheduled runs of a service's `scheduled` hook.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ScheduledHookStatus {
+    pub next_run_at: Option<UnixTimestamp>,
+    pub last_run:    Option<ScheduledRun>,
+}
+
+/// Run the `scheduled` hook every time `schedule` comes around, forever, recording each run in
+/// `status` and sending it over the event stream. `pid` holds the ID of the hook's process while
+/// it runs.
+#[allow(clippy::too_many_arguments)]
+pub async fn run_on_schedule(hook: Arc<ScheduledHook>,
+                             schedule: Schedule,
+                             service_group: ServiceGroup,
+                             package: Pkg,
+                             password: Option<String>,
+                             metadata: ServiceMetadata,
+                             pid: Arc<Mutex<Option<u32>>>,
+                             status: Arc<Mutex<ScheduledHookStatus>>) {
+    loop {
+        let now = Local::now();
+        let Some(next) = schedule.next_after(&now) else {
+            outputln!(preamble service_group,
+                      "Schedule '{}' never comes around, not running the scheduled hook",
+                      schedule);
+            break;
+        };
+        debug!("Running the scheduled hook for {} at {}",
+               service_group, next);
+        status.lock()
+              .expect("Couldn't lock scheduled hook status")
+              .next_run_at = UnixTimestamp::try_from(SystemTime::from(next)).ok();
+        time::sleep((next - now).to_std().unwrap_or_default()).await;
+
+        let started_at = UnixTimestamp::try_from(SystemTime::now()).ok();
+        let result = HookRunner::new(Arc::clone(&hook),
+                                     service_group.clone(),
+                                     package.clone(),
+                                     password.clone()).into_future_recording_pid(Arc::clone(&pid))
+                                                      .await;
+        let exit_code = match result {
+            Ok((output, duration)) => {
+                let exit_code = output.exit_status().code();
+                event::scheduled_hook(metadata.clone(), &schedule, Some(output), Some(duration));
+                exit_code
+            }
+            Err(e) => {
+                error!("Error running scheduled hook for {}: {:?}",
+                       service_group, e);
+                let duration = match e {
+                    Error::WithDuration(_, duration) => Some(duration),
+                    _ => None,
+                };
+                event::scheduled_hook(metadata.clone(), &schedule, None, duration);
+                None
+            }
+        };
+        if let Some(started_at) = started_at {
+            status.lock()
+                  .expect("Couldn't lock scheduled hook status")
+                  .last_run = Some(ScheduledRun { started_at,
+                                                  exit_code });
+        }
+    }
+}
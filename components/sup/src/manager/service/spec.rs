@@ -1,18 +1,24 @@
 use super::{BindingMode,
+            FencingPolicy,
+            RestartPolicy,
             Topology,
             UpdateCondition,
             UpdateStrategy};
 use crate::error::{Error,
                    Result};
+use habitat_common::templating::package::Pkg;
 use habitat_core::{ChannelIdent,
                    fs::atomic_write,
                    os::process::ShutdownTimeout,
                    package::{PackageIdent,
                              PackageInstall},
-                   service::{HealthCheckInterval,
+                   service::{HealthCheck,
+                             HealthCheckInterval,
                              ServiceBind},
                    url::DEFAULT_BLDR_URL,
                    util};
+use habitat_launcher_client::{ResourceLimits,
+                              Sandbox};
 use habitat_sup_protocol::{self,
                            net};
 use log::{debug,
@@ -99,6 +105,44 @@ pub struct ServiceSpec {
     pub desired_state:          DesiredState,
     pub shutdown_timeout:       Option<ShutdownTimeout>,
     pub svc_encrypted_password: Option<String>,
+    pub restart_policy:         RestartPolicy,
+    pub max_restarts:           Option<u32>,
+    /// Overrides the Supervisor's minimum restart backoff period, in seconds
+    pub min_backoff_period:     Option<u64>,
+    /// Overrides the Supervisor's maximum restart backoff period, in seconds
+    pub max_backoff_period:     Option<u64>,
+    /// Services on this Supervisor which, if loaded, must be healthy before this one is started
+    #[serde(with = "util::serde::string_vec")]
+    pub after:                  Vec<PackageIdent>,
+    /// Services on this Supervisor which must be loaded and healthy before this one is started
+    #[serde(with = "util::serde::string_vec")]
+    pub requires:               Vec<PackageIdent>,
+    /// The share of a single CPU the service may use, as a percentage
+    pub cpu_quota:              Option<u32>,
+    /// The most memory the service may use, in bytes
+    pub memory_max:             Option<u64>,
+    pub pids_max:               Option<u64>,
+    /// The service's share of block IO relative to other services, from 1 to 10000
+    pub io_weight:              Option<u32>,
+    /// Mount a private, empty `/tmp` for the service
+    pub private_tmp:            bool,
+    /// Make the package's install directory read-only to the service
+    pub read_only_pkg:          bool,
+    /// Make the service directory read-only to the service, except for its data and var
+    /// directories
+    pub read_only_svc:          bool,
+    /// Capabilities the service may not have, e.g. `CAP_NET_RAW`, or `ALL`
+    pub cap_drop:               Vec<String>,
+    pub no_new_privs:           bool,
+    /// A seccomp profile in seccompiler's JSON format
+    pub seccomp_profile:        Option<PathBuf>,
+    /// Run in place of the package's health check
+    pub health_check:           Option<HealthCheck>,
+    /// The number of consecutive critical health checks after which the service is restarted
+    pub liveness_threshold:     Option<u32>,
+    /// What to do with a leader-topology service whose member was the leader when its service
+    /// group lost quorum
+    pub fencing:                FencingPolicy,
     // it is important that the health check interval
     // is the last field to be serialized because it
     // is serialized as a table. Individual values
@@ -127,7 +171,53 @@ impl ServiceSpec {
                desired_state: DesiredState::default(),
                health_check_interval: HealthCheckInterval::default(),
                svc_encrypted_password: None,
-               shutdown_timeout: None }
+               shutdown_timeout: None,
+               restart_policy: RestartPolicy::default(),
+               max_restarts: None,
+               min_backoff_period: None,
+               max_backoff_period: None,
+               after: Vec::default(),
+               requires: Vec::default(),
+               cpu_quota: None,
+               memory_max: None,
+               pids_max: None,
+               io_weight: None,
+               private_tmp: false,
+               read_only_pkg: false,
+               read_only_svc: false,
+               cap_drop: Vec::default(),
+               no_new_privs: false,
+               seccomp_profile: None,
+               health_check: None,
+               liveness_threshold: None,
+               fencing: FencingPolicy::default() }
+    }
+
+    /// The limits on the resources the service's processes may use.
+    pub fn resource_limits(&self) -> ResourceLimits {
+        ResourceLimits { cpu_quota:  self.cpu_quota,
+                         memory_max: self.memory_max,
+                         pids_max:   self.pids_max,
+                         io_weight:  self.io_weight, }
+    }
+
+    /// The sandbox the service's processes run in, if any of its restrictions are enabled.
+    pub fn sandbox(&self, pkg: &Pkg) -> Option<Sandbox> {
+        let path = |path: &Path| path.to_string_lossy().into_owned();
+        let mut sandbox = Sandbox { private_tmp: self.private_tmp,
+                                    cap_drop: self.cap_drop.clone(),
+                                    no_new_privs: self.no_new_privs,
+                                    seccomp_profile: self.seccomp_profile.as_deref().map(path),
+                                    ..Default::default() };
+        if self.read_only_pkg {
+            sandbox.read_only_paths.push(path(&pkg.path));
+        }
+        if self.read_only_svc {
+            sandbox.read_only_paths.push(path(&pkg.svc_path));
+            sandbox.writable_paths
+                   .extend([path(&pkg.svc_data_path), path(&pkg.svc_var_path)]);
+        }
+        (sandbox != Sandbox::default()).then_some(sandbox)
     }
 
     // This should only be used to provide a default value when deserializing. We intentially do not
@@ -236,43 +326,25 @@ impl ServiceSpec {
         } else {
             self.channel = ChannelIdent::default();
         }
-        if let Some(topology) = svc_load.topology {
-            if let Ok(topology) = Topology::try_from(topology) {
-                self.topology = topology;
-            } else {
-                warn!("Unable to parse topology value from SvcLoad protocol message; ignoring: {}",
-                      topology);
-            }
+        if let Some(topology) = protocol_enum(svc_load.topology, "topology", "SvcLoad") {
+            self.topology = topology;
         }
-        if let Some(update_strategy) = svc_load.update_strategy {
-            if let Ok(update_strategy) = UpdateStrategy::try_from(update_strategy) {
-                self.update_strategy = update_strategy;
-            } else {
-                warn!("Unable to parse update strategy value from SvcLoad protocol message; \
-                       ignoring: {}",
-                      update_strategy);
-            }
+        if let Some(update_strategy) =
+            protocol_enum(svc_load.update_strategy, "update strategy", "SvcLoad")
+        {
+            self.update_strategy = update_strategy;
         }
-        if let Some(update_condition) = svc_load.update_condition {
-            if let Ok(update_condition) = UpdateCondition::try_from(update_condition) {
-                self.update_condition = update_condition;
-            } else {
-                warn!("Unable to parse update condition value from SvcLoad protocol message; \
-                       ignoring: {}",
-                      update_condition);
-            }
+        if let Some(update_condition) =
+            protocol_enum(svc_load.update_condition, "update condition", "SvcLoad")
+        {
+            self.update_condition = update_condition;
         }
         if let Some(list) = svc_load.binds {
             self.binds = list.into();
         }
-        if let Some(binding_mode) = svc_load.binding_mode {
-            if let Ok(binding_mode) = BindingMode::try_from(binding_mode) {
-                self.binding_mode = binding_mode;
-            } else {
-                warn!("Unable to parse binding mode value from SvcLoad protocol message; \
-                       ignoring: {}",
-                      binding_mode);
-            }
+        if let Some(binding_mode) = protocol_enum(svc_load.binding_mode, "binding mode", "SvcLoad")
+        {
+            self.binding_mode = binding_mode;
         }
         if let Some(config_from) = svc_load.config_from {
             self.config_from = Some(PathBuf::from(config_from));
@@ -286,6 +358,49 @@ impl ServiceSpec {
         if let Some(shutdown_timeout) = svc_load.shutdown_timeout {
             self.shutdown_timeout = Some(ShutdownTimeout::from(shutdown_timeout));
         }
+        if let Some(restart_policy) =
+            protocol_enum(svc_load.restart_policy, "restart policy", "SvcLoad")
+        {
+            self.restart_policy = restart_policy;
+        }
+        self.max_restarts = svc_load.max_restarts.or(self.max_restarts);
+        self.min_backoff_period = svc_load.min_backoff_period.or(self.min_backoff_period);
+        self.max_backoff_period = svc_load.max_backoff_period.or(self.max_backoff_period);
+        if let Some(list) = svc_load.after {
+            self.after = list.into();
+        }
+        if let Some(list) = svc_load.requires {
+            self.requires = list.into();
+        }
+        self.cpu_quota = svc_load.cpu_quota.or(self.cpu_quota);
+        self.memory_max = svc_load.memory_max.or(self.memory_max);
+        self.pids_max = svc_load.pids_max.or(self.pids_max);
+        self.io_weight = svc_load.io_weight.or(self.io_weight);
+        self.private_tmp = svc_load.private_tmp.unwrap_or(self.private_tmp);
+        self.read_only_pkg = svc_load.read_only_pkg.unwrap_or(self.read_only_pkg);
+        self.read_only_svc = svc_load.read_only_svc.unwrap_or(self.read_only_svc);
+        if let Some(list) = svc_load.cap_drop {
+            self.cap_drop = list.into();
+        }
+        self.no_new_privs = svc_load.no_new_privs.unwrap_or(self.no_new_privs);
+        if let Some(seccomp_profile) = svc_load.seccomp_profile {
+            self.seccomp_profile = Some(PathBuf::from(seccomp_profile));
+        }
+        if let Some(health_check) = svc_load.health_check {
+            if let Ok(health_check) = HealthCheck::from_str(&health_check) {
+                self.health_check = Some(health_check);
+            } else {
+                warn!("Unable to parse health check value from SvcLoad protocol message; \
+                       ignoring: {}",
+                      health_check);
+            }
+        }
+        if let Some(liveness_threshold) = svc_load.liveness_threshold {
+            self.liveness_threshold = Some(liveness_threshold).filter(|t| *t > 0);
+        }
+        if let Some(fencing) = protocol_enum(svc_load.fencing, "fencing policy", "SvcLoad") {
+            self.fencing = fencing;
+        }
         Ok(self)
     }
 
@@ -299,44 +414,26 @@ impl ServiceSpec {
         if let Some(channel) = svc_update.bldr_channel {
             self.channel = channel.into();
         }
-        if let Some(topology) = svc_update.topology {
-            if let Ok(topology) = Topology::try_from(topology) {
-                self.topology = topology;
-            } else {
-                warn!("Unable to parse topology value from SvcUpdate protocol message; ignoring: \
-                       {}",
-                      topology);
-            }
+        if let Some(topology) = protocol_enum(svc_update.topology, "topology", "SvcUpdate") {
+            self.topology = topology;
         }
-        if let Some(update_strategy) = svc_update.update_strategy {
-            if let Ok(update_strategy) = UpdateStrategy::try_from(update_strategy) {
-                self.update_strategy = update_strategy;
-            } else {
-                warn!("Unable to parse update strategy value from SvcUpdate protocol message; \
-                       ignoring: {}",
-                      update_strategy);
-            }
+        if let Some(update_strategy) =
+            protocol_enum(svc_update.update_strategy, "update strategy", "SvcUpdate")
+        {
+            self.update_strategy = update_strategy;
         }
-        if let Some(update_condition) = svc_update.update_condition {
-            if let Ok(update_condition) = UpdateCondition::try_from(update_condition) {
-                self.update_condition = update_condition;
-            } else {
-                warn!("Unable to parse update condition value from SvcUpdate protocol message; \
-                       ignoring: {}",
-                      update_condition);
-            }
+        if let Some(update_condition) =
+            protocol_enum(svc_update.update_condition, "update condition", "SvcUpdate")
+        {
+            self.update_condition = update_condition;
         }
         if let Some(list) = svc_update.binds {
             self.binds = list.into();
         }
-        if let Some(binding_mode) = svc_update.binding_mode {
-            if let Ok(binding_mode) = BindingMode::try_from(binding_mode) {
-                self.binding_mode = binding_mode;
-            } else {
-                warn!("Unable to parse binding mode value from SvcUpdate protocol message; \
-                       ignoring: {}",
-                      binding_mode);
-            }
+        if let Some(binding_mode) =
+            protocol_enum(svc_update.binding_mode, "binding mode", "SvcUpdate")
+        {
+            self.binding_mode = binding_mode;
         }
         if let Some(svc_encrypted_password) = svc_update.svc_encrypted_password {
             self.svc_encrypted_password = Some(svc_encrypted_password);
@@ -347,6 +444,52 @@ impl ServiceSpec {
         if let Some(shutdown_timeout) = svc_update.shutdown_timeout {
             self.shutdown_timeout = Some(ShutdownTimeout::from(shutdown_timeout));
         }
+        if let Some(restart_policy) =
+            protocol_enum(svc_update.restart_policy, "restart policy", "SvcUpdate")
+        {
+            self.restart_policy = restart_policy;
+        }
+        self.max_restarts = svc_update.max_restarts.or(self.max_restarts);
+        self.min_backoff_period = svc_update.min_backoff_period.or(self.min_backoff_period);
+        self.max_backoff_period = svc_update.max_backoff_period.or(self.max_backoff_period);
+        if let Some(list) = svc_update.after {
+            self.after = list.into();
+        }
+        if let Some(list) = svc_update.requires {
+            self.requires = list.into();
+        }
+        self.cpu_quota = svc_update.cpu_quota.or(self.cpu_quota);
+        self.memory_max = svc_update.memory_max.or(self.memory_max);
+        self.pids_max = svc_update.pids_max.or(self.pids_max);
+        self.io_weight = svc_update.io_weight.or(self.io_weight);
+        self.private_tmp = svc_update.private_tmp.unwrap_or(self.private_tmp);
+        self.read_only_pkg = svc_update.read_only_pkg.unwrap_or(self.read_only_pkg);
+        self.read_only_svc = svc_update.read_only_svc.unwrap_or(self.read_only_svc);
+        if let Some(list) = svc_update.cap_drop {
+            self.cap_drop = list.into();
+        }
+        self.no_new_privs = svc_update.no_new_privs.unwrap_or(self.no_new_privs);
+        if let Some(seccomp_profile) = svc_update.seccomp_profile {
+            self.seccomp_profile =
+                Some(PathBuf::from(seccomp_profile)).filter(|path| !path.as_os_str().is_empty());
+        }
+        if let Some(health_check) = svc_update.health_check {
+            if health_check.is_empty() {
+                self.health_check = None;
+            } else if let Ok(health_check) = HealthCheck::from_str(&health_check) {
+                self.health_check = Some(health_check);
+            } else {
+                warn!("Unable to parse health check value from SvcUpdate protocol message; \
+                       ignoring: {}",
+                      health_check);
+            }
+        }
+        if let Some(liveness_threshold) = svc_update.liveness_threshold {
+            self.liveness_threshold = Some(liveness_threshold).filter(|t| *t > 0);
+        }
+        if let Some(fencing) = protocol_enum(svc_update.fencing, "fencing policy", "SvcUpdate") {
+            self.fencing = fencing;
+        }
     }
 
     /// Given an `old` and a `new` spec, figure out what operations
@@ -438,6 +581,25 @@ impl ServiceSpec {
                         desired_state: _,
                         shutdown_timeout,
                         svc_encrypted_password,
+                        restart_policy,
+                        max_restarts,
+                        min_backoff_period,
+                        max_backoff_period,
+                        after,
+                        requires,
+                        cpu_quota,
+                        memory_max,
+                        pids_max,
+                        io_weight,
+                        private_tmp,
+                        read_only_pkg,
+                        read_only_svc,
+                        cap_drop,
+                        no_new_privs,
+                        seccomp_profile,
+                        health_check,
+                        liveness_threshold,
+                        fencing,
                         health_check_interval,
                     } = &running_spec;
 
@@ -466,6 +628,20 @@ impl ServiceSpec {
                         // TODO (CM): This probably doesn't need to be here
                         || shutdown_timeout != &disk_spec.shutdown_timeout
                         || svc_encrypted_password != &disk_spec.svc_encrypted_password
+                        // Resource limits and sandboxing are applied
+                        // when the service's process is spawned.
+                        || cpu_quota != &disk_spec.cpu_quota
+                        || memory_max != &disk_spec.memory_max
+                        || pids_max != &disk_spec.pids_max
+                        || io_weight != &disk_spec.io_weight
+                        || private_tmp != &disk_spec.private_tmp
+                        || read_only_pkg != &disk_spec.read_only_pkg
+                        || read_only_svc != &disk_spec.read_only_svc
+                        || cap_drop != &disk_spec.cap_drop
+                        || no_new_privs != &disk_spec.no_new_privs
+                        || seccomp_profile != &disk_spec.seccomp_profile
+                        || health_check != &disk_spec.health_check
+                        || liveness_threshold != &disk_spec.liveness_threshold
                         // TODO (CM): This probably doesn't need to be here, either
                         || health_check_interval != &disk_spec.health_check_interval
                     {
@@ -482,6 +658,19 @@ impl ServiceSpec {
                         {
                             ops.insert(RefreshOperation::RestartUpdater);
                         }
+                        if restart_policy != &disk_spec.restart_policy
+                            || max_restarts != &disk_spec.max_restarts
+                            || min_backoff_period != &disk_spec.min_backoff_period
+                            || max_backoff_period != &disk_spec.max_backoff_period
+                        {
+                            ops.insert(RefreshOperation::UpdateRestartConfig);
+                        }
+                        if after != &disk_spec.after || requires != &disk_spec.requires {
+                            ops.insert(RefreshOperation::UpdateStartOrder);
+                        }
+                        if fencing != &disk_spec.fencing {
+                            ops.insert(RefreshOperation::UpdateFencing);
+                        }
 
                         // We should have *something* to do down
                         // here, but if we don't, let's be explicit
@@ -508,6 +697,23 @@ pub(crate) enum RefreshOperation {
     /// This can happen if a user wants to change the channel a
     /// service is updating from, for instance.
     RestartUpdater,
+    /// Apply a new restart policy and backoff to the service.
+    ///
+    /// The service process keeps running; the new settings govern
+    /// what happens the next time it exits.
+    UpdateRestartConfig,
+    /// Record new start ordering dependencies for the service.
+    ///
+    /// Ordering only matters when a service is started, so nothing
+    /// is done to the running service beyond swapping in its new
+    /// spec.
+    UpdateStartOrder,
+    /// Record a new fencing policy for the service.
+    ///
+    /// The policy is consulted the next time the service group
+    /// loses quorum, so nothing is done to the running service
+    /// beyond swapping in its new spec.
+    UpdateFencing,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -536,10 +742,12 @@ impl FromStr for ServiceSpec {
     type Err = Error;
 
     fn from_str(toml: &str) -> result::Result<Self, Self::Err> {
-        let spec: ServiceSpec = toml::from_str(toml).map_err(Error::ServiceSpecParse)?;
+        let mut spec: ServiceSpec = toml::from_str(toml).map_err(Error::ServiceSpecParse)?;
         if spec.ident == PackageIdent::default() {
             return Err(Error::MissingRequiredIdent);
         }
+        // A threshold of 0 would restart the service on every health check
+        spec.liveness_threshold = spec.liveness_threshold.filter(|t| *t > 0);
         Ok(spec)
     }
 }
@@ -554,6 +762,21 @@ impl TryFrom<habitat_sup_protocol::ctl::SvcLoad> for ServiceSpec {
     }
 }
 
+/// Converts an optional raw enum value from a protocol message, warning about and ignoring values
+/// that cannot be parsed.
+fn protocol_enum<T, V>(value: Option<V>, name: &str, message: &str) -> Option<T>
+    where T: TryFrom<V>,
+          V: fmt::Display + Copy
+{
+    let value = value?;
+    let parsed = T::try_from(value).ok();
+    if parsed.is_none() {
+        warn!("Unable to parse {} value from {} protocol message; ignoring: {}",
+              name, message, value);
+    }
+    parsed
+}
+
 #[cfg(test)]
 mod tests {
     use std::{fs::{self,
@@ -573,7 +796,8 @@ mod tests {
     use super::*;
     use crate::error::Error::*;
 
-    use habitat_sup_protocol::ctl::SvcLoad;
+    use habitat_sup_protocol::ctl::{SvcLoad,
+                                    SvcUpdate};
     use std::convert::TryFrom;
 
     fn file_from_str<P: AsRef<Path>>(path: P, content: &str) {
@@ -684,6 +908,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::cognitive_complexity)]
     fn service_spec_to_toml_string() {
         let spec =
             ServiceSpec { ident:                  PackageIdent::from_str("origin/name/1.2.3/\
@@ -703,7 +928,28 @@ mod tests {
                           config_from:            Some(PathBuf::from("/only/for/development")),
                           desired_state:          DesiredState::Down,
                           svc_encrypted_password: None,
-                          shutdown_timeout:       Some(ShutdownTimeout::from_str("10").unwrap()), };
+                          shutdown_timeout:       Some(ShutdownTimeout::from_str("10").unwrap()),
+                          restart_policy:         RestartPolicy::OnFailure,
+                          max_restarts:           Some(3),
+                          min_backoff_period:     Some(5),
+                          max_backoff_period:     None,
+                          after:
+                              vec![PackageIdent::from_str("core/redis").unwrap()],
+                          requires:               vec![],
+                          cpu_quota:              Some(150),
+                          memory_max:             None,
+                          pids_max:               Some(64),
+                          io_weight:              None,
+                          private_tmp:            true,
+                          read_only_pkg:          false,
+                          read_only_svc:          true,
+                          cap_drop:               vec!["CAP_NET_RAW".to_string()],
+                          no_new_privs:           true,
+                          seccomp_profile:        None,
+                          health_check:           Some(HealthCheck::from_str("tcp://localhost:\
+                                                                              6379").unwrap()),
+                          liveness_threshold:     Some(3),
+                          fencing:                FencingPolicy::Stop, };
         let toml = spec.to_toml_string().unwrap();
 
         assert!(toml.contains(r#"ident = "origin/name/1.2.3/20170223130020""#,));
@@ -721,6 +967,23 @@ mod tests {
         assert!(toml.contains(r#"secs = 123"#));
         assert!(toml.contains(r#"nanos = 0"#));
         assert!(toml.contains(r#"shutdown_timeout = 10"#));
+        assert!(toml.contains(r#"restart_policy = "on-failure""#));
+        assert!(toml.contains(r#"max_restarts = 3"#));
+        assert!(toml.contains(r#"min_backoff_period = 5"#));
+        assert!(!toml.contains(r#"max_backoff_period"#));
+        assert!(toml.contains(r#"after = ["core/redis"]"#));
+        assert!(toml.contains(r#"requires = []"#));
+        assert!(toml.contains(r#"cpu_quota = 150"#));
+        assert!(!toml.contains(r#"memory_max"#));
+        assert!(toml.contains(r#"pids_max = 64"#));
+        assert!(toml.contains(r#"private_tmp = true"#));
+        assert!(toml.contains(r#"read_only_svc = true"#));
+        assert!(toml.contains(r#"cap_drop = ["CAP_NET_RAW"]"#));
+        assert!(toml.contains(r#"no_new_privs = true"#));
+        assert!(!toml.contains(r#"seccomp_profile"#));
+        assert!(toml.contains(r#"health_check = "tcp://localhost:6379""#));
+        assert!(toml.contains(r#"liveness_threshold = 3"#));
+        assert!(toml.contains(r#"fencing = "stop""#));
     }
 
     #[test]
@@ -872,7 +1135,26 @@ mod tests {
                           config_from:            Some(PathBuf::from("/only/for/development")),
                           desired_state:          DesiredState::Down,
                           svc_encrypted_password: None,
-                          shutdown_timeout:       Some(ShutdownTimeout::default()), };
+                          shutdown_timeout:       Some(ShutdownTimeout::default()),
+                          restart_policy:         RestartPolicy::default(),
+                          max_restarts:           None,
+                          min_backoff_period:     None,
+                          max_backoff_period:     None,
+                          after:                  vec![],
+                          requires:               vec![],
+                          cpu_quota:              None,
+                          memory_max:             None,
+                          pids_max:               None,
+                          io_weight:              None,
+                          private_tmp:            false,
+                          read_only_pkg:          false,
+                          read_only_svc:          false,
+                          cap_drop:               vec![],
+                          no_new_privs:           false,
+                          seccomp_profile:        None,
+                          health_check:           None,
+                          liveness_threshold:     None,
+                          fencing:                FencingPolicy::default(), };
         spec.to_file(&path).unwrap();
         let toml = string_from_file(path);
 
@@ -1134,6 +1416,195 @@ mod tests {
         assert_eq!(spec.ident.name, "redis");
     }
 
+    #[test]
+    fn service_spec_restart_policy_from_str() {
+        let toml = r#"
+            ident = "origin/name/1.2.3/20170223130020"
+            restart_policy = "never"
+            max_restarts = 5
+            max_backoff_period = 60
+            "#;
+        let spec = ServiceSpec::from_str(toml).unwrap();
+
+        assert_eq!(spec.restart_policy, RestartPolicy::Never);
+        assert_eq!(spec.max_restarts, Some(5));
+        assert_eq!(spec.min_backoff_period, None);
+        assert_eq!(spec.max_backoff_period, Some(60));
+    }
+
+    #[test]
+    fn service_spec_restart_policy_defaults_to_always() {
+        let toml = r#"
+            ident = "origin/name/1.2.3/20170223130020"
+            "#;
+        let spec = ServiceSpec::from_str(toml).unwrap();
+
+        assert_eq!(spec.restart_policy, RestartPolicy::Always);
+        assert_eq!(spec.max_restarts, None);
+    }
+
+    #[test]
+    fn merge_svc_update_restart_policy() {
+        let mut spec = ServiceSpec::new("core/redis".parse().unwrap());
+        spec.max_restarts = Some(3);
+        let svc_update = SvcUpdate { restart_policy: Some(RestartPolicy::OnFailure as i32),
+                                     min_backoff_period: Some(10),
+                                     ..Default::default() };
+        spec.merge_svc_update(svc_update);
+
+        assert_eq!(spec.restart_policy, RestartPolicy::OnFailure);
+        assert_eq!(spec.max_restarts, Some(3));
+        assert_eq!(spec.min_backoff_period, Some(10));
+        assert_eq!(spec.max_backoff_period, None);
+    }
+
+    #[test]
+    fn service_spec_start_order_from_str() {
+        let toml = r#"
+            ident = "myorg/app"
+            after = ["core/redis"]
+            requires = ["core/postgresql", "myorg/migrations/1.0.0"]
+            "#;
+        let spec = ServiceSpec::from_str(toml).unwrap();
+
+        assert_eq!(spec.after,
+                   vec![PackageIdent::from_str("core/redis").unwrap()]);
+        assert_eq!(spec.requires,
+                   vec![PackageIdent::from_str("core/postgresql").unwrap(),
+                        PackageIdent::from_str("myorg/migrations/1.0.0").unwrap(),]);
+    }
+
+    #[test]
+    fn merge_svc_load_start_order() {
+        let mut svc_load = SvcLoad::default();
+        let ident: PackageIdent = "myorg/app".parse().unwrap();
+        svc_load.ident = Some(ident.into());
+        let postgresql: PackageIdent = "core/postgresql".parse().unwrap();
+        svc_load.requires = Some(std::iter::once(postgresql.clone()).collect());
+
+        let spec =
+            ServiceSpec::try_from(svc_load).expect("Failed to convert SvcLoad to ServiceSpec");
+
+        assert!(spec.after.is_empty());
+        assert_eq!(spec.requires, vec![postgresql]);
+    }
+
+    #[test]
+    fn merge_svc_update_resource_limits() {
+        let mut spec = ServiceSpec::new("core/redis".parse().unwrap());
+        spec.memory_max = Some(512 * 1024 * 1024);
+        let svc_update = SvcUpdate { cpu_quota: Some(50),
+                                     io_weight: Some(500),
+                                     ..Default::default() };
+        spec.merge_svc_update(svc_update);
+
+        assert_eq!(spec.cpu_quota, Some(50));
+        assert_eq!(spec.memory_max, Some(512 * 1024 * 1024));
+        assert_eq!(spec.pids_max, None);
+        assert_eq!(spec.io_weight, Some(500));
+    }
+
+    #[test]
+    fn merge_svc_update_sandbox() {
+        let mut spec = ServiceSpec::new("core/redis".parse().unwrap());
+        spec.private_tmp = true;
+        spec.seccomp_profile = Some(PathBuf::from("/etc/seccomp/redis.json"));
+        let svc_update = SvcUpdate { no_new_privs: Some(true),
+                                     cap_drop: Some(["CAP_NET_RAW".to_string()].into_iter()
+                                                                               .collect()),
+                                     ..Default::default() };
+        spec.merge_svc_update(svc_update);
+
+        assert!(spec.private_tmp);
+        assert!(spec.no_new_privs);
+        assert_eq!(spec.cap_drop, vec!["CAP_NET_RAW".to_string()]);
+        assert_eq!(spec.seccomp_profile,
+                   Some(PathBuf::from("/etc/seccomp/redis.json")));
+
+        let svc_update = SvcUpdate { private_tmp: Some(false),
+                                     seccomp_profile: Some(String::new()),
+                                     ..Default::default() };
+        spec.merge_svc_update(svc_update);
+
+        assert!(!spec.private_tmp);
+        assert_eq!(spec.seccomp_profile, None);
+    }
+
+    #[test]
+    fn merge_svc_update_health_check() {
+        let mut spec = ServiceSpec::new("core/redis".parse().unwrap());
+        let svc_update = SvcUpdate { health_check: Some("http://localhost:8080/health \
+                                                         status=204"
+                                                                    .to_string()),
+                                     ..Default::default() };
+        spec.merge_svc_update(svc_update);
+
+        assert_eq!(spec.health_check,
+                   Some(HealthCheck::Http { url:    "http://localhost:8080/health".to_string(),
+                                            status: Some(204),
+                                            body:   None, }));
+
+        let svc_update = SvcUpdate { health_check: Some("tcp://localhost".to_string()),
+                                     ..Default::default() };
+        spec.merge_svc_update(svc_update);
+        assert!(spec.health_check.is_some());
+
+        let svc_update = SvcUpdate { health_check: Some(String::new()),
+                                     ..Default::default() };
+        spec.merge_svc_update(svc_update);
+        assert_eq!(spec.health_check, None);
+    }
+
+    #[test]
+    fn merge_svc_load_ignores_zero_liveness_threshold() {
+        let mut svc_load = SvcLoad::default();
+        let ident: PackageIdent = "core/redis".parse().unwrap();
+        svc_load.ident = Some(ident.into());
+        svc_load.liveness_threshold = Some(3);
+        let spec = ServiceSpec::try_from(svc_load.clone()).unwrap();
+        assert_eq!(spec.liveness_threshold, Some(3));
+
+        svc_load.liveness_threshold = Some(0);
+        let spec = ServiceSpec::try_from(svc_load).unwrap();
+        assert_eq!(spec.liveness_threshold, None);
+    }
+
+    #[test]
+    fn service_spec_from_str_ignores_zero_liveness_threshold() {
+        let toml = r#"
+            ident = "origin/name/1.2.3/20170223130020"
+            liveness_threshold = 0
+            "#;
+        let spec = ServiceSpec::from_str(toml).unwrap();
+        assert_eq!(spec.liveness_threshold, None);
+    }
+
+    #[test]
+    fn merge_svc_update_liveness_threshold() {
+        let mut spec = ServiceSpec::new("core/redis".parse().unwrap());
+        let svc_update = SvcUpdate { liveness_threshold: Some(3),
+                                     ..Default::default() };
+        spec.merge_svc_update(svc_update);
+        assert_eq!(spec.liveness_threshold, Some(3));
+
+        let svc_update = SvcUpdate { liveness_threshold: Some(0),
+                                     ..Default::default() };
+        spec.merge_svc_update(svc_update);
+        assert_eq!(spec.liveness_threshold, None);
+    }
+
+    #[test]
+    fn merge_svc_update_fencing() {
+        let mut spec = ServiceSpec::new("core/postgresql".parse().unwrap());
+        let svc_update = SvcUpdate { fencing: Some(FencingPolicy::Hook as i32),
+                                     ..Default::default() };
+        spec.merge_svc_update(svc_update);
+        assert_eq!(spec.fencing, FencingPolicy::Hook);
+
+        spec.merge_svc_update(habitat_sup_protocol::ctl::SvcUpdate::default());
+        assert_eq!(spec.fencing, FencingPolicy::Hook);
+    }
+
     mod reconcile {
         use super::*;
 
@@ -1257,10 +1728,37 @@ mod tests {
                    restart,
                    svc_encrypted_password,
                    Some("monkeys".to_string()));
+        reconcile!(cpu_quota_causes_restart, restart, cpu_quota, Some(50));
+        reconcile!(memory_max_causes_restart,
+                   restart,
+                   memory_max,
+                   Some(512 * 1024 * 1024));
+        reconcile!(pids_max_causes_restart, restart, pids_max, Some(64));
+        reconcile!(io_weight_causes_restart, restart, io_weight, Some(500));
+        reconcile!(private_tmp_causes_restart, restart, private_tmp, true);
+        reconcile!(read_only_pkg_causes_restart, restart, read_only_pkg, true);
+        reconcile!(read_only_svc_causes_restart, restart, read_only_svc, true);
+        reconcile!(cap_drop_causes_restart,
+                   restart,
+                   cap_drop,
+                   vec!["ALL".to_string()]);
+        reconcile!(no_new_privs_causes_restart, restart, no_new_privs, true);
+        reconcile!(seccomp_profile_causes_restart,
+                   restart,
+                   seccomp_profile,
+                   Some("seccomp.json".into()));
+        reconcile!(health_check_causes_restart,
+                   restart,
+                   health_check,
+                   Some(HealthCheck::Exec { command: "redis-cli ping".to_string(), }));
         reconcile!(health_check_interval_causes_restart,
                    restart,
                    health_check_interval,
                    10000.into());
+        reconcile!(liveness_threshold_causes_restart,
+                   restart,
+                   liveness_threshold,
+                   Some(3));
 
         reconcile!(bldr_url_causes_update,
                    update,
@@ -1282,5 +1780,40 @@ mod tests {
                    update_condition,
                    UpdateCondition::TrackChannel,
                    vec![RefreshOperation::RestartUpdater]);
+        reconcile!(restart_policy_causes_update,
+                   update,
+                   restart_policy,
+                   RestartPolicy::OnFailure,
+                   vec![RefreshOperation::UpdateRestartConfig]);
+        reconcile!(max_restarts_causes_update,
+                   update,
+                   max_restarts,
+                   Some(3),
+                   vec![RefreshOperation::UpdateRestartConfig]);
+        reconcile!(min_backoff_period_causes_update,
+                   update,
+                   min_backoff_period,
+                   Some(10),
+                   vec![RefreshOperation::UpdateRestartConfig]);
+        reconcile!(max_backoff_period_causes_update,
+                   update,
+                   max_backoff_period,
+                   Some(60),
+                   vec![RefreshOperation::UpdateRestartConfig]);
+        reconcile!(after_causes_update,
+                   update,
+                   after,
+                   vec!["core/redis".parse().unwrap()],
+                   vec![RefreshOperation::UpdateStartOrder]);
+        reconcile!(requires_causes_update,
+                   update,
+                   requires,
+                   vec!["core/postgresql".parse().unwrap()],
+                   vec![RefreshOperation::UpdateStartOrder]);
+        reconcile!(fencing_causes_update,
+                   update,
+                   fencing,
+                   FencingPolicy::Stop,
+                   vec![RefreshOperation::UpdateFencing]);
     }
 }
@@ -2,15 +2,18 @@
 ///
 /// The Supervisor is responsible for running any services we are asked to start. It handles
 /// spawning the new process, watching for failure, and ensuring the service is either up or
-/// down. If the process dies, the Supervisor will restart it.
+/// down. If the process dies, the Supervisor will restart it as allowed by the service's
+/// restart policy.
 use super::{ProcessState,
+            hooks::RunHook,
             terminator};
 use crate::{error::{Error,
                     Result},
             manager::ShutdownConfig};
 use anyhow::anyhow;
 use habitat_common::{outputln,
-                     templating::package::Pkg,
+                     templating::{hooks,
+                                  package::Pkg},
                      types::UserInfo};
 #[cfg(unix)]
 use habitat_core::os::users;
@@ -20,16 +23,24 @@ use habitat_core::{fs,
                    os::process::{self,
                                  Pid},
                    service::ServiceGroup};
-use habitat_launcher_client::LauncherCli;
 #[cfg(windows)]
 use habitat_launcher_client::{IPCReadError,
-                              TryIPCCommandError,
                               TryReceiveError};
+use habitat_launcher_client::{LauncherCli,
+                              ResourceLimits,
+                              ResourceUsage,
+                              Sandbox,
+                              TryIPCCommandError};
 #[cfg(windows)]
 use habitat_launcher_protocol as protocol;
+use lazy_static::lazy_static;
 use log::{debug,
           error,
           warn};
+use prometheus::{IntCounterVec,
+                 IntGaugeVec,
+                 register_int_counter_vec,
+                 register_int_gauge_vec};
 use serde::Serialize;
 #[cfg(windows)]
 use std::env;
@@ -50,6 +61,21 @@ const PIDFILE_PERMISSIONS: Permissions = Permissions::Standard;
 #[cfg(not(windows))]
 const PIDFILE_PERMISSIONS: Permissions = Permissions::Explicit(0o644);
 
+/// How often the resource usage of a service with resource limits is sampled from the Launcher.
+const RESOURCE_USAGE_SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+
+lazy_static! {
+    static ref SERVICE_UP: IntGaugeVec = register_int_gauge_vec!("hab_sup_service_up",
+                                                                 "Whether the service process is \
+                                                                  up (1) or down (0)",
+                                                                 &["service_group"]).unwrap();
+    static ref SERVICE_RESTARTS: IntCounterVec =
+        register_int_counter_vec!("hab_sup_service_restarts_total",
+                                  "Total number of times the service's process was started after \
+                                   its first start",
+                                  &["service_group"]).unwrap();
+}
+
 /// Represents an update of the process id
 #[derive(Debug)]
 pub struct PidUpdate {
@@ -63,6 +89,10 @@ pub struct PidUpdate {
     /// is unknown; callers must not take recovery actions (restart, initialize, etc.)
     /// based on a `launcher_error` update.
     pub launcher_error: bool,
+    /// The exit code of the process if it exited on its own since the last
+    /// update. None if it is still running, was killed by a signal, or the
+    /// Launcher could not say.
+    pub exit_code:      Option<i32>,
 }
 
 impl PidUpdate {
@@ -73,16 +103,18 @@ impl PidUpdate {
 /// Represents the queryable state of the supervised process
 #[derive(Debug, Clone, Serialize)]
 pub struct SupervisedProcessQueryModel {
-    pub pid:           Option<Pid>,
-    pub state:         ProcessState,
-    pub state_entered: u64,
+    pub pid:            Option<Pid>,
+    pub state:          ProcessState,
+    pub state_entered:  u64,
+    pub resource_usage: Option<ResourceUsage>,
 }
 
 impl SupervisedProcessQueryModel {
     pub fn new(supervisor: &Supervisor) -> Self {
-        Self { pid:           supervisor.pid,
-               state:         supervisor.state,
-               state_entered: supervisor.since_epoch().as_secs(), }
+        Self { pid:            supervisor.pid,
+               state:          supervisor.state,
+               state_entered:  supervisor.since_epoch().as_secs(),
+               resource_usage: supervisor.resource_usage, }
     }
 }
 
@@ -94,34 +126,62 @@ impl From<&SupervisedProcessQueryModel> for habitat_sup_protocol::types::Process
         #[cfg(not(target_os = "windows"))]
         let pid: Option<u32> = process.pid.map(|value| value as u32);
 
+        let resource_usage =
+            process.resource_usage
+                   .map(|ResourceUsage { cpu_usage_usec,
+                                         memory_current,
+                                         pids_current, }| {
+                            habitat_sup_protocol::types::ResourceUsage { cpu_usage_usec,
+                                                                         memory_current,
+                                                                         pids_current }
+                        });
+
         Self { elapsed: Some(SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(process.state_entered)).and_then(|timestamp| timestamp.elapsed().ok()).map(|timestamp| timestamp.as_secs()).unwrap_or_default()),
-               state:   process.state.into(),
-               pid }
+               state: process.state.into(),
+               pid,
+               resource_usage }
     }
 }
 
 #[derive(Debug)]
 pub struct Supervisor {
-    service_group: ServiceGroup,
-    state:         ProcessState,
-    pid:           Option<Pid>,
+    service_group:    ServiceGroup,
+    state:            ProcessState,
+    pid:              Option<Pid>,
     /// The time at which the Supervisor's state changed. Absolute
     /// precision is not necessary, but being able to get the seconds
     /// since the UNIX epoch is.
-    state_entered: SystemTime,
+    state_entered:    SystemTime,
     /// Path at which the currently-running PID of this service is
     /// written to disk, for use by service hooks.
-    pid_file:      PathBuf,
+    pid_file:         PathBuf,
+    /// Whether this Supervisor has started the service's process
+    /// before; any further start is a restart.
+    started:          bool,
+    /// Whether the service's process was started with resource
+    /// limits, which is what allows the Launcher to report its
+    /// resource usage.
+    limited:          bool,
+    /// The resource usage of the running process, as last sampled
+    /// from the Launcher.
+    resource_usage:   Option<ResourceUsage>,
+    usage_sampled_at: Option<SystemTime>,
 }
 
 impl Supervisor {
     pub fn new(service_group: &ServiceGroup) -> Supervisor {
         let pid_file = fs::svc_pid_file(service_group.service());
+        SERVICE_UP.with_label_values(&[&service_group.to_string()])
+                  .set(0);
         Supervisor { service_group: service_group.clone(),
                      state: ProcessState::Down,
                      state_entered: SystemTime::now(),
                      pid: None,
-                     pid_file }
+                     pid_file,
+                     started: false,
+                     limited: false,
+                     resource_usage: None,
+                     usage_sampled_at: None }
     }
 
     /// Updates the process state from the launcher and returns a PidUpdate
@@ -135,7 +195,8 @@ impl Supervisor {
         let mut pid_update = PidUpdate { old_pid:        self.pid,
                                          new_pid:        None,
                                          timestamp:      None,
-                                         launcher_error: false, };
+                                         launcher_error: false,
+                                         exit_code:      None, };
 
         // When we don't already hold the PID in memory, ask the launcher.
         // Any communication error means we cannot determine the current process
@@ -164,13 +225,50 @@ impl Supervisor {
         pid_update.new_pid = self.pid;
         if self.pid.is_some() {
             pid_update.timestamp = self.change_state(ProcessState::Up);
+            self.sample_resource_usage(launcher);
         } else {
-            pid_update.timestamp = self.change_state(ProcessState::Down);
+            self.resource_usage = None;
+            if pid_update.old_pid.is_some() {
+                // The process just died. By the time it is no longer
+                // alive the Launcher has reaped it, so it knows how
+                // the process exited.
+                pid_update.exit_code =
+                    launcher.exit_code_of(&self.service_group)
+                            .unwrap_or_else(|err| {
+                                error!("Error getting exit code from launcher: {:#}", anyhow!(err));
+                                None
+                            });
+            }
+            // A failed process stays failed until it is started again
+            if self.state != ProcessState::Failed {
+                pid_update.timestamp = self.change_state(ProcessState::Down);
+            }
             Self::cleanup_pidfile(&self.pid_file);
         }
         pid_update
     }
 
+    /// Refresh the resource usage of the running process, if it has resource limits and was not
+    /// sampled recently.
+    fn sample_resource_usage(&mut self, launcher: &LauncherCli) {
+        let due = self.usage_sampled_at
+                      .and_then(|sampled_at| sampled_at.elapsed().ok())
+                      .is_none_or(|elapsed| elapsed >= RESOURCE_USAGE_SAMPLE_INTERVAL);
+        if !self.limited || !due {
+            return;
+        }
+        self.usage_sampled_at = Some(SystemTime::now());
+        match launcher.resource_usage_of(&self.service_group) {
+            Ok(usage) => self.resource_usage = Some(usage),
+            // Launchers which predate resource limits don't know
+            // this query.
+            Err(err) => {
+                debug!("Error getting resource usage from launcher: {:#}",
+                       anyhow!(err))
+            }
+        }
+    }
+
     // NOTE: the &self argument is only used to get access to
     // self.service_group, and even then only for Linux :/
     #[cfg(unix)]
@@ -272,8 +370,16 @@ impl Supervisor {
                  pkg: &Pkg,
                  group: &ServiceGroup,
                  launcher: &LauncherCli,
-                 svc_password: Option<&str>)
+                 svc_password: Option<&str>,
+                 resource_limits: ResourceLimits,
+                 sandbox: Option<Sandbox>)
                  -> Result<()> {
+        if sandbox.is_some() {
+            launcher_supports_sandbox(launcher.supports_sandbox())?;
+        }
+        if !resource_limits.is_empty() {
+            launcher_supports_resource_limits(launcher.supports_resource_limits())?;
+        }
         let user_info = self.user_info(pkg, launcher)?;
         outputln!(preamble self.service_group,
                   "Starting service as user={}, group={}",
@@ -299,23 +405,42 @@ impl Supervisor {
         // Launcher versions on Linux (and current Windows versions)
         // will use these, while newer versions will prefer the UID
         // and GID, ignoring the names.
+        //
+        // The run hook's output is persisted alongside the output of
+        // the other lifecycle hooks so that it can be retrieved with
+        // `hab svc logs`.
+        let stdout_log = hooks::stdout_log_path::<RunHook>(&pkg.name);
+        let stderr_log = hooks::stderr_log_path::<RunHook>(&pkg.name);
+        self.limited = !resource_limits.is_empty();
         let pid = launcher.spawn(group,
                                  &pkg.svc_run,
                                  user_info,
                                  svc_password, // Windows optional
-                                 (*pkg.env).clone())?;
+                                 (*pkg.env).clone(),
+                                 Some((&stdout_log, &stderr_log)),
+                                 self.limited.then_some(resource_limits),
+                                 sandbox)?;
         if pid == 0 {
             warn!(target: "pidfile_tracing", "Spawned service for {} has a PID of 0!", group);
         }
         self.pid = Some(pid);
         self.create_pidfile(&self.pid_file)?;
         self.change_state(ProcessState::Up);
+        if self.started {
+            SERVICE_RESTARTS.with_label_values(&[&self.service_group.to_string()])
+                            .inc();
+        }
+        self.started = true;
         Ok(())
     }
 
-    /// Is the process up or down?
+    /// Is the process up, down or failed?
     pub fn status(&self) -> ProcessState { self.state }
 
+    /// Record that the process is down and will not be restarted because the service exhausted
+    /// its restart policy. The process is reported as failed until it is started again.
+    pub fn mark_failed(&mut self) { self.change_state(ProcessState::Failed); }
+
     /// Returns a future that stops a service asynchronously.
     pub fn stop(&self, shutdown_config: ShutdownConfig) {
         let service_group = self.service_group.clone();
@@ -382,11 +507,22 @@ impl Supervisor {
         }
         self.state = state;
         self.state_entered = SystemTime::now();
+        SERVICE_UP.with_label_values(&[&self.service_group.to_string()])
+                  .set(i64::from(state == ProcessState::Up));
         Some(self.state_entered)
     }
 
     pub fn state_entered(&self) -> SystemTime { self.state_entered }
 
+    /// The last time the Supervisor's state changed or the resource usage of its process was
+    /// sampled.
+    pub fn last_change(&self) -> SystemTime {
+        self.usage_sampled_at
+            .map_or(self.state_entered, |sampled_at| {
+                sampled_at.max(self.state_entered)
+            })
+    }
+
     /// Returns how long after the UNIX Epoch this Supervisor changed
     /// state.
     fn since_epoch(&self) -> Duration {
@@ -395,3 +531,71 @@ impl Supervisor {
             .expect("our time should ALWAYS be after the UNIX Epoch")
     }
 }
+
+/// Stop exporting the process metrics of `service_group`, once it is unloaded.
+pub fn remove_metrics(service_group: &ServiceGroup) {
+    let service_group = service_group.to_string();
+    SERVICE_UP.remove_label_values(&[&service_group]).ok();
+    SERVICE_RESTARTS.remove_label_values(&[&service_group]).ok();
+}
+
+/// Checks whether the Launcher reports that it applies sandboxes before a sandboxed service is
+/// spawned with it. Older Launchers ignore the sandbox and would run the service unconfined.
+fn launcher_supports_sandbox(supported: std::result::Result<bool, TryIPCCommandError>)
+                             -> Result<()> {
+    match supported {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(Error::LauncherPredatesSandbox),
+        Err(err) => {
+            error!("Unable to ask the launcher whether it applies sandboxes, refusing to start a \
+                    sandboxed service: {:#}",
+                   anyhow!(err));
+            Err(Error::LauncherPredatesSandbox)
+        }
+    }
+}
+
+/// Checks whether the Launcher reports that it enforces resource limits before a service with
+/// resource limits is spawned with it. Other Launchers ignore them and would run the service
+/// unlimited.
+fn launcher_supports_resource_limits(supported: std::result::Result<bool, TryIPCCommandError>)
+                                     -> Result<()> {
+    match supported {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(Error::LauncherIgnoresResourceLimits),
+        Err(err) => {
+            error!("Unable to ask the launcher whether it enforces resource limits, refusing to \
+                    start a service with resource limits: {:#}",
+                   anyhow!(err));
+            Err(Error::LauncherIgnoresResourceLimits)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use habitat_launcher_client::TryReceiveError;
+
+    #[test]
+    fn sandboxed_services_are_refused_by_launchers_which_predate_sandboxing() {
+        assert!(launcher_supports_sandbox(Ok(true)).is_ok());
+        assert!(matches!(launcher_supports_sandbox(Ok(false)),
+                         Err(Error::LauncherPredatesSandbox)));
+
+        let timeout = TryIPCCommandError::TryReceive("version", TryReceiveError::Timeout);
+        assert!(matches!(launcher_supports_sandbox(Err(timeout)),
+                         Err(Error::LauncherPredatesSandbox)));
+    }
+
+    #[test]
+    fn services_with_resource_limits_are_refused_by_launchers_which_ignore_them() {
+        assert!(launcher_supports_resource_limits(Ok(true)).is_ok());
+        assert!(matches!(launcher_supports_resource_limits(Ok(false)),
+                         Err(Error::LauncherIgnoresResourceLimits)));
+
+        let timeout = TryIPCCommandError::TryReceive("version", TryReceiveError::Timeout);
+        assert!(matches!(launcher_supports_resource_limits(Err(timeout)),
+                         Err(Error::LauncherIgnoresResourceLimits)));
+    }
+}
@@ -0,0 +1,141 @@
+//! Start and stop ordering between the services loaded on a single Supervisor.
+//!
+//! A service's `after` and `requires` settings name other services on the same Supervisor that
+//! must be healthy before it is started. When the Supervisor shuts down, services are stopped in
+//! the reverse order, so that no service outlives the services it depends on.
+
+use super::service::ServiceSpec;
+use habitat_common::package_graph::PackageGraph;
+use habitat_core::package::{Identifiable,
+                            PackageIdent};
+use std::collections::HashMap;
+
+pub struct ServiceOrder {
+    /// Maps each service's spec ident to the spec idents of the services it starts after.
+    graph: PackageGraph,
+}
+
+impl ServiceOrder {
+    /// Build the ordering between the services described by `specs`.
+    ///
+    /// Dependencies are matched against the idents of `specs`. An `after` dependency which
+    /// matches none of them is ignored. A `requires` dependency which matches none of them is kept
+    /// as it was given, and can never be satisfied.
+    pub fn new<'a, I>(specs: I) -> Self
+        where I: IntoIterator<Item = &'a ServiceSpec>
+    {
+        let specs = specs.into_iter().collect::<Vec<_>>();
+        let resolve = |dep: &PackageIdent| {
+            specs.iter()
+                 .find(|spec| spec.ident.satisfies(dep))
+                 .map(|spec| spec.ident.clone())
+        };
+
+        let mut graph = PackageGraph::default();
+        for spec in &specs {
+            let mut deps = spec.after.iter().filter_map(resolve).collect::<Vec<_>>();
+            deps.extend(spec.requires
+                            .iter()
+                            .map(|dep| resolve(dep).unwrap_or_else(|| dep.clone())));
+            graph.extend(&spec.ident, &deps);
+        }
+        ServiceOrder { graph }
+    }
+
+    /// The services which must be healthy before the service with spec ident `ident` is started.
+    pub fn dependencies(&self, ident: &PackageIdent) -> Vec<&PackageIdent> {
+        self.graph.deps(ident)
+    }
+
+    /// Returns one of the services in a dependency cycle, if there is one. No ordering can be
+    /// honored for services in a cycle.
+    pub fn cycle(&self) -> Option<&PackageIdent> { self.graph.dependency_order().err() }
+
+    /// Group the services into batches which can each be stopped concurrently. Every service is
+    /// in a later batch than all of the services which depend on it.
+    ///
+    /// Returns `Err` with one of the services in a dependency cycle if there is one.
+    pub fn stop_batches(&self) -> Result<Vec<Vec<PackageIdent>>, PackageIdent> {
+        let order = self.graph.dependency_order().map_err(Clone::clone)?;
+
+        // Walk the services dependents first, so a service's batch is
+        // known before the batches of any of its dependencies.
+        let mut batch_of: HashMap<&PackageIdent, usize> = HashMap::new();
+        let mut batches: Vec<Vec<PackageIdent>> = Vec::new();
+        for ident in order.into_iter().rev() {
+            let batch = self.graph
+                            .rdeps(ident)
+                            .iter()
+                            .map(|dependent| batch_of[dependent] + 1)
+                            .max()
+                            .unwrap_or(0);
+            batch_of.insert(ident, batch);
+            if batches.len() <= batch {
+                batches.resize_with(batch + 1, Vec::new);
+            }
+            batches[batch].push(ident.clone());
+        }
+        Ok(batches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(ident: &str, after: &[&str], requires: &[&str]) -> ServiceSpec {
+        let mut spec = ServiceSpec::new(ident.parse().unwrap());
+        spec.after = after.iter().map(|a| a.parse().unwrap()).collect();
+        spec.requires = requires.iter().map(|r| r.parse().unwrap()).collect();
+        spec
+    }
+
+    fn ident(ident: &str) -> PackageIdent { ident.parse().unwrap() }
+
+    #[test]
+    fn dependencies_are_matched_against_loaded_services() {
+        let specs = [spec("core/postgresql", &[], &[]),
+                     spec("core/redis/5.0.7", &[], &[]),
+                     spec("myorg/app",
+                          &["core/redis", "core/nginx"],
+                          &["core/postgresql/11.2", "myorg/migrations"])];
+        let order = ServiceOrder::new(&specs);
+
+        let mut deps = order.dependencies(&ident("myorg/app"));
+        deps.sort_by_key(ToString::to_string);
+        assert_eq!(deps,
+                   vec![&ident("core/postgresql"),
+                        &ident("core/redis/5.0.7"),
+                        &ident("myorg/migrations")]);
+        assert!(order.dependencies(&ident("core/postgresql")).is_empty());
+    }
+
+    #[test]
+    fn services_are_stopped_after_their_dependents() {
+        let specs = [spec("core/postgresql", &[], &[]),
+                     spec("myorg/api", &[], &["core/postgresql"]),
+                     spec("myorg/web", &["myorg/api"], &[]),
+                     spec("core/redis", &[], &[])];
+        let order = ServiceOrder::new(&specs);
+        assert!(order.cycle().is_none());
+
+        let mut batches = order.stop_batches().unwrap();
+        batches[0].sort_by_key(ToString::to_string);
+        assert_eq!(batches,
+                   vec![vec![ident("core/redis"), ident("myorg/web")],
+                        vec![ident("myorg/api")],
+                        vec![ident("core/postgresql")]]);
+    }
+
+    #[test]
+    fn cycles_are_detected() {
+        let specs = [spec("myorg/a", &["myorg/b"], &[]),
+                     spec("myorg/b", &[], &["myorg/a"]),
+                     spec("core/redis", &[], &[])];
+        let order = ServiceOrder::new(&specs);
+
+        let in_cycle = order.cycle().unwrap().clone();
+        assert!(in_cycle == ident("myorg/a") || in_cycle == ident("myorg/b"));
+        assert!(order.stop_batches().is_err());
+    }
+}
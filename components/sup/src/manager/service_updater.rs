@@ -11,9 +11,12 @@ use futures::future::{self,
 use habitat_common::outputln;
 use habitat_core::{package::PackageIdent,
                    service::ServiceGroup};
+use lazy_static::lazy_static;
 use log::debug;
 use parking_lot::{Mutex,
                   RwLock};
+use prometheus::{IntCounterVec,
+                 register_int_counter_vec};
 use std::{self,
           cmp::Ordering,
           collections::HashMap,
@@ -24,6 +27,24 @@ use std::{self,
 
 static LOGKEY: &str = "SU";
 
+lazy_static! {
+    static ref SERVICE_UPDATES: IntCounterVec =
+        register_int_counter_vec!("hab_sup_service_updates_total",
+                                  "Total number of updates found for the service, by update \
+                                   strategy",
+                                  &["service_group", "strategy"]).unwrap();
+}
+
+/// Stop exporting the update metrics of `service_group`, once it is unloaded.
+pub fn remove_metrics(service_group: &ServiceGroup) {
+    let service_group = service_group.to_string();
+    for strategy in ["at-once", "rolling"] {
+        SERVICE_UPDATES.remove_label_values(&[service_group.as_str(), strategy])
+                       .ok();
+    }
+    rolling_update_worker::remove_metrics(&service_group);
+}
+
 /// A handle to an update worker that automatically aborts the worker when dropped.
 struct Worker(AbortHandle);
 
@@ -136,6 +157,8 @@ impl ServiceUpdater {
             let new_ident = package_update_worker.update().await;
             debug!("'{}' at-once updater found update from '{}' to '{}'",
                    service_group, full_ident, new_ident);
+            SERVICE_UPDATES.with_label_values(&[service_group.to_string().as_str(), "at-once"])
+                           .inc();
             Self::update_message(&new_ident, full_ident.as_ref());
             updates.lock().insert(service_group, new_ident);
         }
@@ -159,6 +182,8 @@ impl ServiceUpdater {
             let new_ident = worker.run().await;
             debug!("'{}' rolling updater found update from '{}' to '{}'",
                    service_group, full_ident, new_ident);
+            SERVICE_UPDATES.with_label_values(&[service_group.to_string().as_str(), "rolling"])
+                           .inc();
             Self::update_message(&new_ident, full_ident.as_ref());
             updates.lock().insert(service_group, new_ident);
         }
@@ -5,12 +5,16 @@ use crate::{census::{CensusGroup,
             manager::service::{Service,
                                Topology}};
 use habitat_common::owning_refs::RwLockReadGuardRef;
-use habitat_core::service::ServiceGroup;
+use habitat_core::{service::ServiceGroup,
+                   util::ToI64};
+use lazy_static::lazy_static;
 use log::{debug,
           error,
           trace,
           warn};
 use parking_lot::RwLock;
+use prometheus::{IntGaugeVec,
+                 register_int_gauge_vec};
 use std::{self,
           sync::Arc,
           time::Duration};
@@ -21,6 +25,27 @@ use tokio::{self,
 // loop with this delay after each call.
 const DELAY: Duration = Duration::from_secs(1);
 
+lazy_static! {
+    static ref ROLLING_UPDATE_MEMBERS: IntGaugeVec =
+        register_int_gauge_vec!("hab_sup_rolling_update_members",
+                                "Number of active members in the service group taking part in \
+                                 rolling updates",
+                                &["service_group"]).unwrap();
+    static ref ROLLING_UPDATE_MEMBERS_UPDATED: IntGaugeVec =
+        register_int_gauge_vec!("hab_sup_rolling_update_members_updated",
+                                "Number of active members in the service group running the same \
+                                 package incarnation as the rolling update leader",
+                                &["service_group"]).unwrap();
+}
+
+/// Stop exporting the rolling update metrics of `service_group`, once it is unloaded.
+pub(super) fn remove_metrics(service_group: &str) {
+    ROLLING_UPDATE_MEMBERS.remove_label_values(&[service_group])
+                          .ok();
+    ROLLING_UPDATE_MEMBERS_UPDATED.remove_label_values(&[service_group])
+                                  .ok();
+}
+
 /// The role of the supervisor in the rolling update.
 enum Role {
     Leader,
@@ -208,6 +233,7 @@ impl RollingUpdateWorker {
                 let census_group = self.census_group().await;
                 match (census_group.update_leader(), census_group.me()) {
                     (Some(leader), Some(me)) => {
+                        self.record_progress(&census_group, leader.pkg_incarnation);
                         // If the current leader is no longer alive, it is possible that this
                         // follower is now a leader.
                         if leader.member_id == me.member_id {
@@ -279,6 +305,7 @@ impl RollingUpdateWorker {
                        census_group.me())
                 {
                     (Some(leader), Some(peer), Some(me)) => {
+                        self.record_progress(&census_group, leader.pkg_incarnation);
                         // If the current leader is no longer alive, it is possible that this
                         // follower is now a leader.
                         if leader.member_id == me.member_id {
@@ -344,6 +371,22 @@ impl RollingUpdateWorker {
         }
     }
 
+    /// Publish how far the rolling update has progressed through the service group, as seen by
+    /// this follower.
+    fn record_progress(&self, census_group: &CensusGroup, leader_incarnation: u64) {
+        let (members, updated) =
+            census_group.active_members()
+                        .fold((0_usize, 0_usize), |(members, updated), member| {
+                            (members + 1,
+                             updated + usize::from(member.pkg_incarnation >= leader_incarnation))
+                        });
+        let service_group = self.service_group.to_string();
+        ROLLING_UPDATE_MEMBERS.with_label_values(&[&service_group])
+                              .set(members.to_i64());
+        ROLLING_UPDATE_MEMBERS_UPDATED.with_label_values(&[&service_group])
+                                      .set(updated.to_i64());
+    }
+
     /// Returns a reference to the services census group. The reference is behind a read write lock
     /// so the lifetime of the reference should be minimized to avoid uneccesarily holding the lock.
     async fn census_group(&self) -> RwLockReadGuardRef<'_, CensusRing, CensusGroup> {
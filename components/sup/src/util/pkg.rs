@@ -9,23 +9,27 @@ use hab::{command::pkg::{self,
           error::Result as HabResult};
 use habitat_api_client::BuilderAPIClient;
 use habitat_common::{cli_config::CliConfig,
-                     command::package::install::{self as install_cmd,
-                                                 InstallHookMode,
-                                                 InstallMode,
-                                                 InstallSource,
-                                                 LocalPackageUsage},
+                     command::package::{install::{self as install_cmd,
+                                                  InstallHookMode,
+                                                  InstallMode,
+                                                  InstallSource,
+                                                  LocalPackageUsage},
+                                        lockfile::Lockfile},
                      outputln,
                      ui::{NullUi,
                           UIWriter}};
 use habitat_core::{AUTH_TOKEN_ENVVAR,
                    ChannelIdent,
+                   crypto::keys::KeyCache,
                    env as henv,
                    fs::{self,
                         FS_ROOT_PATH},
                    package::{PackageIdent,
                              PackageInstall,
-                             PackageTarget}};
+                             PackageTarget,
+                             files}};
 use std::path::Path;
+use tokio::task;
 
 static LOGKEY: &str = "UT";
 
@@ -63,6 +67,58 @@ pub async fn install<T>(ui: &mut T,
                                                .map_err(Error::from)
 }
 
+/// Install exactly the packages recorded in `lockfile`, returning
+/// the runnable root package identified by `ident`.
+pub async fn install_from_lockfile<T>(ui: &mut T,
+                                      url: &str,
+                                      lockfile: &Lockfile,
+                                      ident: &PackageIdent)
+                                      -> Result<PackageInstall>
+    where T: UIWriter
+{
+    let fs_root_path = Path::new(&*FS_ROOT_PATH);
+    let auth_token = get_auth_token();
+    let roots = install_cmd::start_from_lockfile(ui,
+                                                 url,
+                                                 lockfile,
+                                                 PRODUCT,
+                                                 VERSION,
+                                                 fs_root_path,
+                                                 &fs::cache_artifact_path(None::<String>),
+                                                 auth_token.as_deref(),
+                                                 &InstallMode::default(),
+                                                 // Install hooks are run in add_service, as in
+                                                 // `install`
+                                                 InstallHookMode::Ignore).await?;
+    let installed = roots.into_iter()
+                         .find(|package| package.ident() == ident)
+                         .ok_or_else(|| Error::PackageNotFound(ident.clone()))?;
+    if installed.is_runnable() {
+        Ok(installed)
+    } else {
+        outputln!("Can't start non-runnable service: {}", installed.ident());
+        Err(Error::PackageNotRunnable(installed.ident().clone()))
+    }
+}
+
+/// Record an installed package and its dependencies in a lockfile.
+pub async fn lock<T>(ui: &mut T, url: &str, package: &PackageInstall) -> Result<Lockfile>
+    where T: UIWriter
+{
+    let fs_root_path = Path::new(&*FS_ROOT_PATH);
+    let auth_token = get_auth_token();
+    install_cmd::lock(ui,
+                      url,
+                      std::slice::from_ref(package),
+                      PRODUCT,
+                      VERSION,
+                      fs_root_path,
+                      &fs::cache_artifact_path(None::<String>),
+                      auth_token.as_deref(),
+                      &InstallMode::default()).await
+                                              .map_err(Error::from)
+}
+
 // `install` but with no ui output and the benefit of thread safety
 pub async fn install_no_ui(url: &str,
                            install_source: &InstallSource,
@@ -105,6 +161,24 @@ pub fn installed<T>(ident: T) -> Option<PackageInstall>
     PackageInstall::load(ident.as_ref(), Some(fs_root_path)).ok()
 }
 
+/// Verifies the installed files of the package `ident`, and of its transitive dependencies,
+/// against their signed `FILES` metafiles.
+///
+/// Every file is hashed, which takes a while for large packages, so this runs on a blocking
+/// thread.
+pub async fn verify_installed_files(ident: PackageIdent, key_cache: KeyCache) -> Result<()> {
+    task::spawn_blocking(move || -> Result<()> {
+        let fs_root_path = Path::new(&*FS_ROOT_PATH);
+        let package = PackageInstall::load(&ident, Some(fs_root_path))?;
+        files::verify(&package, &key_cache)?.into_result(&package)?;
+        for ident in package.tdeps()? {
+            let dependency = PackageInstall::load(&ident, Some(fs_root_path))?;
+            files::verify(&dependency, &key_cache)?.into_result(&dependency)?;
+        }
+        Ok(())
+    }).await?
+}
+
 /// Install a package but only consider packages from a channel. Do not consider any locally
 /// installed packages.
 ///
@@ -23,9 +23,9 @@ impl Client {
             format!("127.0.0.1:{}", port).parse::<SocketAddr>()
                                          .context("Could not parse Butterfly gossip address!")?;
         let butterfly_client =
-            ButterflyClient::new(&gossip_addr.to_string(), None).context("Could not create \
-                                                                          Butterfly Client for \
-                                                                          test!")?;
+            ButterflyClient::new(&gossip_addr.to_string(), None, None).context("Could not \
+                                                                                create Butterfly \
+                                                                                Client for test!")?;
         Ok(Client { butterfly_client })
     }
 
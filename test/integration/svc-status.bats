@@ -37,8 +37,8 @@ teardown() {
     assert_success
 
     # OUTPUT:
-    # package                           type        desired  state  elapsed (s)  pid   group
-    # core/redis/4.0.10/20180801003001  standalone  up       up     3            1016  redis.default
+    # package                           type        desired  state  elapsed (s)  pid   group          cpu (s)  memory (MiB)  pids
+    # core/redis/4.0.10/20180801003001  standalone  up       up     3            1016  redis.default  <none>   <none>        <none>
     assert_line --regexp "core/redis/.*/[0-9]{14}\s+standalone\s+up\s+up\s+.*redis.default"
 }
 